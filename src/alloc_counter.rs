@@ -0,0 +1,49 @@
+//! A global allocator counting the allocations, so that the `bench` sub-command can report the allocations of
+//! the derivations. The `bip380` binary installs it, a program using the library may do the same:
+//!
+//! ```
+//! #[global_allocator]
+//! static ALLOCATOR: bip380::alloc_counter::CountingAllocator = bip380::alloc_counter::CountingAllocator;
+//!
+//! let before = bip380::alloc_counter::allocations();
+//! let bytes = vec![0_u8; 32];
+//! assert!(bip380::alloc_counter::allocations() > before);
+//! # drop(bytes);
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator counting the allocations (including the reallocations) it serves.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+/// The number of the allocations served so far, always 0 if the [`CountingAllocator`] is not installed.
+#[must_use]
+pub fn allocations() -> u64 {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
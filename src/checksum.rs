@@ -0,0 +1,53 @@
+//! The BIP 380 descriptor checksum, for the projects which only need the checksum of the script expressions.
+//!
+//! ```
+//! let checksum = bip380::checksum::compute("raw(deadbeef)")?;
+//! assert_eq!(checksum, "89f8spxm");
+//! assert!(bip380::checksum::verify("raw(deadbeef)", &checksum)?);
+//! # Ok::<(), bip380::checksum::ParsingError>(())
+//! ```
+
+use crate::subcommands::utils::checksum::{checksum_check, checksum_create};
+
+pub use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+/// Computes the checksum of the script expression (the part before the `#`).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script contains a character outside of the input charset of BIP 380,
+/// spanning the character.
+pub fn compute(script: &str) -> Result<String, ParsingError> {
+    checksum_create(script)
+}
+
+/// Verifies that the checksum is the checksum of the script expression (the part before the `#`).
+///
+/// Returns `Ok(false)` if the checksum has an incorrect length, contains a character outside of the checksum
+/// charset or does not match the script.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script contains a character outside of the input charset of BIP 380,
+/// spanning the character.
+pub fn verify(script: &str, checksum: &str) -> Result<bool, ParsingError> {
+    checksum_check(script, checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_and_verify() {
+        let script = "pkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)";
+        assert_eq!(compute(script), Ok("8fhd9pwu".to_string()));
+        assert_eq!(verify(script, "8fhd9pwu"), Ok(true));
+        assert_eq!(verify(script, "8fhd9pwv"), Ok(false));
+        assert_eq!(verify(script, "8fhd9pw"), Ok(false));
+        assert_eq!(
+            compute("raw(\u{e9})").map_err(|err| err.span),
+            Err(Some(4..6))
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! The script expressions (descriptors) as a tree of their script functions, with a canonical serialization,
+//! so that fuzzers and property tests can assert the round-trip stability of `Descriptor::parse(s).to_string()`.
+//!
+//! ```
+//! use bip380::descriptor::Descriptor;
+//!
+//! let descriptor = Descriptor::parse("raw( DEAD beef )")?;
+//! assert_eq!(descriptor.to_string(), "raw(deadbeef)#89f8spxm");
+//! assert_eq!(Descriptor::parse(&descriptor.to_string())?, descriptor);
+//! # Ok::<(), bip380::checksum::ParsingError>(())
+//! ```
+
+use std::fmt::{self, Display};
+
+use crate::{
+    structs::{error_kind::ErrorKind, parsing_error::ParsingError, script_limits::ScriptLimits},
+    subcommands::utils::{
+        checksum::{checksum_check, checksum_create, split_checksum, CHECKSUM_DIVIDER_SYMBOL},
+        script_compiler::normalize_hex_case,
+        script_functions::{
+            check_script_limits, find_script_function, parse_script_function, validate_script,
+        },
+    },
+    traits::script_function::ScriptContext,
+};
+
+/// A valid script expression, its script function applied to the arguments.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Descriptor {
+    name: &'static str,
+    args: Vec<Argument>,
+}
+
+/// An argument of a script function.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Argument {
+    /// A nested script expression, e.g. the redeem script of `sh(...)`.
+    Script(Descriptor),
+    /// Any other argument (a key expression, the threshold of `multi` or the payload of `raw`) in its
+    /// canonical form.
+    Value(String),
+}
+
+impl Descriptor {
+    /// Parses and validates the script expression, verifying its checksum if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the script expression is invalid, exceeds the standard limits (see
+    /// [`ScriptLimits`]) or its checksum does not match.
+    pub fn parse(input: &str) -> Result<Self, ParsingError> {
+        let (script, checksum) = split_checksum(input)?;
+        if let Some(checksum) = checksum {
+            if !checksum_check(script, checksum)? {
+                return Err(ParsingError::with_kind(
+                    ErrorKind::ChecksumMismatch,
+                    "checksum verification failed!",
+                ));
+            }
+        }
+
+        validate_script(script, ScriptContext::TopLevel)?;
+        check_script_limits(script, &ScriptLimits::default())?;
+
+        build_descriptor(&canonical_script(script))
+    }
+
+    /// The name of the script function, e.g. `pkh`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The arguments of the script function.
+    #[must_use]
+    pub fn args(&self) -> &[Argument] {
+        &self.args
+    }
+
+    /// The canonical script expression without its checksum.
+    #[must_use]
+    pub fn script(&self) -> String {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                Argument::Script(descriptor) => descriptor.script(),
+                Argument::Value(value) => value.clone(),
+            })
+            .collect();
+        format!("{}({})", self.name, args.join(","))
+    }
+}
+
+/// The canonical form is the script expression without any whitespace, with the hex encoded public keys and
+/// the `raw` payload lowercased, followed by its checksum. Everything else (e.g. the hardened markers of the
+/// key origins) is kept as it was given.
+impl Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let script = self.script();
+        // the script is made of the characters of the validated input, which all have their checksum symbols
+        let checksum = checksum_create(&script).map_err(|_| fmt::Error)?;
+        write!(f, "{script}{CHECKSUM_DIVIDER_SYMBOL}{checksum}")
+    }
+}
+
+/// Removes the whitespace of the (previously validated) script and lowercases its hexadecimal parts.
+fn canonical_script(script: &str) -> String {
+    let script: String = script.chars().filter(|c| !c.is_whitespace()).collect();
+    normalize_hex_case(&script)
+}
+
+fn build_descriptor(script: &str) -> Result<Descriptor, ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+    let args = args
+        .into_iter()
+        .map(|arg| {
+            if arg.contains('(') && find_script_function(&arg).is_some() {
+                Ok(Argument::Script(build_descriptor(&arg)?))
+            } else {
+                Ok(Argument::Value(arg))
+            }
+        })
+        .collect::<Result<Vec<Argument>, ParsingError>>()?;
+
+    Ok(Descriptor {
+        name: function.name(),
+        args,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "03A34B99F22C790C4E36B2B3C2C35A36DB06226E41C692FC82B8B56AC1C540C5BD";
+    const XPUB: &str = "[DEADBEEF/0h/1']xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/1/*";
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = [
+            format!("pk({KEY})"),
+            format!("  pkh( {KEY} )"),
+            format!("multi(1, {KEY},  {XPUB})"),
+            format!("sh( multi(2, {KEY}, {XPUB}, {KEY}) )"),
+            format!("sh(pkh({XPUB}))"),
+            "raw( DEAD beef )".to_string(),
+        ];
+
+        for input in inputs {
+            let descriptor = Descriptor::parse(&input).unwrap();
+            let canonical = descriptor.to_string();
+            let reparsed = Descriptor::parse(&canonical).unwrap();
+            assert_eq!(reparsed, descriptor, "{input}");
+            assert_eq!(reparsed.to_string(), canonical, "{input}");
+            assert!(!canonical.contains(char::is_whitespace), "{canonical}");
+        }
+    }
+
+    #[test]
+    fn test_tree() {
+        let descriptor = Descriptor::parse(&format!("sh(multi(1,{KEY}))")).unwrap();
+        assert_eq!(descriptor.name(), "sh");
+        let [Argument::Script(multi)] = descriptor.args() else {
+            panic!("sh has a single script argument");
+        };
+        assert_eq!(multi.name(), "multi");
+        assert_eq!(
+            multi.args(),
+            [
+                Argument::Value("1".to_string()),
+                Argument::Value(KEY.to_lowercase())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_descriptors() {
+        for input in [
+            "raw(deadbeef)#00000000",
+            "raw(xyz)",
+            "wsh(pk(02aa))",
+            "sh(sh(pk(02aa)))",
+            "",
+        ] {
+            assert!(Descriptor::parse(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_checksum_over_quote_and_backslash() {
+        // the checksums match, so it is the script that is refused, '"' and '\\' being no hex digits
+        for input in ["raw(de\"ad)#slfa7ly5", "raw(de\\ad)#ptvur25v"] {
+            assert_eq!(
+                Descriptor::parse(input).map_err(|err| err.kind),
+                Err(ErrorKind::InvalidCharacter),
+                "{input}"
+            );
+        }
+        for input in ["raw(de\"ad)#ptvur25v", "raw(de\\ad)#slfa7ly5"] {
+            assert_eq!(
+                Descriptor::parse(input).map_err(|err| err.kind),
+                Err(ErrorKind::ChecksumMismatch),
+                "{input}"
+            );
+        }
+    }
+}
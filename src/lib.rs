@@ -1,16 +1,95 @@
-use parsers::arg_parser::{self, Command};
-use subcommands::derive_key::derive_key;
-use subcommands::key_expression::key_expression;
-use subcommands::script_expression::script_expression;
-use utils::info_messages::HELP_MESSAGE;
+// a build leaving out some of the sub-commands (see the features in Cargo.toml) does not use all the shared helpers,
+// and the remaining commands may differ in size a lot
+#![cfg_attr(
+    not(all(
+        feature = "address",
+        feature = "analyze",
+        feature = "coldcard-export",
+        feature = "compose",
+        feature = "convert-key",
+        feature = "demo",
+        feature = "derive-key",
+        feature = "generate-seed",
+        feature = "hwi-import",
+        feature = "key-expression",
+        feature = "lint",
+        feature = "mnemonic",
+        feature = "path",
+        feature = "process",
+        feature = "rpc",
+        feature = "script-expression",
+        feature = "seed-xor",
+        feature = "serve",
+        feature = "slip39",
+        feature = "wallet-export",
+        feature = "wallet-init"
+    )),
+    allow(unused, clippy::large_enum_variant)
+)]
 
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use parsers::arg_parser::{self, Command, Inputs};
+use structs::{
+    batch_stats::{format_duration, BatchStats},
+    global_config::GlobalConfig,
+    output_format::OutputFormat,
+    parsing_error::ParsingError,
+    result_cache::{ResultCache, DEFAULT_CACHE_CAPACITY},
+};
+#[cfg(feature = "analyze")]
+use subcommands::analyze::DescriptorAnalysis;
+#[cfg(feature = "serve")]
+use subcommands::serve::serve;
+use subcommands::utils::json::{parse_json, JsonValue};
+use utils::{
+    info_messages::{secret_argument_warning, HELP_MESSAGE},
+    logging::init_logging,
+    signals::{install_shutdown_handlers, shutdown_exit_code, shutdown_requested},
+};
+
+pub mod alloc_counter;
+pub mod checksum;
+pub mod descriptor;
 mod parsers;
 mod structs;
 mod subcommands;
+pub mod taproot;
 mod test_utils;
 mod traits;
 mod utils;
 
+#[cfg(not(any(
+    feature = "address",
+    feature = "analyze",
+    feature = "bench",
+    feature = "coldcard-export",
+    feature = "compose",
+    feature = "convert-key",
+    feature = "demo",
+    feature = "derive-key",
+    feature = "generate-seed",
+    feature = "hwi-import",
+    feature = "key-expression",
+    feature = "lint",
+    feature = "mnemonic",
+    feature = "path",
+    feature = "process",
+    feature = "rpc",
+    feature = "script-expression",
+    feature = "seed-xor",
+    feature = "serve",
+    feature = "slip39",
+    feature = "wallet-export",
+    feature = "wallet-init"
+)))]
+compile_error!(
+    "At least one of the sub-command features must be enabled, see the features in Cargo.toml"
+);
+
 /// Exit codes
 pub const SUCCESS: i32 = 0;
 pub const FAILURE: i32 = 1;
@@ -31,57 +110,215 @@ pub const FAILURE: i32 = 1;
 /// This function propagates any errors returned by `arg_parser::parse_args` or by subcommands and maps them
 /// to a failure return code.
 pub fn run_cli(args: Vec<&str>) -> Result<(), i32> {
-    let (command, inputs) = arg_parser::parse_args(args).map_err(|err| {
+    // the arguments read from a file are not exposed by the shell history or the process list
+    let secret_argument = arg_parser::find_secret_argument(&args);
+
+    let args = arg_parser::expand_args_files(args).map_err(|err| {
+        eprintln!("{err}");
+        FAILURE
+    })?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let (command, global_config, inputs) = arg_parser::parse_args(args).map_err(|err| {
         eprintln!("{err}");
         FAILURE
     })?;
 
+    init_logging(global_config.log_file.as_deref()).map_err(|err| {
+        eprintln!("{err}");
+        FAILURE
+    })?;
+
+    if let Some(secret_kind) = secret_argument {
+        if command != Command::Help && !global_config.no_secret_warning {
+            eprintln!("{}", secret_argument_warning(secret_kind));
+        }
+    }
+
     match command {
-        Command::KeyExpression(config) => {
-            for input in inputs {
-                match key_expression(input, &config) {
-                    Ok(result) => println!("{result}"),
-                    Err(err) => {
-                        eprintln!("{err}");
-                        return Err(FAILURE);
-                    }
-                }
-            }
+        Command::Help => {
+            println!("{HELP_MESSAGE}");
+            Ok(())
         }
-        Command::ScriptExpression(config) => {
-            for input in inputs {
-                match script_expression(&input, &config) {
-                    Ok(result) => println!("{result}"),
-                    Err(err) => {
-                        eprintln!("{err}");
-                        return Err(FAILURE);
-                    }
-                }
-            }
+        #[cfg(feature = "serve")]
+        Command::Serve(config) => {
+            install_shutdown_handlers();
+            serve(&config).map_err(|err| {
+                eprintln!("{err}");
+                FAILURE
+            })?;
+            shutdown_exit_code().map_or(Ok(()), Err)
+        }
+        #[cfg(feature = "analyze")]
+        Command::Analyze => {
+            install_shutdown_handlers();
+            analyze_inputs(inputs, &global_config)
+        }
+        command => {
+            // the repeated inputs are answered from the cache, unless every input has to be processed
+            // (an import to Bitcoin Core is a side effect, a SLIP-39 split is random)
+            let capacity = if global_config.no_cache
+                || global_config.core_import.is_some()
+                || !command.is_deterministic()
+            {
+                0
+            } else {
+                DEFAULT_CACHE_CAPACITY
+            };
+            let mut cache = ResultCache::new(capacity);
+            install_shutdown_handlers();
+            process_inputs(inputs, &global_config, |input| {
+                cache.get_or_insert_with(input, || command.output(input, &global_config))
+            })
         }
-        Command::DeriveKey(config) => {
-            for input in inputs {
-                match derive_key(&input, &config) {
-                    Ok(result) => println!("{result}"),
-                    Err(err) => {
-                        eprintln!("{err}");
-                        return Err(FAILURE);
-                    }
+    }
+}
+
+/// Runs `process` on every input, printing its result to standard output (flushed right away) and its
+/// error to standard error.
+///
+/// Unless watching or checking, the processing stops at the first error. With `--any`, the errors are reported but
+/// the processing stops at the first success instead, failing only if no input succeeded. When sorting, the results
+/// are buffered and emitted sorted once the processing stops (including the results preceding an error). When
+/// checking, the results are not printed at all. A signal requesting the shutdown stops the processing after the
+/// current input the same way, exiting with the code of the signal. Statistics of the processed inputs are printed to standard error at the end,
+/// if requested.
+fn process_inputs(
+    inputs: Inputs,
+    global_config: &GlobalConfig,
+    mut process: impl FnMut(&str) -> Result<String, ParsingError>,
+) -> Result<(), i32> {
+    let mut sorted_results = Vec::new();
+    let mut outcome = Ok(());
+    let mut stats = BatchStats::start();
+
+    for (index, input) in inputs.enumerate() {
+        let started = Instant::now();
+        let result = tracing::debug_span!("input", index).in_scope(|| process(&input));
+        let elapsed = started.elapsed();
+        let succeeded = result.is_ok();
+        if succeeded {
+            stats.succeeded += 1;
+        } else {
+            stats.failed += 1;
+        }
+
+        let result = if global_config.timing {
+            // the position in the whole input, including the skipped inputs
+            let position = global_config.skip + index + 1;
+            stats.record_time(position, elapsed);
+            if global_config.output != OutputFormat::Json {
+                eprintln!("Input #{position} took {}", format_duration(elapsed));
+            }
+            result.map(|result| match global_config.output {
+                OutputFormat::Json => with_elapsed(&result, elapsed),
+                _ => result,
+            })
+        } else {
+            result
+        };
+        let error_json = |err: &ParsingError| {
+            let json = err.to_json(&input);
+            if global_config.timing {
+                with_elapsed(&json, elapsed)
+            } else {
+                json
+            }
+        };
+
+        match result {
+            Ok(_) if global_config.check => {}
+            Ok(result) if global_config.sort => sorted_results.push(result),
+            Ok(result) => println!("{result}"),
+            // the failed candidates of a search are reported on a single line each
+            Err(err) if global_config.any => match global_config.output {
+                OutputFormat::Json => eprintln!("{}", error_json(&err)),
+                _ => eprintln!("{err}"),
+            },
+            Err(err) => {
+                match global_config.output {
+                    OutputFormat::Json => eprintln!("{}", error_json(&err)),
+                    // a check reports every invalid input on a single line
+                    _ if global_config.check => eprintln!("{err}"),
+                    _ => eprintln!("{}", err.render(&input)),
+                }
+                outcome = Err(FAILURE);
+                // a watched source never ends and a check reports all the invalid inputs
+                if global_config.watch.is_none() && !global_config.check {
+                    break;
                 }
             }
         }
-        Command::Help => {
-            println!("{HELP_MESSAGE}");
+        // a watching consumer expects every result as soon as it is available
+        let _ = io::stdout().flush();
+        if (succeeded && global_config.any) || shutdown_requested() {
+            break;
         }
     }
 
+    sorted_results.sort();
+    for result in sorted_results {
+        println!("{result}");
+    }
+
+    if global_config.stats {
+        stats.finish();
+        eprintln!("{stats}");
+    }
+
+    if let Some(code) = shutdown_exit_code() {
+        return Err(code);
+    }
+    if global_config.any && stats.succeeded == 0 {
+        return Err(FAILURE);
+    }
+    outcome
+}
+
+/// Analyzes all the inputs together (see [`DescriptorAnalysis`]), printing the error of every invalid descriptor
+/// to standard error with its position and the report to standard output once the inputs end (or a signal
+/// requests the shutdown). Fails if any of the descriptors is invalid.
+#[cfg(feature = "analyze")]
+fn analyze_inputs(inputs: Inputs, global_config: &GlobalConfig) -> Result<(), i32> {
+    let mut analysis = DescriptorAnalysis::default();
+    for (index, input) in inputs.enumerate() {
+        if let Err(err) = analysis.add(&input) {
+            // the position in the whole input, including the skipped inputs
+            eprintln!("Input #{}: {err}", global_config.skip + index + 1);
+        }
+        if shutdown_requested() {
+            break;
+        }
+    }
+    println!("{analysis}");
+
+    if let Some(code) = shutdown_exit_code() {
+        return Err(code);
+    }
+    if analysis.invalid > 0 {
+        return Err(FAILURE);
+    }
     Ok(())
 }
 
+/// Appends the processing time of the input (in microseconds) to its JSON object as `elapsed_us`.
+fn with_elapsed(json: &str, elapsed: Duration) -> String {
+    match parse_json(json) {
+        Ok(JsonValue::Object(mut members)) => {
+            members.push((
+                "elapsed_us".to_string(),
+                JsonValue::Number(elapsed.as_micros().to_string()),
+            ));
+            JsonValue::Object(members).to_string()
+        }
+        _ => json.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use std::vec;
+    use std::{time::Duration, vec};
 
     use super::*;
     use crate::test_utils::get_cmd;
@@ -114,4 +351,641 @@ mod tests {
             .success()
             .stdout(expected_help_message);
     }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_watch_keeps_processing_after_error() {
+        let key = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600";
+        let output = get_cmd()
+            .args(vec!["key-expression", "--watch", "-"])
+            .write_stdin(format!("invalid\n{key}\n"))
+            .timeout(Duration::from_secs(2))
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("{key}\n")
+        );
+        assert!(!output.stderr.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "derive-key")]
+    fn test_watch_file() {
+        let path = std::env::temp_dir().join(format!("bip380-watch-{}", std::process::id()));
+        std::fs::write(&path, "000102030405060708090a0b0c0d0e0f\n").unwrap();
+
+        let output = get_cmd()
+            .args(vec!["derive-key", "--watch", path.to_str().unwrap()])
+            .timeout(Duration::from_secs(2))
+            .output()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(String::from_utf8(output.stdout).unwrap().starts_with("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "signals", feature = "derive-key", unix))]
+    fn test_signal_stops_watch() {
+        let path = std::env::temp_dir().join(format!("bip380-signal-{}", std::process::id()));
+        std::fs::write(&path, "000102030405060708090a0b0c0d0e0f\n").unwrap();
+
+        let child =
+            std::process::Command::new(assert_cmd::cargo::cargo_bin(env!("CARGO_PKG_NAME")))
+                .args(["derive-key", "--stats", "--watch", path.to_str().unwrap()])
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                .spawn()
+                .unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+        std::process::Command::new("kill")
+            .args(["-TERM", &child.id().to_string()])
+            .status()
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // the watched file is never exhausted, yet the summary is printed and the exit code tells the signal
+        assert_eq!(output.status.code(), Some(143));
+        assert!(String::from_utf8(output.stdout).unwrap().starts_with("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"));
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("Processed 1 inputs: 1 succeeded, 0 failed in "));
+    }
+
+    #[test]
+    #[cfg(feature = "derive-key")]
+    fn test_sort() {
+        get_cmd()
+            .args(vec!["derive-key", "-", "--sort", "--template", "{fingerprint}"])
+            .write_stdin("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542\n000102030405060708090a0b0c0d0e0f\n")
+            .assert()
+            .success()
+            .stdout("3442193e\nbd16bee5\n");
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_check() {
+        get_cmd()
+            .args(vec!["script-expression", "--check", "-"])
+            .write_stdin("raw(deadbeef)#89f8spxm\nraw(deadbeef)\n")
+            .assert()
+            .success()
+            .stdout("");
+
+        let output = get_cmd()
+            .args(vec![
+                "script-expression",
+                "--check",
+                "--verify-checksum",
+                "-",
+            ])
+            .write_stdin("raw(deadbeef)#00000000\nraw(deadbeef)#89f8spxm\nraw(deadbeef)#11111111\n")
+            .assert()
+            .failure()
+            .get_output()
+            .clone();
+        assert!(output.stdout.is_empty());
+        assert_eq!(String::from_utf8(output.stderr).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_positional_inputs_with_stdin() {
+        let output = get_cmd()
+            .args(vec![
+                "script-expression",
+                "raw(01)",
+                "-",
+                "raw(03)",
+                "--compute-checksum",
+            ])
+            .write_stdin("raw(02)\n")
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        let scripts: Vec<String> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.split('#').next().unwrap().to_string())
+            .collect();
+        assert_eq!(scripts, vec!["raw(01)", "raw(02)", "raw(03)"]);
+
+        get_cmd()
+            .args(vec!["script-expression", "-", "raw(01)", "-"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_skip_and_limit() {
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--skip",
+                "1",
+                "--limit",
+                "2",
+                "-",
+            ])
+            .write_stdin("raw(01)\nraw(02)\n\nraw(03)\nraw(04)\n")
+            .assert()
+            .success()
+            .stdout("raw(02)\nraw(03)\n");
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_stats() {
+        let output = get_cmd()
+            .args(vec!["script-expression", "--stats", "--check", "-"])
+            .write_stdin("raw(01)\nraw(zz)\nraw(02)\n")
+            .assert()
+            .failure()
+            .get_output()
+            .clone();
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("Processed 3 inputs: 2 succeeded, 1 failed in "));
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_timing() {
+        let output = get_cmd()
+            .args(vec![
+                "script-expression",
+                "--timing",
+                "--stats",
+                "--skip",
+                "1",
+                "--output",
+                "json",
+                "-",
+            ])
+            .write_stdin("raw(00)\nraw(01)\nraw(02)\n")
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), 2);
+        for line in stdout.lines() {
+            let json = parse_json(line).unwrap();
+            assert!(matches!(json.get("elapsed_us"), Some(JsonValue::Number(_))));
+        }
+
+        // the positions of the slowest inputs count the skipped ones
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let slowest = stderr.lines().last().unwrap();
+        assert!(slowest.starts_with("Slowest inputs: #"));
+        assert!(slowest.contains("#2 (") && slowest.contains("#3 ("));
+        assert!(!slowest.contains("#1 ("));
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_args_file() {
+        let path = std::env::temp_dir().join(format!("bip380-args-file-{}", std::process::id()));
+        std::fs::write(
+            &path,
+            "--key\nkey=0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600\n",
+        )
+        .unwrap();
+
+        let script = "pkh(0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600)";
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--args-file",
+                path.to_str().unwrap(),
+                "pkh($key)",
+            ])
+            .assert()
+            .success()
+            .stdout(format!("{script}#{}\n", checksum::compute(script).unwrap()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "script-expression", feature = "slip39"))]
+    fn test_repeated_inputs() {
+        for cache_flag in [None, Some("--no-cache")] {
+            // the cached errors are reported again for every repeated input
+            let output = get_cmd()
+                .args(["script-expression", "--check", "--stats", "-"])
+                .args(cache_flag)
+                .write_stdin("raw(01)\nraw(zz)\nraw(01)\nraw(zz)\n")
+                .assert()
+                .failure()
+                .get_output()
+                .clone();
+
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            let lines: Vec<&str> = stderr.lines().collect();
+            assert_eq!(lines.len(), 3);
+            assert_eq!(lines[0], lines[1]);
+            assert!(lines[2].starts_with("Processed 4 inputs: 2 succeeded, 2 failed in "));
+        }
+
+        // the shares of a split are random, so a repeated secret is never answered from the cache
+        let output = get_cmd()
+            .args([
+                "slip39",
+                "--split",
+                "--group",
+                "1of1",
+                "--no-secret-warning",
+                "-",
+            ])
+            .write_stdin("000102030405060708090a0b0c0d0e0f\n000102030405060708090a0b0c0d0e0f\n")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let shares: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+        assert_eq!(shares.len(), 2);
+        assert_ne!(shares[0], shares[1]);
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_files() {
+        let dir = std::env::temp_dir();
+        let first = dir.join(format!("bip380-files-1-{}", std::process::id()));
+        let second = dir.join(format!("bip380-files-2-{}", std::process::id()));
+        std::fs::write(&first, "raw(01)\n\nraw(02)\n").unwrap();
+        std::fs::write(&second, "raw(03)").unwrap();
+
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--input-file",
+                first.to_str().unwrap(),
+                "--input-file",
+                "-",
+                "--input-file",
+                second.to_str().unwrap(),
+            ])
+            .write_stdin("raw(ff)\n")
+            .assert()
+            .success()
+            .stdout("raw(01)\nraw(02)\nraw(ff)\nraw(03)\n");
+
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--input-file",
+                first.to_str().unwrap(),
+                "--input-file",
+                "/nonexistent/input/file",
+            ])
+            .assert()
+            .failure()
+            .stdout("");
+
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--input-file",
+                first.to_str().unwrap(),
+                "raw(04)",
+            ])
+            .assert()
+            .failure()
+            .stderr(
+                "Parsing error [E000]: use only the input arguments or '--input-file', not both\n",
+            );
+
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "script-expression", feature = "gzip", feature = "zstd"))]
+    fn test_compressed_input_files() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir();
+        let gzip = dir.join(format!("bip380-input-{}.txt.gz", std::process::id()));
+        let zstd = dir.join(format!("bip380-input-{}.txt.zst", std::process::id()));
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"raw(01)\nraw(02)\n").unwrap();
+        std::fs::write(&gzip, encoder.finish().unwrap()).unwrap();
+        std::fs::write(&zstd, zstd::encode_all(&b"raw(03)\n"[..], 0).unwrap()).unwrap();
+
+        // decompressed in the process, no gzip or zstd needs to be installed
+        get_cmd()
+            .env("PATH", "")
+            .args(vec![
+                "script-expression",
+                "--input-file",
+                gzip.to_str().unwrap(),
+                "--input-file",
+                zstd.to_str().unwrap(),
+            ])
+            .assert()
+            .success()
+            .stdout("raw(01)\nraw(02)\nraw(03)\n");
+
+        std::fs::remove_file(gzip).unwrap();
+        std::fs::remove_file(zstd).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "derive-key", feature = "key-expression"))]
+    fn test_log_file() {
+        let log_file = std::env::temp_dir().join(format!("bip380-log-{}", std::process::id()));
+        let _ = std::fs::remove_file(&log_file);
+
+        get_cmd()
+            .env(utils::logging::LOG_ENV_VAR, "debug")
+            .args(vec![
+                "derive-key",
+                "000102030405060708090a0b0c0d0e0f",
+                "--log-file",
+                log_file.to_str().unwrap(),
+                "--no-secret-warning",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+
+        let logs = std::fs::read_to_string(&log_file).unwrap();
+        assert!(logs.contains("derive_extended_keys"));
+        assert!(!logs.contains("000102030405060708090a0b0c0d0e0f"));
+        std::fs::remove_file(log_file).unwrap();
+
+        get_cmd()
+            .env(utils::logging::LOG_ENV_VAR, "[[")
+            .args(vec!["key-expression", "invalid"])
+            .assert()
+            .failure()
+            .stdout("");
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_error_span() {
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--verify-checksum",
+                "raw(deadbeef)#00000000",
+            ])
+            .assert()
+            .failure()
+            .stderr(
+                "Parsing error [E001]: checksum verification failed!\n  raw(deadbeef)#00000000\n                ^^^^^^^^\n",
+            );
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "hwi-import",
+        feature = "mnemonic",
+        feature = "rpc",
+        feature = "seed-xor",
+        feature = "slip39"
+    ))]
+    fn test_seed_share_and_json_error_codes() {
+        // none of the errors of the seeds, the shares and the JSON inputs falls back to E000
+        for (args, stdin) in [
+            (vec!["mnemonic", "--from-entropy", "00"], ""),
+            (vec!["mnemonic", "--from-seedqr", "123"], ""),
+            (vec!["seed-xor", "--split", "2", "0"], ""),
+            (vec!["seed-xor", "--combine", "-"], "abandon abandon\n"),
+            (vec!["slip39", "--split", "--group", "2of3", "0"], ""),
+            (vec!["slip39", "--combine", "foo bar"], ""),
+            (vec!["hwi-import", "-"], "nope\n"),
+            (vec!["hwi-import", "-"], "[]\n"),
+        ] {
+            let output = get_cmd().args(&args).write_stdin(stdin).assert().failure();
+            let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+            assert!(stderr.contains("Parsing error [E0"), "{args:?}: {stderr}");
+            assert!(!stderr.contains("[E000]"), "{args:?}: {stderr}");
+        }
+
+        for request in [
+            "nope",
+            "[1]",
+            r#"{"cmd": "mnemonic", "input": "00", "flags": 1}"#,
+        ] {
+            let output = get_cmd()
+                .arg("--rpc")
+                .write_stdin(format!("{request}\n"))
+                .assert()
+                .success();
+            let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+            assert!(stdout.contains(r#""code":"E0"#), "{request}: {stdout}");
+            assert!(!stdout.contains(r#""code":"E000""#), "{request}: {stdout}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_output_json() {
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--verify-checksum",
+                "--output",
+                "json",
+                "raw(deadbeef)#89f8spxm",
+                "raw(deadbeef)#00000000",
+            ])
+            .assert()
+            .failure()
+            .stdout(concat!(
+                r#"{"script":"raw(deadbeef)","checksum":"89f8spxm","output":"Veritification of the 'raw(deadbeef)#89f8spxm' script succeeded!"}"#,
+                "\n"
+            ))
+            .stderr(concat!(
+                r#"{"input":"raw(deadbeef)#00000000","kind":"ChecksumMismatch","code":"E001","message":"checksum verification failed!","span":[14,22]}"#,
+                "\n"
+            ));
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_output_json_select() {
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--verify-checksum",
+                "--output",
+                "json",
+                "--select",
+                "checksum,script",
+                "raw(deadbeef)#89f8spxm",
+            ])
+            .assert()
+            .success()
+            .stdout(concat!(
+                r#"{"checksum":"89f8spxm","script":"raw(deadbeef)"}"#,
+                "\n"
+            ));
+
+        get_cmd()
+            .args(vec![
+                "script-expression",
+                "--output",
+                "json",
+                "--select",
+                "xpub",
+                "raw(deadbeef)",
+            ])
+            .assert()
+            .failure()
+            .stderr(concat!(
+                r#"{"input":"raw(deadbeef)","kind":"InvalidInput","code":"E000","message":"Unknown field 'xpub' of '--select', the available fields are: script, checksum, output"}"#,
+                "\n"
+            ));
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_any() {
+        get_cmd()
+            .args(vec!["script-expression", "--verify-checksum", "--any", "-"])
+            .write_stdin("raw(deadbeef)#00000000\nraw(deadbeef)#89f8spxm\nraw(deadbeef)#11111111\n")
+            .assert()
+            .success()
+            .stdout("Veritification of the 'raw(deadbeef)#89f8spxm' script succeeded!\n")
+            .stderr("Parsing error [E001]: checksum verification failed!\n");
+
+        get_cmd()
+            .args(vec!["script-expression", "--verify-checksum", "--any", "--check", "-"])
+            .write_stdin("raw(deadbeef)#00000000\nraw(deadbeef)#11111111\n")
+            .assert()
+            .failure()
+            .stderr("Parsing error [E001]: checksum verification failed!\nParsing error [E001]: checksum verification failed!\n");
+    }
+
+    #[test]
+    #[cfg(feature = "analyze")]
+    fn test_analyze() {
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        get_cmd()
+            .args(vec!["analyze", "-"])
+            .write_stdin(format!(
+                "pk([deadbeef/0h]{key})\nsh(multi(1,{key},{key}))\nraw(deadbeef)#89f8spxm\n"
+            ))
+            .assert()
+            .success()
+            .stdout("Analyzed 3 descriptors, 0 invalid\nScript types:\n  pk: 1\n  raw: 1\n  sh(multi): 1\nFingerprints:\n  deadbeef: 1\nExtended public keys: none\nLargest multisig: 1 of 2 keys\n");
+
+        get_cmd()
+            .args(vec!["analyze", "raw(deadbeef)#00000000", "raw(deadbeef)"])
+            .assert()
+            .failure()
+            .stdout("Analyzed 2 descriptors, 1 invalid\nScript types:\n  raw: 1\nFingerprints: none\nExtended public keys: none\nLargest multisig: none\n")
+            .stderr("Input #1: Parsing error [E001]: checksum verification failed!\n");
+    }
+
+    #[test]
+    #[cfg(feature = "derive-key")]
+    fn test_secret_argument_warning() {
+        get_cmd()
+            .args(vec!["derive-key", "000102030405060708090a0b0c0d0e0f"])
+            .assert()
+            .success()
+            .stderr(format!(
+                "{}\n",
+                secret_argument_warning(structs::secret_kind::SecretKind::Seed)
+            ));
+
+        get_cmd()
+            .args(vec![
+                "derive-key",
+                "000102030405060708090a0b0c0d0e0f",
+                "--no-secret-warning",
+            ])
+            .assert()
+            .success()
+            .stderr("");
+
+        get_cmd()
+            .args(vec!["derive-key", "-"])
+            .write_stdin("000102030405060708090a0b0c0d0e0f\n")
+            .assert()
+            .success()
+            .stderr("");
+    }
+
+    #[test]
+    #[cfg(feature = "address")]
+    fn test_x_only_key_argument_no_warning() {
+        // the x-only public key has the length of a seed, but address does not take seeds
+        get_cmd()
+            .args(vec![
+                "address",
+                "--type",
+                "p2tr",
+                "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+            ])
+            .assert()
+            .success()
+            .stdout("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr\n")
+            .stderr("");
+    }
+
+    #[test]
+    #[cfg(all(feature = "derive-key", feature = "key-expression"))]
+    fn test_no_private() {
+        get_cmd()
+            .args(vec![
+                "derive-key",
+                "--no-private",
+                "--no-secret-warning",
+                "000102030405060708090a0b0c0d0e0f",
+            ])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(format!(
+                "{}\n",
+                ParsingError::with_kind(
+                    structs::error_kind::ErrorKind::PrivateOutput,
+                    &utils::error_messages::private_output_err(
+                        structs::secret_kind::SecretKind::ExtendedPrivateKey
+                    )
+                )
+            ));
+
+        get_cmd()
+            .args(vec![
+                "derive-key",
+                "--no-private",
+                "--no-secret-warning",
+                "--template",
+                "{xpub}",
+                "000102030405060708090a0b0c0d0e0f",
+            ])
+            .assert()
+            .success()
+            .stdout("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8\n");
+
+        get_cmd()
+            .args(vec![
+                "key-expression",
+                "--no-private",
+                "--no-secret-warning",
+                "L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1",
+            ])
+            .assert()
+            .failure()
+            .stdout("");
+    }
 }
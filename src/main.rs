@@ -1,7 +1,45 @@
+// a build leaving out some of the sub-commands (see the features in Cargo.toml) does not use all the shared helpers,
+// and the remaining commands may differ in size a lot
+#![cfg_attr(
+    not(all(
+        feature = "address",
+        feature = "analyze",
+        feature = "coldcard-export",
+        feature = "compose",
+        feature = "convert-key",
+        feature = "demo",
+        feature = "derive-key",
+        feature = "generate-seed",
+        feature = "hwi-import",
+        feature = "key-expression",
+        feature = "lint",
+        feature = "mnemonic",
+        feature = "path",
+        feature = "process",
+        feature = "rpc",
+        feature = "script-expression",
+        feature = "seed-xor",
+        feature = "serve",
+        feature = "slip39",
+        feature = "wallet-export",
+        feature = "wallet-init"
+    )),
+    allow(unused, clippy::large_enum_variant)
+)]
+
 use std::{env, ffi::OsString};
 
 use bip380::run_cli;
 
+// the bin re-declares the modules of the library, the copy of bench finds the allocation counter here
+#[cfg(feature = "bench")]
+use bip380::alloc_counter::{self, CountingAllocator};
+
+/// Counts the allocations for the bench sub-command.
+#[cfg(feature = "bench")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 pub mod parsers;
 pub mod structs;
 pub mod subcommands;
@@ -1,93 +1,816 @@
-use std::io::{stdin, BufRead, BufReader};
+use std::{
+    fs::File,
+    io::{stdin, BufRead, BufReader},
+};
 
+#[cfg(feature = "serve")]
+use crate::structs::serve_config::ServeConfig;
+#[cfg(feature = "rpc")]
+use crate::subcommands::rpc::handle_rpc_request;
+#[cfg(feature = "address")]
+use crate::{
+    structs::address_config::AddressConfig,
+    subcommands::address::{address, address_fields},
+};
+#[cfg(feature = "bench")]
+use crate::{
+    structs::bench_config::BenchConfig,
+    subcommands::bench::{bench, bench_fields},
+};
+#[cfg(feature = "coldcard-export")]
+use crate::{
+    structs::coldcard_export_config::ColdcardExportConfig,
+    subcommands::coldcard_export::{coldcard_export, coldcard_export_fields},
+};
+#[cfg(feature = "compose")]
+use crate::{
+    structs::compose_config::ComposeConfig,
+    subcommands::compose::{compose, compose_fields},
+};
+#[cfg(feature = "convert-key")]
+use crate::{
+    structs::convert_key_config::ConvertKeyConfig,
+    subcommands::convert_key::{convert_key, convert_key_fields},
+};
+#[cfg(feature = "demo")]
+use crate::{
+    structs::demo_config::DemoConfig,
+    subcommands::demo::{demo, demo_fields},
+};
+#[cfg(feature = "derive-key")]
+use crate::{
+    structs::derive_key_config::DeriveKeyConfig,
+    subcommands::derive_key::{derive_extended_keys, derive_key},
+};
+#[cfg(feature = "generate-seed")]
+use crate::{
+    structs::generate_seed_config::GenerateSeedConfig,
+    subcommands::generate_seed::{generate_seed, generate_seed_fields},
+};
+#[cfg(feature = "hwi-import")]
+use crate::{
+    structs::hwi_import_config::HwiImportConfig,
+    subcommands::hwi_import::{hwi_import, hwi_import_fields},
+};
+#[cfg(feature = "key-expression")]
+use crate::{
+    structs::key_expression_config::KeyExpressionConfig,
+    subcommands::key_expression::{key_expression, key_expression_fields},
+};
+#[cfg(feature = "lint")]
+use crate::{
+    structs::lint_config::LintConfig,
+    subcommands::lint::{lint, lint_fields},
+};
+#[cfg(feature = "mnemonic")]
+use crate::{
+    structs::mnemonic_config::MnemonicConfig,
+    subcommands::mnemonic::{mnemonic, mnemonic_fields},
+};
+#[cfg(feature = "path")]
+use crate::{
+    structs::path_config::PathConfig,
+    subcommands::path::{derivation_path, derivation_path_fields},
+};
+#[cfg(feature = "process")]
+use crate::{
+    structs::process_config::ProcessConfig,
+    subcommands::process::{process, process_fields},
+};
+#[cfg(feature = "script-expression")]
+use crate::{
+    structs::script_expression_config::{ScriptExpressionConfig, ScriptOutput},
+    subcommands::script_expression::{script_expression, script_expression_fields},
+};
+#[cfg(feature = "seed-xor")]
+use crate::{
+    structs::seed_xor_config::{SeedXorConfig, SeedXorMode},
+    subcommands::seed_xor::{seed_xor, seed_xor_fields},
+};
+#[cfg(feature = "slip39")]
+use crate::{
+    structs::slip39_config::{Slip39Config, Slip39Mode},
+    subcommands::slip39::{slip39, slip39_fields},
+};
+#[cfg(feature = "wallet-export")]
+use crate::{
+    structs::wallet_export_config::WalletExportConfig, subcommands::wallet_export::wallet_export,
+};
+#[cfg(feature = "wallet-init")]
+use crate::{
+    structs::wallet_init_config::WalletInitConfig,
+    subcommands::wallet_init::{wallet_account, wallet_init},
+};
 use crate::{
     structs::{
-        derive_key_config::DeriveKeyConfig, key_expression_config::KeyExpressionConfig,
-        parsing_error::ParsingError, script_expression_config::ScriptExpressionConfig,
+        error_kind::ErrorKind, global_config::GlobalConfig, output_format::OutputFormat,
+        parsing_error::ParsingError, secret_kind::SecretKind,
+    },
+    subcommands::{
+        core_import::{import_descriptors, DescriptorImport},
+        utils::{
+            output_encoders::output_encoder,
+            output_template::render_template,
+            secret::{classify_secret, find_private_key},
+            ur::encode_ur,
+            ur_registry::{crypto_hdkey, crypto_output},
+        },
     },
+    traits::output_fields::OutputFields,
     traits::parsable::Parsable,
-    utils::error_messages::{MISSING_ARG_ERR_MSG, MISSING_INPUT_ERR_MSG},
+    utils::error_messages::{
+        missing_follow_up_val_err, private_output_err, unavailable_command_err,
+        unexpected_positional_arg_err, CORE_IMPORT_COMMAND_ERR_MSG, MISSING_ARG_ERR_MSG,
+        MISSING_INPUT_ERR_MSG, REPEATED_STDIN_ERR_MSG, UR_COMMAND_ERR_MSG,
+    },
     FAILURE,
 };
 
+use super::{
+    decompress_reader::{compression_of, decompress_reader},
+    watch_reader::watch_inputs,
+};
+
+/// The sub-commands (and their features) that can be compiled out, see `Cargo.toml`.
+const FEATURE_COMMANDS: [(&str, &str); 22] = [
+    ("convert-key", "convert-key"),
+    ("derive-key", "derive-key"),
+    ("key-expression", "key-expression"),
+    ("script-expression", "script-expression"),
+    ("wallet-init", "wallet-init"),
+    ("path", "path"),
+    ("mnemonic", "mnemonic"),
+    ("generate-seed", "generate-seed"),
+    ("slip39", "slip39"),
+    ("seed-xor", "seed-xor"),
+    ("coldcard-export", "coldcard-export"),
+    ("wallet-export", "wallet-export"),
+    ("process", "process"),
+    ("address", "address"),
+    ("lint", "lint"),
+    ("bench", "bench"),
+    ("demo", "demo"),
+    ("compose", "compose"),
+    ("hwi-import", "hwi-import"),
+    ("analyze", "analyze"),
+    ("--rpc", "rpc"),
+    ("serve", "serve"),
+];
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Command {
     Help,
+    #[cfg(feature = "convert-key")]
+    ConvertKey(ConvertKeyConfig),
+    #[cfg(feature = "derive-key")]
     DeriveKey(DeriveKeyConfig),
+    #[cfg(feature = "key-expression")]
     KeyExpression(KeyExpressionConfig),
+    #[cfg(feature = "script-expression")]
     ScriptExpression(ScriptExpressionConfig),
+    #[cfg(feature = "wallet-init")]
+    WalletInit(WalletInitConfig),
+    #[cfg(feature = "path")]
+    Path(PathConfig),
+    #[cfg(feature = "mnemonic")]
+    Mnemonic(MnemonicConfig),
+    #[cfg(feature = "generate-seed")]
+    GenerateSeed(GenerateSeedConfig),
+    #[cfg(feature = "slip39")]
+    Slip39(Slip39Config),
+    #[cfg(feature = "seed-xor")]
+    SeedXor(SeedXorConfig),
+    #[cfg(feature = "coldcard-export")]
+    ColdcardExport(ColdcardExportConfig),
+    #[cfg(feature = "wallet-export")]
+    WalletExport(WalletExportConfig),
+    #[cfg(feature = "process")]
+    Process(ProcessConfig),
+    #[cfg(feature = "address")]
+    Address(AddressConfig),
+    #[cfg(feature = "lint")]
+    Lint(LintConfig),
+    #[cfg(feature = "bench")]
+    Bench(BenchConfig),
+    #[cfg(feature = "demo")]
+    Demo(DemoConfig),
+    #[cfg(feature = "compose")]
+    Compose(ComposeConfig),
+    #[cfg(feature = "hwi-import")]
+    HwiImport(HwiImportConfig),
+    #[cfg(feature = "analyze")]
+    Analyze,
+    #[cfg(feature = "serve")]
+    Serve(ServeConfig),
+    #[cfg(feature = "rpc")]
+    Rpc,
+}
+
+impl Command {
+    /// Returns whether the output of an input is the same every time it is processed, so the results of the
+    /// repeated inputs can be cached. Splitting a SLIP-39 secret or a `SeedXOR` seed draws random shares (also
+    /// through an RPC request) and a benchmark measures the time it takes.
+    #[must_use]
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            #[cfg(feature = "slip39")]
+            Command::Slip39(config) => config.mode == Slip39Mode::Combine,
+            #[cfg(feature = "seed-xor")]
+            Command::SeedXor(config) => config.mode == SeedXorMode::Combine,
+            #[cfg(feature = "rpc")]
+            Command::Rpc => false,
+            #[cfg(feature = "bench")]
+            Command::Bench(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Runs the sub-command on a single input, returning its output.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the sub-command fails on the input, or if the command does not
+    /// process inputs (help and serve).
+    pub fn run(&self, input: &str) -> Result<String, ParsingError> {
+        match self {
+            #[cfg(feature = "convert-key")]
+            Command::ConvertKey(config) => convert_key(input, config),
+            #[cfg(feature = "derive-key")]
+            Command::DeriveKey(config) => derive_key(input, config),
+            #[cfg(feature = "key-expression")]
+            Command::KeyExpression(config) => key_expression(input.to_string(), config),
+            #[cfg(feature = "script-expression")]
+            Command::ScriptExpression(config) => script_expression(input, config),
+            #[cfg(feature = "wallet-init")]
+            Command::WalletInit(config) => wallet_init(input, config),
+            #[cfg(feature = "path")]
+            Command::Path(config) => derivation_path(input, config),
+            #[cfg(feature = "mnemonic")]
+            Command::Mnemonic(config) => mnemonic(input, config),
+            #[cfg(feature = "generate-seed")]
+            Command::GenerateSeed(config) => generate_seed(input, config),
+            #[cfg(feature = "slip39")]
+            Command::Slip39(config) => slip39(input, config),
+            #[cfg(feature = "seed-xor")]
+            Command::SeedXor(config) => seed_xor(input, config),
+            #[cfg(feature = "coldcard-export")]
+            Command::ColdcardExport(config) => coldcard_export(input, config),
+            #[cfg(feature = "wallet-export")]
+            Command::WalletExport(config) => wallet_export(input, config),
+            #[cfg(feature = "process")]
+            Command::Process(config) => process(input, config),
+            #[cfg(feature = "address")]
+            Command::Address(config) => address(input, config),
+            #[cfg(feature = "lint")]
+            Command::Lint(config) => lint(input, config),
+            #[cfg(feature = "bench")]
+            Command::Bench(config) => bench(input, config),
+            #[cfg(feature = "demo")]
+            Command::Demo(config) => demo(input, config),
+            #[cfg(feature = "compose")]
+            Command::Compose(config) => compose(input, config),
+            #[cfg(feature = "hwi-import")]
+            Command::HwiImport(config) => hwi_import(input, config),
+            #[cfg(feature = "rpc")]
+            Command::Rpc => Ok(handle_rpc_request(input)),
+            _ => Err(ParsingError::new("The command does not process any inputs")),
+        }
+    }
+
+    /// Runs the sub-command on a single input, returning the named fields of its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the sub-command fails on the input, or if the command has no
+    /// fields to format (help, serve, RPC and wallet-export).
+    pub fn fields(&self, input: &str) -> Result<Vec<(&'static str, String)>, ParsingError> {
+        match self {
+            #[cfg(feature = "convert-key")]
+            Command::ConvertKey(config) => Ok(convert_key_fields(input, config)?.fields()),
+            #[cfg(feature = "derive-key")]
+            Command::DeriveKey(config) => Ok(derive_extended_keys(input, config)?.fields()),
+            #[cfg(feature = "key-expression")]
+            Command::KeyExpression(config) => key_expression_fields(input, config),
+            #[cfg(feature = "script-expression")]
+            Command::ScriptExpression(config) => script_expression_fields(input, config),
+            #[cfg(feature = "wallet-init")]
+            Command::WalletInit(config) => Ok(wallet_account(input, config)?.fields()),
+            #[cfg(feature = "path")]
+            Command::Path(config) => derivation_path_fields(input, config),
+            #[cfg(feature = "mnemonic")]
+            Command::Mnemonic(config) => mnemonic_fields(input, config),
+            #[cfg(feature = "generate-seed")]
+            Command::GenerateSeed(config) => generate_seed_fields(input, config),
+            #[cfg(feature = "slip39")]
+            Command::Slip39(config) => slip39_fields(input, config),
+            #[cfg(feature = "seed-xor")]
+            Command::SeedXor(config) => seed_xor_fields(input, config),
+            #[cfg(feature = "coldcard-export")]
+            Command::ColdcardExport(config) => coldcard_export_fields(input, config),
+            #[cfg(feature = "process")]
+            Command::Process(config) => process_fields(input, config),
+            #[cfg(feature = "address")]
+            Command::Address(config) => address_fields(input, config),
+            #[cfg(feature = "lint")]
+            Command::Lint(config) => lint_fields(input, config),
+            #[cfg(feature = "bench")]
+            Command::Bench(config) => bench_fields(input, config),
+            #[cfg(feature = "demo")]
+            Command::Demo(config) => demo_fields(input, config),
+            #[cfg(feature = "compose")]
+            Command::Compose(config) => compose_fields(input, config),
+            #[cfg(feature = "hwi-import")]
+            Command::HwiImport(config) => hwi_import_fields(input, config),
+            _ => Err(ParsingError::new(
+                "The command does not support '--template', '--output json' or '--output csv'",
+            )),
+        }
+    }
+
+    /// Runs the sub-command on a single input, returning its output formatted with the template, if any,
+    /// or encoded by the [`OutputEncoder`](crate::traits::output_encoder::OutputEncoder) of the output format.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the sub-command fails on the input, the template cannot be rendered,
+    /// the output contains private material refused by the configuration or the import of its descriptors
+    /// to Bitcoin Core fails.
+    pub fn output(
+        &self,
+        input: &str,
+        global_config: &GlobalConfig,
+    ) -> Result<String, ParsingError> {
+        let output = match &global_config.template {
+            Some(template) => render_template(template, &self.fields(input)?),
+            None => output_encoder(global_config).encode(self, input),
+        }?;
+
+        // the decoded fields of an extended private key contain the private key in hexadecimal
+        let private_input = match self {
+            #[cfg(feature = "convert-key")]
+            Command::ConvertKey(_) => find_private_key(input),
+            _ => None,
+        };
+        if let Some(secret_kind) = find_private_key(&output).or(private_input) {
+            if global_config.no_private {
+                return Err(ParsingError::with_kind(
+                    ErrorKind::PrivateOutput,
+                    &private_output_err(secret_kind),
+                ));
+            }
+        }
+
+        if let Some(core_import) = &global_config.core_import {
+            import_descriptors(core_import, &self.descriptor_imports(input)?)?;
+        }
+
+        Ok(output)
+    }
+
+    /// Encodes the result as a uniform resource, one part per line: the script expression as `crypto-output`,
+    /// the key expression or the derived extended public key as `crypto-hdkey`.
+    pub(crate) fn uniform_resource(
+        &self,
+        input: &str,
+        max_fragment_length: usize,
+    ) -> Result<String, ParsingError> {
+        let fields = self.fields(input)?;
+        let (ur_type, message): (&str, Vec<u8>) = match self {
+            #[cfg(feature = "script-expression")]
+            Command::ScriptExpression(config) if config.output == ScriptOutput::Expression => {
+                ("crypto-output", crypto_output(&field(&fields, "script"))?)
+            }
+            #[cfg(feature = "key-expression")]
+            Command::KeyExpression(_) => {
+                ("crypto-hdkey", crypto_hdkey(&field(&fields, "expression"))?)
+            }
+            #[cfg(feature = "derive-key")]
+            Command::DeriveKey(_) => ("crypto-hdkey", crypto_hdkey(&field(&fields, "xpub"))?),
+            _ => return Err(ParsingError::new(UR_COMMAND_ERR_MSG)),
+        };
+
+        Ok(encode_ur(ur_type, &message, max_fragment_length).join("\n"))
+    }
+
+    /// Returns the descriptors of the result to import to Bitcoin Core: the script expression (which must
+    /// have a checksum), or the active receive and change descriptors of the wallet account.
+    fn descriptor_imports(&self, input: &str) -> Result<Vec<DescriptorImport>, ParsingError> {
+        let fields = self.fields(input)?;
+        let field = |name: &str| field(&fields, name);
+
+        match self {
+            #[cfg(feature = "script-expression")]
+            Command::ScriptExpression(_) => {
+                let checksum = field("checksum");
+                if checksum.is_empty() {
+                    return Err(ParsingError::new(
+                        "The script expression must have a checksum to be imported to Bitcoin Core, see '--compute-checksum'",
+                    ));
+                }
+                Ok(vec![DescriptorImport {
+                    descriptor: format!("{}#{checksum}", field("script")),
+                    internal: None,
+                }])
+            }
+            #[cfg(feature = "wallet-init")]
+            Command::WalletInit(_) => Ok(vec![
+                DescriptorImport {
+                    descriptor: field("receive"),
+                    internal: Some(false),
+                },
+                DescriptorImport {
+                    descriptor: field("change"),
+                    internal: Some(true),
+                },
+            ]),
+            _ => Err(ParsingError::new(CORE_IMPORT_COMMAND_ERR_MSG)),
+        }
+    }
+}
+
+/// Returns the value of the named field, empty if there is no such field.
+fn field(fields: &[(&'static str, String)], name: &str) -> String {
+    fields
+        .iter()
+        .find(|(field_name, _)| *field_name == name)
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default()
 }
 
 pub type Inputs = Box<dyn Iterator<Item = String>>;
 
+/// The prefix of an argument naming a file whose content is a single input, e.g. `@descriptor.txt`.
+pub(crate) const INPUT_FILE_PREFIX: char = '@';
+
 /// Get the inputs for the sub-command
-/// The inputs are read from stdin if the '-' argument is present in args
-/// Otherwise, the argument right after the sub-command is the input
-/// Only the argument immediately following the sub-command is used as input.
-/// Additional flags or arguments (e.g., --foo) are not considered.
-fn get_inputs(args: &Vec<&str>) -> Result<Inputs, ParsingError> {
-    // if '-' is present in args, we should read from stdin
-    if args.contains(&"-") {
-        Ok(Box::new(
-            BufReader::new(stdin())
-                .lines()
-                .map(|line| {
-                    line.unwrap_or_else(|e| {
-                        eprintln!("Error reading from stdin: {e}");
-                        std::process::exit(FAILURE);
-                    })
+/// The inputs are followed indefinitely if the watch source is configured
+/// The inputs are read line by line from the files of `--input-file`, if any, instead of the arguments
+/// Otherwise, all the arguments following the sub-command are the inputs, processed in order, where
+/// the '-' argument stands for the lines read from stdin at its position (and can be given only once)
+/// and an '@path' argument for the whole content of the file (trimmed) as a single input.
+fn get_inputs(args: &[&str], global_config: &GlobalConfig) -> Result<Inputs, ParsingError> {
+    if let Some(source) = &global_config.watch {
+        return watch_inputs(source);
+    }
+
+    let positionals = &args[1..];
+    if !global_config.input_files.is_empty() {
+        if !positionals.is_empty() {
+            return Err(ParsingError::new(
+                "use only the input arguments or '--input-file', not both",
+            ));
+        }
+        return file_inputs(&global_config.input_files);
+    }
+
+    if positionals.is_empty() {
+        return Err(ParsingError::new(MISSING_INPUT_ERR_MSG));
+    }
+    if positionals.iter().filter(|arg| **arg == "-").count() > 1 {
+        return Err(ParsingError::new(REPEATED_STDIN_ERR_MSG));
+    }
+
+    let mut inputs: Inputs = Box::new(std::iter::empty());
+    for arg in positionals {
+        let input: Inputs = if *arg == "-" {
+            read_lines(BufReader::new(stdin()), "stdin")
+        } else if let Some(path) = arg.strip_prefix(INPUT_FILE_PREFIX) {
+            Box::new(std::iter::once(read_input_file(path)?))
+        } else {
+            Box::new(std::iter::once((*arg).to_string()))
+        };
+        inputs = Box::new(inputs.chain(input));
+    }
+
+    Ok(inputs)
+}
+
+/// Reads the whole content of the file (trimmed) as a single input, for an `@path` argument.
+/// The file is read upfront, so a missing file is reported before any input is processed.
+fn read_input_file(path: &str) -> Result<String, ParsingError> {
+    std::fs::read_to_string(path)
+        .map(|content| content.trim().to_string())
+        .map_err(|e| ParsingError::new(&format!("Could not read the input file '{path}': {e}")))
+}
+
+/// Reads the non-empty lines of the files one after another, '-' standing for stdin.
+/// All the files are opened upfront, so a missing file is reported before any input is processed.
+/// The `.gz` and `.zst` files are decompressed (see [`decompress_reader`]) while their lines are read.
+fn file_inputs(paths: &[String]) -> Result<Inputs, ParsingError> {
+    let mut inputs: Inputs = Box::new(std::iter::empty());
+    for path in paths {
+        let lines = if path == "-" {
+            read_lines(BufReader::new(stdin()), "stdin")
+        } else {
+            let file = File::open(path).map_err(|e| {
+                ParsingError::new(&format!("Could not open the input file '{path}': {e}"))
+            })?;
+            match compression_of(path) {
+                Some(compression) => read_lines(
+                    BufReader::new(decompress_reader(compression, file, path)?),
+                    path,
+                ),
+                None => read_lines(BufReader::new(file), path),
+            }
+        };
+        inputs = Box::new(inputs.chain(lines));
+    }
+
+    Ok(inputs)
+}
+
+/// Reads the non-empty lines, exiting the process if the reading fails.
+fn read_lines(reader: impl BufRead + 'static, source: &str) -> Inputs {
+    let source = source.to_string();
+    Box::new(
+        reader
+            .lines()
+            .map(move |line| {
+                line.unwrap_or_else(|e| {
+                    eprintln!("Error reading from {source}: {e}");
+                    std::process::exit(FAILURE);
                 })
-                .filter(|line| !line.is_empty()),
-        ))
-    } else {
-        let mut inputs_peekable = args.iter().skip(1).peekable();
-        match inputs_peekable.peek() {
-            None => Err(ParsingError::new(MISSING_INPUT_ERR_MSG)),
-            Some(_) => Ok(Box::new(
-                inputs_peekable
-                    .map(std::string::ToString::to_string)
-                    .collect::<Vec<String>>()
-                    .into_iter(),
-            )),
+            })
+            .filter(|line| !line.is_empty()),
+    )
+}
+
+/// The flag whose value names a file of further arguments, see [`expand_args_files`].
+const ARGS_FILE_FLAG: &str = "--args-file";
+
+/// Replaces every `--args-file {path}` with the arguments read from the file, one argument per (non-empty) line,
+/// so that the arguments too many for the command line (e.g. many `--key` substitutions) can be passed at once.
+/// A line is taken as a whole, without quoting, so an argument may contain whitespace (such as a mnemonic).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the flag has no value, the file cannot be read or it names another file of
+/// arguments.
+pub fn expand_args_files(args: Vec<&str>) -> Result<Vec<String>, ParsingError> {
+    let mut expanded = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg != ARGS_FILE_FLAG {
+            expanded.push(arg.to_string());
+            continue;
+        }
+
+        let path = args
+            .next()
+            .ok_or_else(|| ParsingError::new(&missing_follow_up_val_err(ARGS_FILE_FLAG)))?;
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ParsingError::new(&format!("Could not read the arguments file '{path}': {e}"))
+        })?;
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            if line == ARGS_FILE_FLAG {
+                return Err(ParsingError::new(&format!(
+                    "The arguments file '{path}' cannot name another arguments file"
+                )));
+            }
+            expanded.push(line.to_string());
         }
     }
+
+    Ok(expanded)
 }
 
-/// Parses the provided command-line arguments and returns the corresponding command and its inputs.
+/// The sub-commands whose input may be a hexadecimal seed (or entropy), the hexadecimal arguments of the other ones
+/// are not secrets (e.g. the x-only public keys).
+const SEED_INPUT_COMMANDS: [&str; 7] = [
+    "derive-key",
+    "wallet-init",
+    "bench",
+    "process",
+    "mnemonic",
+    "slip39",
+    "seed-xor",
+];
+
+/// Returns the kind of the first secret (see [`classify_secret`]) passed among the command-line arguments, if any.
+#[must_use]
+pub fn find_secret_argument(args: &[&str]) -> Option<SecretKind> {
+    // the shared options may precede the sub-command, so it is looked for among all the arguments
+    let seed_input = args.iter().any(|arg| SEED_INPUT_COMMANDS.contains(arg));
+    args.iter().find_map(|arg| classify_secret(arg, seed_input))
+}
+
+/// Parses the provided command-line arguments and returns the corresponding command, the options shared
+/// by all sub-commands and the inputs.
 ///
 /// If the `--help` flag is present in the arguments, this function returns the `Help` command and an empty iterator.
-/// Otherwise, it expects the first argument to be one of the supported subcommands and parses its configuration.
-/// The function also determines the input source: if `-` is present in the arguments, input is read from stdin; otherwise,
-/// the argument(s) following the subcommand are used as input.
+/// Otherwise, the arguments are parsed in three stages, each taking its arguments out of the rest:
+/// 1. The options shared by all sub-commands ([`GlobalConfig`]), which may appear anywhere.
+/// 2. The sub-command, which must be the first of the remaining arguments, and its own flags (see [`parse_command`]).
+///    The shared options the sub-command does not support are refused at this point (see [`check_global_config`]).
+/// 3. The positional arguments left are the inputs, processed in order, where `-` stands for the lines read from stdin
+///    and `@path` for the content of the file as a single input.
+///    With the `--input-file` option, the inputs are read from the files instead, and no positional argument is left. The `--watch` option overrides
+///    all of these (see [`get_inputs`]).
 ///
 /// # Errors
 ///
 /// Returns a [`ParsingError`] if:
 /// - No arguments are provided,
 /// - The subcommand is invalid,
-/// - Parsing the subcommand configuration fails,
-/// - No input is provided when required.
-pub fn parse_args(mut args: Vec<&str>) -> Result<(Command, Inputs), ParsingError> {
+/// - Parsing the subcommand or the shared configuration fails,
+/// - No input is provided when required,
+/// - The watched file or an input file cannot be opened.
+pub fn parse_args(args: Vec<&str>) -> Result<(Command, GlobalConfig, Inputs), ParsingError> {
+    parse_args_and_inputs(args, None)
+}
+
+/// Parses the arguments like [`parse_args`], but with the inputs given apart from them (e.g. by a request sent to
+/// the server), so that an input is taken as it is and never as a flag, the standard input or a file.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if parsing the arguments fails (see [`parse_args`]), or a positional argument is left
+/// among them.
+#[cfg(any(feature = "serve", feature = "rpc"))]
+pub fn parse_args_with_inputs(
+    args: Vec<&str>,
+    inputs: Vec<String>,
+) -> Result<(Command, GlobalConfig, Inputs), ParsingError> {
+    parse_args_and_inputs(args, Some(inputs))
+}
+
+fn parse_args_and_inputs(
+    mut args: Vec<&str>,
+    given_inputs: Option<Vec<String>>,
+) -> Result<(Command, GlobalConfig, Inputs), ParsingError> {
     // if args includes --help, we should print the help message
     if args.contains(&"--help") {
-        return Ok((Command::Help, Box::new(std::iter::empty::<String>())));
+        return Ok((
+            Command::Help,
+            GlobalConfig::default(),
+            Box::new(std::iter::empty::<String>()),
+        ));
     }
 
-    // if --help is not present, then exacly one of the three sub-commands must be present and must be the first one argument
-    let first_arg = args
+    let mut global_config = GlobalConfig::parse(&mut args)?;
+
+    let command = parse_command(&mut args)?;
+    // the server reads its requests from the socket, not from the inputs
+    #[cfg(feature = "serve")]
+    if let Command::Serve(_) = command {
+        return Ok((
+            command,
+            global_config,
+            Box::new(std::iter::empty::<String>()),
+        ));
+    }
+    check_global_config(&command, &global_config)?;
+    // the keys are printed in the order of sortedmulti(...) once all of them are read
+    #[cfg(feature = "key-expression")]
+    if let Command::KeyExpression(config) = &command {
+        global_config.sort |= config.sortedmulti_order;
+    }
+
+    let inputs = match given_inputs {
+        None => get_inputs(&args, &global_config)?,
+        Some(inputs) => match args.get(1) {
+            Some(arg) => return Err(ParsingError::new(&unexpected_positional_arg_err(arg))),
+            None => Box::new(inputs.into_iter()),
+        },
+    };
+    let inputs: Inputs = match global_config.limit {
+        Some(limit) => Box::new(inputs.skip(global_config.skip).take(limit)),
+        None => Box::new(inputs.skip(global_config.skip)),
+    };
+
+    Ok((command, global_config, inputs))
+}
+
+/// Parses the sub-command, which must be the first of the arguments, together with its own flags,
+/// leaving only the positional arguments.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if no arguments are left, the sub-command is invalid or parsing its flags fails.
+fn parse_command(args: &mut Vec<&str>) -> Result<Command, ParsingError> {
+    let first_arg = *args
         .first()
         .ok_or_else(|| ParsingError::new(MISSING_ARG_ERR_MSG))?;
 
-    let command = match *first_arg {
-        "derive-key" => Command::DeriveKey(DeriveKeyConfig::parse(&mut args)?),
-        "key-expression" => Command::KeyExpression(KeyExpressionConfig::parse(&mut args)?),
-        "script-expression" => Command::ScriptExpression(ScriptExpressionConfig::parse(&mut args)?),
-        _ => return Err(ParsingError::new(&format!("Invalid argument: {first_arg}"))),
+    Ok(match first_arg {
+        #[cfg(feature = "convert-key")]
+        "convert-key" => Command::ConvertKey(ConvertKeyConfig::parse(args)?),
+        #[cfg(feature = "derive-key")]
+        "derive-key" => Command::DeriveKey(DeriveKeyConfig::parse(args)?),
+        #[cfg(feature = "key-expression")]
+        "key-expression" => Command::KeyExpression(KeyExpressionConfig::parse(args)?),
+        #[cfg(feature = "script-expression")]
+        "script-expression" => Command::ScriptExpression(ScriptExpressionConfig::parse(args)?),
+        #[cfg(feature = "wallet-init")]
+        "wallet-init" => Command::WalletInit(WalletInitConfig::parse(args)?),
+        #[cfg(feature = "path")]
+        "path" => Command::Path(PathConfig::parse(args)?),
+        #[cfg(feature = "mnemonic")]
+        "mnemonic" => Command::Mnemonic(MnemonicConfig::parse(args)?),
+        #[cfg(feature = "generate-seed")]
+        "generate-seed" => Command::GenerateSeed(GenerateSeedConfig::parse(args)?),
+        #[cfg(feature = "slip39")]
+        "slip39" => Command::Slip39(Slip39Config::parse(args)?),
+        #[cfg(feature = "seed-xor")]
+        "seed-xor" => Command::SeedXor(SeedXorConfig::parse(args)?),
+        #[cfg(feature = "coldcard-export")]
+        "coldcard-export" => Command::ColdcardExport(ColdcardExportConfig::parse(args)?),
+        #[cfg(feature = "wallet-export")]
+        "wallet-export" => Command::WalletExport(WalletExportConfig::parse(args)?),
+        #[cfg(feature = "process")]
+        "process" => Command::Process(ProcessConfig::parse(args)?),
+        #[cfg(feature = "address")]
+        "address" => Command::Address(AddressConfig::parse(args)?),
+        #[cfg(feature = "lint")]
+        "lint" => Command::Lint(LintConfig::parse(args)?),
+        #[cfg(feature = "bench")]
+        "bench" => Command::Bench(BenchConfig::parse(args)?),
+        #[cfg(feature = "demo")]
+        "demo" => Command::Demo(DemoConfig::parse(args)?),
+        #[cfg(feature = "compose")]
+        "compose" => Command::Compose(ComposeConfig::parse(args)?),
+        #[cfg(feature = "hwi-import")]
+        "hwi-import" => Command::HwiImport(HwiImportConfig::parse(args)?),
+        #[cfg(feature = "analyze")]
+        "analyze" => Command::Analyze,
+        #[cfg(feature = "rpc")]
+        "--rpc" => {
+            // the requests are always read from the standard input
+            args.push("-");
+            Command::Rpc
+        }
+        #[cfg(feature = "serve")]
+        "serve" => Command::Serve(ServeConfig::parse(args)?),
+        _ => {
+            return Err(ParsingError::new(&match FEATURE_COMMANDS
+                .iter()
+                .find(|(name, _)| *name == first_arg)
+            {
+                Some((name, feature)) => unavailable_command_err(name, feature),
+                None => format!("Invalid argument: {first_arg}"),
+            }))
+        }
+    })
+}
+
+/// Refuses the shared options the sub-command does not support.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if `--import-to-core` or `--ur` is given to a sub-command not supporting it, or
+/// `--show-intermediate` or `--plan` of derive-key is combined with a formatted output.
+fn check_global_config(
+    command: &Command,
+    global_config: &GlobalConfig,
+) -> Result<(), ParsingError> {
+    // whether the sub-command supports '--import-to-core' and '--output ur', and its flag printing plain text only
+    let (core_import, ur, plain_only_flag): (bool, bool, Option<&str>) = match command {
+        #[cfg(feature = "script-expression")]
+        Command::ScriptExpression(_) => (true, true, None),
+        #[cfg(feature = "wallet-init")]
+        Command::WalletInit(_) => (true, false, None),
+        #[cfg(feature = "key-expression")]
+        Command::KeyExpression(config) if config.sortedmulti_order => {
+            (false, false, Some("--sortedmulti-order"))
+        }
+        #[cfg(feature = "key-expression")]
+        Command::KeyExpression(_) => (false, true, None),
+        #[cfg(feature = "derive-key")]
+        Command::DeriveKey(config) if config.show_intermediate => {
+            (false, true, Some("--show-intermediate"))
+        }
+        #[cfg(feature = "derive-key")]
+        Command::DeriveKey(config) if config.plan => (false, true, Some("--plan")),
+        #[cfg(feature = "derive-key")]
+        Command::DeriveKey(_) => (false, true, None),
+        // the inputs are analyzed together into a single report
+        #[cfg(feature = "analyze")]
+        Command::Analyze => (false, false, Some("analyze")),
+        _ => (false, false, None),
     };
 
-    let inputs = get_inputs(&args)?;
+    if global_config.core_import.is_some() && !core_import {
+        return Err(ParsingError::new(CORE_IMPORT_COMMAND_ERR_MSG));
+    }
+    if let Some(flag) = plain_only_flag {
+        if global_config.template.is_some() {
+            return Err(ParsingError::new(&format!(
+                "'{flag}' cannot be combined with '--template'"
+            )));
+        }
+        if global_config.output != OutputFormat::Plain {
+            return Err(ParsingError::new(&format!(
+                "'{flag}' cannot be combined with '--output {}'",
+                global_config.output
+            )));
+        }
+    }
+    if global_config.output == OutputFormat::Ur && !ur {
+        return Err(ParsingError::new(UR_COMMAND_ERR_MSG));
+    }
 
-    Ok((command, inputs))
+    Ok(())
 }
 
 mod tests {
@@ -107,25 +830,37 @@ mod tests {
         ];
 
         for arg in help_command_args.iter() {
-            assert!(matches!(parse_args(arg.to_vec()), Ok((Command::Help, _))));
+            assert!(matches!(
+                parse_args(arg.to_vec()),
+                Ok((Command::Help, _, _))
+            ));
         }
     }
 
     #[test]
     fn test_parse_args_command_output() {
+        #[cfg(feature = "key-expression")]
         assert!(matches!(
             parse_args(vec!["key-expression", "arg1"]),
-            Ok((Command::KeyExpression(_), _))
+            Ok((Command::KeyExpression(_), _, _))
         ));
 
+        #[cfg(feature = "script-expression")]
         assert!(matches!(
             parse_args(vec!["script-expression", "arg2"]),
-            Ok((Command::ScriptExpression(_), _))
+            Ok((Command::ScriptExpression(_), _, _))
         ));
 
+        #[cfg(feature = "derive-key")]
         assert!(matches!(
             parse_args(vec!["derive-key", "arg3"]),
-            Ok((Command::DeriveKey(_), _))
+            Ok((Command::DeriveKey(_), _, _))
+        ));
+
+        #[cfg(feature = "serve")]
+        assert!(matches!(
+            parse_args(vec!["serve", "--socket", "/tmp/bip380.sock"]),
+            Ok((Command::Serve(_), _, _))
         ));
     }
 
@@ -137,6 +872,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "derive-key")]
     fn test_parse_args_flag_dropping() {
         let example_arg_set = vec!["derive-key", "--path", "100/200h", "argument"];
 
@@ -144,20 +880,173 @@ mod tests {
 
         assert!(result.is_ok());
 
-        let inputs: Vec<String> = result.unwrap().1.collect();
+        let inputs: Vec<String> = result.unwrap().2.collect();
 
         assert_eq!(inputs, vec!["argument"]);
     }
 
     #[test]
     fn test_inputs() {
-        let inputs = get_inputs(&vec!["key-expression", "input"]).unwrap();
+        let inputs = get_inputs(&["key-expression", "input"], &GlobalConfig::default()).unwrap();
         assert_eq!(inputs.collect::<Vec<String>>(), vec!["input"]);
 
-        assert!(get_inputs(&vec!["key-expression", "-"]).is_ok());
+        assert!(get_inputs(&["key-expression", "-"], &GlobalConfig::default()).is_ok());
+
+        assert!(get_inputs(&["key-expression"], &GlobalConfig::default()).is_err());
+
+        let inputs = get_inputs(
+            &["key-expression", "input1", "input2"],
+            &GlobalConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(inputs.collect::<Vec<String>>(), vec!["input1", "input2"]);
+
+        assert!(get_inputs(
+            &["key-expression", "-", "input", "-"],
+            &GlobalConfig::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_input_file() {
+        let path = std::env::temp_dir().join(format!("bip380-input-{}", std::process::id()));
+        std::fs::write(&path, "  raw(deadbeef)\n\n").unwrap();
+        let arg = format!("@{}", path.to_str().unwrap());
+
+        let inputs = get_inputs(
+            &["script-expression", "input", &arg],
+            &GlobalConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            inputs.collect::<Vec<String>>(),
+            vec!["input", "raw(deadbeef)"]
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(get_inputs(&["script-expression", &arg], &GlobalConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_expand_args_files() {
+        let path = std::env::temp_dir().join(format!("bip380-args-{}", std::process::id()));
+        std::fs::write(&path, "--key\nalice=KEY 1\n\n--compute-checksum\n").unwrap();
+        let path_arg = path.to_str().unwrap();
+
+        assert_eq!(
+            expand_args_files(vec!["script-expression", "--args-file", path_arg, "-"]),
+            Ok(vec![
+                "script-expression".to_string(),
+                "--key".to_string(),
+                "alice=KEY 1".to_string(),
+                "--compute-checksum".to_string(),
+                "-".to_string(),
+            ])
+        );
+
+        std::fs::write(&path, "--args-file\nother.txt\n").unwrap();
+        assert!(expand_args_files(vec!["script-expression", "--args-file", path_arg]).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(expand_args_files(vec!["script-expression", "--args-file", path_arg]).is_err());
+        assert_eq!(
+            expand_args_files(vec!["script-expression", "--args-file"]),
+            Err(ParsingError::new(&missing_follow_up_val_err("--args-file")))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "key-expression", feature = "script-expression"))]
+    fn test_ur_output() {
+        let run = |args: Vec<&str>| {
+            let (command, global_config, mut inputs) = parse_args(args)?;
+            command.output(&inputs.next().unwrap(), &global_config)
+        };
+
+        assert!(run(vec![
+            "key-expression",
+            "--ur",
+            "[d34db33f/44'/0'/0']xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/1/*"
+        ])
+        .unwrap()
+        .starts_with("ur:crypto-hdkey/"));
+
+        let parts = run(vec![
+            "script-expression",
+            "--ur",
+            "--ur-max-fragment",
+            "20",
+            "pkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)",
+        ])
+        .unwrap();
+        assert!(parts.lines().count() > 1);
+        assert!(parts
+            .lines()
+            .all(|part| part.starts_with("ur:crypto-output/")));
+
+        assert_eq!(
+            run(vec![
+                "key-expression",
+                "--output",
+                "ur",
+                "[d34db33f/44'/0'/0']xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/1/*"
+            ]),
+            run(vec![
+                "key-expression",
+                "--ur",
+                "[d34db33f/44'/0'/0']xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/1/*"
+            ])
+        );
+
+        assert!(parse_args(vec!["wallet-init", "--ur", "-"]).is_err());
+        assert!(run(vec![
+            "key-expression",
+            "--ur",
+            "L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_csv_and_qr_output() {
+        let run = |args: Vec<&str>| {
+            let (command, global_config, mut inputs) = parse_args(args)?;
+            command.output(&inputs.next().unwrap(), &global_config)
+        };
+
+        assert_eq!(
+            run(vec![
+                "script-expression",
+                "--output",
+                "csv",
+                "raw(deadbeef)#89f8spxm"
+            ]),
+            Ok("raw(deadbeef),89f8spxm,raw(deadbeef)#89f8spxm".to_string())
+        );
+        assert_eq!(
+            run(vec![
+                "script-expression",
+                "--output",
+                "csv",
+                "--select",
+                "checksum,script",
+                "raw(deadbeef)#89f8spxm"
+            ]),
+            Ok("89f8spxm,raw(deadbeef)".to_string())
+        );
 
-        assert!(get_inputs(&vec!["key-expression"]).is_err());
+        let qr = run(vec!["script-expression", "--output", "qr", "raw(deadbeef)"]).unwrap();
+        assert_eq!(qr.lines().count(), 15);
+        assert!(qr.lines().all(|line| line.chars().count() == 29));
 
-        assert!(get_inputs(&vec!["key-expression", "input1", "input2"]).is_ok());
+        #[cfg(feature = "derive-key")]
+        assert_eq!(
+            parse_args(vec!["derive-key", "--plan", "--output", "csv", "-"]).err(),
+            Some(ParsingError::new(
+                "'--plan' cannot be combined with '--output csv'"
+            ))
+        );
     }
 }
@@ -0,0 +1,157 @@
+use std::{fs::File, io::Read};
+
+use crate::structs::parsing_error::ParsingError;
+
+/// The compression formats of the input files.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Compression {
+    /// The `.gz` files, decompressed with the `gzip` feature.
+    Gzip,
+    /// The `.zst` files, decompressed with the `zstd` feature.
+    Zstd,
+}
+
+/// The compressions of the input files by their extensions.
+const COMPRESSIONS: [(&str, Compression); 2] =
+    [(".gz", Compression::Gzip), (".zst", Compression::Zstd)];
+
+/// Returns the compression of the file by its extension, `None` for an uncompressed file.
+#[must_use]
+pub fn compression_of(path: &str) -> Option<Compression> {
+    COMPRESSIONS
+        .iter()
+        .find(|(extension, _)| path.ends_with(extension))
+        .map(|(_, compression)| *compression)
+}
+
+/// Returns a reader of the decompressed content of the opened file, decompressed in the process while it is
+/// read, so even huge dumps are never held in memory as a whole. Reading fails if the content is corrupted.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the decompression cannot be started, or the utility was built without the feature
+/// of the compression.
+pub fn decompress_reader(
+    compression: Compression,
+    file: File,
+    path: &str,
+) -> Result<Box<dyn Read>, ParsingError> {
+    match compression {
+        #[cfg(feature = "gzip")]
+        // a file of several concatenated members (e.g. appended dumps) is decompressed as a whole
+        Compression::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(file))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => zstd::Decoder::new(file)
+            .map(|decoder| Box::new(decoder) as Box<dyn Read>)
+            .map_err(|e| {
+                ParsingError::new(&format!(
+                    "Could not decompress the input file '{path}': {e}"
+                ))
+            }),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => Err(unavailable_compression_err(path, "gzip")),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(unavailable_compression_err(path, "zstd")),
+    }
+}
+
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn unavailable_compression_err(path: &str, feature: &str) -> ParsingError {
+    ParsingError::new(&format!(
+        "Could not decompress the input file '{path}', the utility was built without the '{feature}' feature"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+
+    use super::*;
+
+    /// Writes the content to a temporary file, returning its path.
+    fn temp_file(name: &str, content: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("bip380-{}-{name}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    fn read_lines(
+        compression: Compression,
+        path: &std::path::Path,
+    ) -> std::io::Result<Vec<String>> {
+        let reader = decompress_reader(compression, File::open(path).unwrap(), "dump").unwrap();
+        // a corrupted content keeps failing, so the reading stops at the first error
+        BufReader::new(reader).lines().collect()
+    }
+
+    #[test]
+    fn test_compression_of() {
+        assert_eq!(compression_of("dump.txt.gz"), Some(Compression::Gzip));
+        assert_eq!(compression_of("dump.zst"), Some(Compression::Zstd));
+        assert_eq!(compression_of("dump.txt"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_decompress_gzip() {
+        use std::io::Write;
+
+        use flate2::{write::GzEncoder, Compression as Level};
+
+        let gzip = |content: &[u8]| {
+            let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+            encoder.write_all(content).unwrap();
+            encoder.finish().unwrap()
+        };
+        // two concatenated members, as appending to a .gz file produces
+        let path = temp_file(
+            "decompress.gz",
+            &[gzip(b"raw(deadbeef)\n"), gzip(b"raw(00)\n")].concat(),
+        );
+        assert_eq!(
+            read_lines(Compression::Gzip, &path).unwrap(),
+            vec!["raw(deadbeef)", "raw(00)"]
+        );
+
+        // not a gzip file
+        std::fs::write(&path, "raw(deadbeef)\n").unwrap();
+        assert!(read_lines(Compression::Gzip, &path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn test_decompress_zstd() {
+        let path = temp_file(
+            "decompress.zst",
+            &zstd::encode_all(&b"raw(deadbeef)\nraw(00)\n"[..], 0).unwrap(),
+        );
+        assert_eq!(
+            read_lines(Compression::Zstd, &path).unwrap(),
+            vec!["raw(deadbeef)", "raw(00)"]
+        );
+
+        // not a zstd file
+        std::fs::write(&path, "raw(deadbeef)\n").unwrap();
+        assert!(read_lines(Compression::Zstd, &path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(feature = "zstd"))]
+    fn test_decompress_without_feature() {
+        let path = temp_file("decompress-unavailable.zst", b"");
+        let result = decompress_reader(Compression::Zstd, File::open(&path).unwrap(), "dump.zst");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            result.err(),
+            Some(ParsingError::new(
+                "Could not decompress the input file 'dump.zst', the utility was built without the 'zstd' feature"
+            ))
+        );
+    }
+}
@@ -50,29 +50,117 @@ pub fn parse_boolean_flag(args: &mut Vec<&str>, key: &str) -> bool {
 /// - The flag is present but not followed by a value,
 /// - The flag appears multiple times with values.
 pub fn parse_value_flag(args: &mut Vec<&str>, key: &str) -> Result<Option<String>, ParsingError> {
-    let flag = key.flagify();
-    if args.last() == Some(&flag.as_str()) {
-        return Err(ParsingError::new(&missing_follow_up_val_err(&flag)));
+    Ok(parse_value_flags(args, &[key])?.pop().flatten())
+}
+
+/// Parses several different value flags from the provided arguments in a single pass,
+/// removing every found flag together with its value.
+///
+/// The arguments are left untouched if an error is returned.
+///
+/// # Arguments
+///
+/// * `args` - A mutable reference to a vector of argument string slices.
+/// * `keys` - The flag keys (without leading dashes) to search for.
+///
+/// # Returns
+///
+/// Returns a vector with one entry per key (in the order of `keys`) holding `Some(value)`
+/// if the flag was present, otherwise `None`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - Any of the flags is present but not followed by a value,
+/// - Any of the flags appears multiple times with values.
+pub fn parse_value_flags(
+    args: &mut Vec<&str>,
+    keys: &[&str],
+) -> Result<Vec<Option<String>>, ParsingError> {
+    let flags: Vec<String> = keys.iter().map(|key| key.flagify()).collect();
+    let mut values: Vec<Option<String>> = vec![None; flags.len()];
+    let mut flag_indexes: Vec<usize> = Vec::new();
+
+    let mut index = 0;
+    while index < args.len() {
+        let Some(flag_position) = flags.iter().position(|flag| flag == args[index]) else {
+            index += 1;
+            continue;
+        };
+        let flag = &flags[flag_position];
+        let value = args
+            .get(index + 1)
+            .ok_or_else(|| ParsingError::new(&missing_follow_up_val_err(flag)))?;
+        if values[flag_position].is_some() {
+            return Err(ParsingError::new(&multiple_value_flags_detected_err(flag)));
+        }
+        values[flag_position] = Some((*value).to_string());
+        flag_indexes.push(index);
+        index += 2;
     }
 
-    match args.windows(2).enumerate().find_map(|(index, w)| match w {
-        [argument_1, argument_2] if *argument_1 == flag => Some((index, String::from(*argument_2))),
-        _ => None,
-    }) {
-        Some((flag_index, _)) if args[(flag_index + 2)..args.len()].contains(&flag.as_str()) => {
-            Err(ParsingError::new(&multiple_value_flags_detected_err(&flag)))
+    if !flag_indexes.is_empty() {
+        let mut removed = flag_indexes.iter().peekable();
+        let mut write_index = 0;
+        for read_index in 0..args.len() {
+            if let Some(&&flag_index) = removed.peek() {
+                if read_index == flag_index {
+                    continue;
+                }
+                if read_index == flag_index + 1 {
+                    removed.next();
+                    continue;
+                }
+            }
+            args[write_index] = args[read_index];
+            write_index += 1;
         }
-        Some((flag_index, flag_value)) => {
-            let mut index_counter: usize = 0;
-            args.retain(|_| {
-                let should_remove = (flag_index..=flag_index + 1).contains(&index_counter);
-                index_counter += 1;
-                !should_remove
-            });
-            Ok(Some(flag_value))
+        args.truncate(write_index);
+    }
+
+    Ok(values)
+}
+
+/// Parses a value flag that may be repeated, removing every occurrence of the flag together with its value.
+///
+/// The arguments are left untouched if an error is returned.
+///
+/// # Arguments
+///
+/// * `args` - A mutable reference to a vector of argument string slices.
+/// * `key` - The flag key (without leading dashes) to search for.
+///
+/// # Returns
+///
+/// Returns the values of all occurrences of the flag in the order they were provided.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if any occurrence of the flag is not followed by a value.
+pub fn parse_repeated_value_flag(
+    args: &mut Vec<&str>,
+    key: &str,
+) -> Result<Vec<String>, ParsingError> {
+    let flag = key.flagify();
+    let mut values: Vec<String> = Vec::new();
+    let mut remaining_args: Vec<&str> = Vec::with_capacity(args.len());
+
+    let mut index = 0;
+    while index < args.len() {
+        if args[index] == flag {
+            let value = args
+                .get(index + 1)
+                .ok_or_else(|| ParsingError::new(&missing_follow_up_val_err(&flag)))?;
+            values.push((*value).to_string());
+            index += 2;
+        } else {
+            remaining_args.push(args[index]);
+            index += 1;
         }
-        None => Ok(None),
     }
+
+    *args = remaining_args;
+    Ok(values)
 }
 
 mod tests {
@@ -181,4 +269,90 @@ mod tests {
             Ok(None)
         );
     }
+
+    #[test]
+    fn test_multiple_different_value_flags() {
+        let mut example_arg_set = vec![
+            "derive-key",
+            "--first",
+            "1",
+            "input",
+            "--third",
+            "3",
+            "--some-bool-flag",
+        ];
+
+        assert_eq!(
+            parse_value_flags(&mut example_arg_set, &["first", "second", "third"]),
+            Ok(vec![Some("1".to_string()), None, Some("3".to_string())])
+        );
+
+        assert_eq!(
+            example_arg_set,
+            vec!["derive-key", "input", "--some-bool-flag"]
+        );
+    }
+
+    #[test]
+    fn test_value_flags_error_leaves_args_untouched() {
+        let example_arg_set = vec!["derive-key", "--first", "1", "--second"];
+        let mut example_arg_set_cloned = example_arg_set.clone();
+
+        assert_eq!(
+            parse_value_flags(&mut example_arg_set_cloned, &["first", "second"]),
+            Err(ParsingError::new(&missing_follow_up_val_err(
+                &"second".flagify()
+            )))
+        );
+
+        assert_eq!(example_arg_set, example_arg_set_cloned);
+    }
+
+    #[test]
+    fn test_value_flag_as_value_of_itself() {
+        let flag_key = "example-value-flag";
+        let flag = flag_key.flagify();
+        let mut example_arg_set = vec!["derive-key", flag.as_str(), flag.as_str(), "input"];
+
+        assert_eq!(
+            parse_value_flag(&mut example_arg_set, flag_key),
+            Ok(Some(flag.clone()))
+        );
+
+        assert_eq!(example_arg_set, vec!["derive-key", "input"]);
+    }
+
+    #[test]
+    fn test_repeated_value_flag() {
+        let mut example_arg_set = vec![
+            "script-expression",
+            "--key",
+            "alice=02aa",
+            "input",
+            "--key",
+            "bob=03bb",
+        ];
+
+        assert_eq!(
+            parse_repeated_value_flag(&mut example_arg_set, "key"),
+            Ok(vec!["alice=02aa".to_string(), "bob=03bb".to_string()])
+        );
+
+        assert_eq!(example_arg_set, vec!["script-expression", "input"]);
+    }
+
+    #[test]
+    fn test_repeated_value_flag_missing_value() {
+        let example_arg_set = vec!["script-expression", "--key", "alice=02aa", "--key"];
+        let mut example_arg_set_cloned = example_arg_set.clone();
+
+        assert_eq!(
+            parse_repeated_value_flag(&mut example_arg_set_cloned, "key"),
+            Err(ParsingError::new(&missing_follow_up_val_err(
+                &"key".flagify()
+            )))
+        );
+
+        assert_eq!(example_arg_set, example_arg_set_cloned);
+    }
 }
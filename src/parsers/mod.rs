@@ -1,2 +1,4 @@
 pub mod arg_parser;
+pub mod decompress_reader;
 pub mod flag_parser;
+pub mod watch_reader;
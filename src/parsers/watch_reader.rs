@@ -0,0 +1,97 @@
+use std::{
+    fs::File,
+    io::{stdin, BufRead, BufReader},
+    thread,
+    time::Duration,
+};
+
+use crate::{structs::parsing_error::ParsingError, utils::signals::shutdown_requested, FAILURE};
+
+use super::arg_parser::Inputs;
+
+/// How long to wait for new data once the end of the watched source is reached.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An endless iterator over the non-empty lines of a reader.
+///
+/// Unlike [`BufRead::lines`], reaching the end of the reader does not end the iteration, the reader
+/// is polled for more data instead. A line is only yielded once its line break has been read. The iteration ends
+/// only when a signal requests the shutdown while waiting for more data.
+pub struct WatchLines<R> {
+    reader: R,
+    line: String,
+}
+
+impl<R: BufRead> WatchLines<R> {
+    pub fn new(reader: R) -> Self {
+        WatchLines {
+            reader,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for WatchLines<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) if shutdown_requested() => return None,
+                Ok(0) => thread::sleep(POLL_INTERVAL),
+                Ok(_) if self.line.ends_with('\n') => {
+                    let line = std::mem::take(&mut self.line);
+                    let line = line.trim_end_matches(['\n', '\r']);
+                    if !line.is_empty() {
+                        return Some(line.to_string());
+                    }
+                }
+                // the rest of the line has not been written yet
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Error reading the watched input: {e}");
+                    std::process::exit(FAILURE);
+                }
+            }
+        }
+    }
+}
+
+/// Opens the watched source, `-` being the standard input and anything else a path to a file.
+///
+/// A file is read from its beginning and then followed as it grows (like `tail -f`).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the file cannot be opened.
+pub fn watch_inputs(source: &str) -> Result<Inputs, ParsingError> {
+    if source == "-" {
+        return Ok(Box::new(WatchLines::new(BufReader::new(stdin()))));
+    }
+
+    let file = File::open(source).map_err(|e| {
+        ParsingError::new(&format!("Could not open the watched file '{source}': {e}"))
+    })?;
+    Ok(Box::new(WatchLines::new(BufReader::new(file))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_watch_lines() {
+        let lines: Vec<String> = WatchLines::new(Cursor::new("first\n\nsecond\r\nthird\n"))
+            .take(3)
+            .collect();
+
+        assert_eq!(lines, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn test_watch_missing_file() {
+        assert!(watch_inputs("/nonexistent/watched/file").is_err());
+    }
+}
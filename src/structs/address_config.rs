@@ -0,0 +1,170 @@
+use crate::{
+    parsers::flag_parser::parse_value_flags,
+    subcommands::utils::{hexadecimal::decode_hex, number::parse_index},
+    traits::parsable::Parsable,
+};
+
+use super::{
+    address_type::AddressType, explorer::Explorer, network::Network, parsing_error::ParsingError,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AddressConfig {
+    pub address_type: AddressType,
+    pub network: Network,
+    /// The index the wildcard of a ranged key expression is replaced with.
+    pub index: Option<u32>,
+    /// The merkle root of the script tree a P2TR output key commits to, none for a key path only output.
+    pub merkle_root: Option<[u8; 32]>,
+    /// The block explorer whose URL of the address is printed alongside it.
+    pub explorer: Option<Explorer>,
+}
+
+impl Parsable for AddressConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [address_type, network, index, merkle_root, explorer]: [Option<String>; 5] =
+            parse_value_flags(
+                args,
+                &["type", "network", "index", "merkle-root", "explorer-url"],
+            )?
+            .try_into()
+            .unwrap_or_default();
+
+        let address_type = AddressType::try_from(
+            address_type
+                .ok_or_else(|| {
+                    ParsingError::new(
+                        "The address sub-command requires '--type {p2pkh,p2wpkh,p2sh-p2wpkh,p2tr}'",
+                    )
+                })?
+                .as_str(),
+        )?;
+
+        let network = network
+            .map(|network| Network::try_from(network.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        let index = index
+            .map(|index| {
+                parse_index(&index).ok_or_else(|| {
+                    ParsingError::new(&format!(
+                        "Value '{index}' of '--index' must be from the range [0,...,2^31-1]"
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let merkle_root = merkle_root
+            .map(|merkle_root| {
+                if address_type != AddressType::P2tr {
+                    return Err(ParsingError::new(
+                        "'--merkle-root' can only be used with '--type p2tr'",
+                    ));
+                }
+                Some(merkle_root.as_str())
+                    .filter(|merkle_root| {
+                        merkle_root.len() == 64 && merkle_root.chars().all(|c| c.is_ascii_hexdigit())
+                    })
+                    .and_then(|merkle_root| decode_hex(merkle_root).ok()?.try_into().ok())
+                    .ok_or_else(|| {
+                        ParsingError::new(&format!(
+                            "Value '{merkle_root}' of '--merkle-root' must be 32 bytes in hexadecimal"
+                        ))
+                    })
+            })
+            .transpose()?;
+
+        let explorer = explorer
+            .map(|explorer| Explorer::try_from(explorer.as_str()))
+            .transpose()?;
+
+        Ok(AddressConfig {
+            address_type,
+            network,
+            index,
+            merkle_root,
+            explorer,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            address_config::AddressConfig, address_type::AddressType, explorer::Explorer,
+            network::Network, parsing_error::ParsingError,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_flags_provided() {
+        let mut args = vec!["address", "input", "--type", "p2sh-p2wpkh"];
+        assert_eq!(
+            AddressConfig::parse(&mut args),
+            Ok(AddressConfig {
+                address_type: AddressType::P2shP2wpkh,
+                network: Network::Mainnet,
+                index: None,
+                merkle_root: None,
+                explorer: None,
+            })
+        );
+        assert_eq!(args, vec!["address", "input"]);
+
+        let mut args = vec![
+            "address",
+            "--type",
+            "p2tr",
+            "--network",
+            "testnet",
+            "--index",
+            "7",
+            "--explorer-url",
+            "mempool",
+            "-",
+        ];
+        assert_eq!(
+            AddressConfig::parse(&mut args),
+            Ok(AddressConfig {
+                address_type: AddressType::P2tr,
+                network: Network::Testnet,
+                index: Some(7),
+                merkle_root: None,
+                explorer: Some(Explorer::Mempool),
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        for args in [
+            vec!["address", "-"],
+            vec!["address", "--type", "p2wsh", "-"],
+            vec!["address", "--type", "p2tr", "--index", "2147483648", "-"],
+            vec!["address", "--type", "p2tr", "--network", "signet", "-"],
+            vec![
+                "address",
+                "--type",
+                "p2wpkh",
+                "--merkle-root",
+                &"00".repeat(32),
+                "-",
+            ],
+            vec!["address", "--type", "p2tr", "--merkle-root", "000", "-"],
+            vec![
+                "address",
+                "--type",
+                "p2tr",
+                "--explorer-url",
+                "esplora",
+                "-",
+            ],
+        ] {
+            let mut args = args;
+            assert!(AddressConfig::parse(&mut args).is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use super::{key_context::KeyContext, parsing_error::ParsingError};
+
+/// The single-key address types a key expression can be encoded as.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AddressType {
+    /// Legacy pay-to-pubkey-hash (`1...` on mainnet).
+    P2pkh,
+    /// Native segwit pay-to-witness-pubkey-hash (`bc1q...` on mainnet).
+    P2wpkh,
+    /// Pay-to-witness-pubkey-hash nested in pay-to-script-hash (`3...` on mainnet).
+    P2shP2wpkh,
+    /// Taproot key path spend without a script tree (`bc1p...` on mainnet).
+    P2tr,
+}
+
+impl AddressType {
+    /// Returns the context the key expression of the address type is validated in.
+    #[must_use]
+    pub fn key_context(self) -> KeyContext {
+        match self {
+            AddressType::P2pkh => KeyContext::Legacy,
+            AddressType::P2wpkh | AddressType::P2shP2wpkh => KeyContext::SegwitV0,
+            AddressType::P2tr => KeyContext::Taproot,
+        }
+    }
+}
+
+impl std::fmt::Display for AddressType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            AddressType::P2pkh => "p2pkh",
+            AddressType::P2wpkh => "p2wpkh",
+            AddressType::P2shP2wpkh => "p2sh-p2wpkh",
+            AddressType::P2tr => "p2tr",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl TryFrom<&str> for AddressType {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "p2pkh" => Ok(AddressType::P2pkh),
+            "p2wpkh" => Ok(AddressType::P2wpkh),
+            "p2sh-p2wpkh" => Ok(AddressType::P2shP2wpkh),
+            "p2tr" => Ok(AddressType::P2tr),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported address type '{value}', expected one of 'p2pkh', 'p2wpkh', 'p2sh-p2wpkh' or 'p2tr'"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// How many of the slowest inputs are reported with `--timing`.
+const SLOWEST_INPUT_COUNT: usize = 5;
+
+/// Counts of the processed inputs of a batch, reported by `--stats`.
+pub struct BatchStats {
+    pub succeeded: usize,
+    pub failed: usize,
+    started: Instant,
+    elapsed: Option<Duration>,
+    /// The positions of the slowest timed inputs with their processing times, the slowest first.
+    slowest: Vec<(usize, Duration)>,
+}
+
+impl BatchStats {
+    #[must_use]
+    pub fn start() -> Self {
+        BatchStats {
+            succeeded: 0,
+            failed: 0,
+            started: Instant::now(),
+            elapsed: None,
+            slowest: Vec::new(),
+        }
+    }
+
+    /// Records the processing time of the input at the (1-based) position, keeping only the slowest inputs.
+    pub fn record_time(&mut self, position: usize, elapsed: Duration) {
+        let index = self
+            .slowest
+            .partition_point(|(_, slower)| *slower >= elapsed);
+        if index < SLOWEST_INPUT_COUNT {
+            self.slowest.insert(index, (position, elapsed));
+            self.slowest.truncate(SLOWEST_INPUT_COUNT);
+        }
+    }
+
+    /// Stops measuring the elapsed time of the batch.
+    pub fn finish(&mut self) {
+        self.elapsed = Some(self.started.elapsed());
+    }
+
+    #[must_use]
+    pub fn processed(&self) -> usize {
+        self.succeeded + self.failed
+    }
+}
+
+impl Display for BatchStats {
+    // the precision of the throughput does not matter
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let elapsed = self
+            .elapsed
+            .unwrap_or_else(|| self.started.elapsed())
+            .as_secs_f64();
+        let throughput = if elapsed > 0.0 {
+            self.processed() as f64 / elapsed
+        } else {
+            0.0
+        };
+
+        write!(
+            f,
+            "Processed {} inputs: {} succeeded, {} failed in {elapsed:.3}s ({throughput:.1} inputs/s)",
+            self.processed(),
+            self.succeeded,
+            self.failed
+        )?;
+        if !self.slowest.is_empty() {
+            let slowest: Vec<String> = self
+                .slowest
+                .iter()
+                .map(|(position, elapsed)| format!("#{position} ({})", format_duration(*elapsed)))
+                .collect();
+            write!(f, "\nSlowest inputs: {}", slowest.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the processing time of an input in milliseconds, e.g. `1.203ms`.
+#[must_use]
+pub fn format_duration(elapsed: Duration) -> String {
+    format!("{:.3}ms", elapsed.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,73 @@
+use bip32::DerivationPath;
+
+use crate::{parsers::flag_parser::parse_value_flags, traits::parsable::Parsable};
+
+use super::{derive_key_config::parse_derivation_path, parsing_error::ParsingError};
+
+/// The number of the keys derived by default.
+const DEFAULT_COUNT: u32 = 1000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// The number of the keys to derive, the children `0` to `count - 1` of the path.
+    pub count: u32,
+    /// The path the derived keys are the children of.
+    pub path: DerivationPath,
+}
+
+impl Parsable for BenchConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [count, path]: [Option<String>; 2] = parse_value_flags(args, &["count", "path"])?
+            .try_into()
+            .unwrap_or_default();
+
+        let count = match count {
+            None => DEFAULT_COUNT,
+            Some(count) => count
+                .parse()
+                .ok()
+                .filter(|count| (1..=1 << 31).contains(count))
+                .ok_or_else(|| {
+                    ParsingError::new(&format!(
+                        "Value '{count}' of '--count' must be from the range [1,...,2^31]"
+                    ))
+                })?,
+        };
+
+        let path = path
+            .map(|path| parse_derivation_path(&path))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(BenchConfig { count, path })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{structs::bench_config::BenchConfig, traits::parsable::Parsable};
+
+    #[test]
+    fn test_bench_flags() {
+        let mut args = vec!["bench", "-", "--count", "10", "--path", "m/0h/1"];
+        assert_eq!(
+            BenchConfig::parse(&mut args),
+            Ok(BenchConfig {
+                count: 10,
+                path: "m/0'/1".parse().unwrap()
+            })
+        );
+        assert_eq!(args, vec!["bench", "-"]);
+
+        let mut args = vec!["bench", "-"];
+        assert_eq!(
+            BenchConfig::parse(&mut args).map(|config| config.count),
+            Ok(1000)
+        );
+
+        for count in ["0", "2147483649", "ten"] {
+            let mut args = vec!["bench", "-", "--count", count];
+            assert!(BenchConfig::parse(&mut args).is_err(), "{count}");
+        }
+    }
+}
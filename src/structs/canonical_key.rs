@@ -0,0 +1,24 @@
+use super::parsing_error::ParsingError;
+
+/// The canonical form the hex encoded public key of a key expression is printed in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CanonicalKey {
+    /// The key in lowercase hexadecimal.
+    Lowercase,
+    /// The key in lowercase hexadecimal, an uncompressed (04) key is compressed as well.
+    Compressed,
+}
+
+impl TryFrom<&str> for CanonicalKey {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "lowercase" => Ok(CanonicalKey::Lowercase),
+            "compressed" => Ok(CanonicalKey::Compressed),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported canonical key form '{value}', expected one of 'lowercase' or 'compressed'"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{parsers::flag_parser::parse_value_flag, traits::parsable::Parsable};
+
+use super::parsing_error::ParsingError;
+
+/// The longest wallet name Coldcard accepts.
+const MAX_NAME_LENGTH: usize = 20;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ColdcardExportConfig {
+    /// The name of the multisig wallet shown by Coldcard.
+    pub name: String,
+}
+
+impl Default for ColdcardExportConfig {
+    fn default() -> Self {
+        ColdcardExportConfig {
+            name: "Multisig".to_string(),
+        }
+    }
+}
+
+impl Parsable for ColdcardExportConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let name = match parse_value_flag(args, "name")? {
+            Some(name)
+                if !name.is_empty()
+                    && name.len() <= MAX_NAME_LENGTH
+                    && name.chars().all(|c| c.is_ascii_graphic() || c == ' ') =>
+            {
+                name
+            }
+            Some(name) => {
+                return Err(ParsingError::new(&format!(
+                    "Wallet name '{name}' must consist of 1 to {MAX_NAME_LENGTH} printable ASCII characters"
+                )))
+            }
+            None => ColdcardExportConfig::default().name,
+        };
+
+        Ok(ColdcardExportConfig { name })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{coldcard_export_config::ColdcardExportConfig, parsing_error::ParsingError},
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_name_flag_provided() {
+        let mut args = vec!["coldcard-export", "-", "--name", "Vault 2of3"];
+
+        assert_eq!(
+            ColdcardExportConfig::parse(&mut args),
+            Ok(ColdcardExportConfig {
+                name: "Vault 2of3".to_string()
+            })
+        );
+        assert_eq!(args, vec!["coldcard-export", "-"]);
+
+        let mut args = vec!["coldcard-export", "-"];
+        assert_eq!(
+            ColdcardExportConfig::parse(&mut args),
+            Ok(ColdcardExportConfig::default())
+        );
+    }
+
+    #[test]
+    fn test_invalid_name() {
+        for name in ["", "a name longer than twenty", "tab\tname", "náme"] {
+            let mut args = vec!["coldcard-export", "-", "--name", name];
+            assert!(ColdcardExportConfig::parse(&mut args).is_err(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    parsers::flag_parser::parse_value_flags, subcommands::utils::number::parse_threshold,
+    traits::parsable::Parsable,
+};
+
+use super::{compose_script::ComposeScript, parsing_error::ParsingError};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ComposeConfig {
+    pub script: ComposeScript,
+    /// The number of the signatures a multisig script requires, none for a single key script.
+    pub threshold: Option<usize>,
+}
+
+impl Parsable for ComposeConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [script, threshold]: [Option<String>; 2] =
+            parse_value_flags(args, &["script", "threshold"])?
+                .try_into()
+                .unwrap_or_default();
+
+        let script = script
+            .map(|script| ComposeScript::try_from(script.as_str()))
+            .transpose()?
+            .ok_or_else(|| {
+                ParsingError::new("The compose sub-command requires '--script {script}'")
+            })?;
+
+        let threshold = threshold
+            .map(|threshold| {
+                parse_threshold(&threshold)
+                    .ok()
+                    .filter(|threshold| *threshold > 0)
+                    .ok_or_else(|| {
+                        ParsingError::new(&format!(
+                            "Value '{threshold}' of '--threshold' must be a positive integer"
+                        ))
+                    })
+            })
+            .transpose()?;
+
+        match (script, threshold) {
+            (ComposeScript::Multi { .. }, None) => Err(ParsingError::new(
+                "A multisig script requires '--threshold {k}'",
+            )),
+            (ComposeScript::Single(_), Some(_)) => Err(ParsingError::new(
+                "Only a multisig script takes '--threshold {k}'",
+            )),
+            _ => Ok(ComposeConfig { script, threshold }),
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            compose_config::ComposeConfig, compose_script::ComposeScript, script_type::ScriptType,
+        },
+        subcommands::utils::multisig::MultisigFormat,
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_compose_flags() {
+        let mut args = vec![
+            "compose",
+            "K1,K2",
+            "--script",
+            "wsh-sortedmulti",
+            "--threshold",
+            "2",
+        ];
+        assert_eq!(
+            ComposeConfig::parse(&mut args),
+            Ok(ComposeConfig {
+                script: ComposeScript::Multi {
+                    format: MultisigFormat::P2wsh,
+                    sorted: true,
+                },
+                threshold: Some(2),
+            })
+        );
+        assert_eq!(args, vec!["compose", "K1,K2"]);
+
+        let mut args = vec!["compose", "K", "--script", "tr"];
+        assert_eq!(
+            ComposeConfig::parse(&mut args),
+            Ok(ComposeConfig {
+                script: ComposeScript::Single(ScriptType::Tr),
+                threshold: None,
+            })
+        );
+
+        for mut args in [
+            vec!["compose", "K"],
+            vec!["compose", "K", "--script", "wsh-multi"],
+            vec!["compose", "K", "--script", "wsh-multi", "--threshold", "0"],
+            vec!["compose", "K", "--script", "wpkh", "--threshold", "1"],
+        ] {
+            assert!(ComposeConfig::parse(&mut args).is_err());
+        }
+    }
+}
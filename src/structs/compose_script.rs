@@ -0,0 +1,102 @@
+use crate::subcommands::utils::multisig::MultisigFormat;
+
+use super::{key_context::KeyContext, parsing_error::ParsingError, script_type::ScriptType};
+
+/// The most keys of a `multi(...)` in a P2SH redeem script, which may not exceed 520 bytes.
+const MAX_P2SH_MULTI_KEYS: usize = 15;
+/// The most keys of a `multi(...)` in a witness script.
+const MAX_WITNESS_MULTI_KEYS: usize = 20;
+
+/// The script the compose sub-command assembles around the key expressions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ComposeScript {
+    /// A script of a single key, e.g. `wpkh(KEY)`.
+    Single(ScriptType),
+    /// A `multi(...)` (or with `sorted`, a `sortedmulti(...)`) script in the address format.
+    Multi {
+        format: MultisigFormat,
+        sorted: bool,
+    },
+}
+
+impl ComposeScript {
+    /// Returns the context the key expressions of the script appear in.
+    #[must_use]
+    pub fn key_context(self) -> KeyContext {
+        match self {
+            ComposeScript::Single(script_type) => script_type.address_type().key_context(),
+            ComposeScript::Multi {
+                format: MultisigFormat::P2sh,
+                ..
+            } => KeyContext::P2sh,
+            ComposeScript::Multi { .. } => KeyContext::SegwitV0,
+        }
+    }
+
+    /// Returns the most key expressions the script can hold.
+    #[must_use]
+    pub fn max_keys(self) -> usize {
+        match self {
+            ComposeScript::Single(_) => 1,
+            ComposeScript::Multi {
+                format: MultisigFormat::P2sh,
+                ..
+            } => MAX_P2SH_MULTI_KEYS,
+            ComposeScript::Multi { .. } => MAX_WITNESS_MULTI_KEYS,
+        }
+    }
+}
+
+impl TryFrom<&str> for ComposeScript {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let multi = |format, sorted| Ok(ComposeScript::Multi { format, sorted });
+
+        match value {
+            "sh-multi" => multi(MultisigFormat::P2sh, false),
+            "wsh-multi" => multi(MultisigFormat::P2wsh, false),
+            "sh-wsh-multi" => multi(MultisigFormat::P2shP2wsh, false),
+            "sh-sortedmulti" => multi(MultisigFormat::P2sh, true),
+            "wsh-sortedmulti" => multi(MultisigFormat::P2wsh, true),
+            "sh-wsh-sortedmulti" => multi(MultisigFormat::P2shP2wsh, true),
+            _ => ScriptType::try_from(value)
+                .map(ComposeScript::Single)
+                .map_err(|_| {
+                    ParsingError::new(&format!(
+                        "Unsupported script '{value}', expected one of 'pkh', 'sh-wpkh', 'wpkh', 'tr', \
+                         '{{sh,wsh,sh-wsh}}-multi' or '{{sh,wsh,sh-wsh}}-sortedmulti'"
+                    ))
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compose_script_parsing() {
+        assert_eq!(
+            ComposeScript::try_from("wpkh"),
+            Ok(ComposeScript::Single(ScriptType::Wpkh))
+        );
+        assert_eq!(
+            ComposeScript::try_from("sh-wsh-sortedmulti"),
+            Ok(ComposeScript::Multi {
+                format: MultisigFormat::P2shP2wsh,
+                sorted: true
+            })
+        );
+        assert_eq!(
+            ComposeScript::try_from("sh-multi").map(ComposeScript::key_context),
+            Ok(KeyContext::P2sh)
+        );
+        assert_eq!(
+            ComposeScript::try_from("wsh-multi").map(ComposeScript::max_keys),
+            Ok(20)
+        );
+        assert!(ComposeScript::try_from("wsh").is_err());
+    }
+}
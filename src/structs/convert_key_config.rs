@@ -0,0 +1,81 @@
+use crate::{parsers::flag_parser::parse_boolean_flag, traits::parsable::Parsable};
+
+use super::parsing_error::ParsingError;
+
+/// The direction of the convert-key sub-command.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConvertKeyMode {
+    /// Decomposes an extended key into its BIP 32 fields.
+    Decode,
+    /// Reassembles an extended key from its BIP 32 fields.
+    Encode,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConvertKeyConfig {
+    pub mode: ConvertKeyMode,
+}
+
+impl Parsable for ConvertKeyConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let mode = match (
+            parse_boolean_flag(args, "decode"),
+            parse_boolean_flag(args, "encode"),
+        ) {
+            (true, false) => ConvertKeyMode::Decode,
+            (false, true) => ConvertKeyMode::Encode,
+            (true, true) => {
+                return Err(ParsingError::new(
+                    "use only '--decode' or '--encode', not both",
+                ))
+            }
+            (false, false) => {
+                return Err(ParsingError::new(
+                    "The convert-key sub-command requires either '--decode' or '--encode'",
+                ))
+            }
+        };
+
+        Ok(ConvertKeyConfig { mode })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            convert_key_config::{ConvertKeyConfig, ConvertKeyMode},
+            parsing_error::ParsingError,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_mode_flags_provided() {
+        let mut args = vec!["convert-key", "--decode", "xpub"];
+        assert_eq!(
+            ConvertKeyConfig::parse(&mut args),
+            Ok(ConvertKeyConfig {
+                mode: ConvertKeyMode::Decode
+            })
+        );
+        assert_eq!(args, vec!["convert-key", "xpub"]);
+
+        let mut args = vec!["convert-key", "--encode", "-"];
+        assert_eq!(
+            ConvertKeyConfig::parse(&mut args),
+            Ok(ConvertKeyConfig {
+                mode: ConvertKeyMode::Encode
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_mode_flags() {
+        let mut args = vec!["convert-key", "--decode", "--encode", "xpub"];
+        assert!(ConvertKeyConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["convert-key", "xpub"];
+        assert!(ConvertKeyConfig::parse(&mut args).is_err());
+    }
+}
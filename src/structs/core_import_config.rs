@@ -0,0 +1,81 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flags},
+    traits::parsable::Parsable,
+};
+
+use super::parsing_error::ParsingError;
+
+/// Where the descriptors are imported with `--import-to-core`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CoreImportConfig {
+    /// The JSON-RPC endpoint of the Bitcoin Core wallet, e.g. `http://127.0.0.1:8332/wallet/watchonly`.
+    pub rpc_url: String,
+    /// The path to the cookie file holding the RPC credentials (`.cookie` in the data directory).
+    pub rpc_cookie: String,
+}
+
+impl Parsable for Option<CoreImportConfig> {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let import_to_core = parse_boolean_flag(args, "import-to-core");
+        let [rpc_url, rpc_cookie]: [Option<String>; 2] =
+            parse_value_flags(args, &["rpc-url", "rpc-cookie"])?
+                .try_into()
+                .unwrap_or_default();
+
+        match (import_to_core, rpc_url, rpc_cookie) {
+            (true, Some(rpc_url), Some(rpc_cookie)) => Ok(Some(CoreImportConfig {
+                rpc_url,
+                rpc_cookie,
+            })),
+            (true, _, _) => Err(ParsingError::new(
+                "'--import-to-core' requires both '--rpc-url' and '--rpc-cookie'",
+            )),
+            (false, None, None) => Ok(None),
+            (false, _, _) => Err(ParsingError::new(
+                "'--rpc-url' and '--rpc-cookie' can only be used with '--import-to-core'",
+            )),
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{core_import_config::CoreImportConfig, parsing_error::ParsingError},
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_import_flags_provided() {
+        let mut args = vec![
+            "wallet-init",
+            "-",
+            "--import-to-core",
+            "--rpc-url",
+            "http://127.0.0.1:8332/wallet/watchonly",
+            "--rpc-cookie",
+            "/home/user/.bitcoin/.cookie",
+        ];
+
+        assert_eq!(
+            Option::<CoreImportConfig>::parse(&mut args),
+            Ok(Some(CoreImportConfig {
+                rpc_url: "http://127.0.0.1:8332/wallet/watchonly".to_string(),
+                rpc_cookie: "/home/user/.bitcoin/.cookie".to_string(),
+            }))
+        );
+        assert_eq!(args, vec!["wallet-init", "-"]);
+
+        let mut args = vec!["wallet-init", "-"];
+        assert_eq!(Option::<CoreImportConfig>::parse(&mut args), Ok(None));
+    }
+
+    #[test]
+    fn test_incomplete_import_flags() {
+        let mut args = vec!["wallet-init", "-", "--import-to-core", "--rpc-url", "x"];
+        assert!(Option::<CoreImportConfig>::parse(&mut args).is_err());
+
+        let mut args = vec!["wallet-init", "-", "--rpc-cookie", "x"];
+        assert!(Option::<CoreImportConfig>::parse(&mut args).is_err());
+    }
+}
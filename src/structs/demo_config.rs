@@ -0,0 +1,76 @@
+use crate::{parsers::flag_parser::parse_value_flags, traits::parsable::Parsable};
+
+use super::{network::Network, parsing_error::ParsingError, script_type::ScriptType};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DemoConfig {
+    /// The script type of the example account, chosen by its purpose (84 by default).
+    pub script_type: ScriptType,
+    pub network: Network,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        DemoConfig {
+            script_type: ScriptType::Wpkh,
+            network: Network::default(),
+        }
+    }
+}
+
+impl Parsable for DemoConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [purpose, network]: [Option<String>; 2] =
+            parse_value_flags(args, &["purpose", "network"])?
+                .try_into()
+                .unwrap_or_default();
+
+        let script_type = purpose
+            .map(|purpose| ScriptType::from_purpose(&purpose))
+            .transpose()?
+            .unwrap_or(ScriptType::Wpkh);
+
+        let network = network
+            .map(|network| Network::try_from(network.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(DemoConfig {
+            script_type,
+            network,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{demo_config::DemoConfig, network::Network, script_type::ScriptType},
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_demo_flags() {
+        let mut args = vec!["demo", "1", "--purpose", "86", "--network", "testnet"];
+        assert_eq!(
+            DemoConfig::parse(&mut args),
+            Ok(DemoConfig {
+                script_type: ScriptType::Tr,
+                network: Network::Testnet,
+            })
+        );
+        assert_eq!(args, vec!["demo", "1"]);
+
+        let mut args = vec!["demo", "1"];
+        assert_eq!(
+            DemoConfig::parse(&mut args),
+            Ok(DemoConfig {
+                script_type: ScriptType::Wpkh,
+                network: Network::Mainnet,
+            })
+        );
+
+        let mut args = vec!["demo", "1", "--purpose", "45"];
+        assert!(DemoConfig::parse(&mut args).is_err());
+    }
+}
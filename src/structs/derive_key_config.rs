@@ -1,34 +1,72 @@
 use bip32::DerivationPath;
 
-use crate::{parsers::flag_parser::parse_value_flag, traits::parsable::Parsable};
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flag},
+    subcommands::utils::number::parse_path_steps,
+    traits::parsable::Parsable,
+};
 
 use super::parsing_error::ParsingError;
 
 #[derive(Debug, PartialEq, Eq, Default)]
 pub struct DeriveKeyConfig {
     pub path: DerivationPath,
+    /// Whether the key at every prefix of the path is printed, instead of only the derived key.
+    pub show_intermediate: bool,
+    /// Whether the derivation from an extended public key stops before the first hardened step of the path,
+    /// instead of failing.
+    pub split_at_hardened: bool,
+    /// Whether only the plan of the derivation (the kind of the input, the full path and the keys to derive) is
+    /// printed, without deriving any key.
+    pub plan: bool,
 }
 
 impl Parsable for DeriveKeyConfig {
     fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
         let path = parse_value_flag(args, "path")?
-            .map(|mut raw_path| {
-                match raw_path.chars().nth(0) {
-                    Some('/') => raw_path.insert(0, 'm'),
-                    _ => raw_path.insert_str(0, "m/"),
-                }
-
-                raw_path
-                    .to_lowercase()
-                    .parse::<DerivationPath>()
-                    .map_err(|err| ParsingError {
-                        message: err.to_string(),
-                    })
-            })
+            .map(|raw_path| parse_derivation_path(&raw_path))
             .transpose()?
             .unwrap_or("m".parse()?);
-        Ok(DeriveKeyConfig { path })
+        let show_intermediate = parse_boolean_flag(args, "show-intermediate");
+        let split_at_hardened = parse_boolean_flag(args, "split-at-hardened");
+        let plan = parse_boolean_flag(args, "plan");
+        if plan && show_intermediate {
+            return Err(ParsingError::new(
+                "use only '--plan' or '--show-intermediate', not both",
+            ));
+        }
+        Ok(DeriveKeyConfig {
+            path,
+            show_intermediate,
+            split_at_hardened,
+            plan,
+        })
+    }
+}
+
+/// Parses a derivation path, with or without the leading `m/` or `/`.
+///
+/// A single leading `m` (or `M`) is stripped before the path is normalized, so a pasted absolute path such as
+/// `m/44h/0h/0h` is not mangled into `m/m/44h/...`, and `m` alone is the empty path. The hardened indexes may be
+/// marked with any of `h`, `H` or `'`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the path is malformed (including a repeated `m`) or an index is out of the range
+/// [0,...,2^31-1].
+pub fn parse_derivation_path(raw_path: &str) -> Result<DerivationPath, ParsingError> {
+    let raw_path = raw_path.to_lowercase();
+    let relative_path = match raw_path.strip_prefix('m') {
+        Some("") => return Ok(DerivationPath::default()),
+        Some(rest) => rest.strip_prefix('/').unwrap_or(&raw_path),
+        None => raw_path.strip_prefix('/').unwrap_or(&raw_path),
+    };
+
+    let mut path = DerivationPath::default();
+    for step in parse_path_steps(relative_path)? {
+        path.push(step);
     }
+    Ok(path)
 }
 
 mod tests {
@@ -37,7 +75,10 @@ mod tests {
 
     #[allow(unused_imports)]
     use crate::{
-        structs::{derive_key_config::DeriveKeyConfig, parsing_error::ParsingError},
+        structs::{
+            derive_key_config::{parse_derivation_path, DeriveKeyConfig},
+            parsing_error::ParsingError,
+        },
         traits::parsable::Parsable,
     };
 
@@ -52,7 +93,12 @@ mod tests {
 
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Ok(DeriveKeyConfig { path: parsed_path })
+            Ok(DeriveKeyConfig {
+                path: parsed_path,
+                show_intermediate: false,
+                split_at_hardened: false,
+                plan: false
+            })
         )
     }
 
@@ -67,24 +113,110 @@ mod tests {
 
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Ok(DeriveKeyConfig { path: parsed_path })
+            Ok(DeriveKeyConfig {
+                path: parsed_path,
+                show_intermediate: false,
+                split_at_hardened: false,
+                plan: false
+            })
         )
     }
 
     #[test]
-    fn test_invalid_path_slash_only() {
-        let path = "/";
+    fn test_valid_path_with_master_prefix() {
+        let path = "m/300/500h";
         let mut args = vec!["derive-key", "--path", path];
 
-        let path_parse_error = format!("m{path}")
-            .parse::<DerivationPath>()
-            .expect_err("Invalid path should not be parsed.");
+        assert_eq!(
+            DeriveKeyConfig::parse(&mut args),
+            Ok(DeriveKeyConfig {
+                path: path.parse().unwrap(),
+                show_intermediate: false,
+                split_at_hardened: false,
+                plan: false
+            })
+        )
+    }
+
+    #[test]
+    fn test_master_prefix_edge_cases() {
+        for (path, expected) in [
+            ("m", "m"),
+            ("M", "m"),
+            ("M/44H/0h/0'", "m/44'/0'/0'"),
+            ("m/0", "m/0"),
+        ] {
+            assert_eq!(
+                parse_derivation_path(path),
+                Ok(expected.parse().unwrap()),
+                "{path}"
+            );
+        }
+
+        for path in ["m/", "m//0", "m/m/44h", "/m/44h", "mm/0", "m44h", "m/0/m"] {
+            assert!(parse_derivation_path(path).is_err(), "{path}");
+        }
+    }
+
+    #[test]
+    fn test_show_intermediate_flag_provided() {
+        let mut args = vec!["derive-key", "--show-intermediate", "--path", "0h/1", "-"];
+
+        assert_eq!(
+            DeriveKeyConfig::parse(&mut args),
+            Ok(DeriveKeyConfig {
+                path: "m/0'/1".parse().unwrap(),
+                show_intermediate: true,
+                split_at_hardened: false,
+                plan: false
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "-"]);
+    }
+
+    #[test]
+    fn test_split_at_hardened_flag_provided() {
+        let mut args = vec!["derive-key", "xpub", "--split-at-hardened"];
 
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Err(ParsingError {
-                message: path_parse_error.to_string()
+            Ok(DeriveKeyConfig {
+                path: DerivationPath::default(),
+                show_intermediate: false,
+                split_at_hardened: true,
+                plan: false
             })
+        );
+        assert_eq!(args, vec!["derive-key", "xpub"]);
+    }
+
+    #[test]
+    fn test_plan_flag_provided() {
+        let mut args = vec!["derive-key", "--plan", "--path", "44h/0h", "-"];
+
+        assert_eq!(
+            DeriveKeyConfig::parse(&mut args),
+            Ok(DeriveKeyConfig {
+                path: "m/44'/0'".parse().unwrap(),
+                show_intermediate: false,
+                split_at_hardened: false,
+                plan: true
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "-"]);
+
+        let mut args = vec!["derive-key", "--plan", "--show-intermediate", "-"];
+        assert!(DeriveKeyConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_invalid_path_slash_only() {
+        let path = "/";
+        let mut args = vec!["derive-key", "--path", path];
+
+        assert_eq!(
+            DeriveKeyConfig::parse(&mut args),
+            Err(ParsingError::new("The derivation path has an empty step"))
         )
     }
 
@@ -93,15 +225,9 @@ mod tests {
         let path = "1/";
         let mut args = vec!["derive-key", "--path", path];
 
-        let path_parse_error = format!("m/{path}")
-            .parse::<DerivationPath>()
-            .expect_err("Invalid path should not be parsed.");
-
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Err(ParsingError {
-                message: path_parse_error.to_string()
-            })
+            Err(ParsingError::new("The derivation path has an empty step"))
         )
     }
 
@@ -110,15 +236,11 @@ mod tests {
         let path = "a";
         let mut args = vec!["derive-key", "--path", path];
 
-        let path_parse_error = format!("m/{path}")
-            .parse::<DerivationPath>()
-            .expect_err("Invalid path should not be parsed.");
-
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Err(ParsingError {
-                message: path_parse_error.to_string()
-            })
+            Err(ParsingError::new(
+                "The derivation index 'a' must be a decimal number without a sign"
+            ))
         )
     }
 
@@ -127,15 +249,9 @@ mod tests {
         let path = "//";
         let mut args = vec!["derive-key", "--path", path];
 
-        let path_parse_error = format!("m{path}")
-            .parse::<DerivationPath>()
-            .expect_err("Invalid path should not be parsed.");
-
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Err(ParsingError {
-                message: path_parse_error.to_string()
-            })
+            Err(ParsingError::new("The derivation path has an empty step"))
         )
     }
 
@@ -144,15 +260,11 @@ mod tests {
         let path = "/2147483648";
         let mut args = vec!["derive-key", "--path", path];
 
-        let path_parse_error = format!("m{path}")
-            .parse::<DerivationPath>()
-            .expect_err("Invalid path should not be parsed.");
-
         assert_eq!(
             DeriveKeyConfig::parse(&mut args),
-            Err(ParsingError {
-                message: path_parse_error.to_string()
-            })
+            Err(ParsingError::new(
+                "The derivation index '2147483648' is out of the range [0,...,2^31-1]"
+            ))
         )
     }
 }
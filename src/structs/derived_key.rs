@@ -0,0 +1,82 @@
+use std::fmt::Display;
+
+use bip32::{ChildNumber, Prefix, XPrv, XPub};
+
+use crate::{subcommands::utils::hexadecimal::encode_hex, traits::output_fields::OutputFields};
+
+/// The extended keys derived by the derive-key sub-command, the private one is only known when
+/// deriving from a seed or an extended private key.
+pub struct DerivedKey {
+    pub xpub: XPub,
+    pub xprv: Option<XPrv>,
+    /// The cumulative key origin (e.g. `[d34db33f/44h/0h/0h/1]`) of a key derived from an input with a key
+    /// origin, which prefixes both keys in the output.
+    pub origin: Option<String>,
+}
+
+impl DerivedKey {
+    /// Derives the child keys (the private one only if this private key is known).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`bip32::Error`] if the child cannot be derived, e.g. a hardened child of a public key.
+    pub fn derive_child(&self, child_number: ChildNumber) -> Result<DerivedKey, bip32::Error> {
+        match &self.xprv {
+            Some(xprv) => {
+                let xprv = xprv.derive_child(child_number)?;
+                Ok(DerivedKey {
+                    xpub: xprv.public_key(),
+                    xprv: Some(xprv),
+                    origin: None,
+                })
+            }
+            None => Ok(DerivedKey {
+                xpub: self.xpub.derive_child(child_number)?,
+                xprv: None,
+                origin: None,
+            }),
+        }
+    }
+
+    fn xprv_string(&self) -> String {
+        self.xprv
+            .as_ref()
+            .map(|xprv| xprv.to_string(Prefix::XPRV).to_string())
+            .unwrap_or_default()
+    }
+
+    fn origin_string(&self) -> &str {
+        self.origin.as_deref().unwrap_or_default()
+    }
+}
+
+impl Display for DerivedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let origin = self.origin_string();
+        let xprv = self.xprv_string();
+        write!(
+            f,
+            "{origin}{}:{}",
+            self.xpub.to_string(Prefix::XPUB),
+            if xprv.is_empty() {
+                xprv
+            } else {
+                format!("{origin}{xprv}")
+            }
+        )
+    }
+}
+
+impl OutputFields for DerivedKey {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let attrs = self.xpub.attrs();
+        vec![
+            ("xpub", self.xpub.to_string(Prefix::XPUB)),
+            ("xprv", self.xprv_string()),
+            ("fingerprint", encode_hex(&self.xpub.fingerprint())),
+            ("parent_fingerprint", encode_hex(&attrs.parent_fingerprint)),
+            ("depth", attrs.depth.to_string()),
+            ("origin", self.origin_string().to_string()),
+        ]
+    }
+}
@@ -0,0 +1,78 @@
+/// The kind of a [`ParsingError`](super::parsing_error::ParsingError), letting consumers of the JSON output
+/// route the failures without matching the messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ErrorKind {
+    /// The input (or an option) is not valid, the kind of all errors not classified more precisely.
+    #[default]
+    InvalidInput,
+    /// The checksum of a script expression does not have the expected length, or it is preceded by more than one
+    /// separator.
+    InvalidChecksum,
+    /// The checksum of a script expression (or of a WIF key, or of a SLIP-39 share) does not match.
+    ChecksumMismatch,
+    /// The checksum of a script expression is required for the verification, but missing.
+    MissingChecksum,
+    /// An extended key does not belong to the selected network.
+    NetworkMismatch,
+    /// The key origin of a key expression does not match the master key.
+    OriginMismatch,
+    /// An address of the list does not match the address the descriptor derives at its index.
+    AddressMismatch,
+    /// The change descriptor differs from the receive descriptor in more than the branch of its keys.
+    ChangeMismatch,
+    /// A warning of the lint sub-command is turned into an error by `--deny`.
+    DeniedWarning,
+    /// A required key is missing from the descriptor, or a forbidden one appears in it.
+    PolicyViolation,
+    /// The output would contain private material, which is refused by `--no-private`.
+    PrivateOutput,
+    /// A hardened child is to be derived from an extended public key.
+    HardenedFromXpub,
+    /// A character is not allowed at its position, e.g. outside of the charset of BIP 380 or in a key expression.
+    InvalidCharacter,
+    /// The threshold of a multisig is not a canonical number, or it is out of the range of its keys.
+    InvalidThreshold,
+    /// A key cannot be decoded (e.g. a hex encoded public key off the curve) or is not allowed in its context.
+    InvalidKey,
+    /// A JSON input (a request of `--rpc` or `serve --http`, or the output of HWI) is not valid JSON, or not of the
+    /// expected form.
+    InvalidJson,
+    /// A seed, the entropy or the mnemonic of a seed, or a master secret is malformed or of an unsupported length.
+    InvalidSeed,
+    /// A SLIP-39 or `SeedXOR` share is malformed, or the shares do not belong together or are not enough.
+    InvalidShare,
+}
+
+impl ErrorKind {
+    /// The stable code of the kind (e.g. `E001`), printed with every error so that tools and documentation can
+    /// reference the exact condition. The codes are never reassigned, a new kind gets the next free one.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidInput => "E000",
+            ErrorKind::ChecksumMismatch => "E001",
+            ErrorKind::InvalidChecksum => "E002",
+            ErrorKind::MissingChecksum => "E003",
+            ErrorKind::NetworkMismatch => "E004",
+            ErrorKind::OriginMismatch => "E005",
+            ErrorKind::PrivateOutput => "E006",
+            ErrorKind::AddressMismatch => "E007",
+            ErrorKind::DeniedWarning => "E008",
+            ErrorKind::PolicyViolation => "E009",
+            ErrorKind::ChangeMismatch => "E010",
+            ErrorKind::HardenedFromXpub => "E011",
+            ErrorKind::InvalidCharacter => "E012",
+            ErrorKind::InvalidThreshold => "E013",
+            ErrorKind::InvalidKey => "E014",
+            ErrorKind::InvalidJson => "E015",
+            ErrorKind::InvalidSeed => "E016",
+            ErrorKind::InvalidShare => "E017",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
@@ -0,0 +1,76 @@
+use super::{network::Network, parsing_error::ParsingError};
+
+/// The placeholder of a custom explorer URL template the address is substituted for.
+const ADDRESS_PLACEHOLDER: &str = "{address}";
+
+/// The block explorer the URLs printed alongside the addresses point to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Explorer {
+    /// `https://mempool.space`.
+    Mempool,
+    /// `https://blockstream.info`.
+    Blockstream,
+    /// A URL template with the `{address}` placeholder, e.g. `https://explorer.example/address/{address}`.
+    Custom(String),
+}
+
+impl Explorer {
+    /// Returns the URL of the address on the explorer, the testnet addresses pointing to its testnet pages.
+    #[must_use]
+    pub fn address_url(&self, address: &str, network: Network) -> String {
+        let network_path = match network {
+            Network::Mainnet => "",
+            Network::Testnet => "/testnet",
+        };
+        match self {
+            Explorer::Mempool => format!("https://mempool.space{network_path}/address/{address}"),
+            Explorer::Blockstream => {
+                format!("https://blockstream.info{network_path}/address/{address}")
+            }
+            Explorer::Custom(template) => template.replace(ADDRESS_PLACEHOLDER, address),
+        }
+    }
+}
+
+impl TryFrom<&str> for Explorer {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "mempool" => Ok(Explorer::Mempool),
+            "blockstream" => Ok(Explorer::Blockstream),
+            template if template.contains(ADDRESS_PLACEHOLDER) => {
+                Ok(Explorer::Custom(template.to_string()))
+            }
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported explorer '{value}', expected 'mempool', 'blockstream' or a URL template with the '{ADDRESS_PLACEHOLDER}' placeholder"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+    #[test]
+    fn test_address_url() {
+        assert_eq!(
+            Explorer::try_from("mempool")
+                .map(|explorer| explorer.address_url(ADDRESS, Network::Mainnet)),
+            Ok(format!("https://mempool.space/address/{ADDRESS}"))
+        );
+        assert_eq!(
+            Explorer::Blockstream.address_url(ADDRESS, Network::Testnet),
+            format!("https://blockstream.info/testnet/address/{ADDRESS}")
+        );
+        assert_eq!(
+            Explorer::try_from("https://explorer.example/a/{address}?tab=utxo")
+                .map(|explorer| explorer.address_url(ADDRESS, Network::Mainnet)),
+            Ok(format!("https://explorer.example/a/{ADDRESS}?tab=utxo"))
+        );
+        assert!(Explorer::try_from("https://explorer.example/address/").is_err());
+    }
+}
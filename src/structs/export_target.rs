@@ -0,0 +1,24 @@
+use super::parsing_error::ParsingError;
+
+/// The wallet software the wallet-export sub-command produces the wallet file for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExportTarget {
+    /// The Specter Desktop wallet JSON, with the receive descriptor and the devices of the cosigners.
+    Specter,
+    /// The Sparrow wallet JSON, with the receive and change (multipath) descriptor.
+    Sparrow,
+}
+
+impl TryFrom<&str> for ExportTarget {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "specter" => Ok(ExportTarget::Specter),
+            "sparrow" => Ok(ExportTarget::Sparrow),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported export target '{value}', expected one of 'specter' or 'sparrow'"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Display;
+
+use bip32::ExtendedKey;
+
+use crate::{subcommands::utils::hexadecimal::encode_hex, traits::output_fields::OutputFields};
+
+/// The BIP 32 fields of an extended key, as decomposed by the convert-key sub-command.
+pub struct ExtendedKeyFields {
+    pub key: ExtendedKey,
+}
+
+impl Display for ExtendedKeyFields {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let fields = self.fields();
+        let lines: Vec<String> = fields
+            .iter()
+            .skip(1)
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl OutputFields for ExtendedKeyFields {
+    /// The Base58 encoded key followed by its fields in hexadecimal (only the fields are displayed).
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let attrs = &self.key.attrs;
+        vec![
+            ("encoded", self.key.to_string()),
+            ("version", encode_hex(&self.key.prefix.to_bytes())),
+            ("depth", encode_hex(&[attrs.depth])),
+            ("parent_fingerprint", encode_hex(&attrs.parent_fingerprint)),
+            ("child_number", encode_hex(&attrs.child_number.to_bytes())),
+            ("chain_code", encode_hex(&attrs.chain_code)),
+            ("key", encode_hex(&self.key.key_bytes)),
+        ]
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flag},
+    traits::parsable::Parsable,
+};
+
+use super::parsing_error::ParsingError;
+
+/// The physical source of the entropy of the generated seed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EntropySource {
+    /// Rolls of a six-sided die, `1` to `6`.
+    Dice,
+    /// Flips of a coin, `0`/`1` or `H`/`T`.
+    Coins,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GenerateSeedConfig {
+    pub source: EntropySource,
+    /// The number of words of the generated mnemonic, 12 (128 bits of entropy) or 24 (256 bits).
+    pub words: usize,
+}
+
+impl Parsable for GenerateSeedConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let source = match (
+            parse_boolean_flag(args, "dice"),
+            parse_boolean_flag(args, "coins"),
+        ) {
+            (true, false) => EntropySource::Dice,
+            (false, true) => EntropySource::Coins,
+            (true, true) => {
+                return Err(ParsingError::new(
+                    "use only '--dice' or '--coins', not both",
+                ))
+            }
+            (false, false) => {
+                return Err(ParsingError::new(
+                    "The generate-seed sub-command requires either '--dice' or '--coins'",
+                ))
+            }
+        };
+
+        let words = match parse_value_flag(args, "words")?.as_deref() {
+            None | Some("24") => 24,
+            Some("12") => 12,
+            Some(words) => {
+                return Err(ParsingError::new(&format!(
+                    "Unsupported number of words '{words}', expected one of '12' or '24'"
+                )))
+            }
+        };
+
+        Ok(GenerateSeedConfig { source, words })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            generate_seed_config::{EntropySource, GenerateSeedConfig},
+            parsing_error::ParsingError,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_flags_provided() {
+        let mut args = vec!["generate-seed", "--dice", "-"];
+        assert_eq!(
+            GenerateSeedConfig::parse(&mut args),
+            Ok(GenerateSeedConfig {
+                source: EntropySource::Dice,
+                words: 24
+            })
+        );
+        assert_eq!(args, vec!["generate-seed", "-"]);
+
+        let mut args = vec!["generate-seed", "--words", "12", "--coins", "-"];
+        assert_eq!(
+            GenerateSeedConfig::parse(&mut args),
+            Ok(GenerateSeedConfig {
+                source: EntropySource::Coins,
+                words: 12
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        let mut args = vec!["generate-seed", "--dice", "--coins", "-"];
+        assert!(GenerateSeedConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["generate-seed", "-"];
+        assert!(GenerateSeedConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["generate-seed", "--dice", "--words", "18", "-"];
+        assert!(GenerateSeedConfig::parse(&mut args).is_err());
+    }
+}
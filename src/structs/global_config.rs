@@ -0,0 +1,563 @@
+use crate::{
+    parsers::flag_parser::{
+        parse_boolean_flag, parse_repeated_value_flag, parse_value_flag, parse_value_flags,
+    },
+    subcommands::utils::ur::MIN_FRAGMENT_LENGTH,
+    traits::parsable::Parsable,
+};
+
+use super::{
+    core_import_config::CoreImportConfig, output_format::OutputFormat, parsing_error::ParsingError,
+};
+
+/// Options shared by all sub-commands, affecting how the inputs are read and processed.
+#[derive(Debug, PartialEq, Eq, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct GlobalConfig {
+    /// The source to follow indefinitely, `-` for the standard input or a path to a file.
+    pub watch: Option<String>,
+    /// The template to format every result with, see [`render_template`](crate::subcommands::utils::output_template::render_template).
+    pub template: Option<String>,
+    /// How the results and the errors are printed.
+    pub output: OutputFormat,
+    /// Whether the results are buffered and emitted lexicographically sorted.
+    pub sort: bool,
+    /// Whether the results are suppressed, leaving only the errors and the exit code.
+    pub check: bool,
+    /// Whether the processing succeeds as soon as an input succeeds (and fails only if all of them fail).
+    pub any: bool,
+    /// Whether a summary of the processed inputs is printed to the standard error after the batch.
+    pub stats: bool,
+    /// Whether the processing time of every input is reported, and the slowest inputs with the `--stats` summary.
+    pub timing: bool,
+    /// The files to read the inputs from line by line, one after another (`-` standing for the standard input),
+    /// instead of the positional arguments.
+    pub input_files: Vec<String>,
+    /// Whether the warning about secrets passed as command-line arguments is silenced.
+    pub no_secret_warning: bool,
+    /// Whether a result containing private material (an extended private key or a WIF private key) is refused.
+    pub no_private: bool,
+    /// The file the logs are appended to instead of the standard error.
+    pub log_file: Option<String>,
+    /// The longest fragment of a multi-part uniform resource of `--output ur`, in bytes (the default if not given).
+    pub ur_max_fragment: Option<usize>,
+    /// The Bitcoin Core wallet the resulting descriptors are imported to, if any.
+    pub core_import: Option<CoreImportConfig>,
+    /// How many inputs are skipped before the processing starts.
+    pub skip: usize,
+    /// How many inputs (after the skipped ones) are processed at most.
+    pub limit: Option<usize>,
+    /// Whether the results of the repeated inputs are processed again instead of being cached.
+    pub no_cache: bool,
+    /// The fields the JSON output is limited to, in the given order (all of them if empty).
+    pub select: Vec<String>,
+}
+
+impl Parsable for GlobalConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [watch, template, skip, limit, log_file, output, ur_max_fragment]: [Option<String>; 7] =
+            parse_value_flags(
+                args,
+                &[
+                    "watch",
+                    "template",
+                    "skip",
+                    "limit",
+                    "log-file",
+                    "output",
+                    "ur-max-fragment",
+                ],
+            )?
+            .try_into()
+            .unwrap_or_default();
+        let skip = skip
+            .map(|skip| parse_count(&skip, "skip"))
+            .transpose()?
+            .unwrap_or_default();
+        let limit = limit
+            .map(|limit| parse_count(&limit, "limit"))
+            .transpose()?;
+
+        let output = parse_output(args, output.as_deref())?;
+        if output != OutputFormat::Plain && template.is_some() {
+            return Err(ParsingError::new(&format!(
+                "use only '--template' or '--output {output}', not both"
+            )));
+        }
+
+        let select = parse_value_flag(args, "select")?
+            .map(|select| parse_select(&select))
+            .transpose()?
+            .unwrap_or_default();
+        if !select.is_empty() && !matches!(output, OutputFormat::Json | OutputFormat::Csv) {
+            return Err(ParsingError::new(
+                "'--select' can only be used with '--output json' or '--output csv'",
+            ));
+        }
+
+        let ur_max_fragment = ur_max_fragment
+            .map(|length| match parse_count(&length, "ur-max-fragment")? {
+                length if length >= MIN_FRAGMENT_LENGTH => Ok(length),
+                _ => Err(ParsingError::new(&format!(
+                    "Value '{length}' of '--ur-max-fragment' must be at least {MIN_FRAGMENT_LENGTH}"
+                ))),
+            })
+            .transpose()?;
+        if ur_max_fragment.is_some() && output != OutputFormat::Ur {
+            return Err(ParsingError::new(
+                "'--ur-max-fragment' can only be used with '--output ur'",
+            ));
+        }
+
+        let sort = parse_boolean_flag(args, "sort");
+        if sort && watch.is_some() {
+            return Err(ParsingError::new(
+                "use only '--sort' or '--watch', not both, the watched inputs never end to be sorted",
+            ));
+        }
+
+        let check = parse_boolean_flag(args, "check");
+        let any = parse_boolean_flag(args, "any");
+        if any && sort {
+            return Err(ParsingError::new(
+                "use only '--any' or '--sort', not both, the processing stops at the first success",
+            ));
+        }
+        let stats = parse_boolean_flag(args, "stats");
+        let timing = parse_boolean_flag(args, "timing");
+        let input_files = parse_repeated_value_flag(args, "input-file")?;
+        let no_secret_warning = parse_boolean_flag(args, "no-secret-warning");
+        let no_private = parse_boolean_flag(args, "no-private");
+        let no_cache = parse_boolean_flag(args, "no-cache");
+        let core_import = Option::<CoreImportConfig>::parse(args)?;
+
+        Ok(GlobalConfig {
+            watch,
+            template,
+            output,
+            sort,
+            check,
+            any,
+            stats,
+            timing,
+            input_files,
+            no_secret_warning,
+            no_private,
+            log_file,
+            ur_max_fragment,
+            core_import,
+            skip,
+            limit,
+            no_cache,
+            select,
+        })
+    }
+}
+
+/// Parses the format of `--output` (plain by default), `--ur` being its shorthand for `--output ur`.
+fn parse_output(args: &mut Vec<&str>, output: Option<&str>) -> Result<OutputFormat, ParsingError> {
+    let output = output.map(OutputFormat::try_from).transpose()?;
+    match (parse_boolean_flag(args, "ur"), output) {
+        (true, Some(output)) if output != OutputFormat::Ur => Err(ParsingError::new(&format!(
+            "use only '--ur' or '--output {output}', not both"
+        ))),
+        (true, _) => Ok(OutputFormat::Ur),
+        (false, output) => Ok(output.unwrap_or_default()),
+    }
+}
+
+/// Parses the comma separated field names of `--select`.
+fn parse_select(value: &str) -> Result<Vec<String>, ParsingError> {
+    value
+        .split(',')
+        .map(|name| match name.trim() {
+            "" => Err(ParsingError::new(&format!(
+                "Value '{value}' of '--select' must be field names separated by commas"
+            ))),
+            name => Ok(name.to_string()),
+        })
+        .collect()
+}
+
+fn parse_count(value: &str, key: &str) -> Result<usize, ParsingError> {
+    value.parse().map_err(|_| {
+        ParsingError::new(&format!(
+            "Value '{value}' of '--{key}' must be a non-negative integer"
+        ))
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            global_config::GlobalConfig, output_format::OutputFormat, parsing_error::ParsingError,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_no_flags_provided() {
+        let mut args = vec!["key-expression", "input"];
+
+        assert_eq!(GlobalConfig::parse(&mut args), Ok(GlobalConfig::default()));
+        assert_eq!(args, vec!["key-expression", "input"]);
+    }
+
+    #[test]
+    fn test_watch_flag_provided() {
+        let mut args = vec!["key-expression", "--watch", "-"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                watch: Some("-".to_string()),
+                template: None,
+                output: OutputFormat::Plain,
+                sort: false,
+                check: false,
+                any: false,
+                stats: false,
+                timing: false,
+                input_files: vec![],
+                no_secret_warning: false,
+                no_private: false,
+                log_file: None,
+                ur_max_fragment: None,
+                core_import: None,
+                skip: 0,
+                limit: None,
+                no_cache: false,
+                select: vec![]
+            })
+        );
+        assert_eq!(args, vec!["key-expression"]);
+
+        let mut args = vec!["key-expression", "--watch"];
+        assert!(GlobalConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_template_flag_provided() {
+        let mut args = vec!["derive-key", "input", "--template", "{xpub},{fingerprint}"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                watch: None,
+                template: Some("{xpub},{fingerprint}".to_string()),
+                output: OutputFormat::Plain,
+                sort: false,
+                check: false,
+                any: false,
+                stats: false,
+                timing: false,
+                input_files: vec![],
+                no_secret_warning: false,
+                no_private: false,
+                log_file: None,
+                ur_max_fragment: None,
+                core_import: None,
+                skip: 0,
+                limit: None,
+                no_cache: false,
+                select: vec![]
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "input"]);
+    }
+
+    #[test]
+    fn test_sort_flag_provided() {
+        let mut args = vec!["key-expression", "-", "--sort"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                watch: None,
+                template: None,
+                output: OutputFormat::Plain,
+                sort: true,
+                check: false,
+                any: false,
+                stats: false,
+                timing: false,
+                input_files: vec![],
+                no_secret_warning: false,
+                no_private: false,
+                log_file: None,
+                ur_max_fragment: None,
+                core_import: None,
+                skip: 0,
+                limit: None,
+                no_cache: false,
+                select: vec![]
+            })
+        );
+
+        let mut args = vec!["key-expression", "--sort", "--watch", "-"];
+        assert!(GlobalConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_timing_flag_provided() {
+        let mut args = vec!["script-expression", "--timing", "--stats", "-"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                stats: true,
+                timing: true,
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+    }
+
+    #[test]
+    fn test_check_flag_provided() {
+        let mut args = vec!["script-expression", "--check", "-"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                check: true,
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+    }
+
+    #[test]
+    fn test_any_flag_provided() {
+        let mut args = vec!["script-expression", "--any", "--check", "-"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                check: true,
+                any: true,
+                ..GlobalConfig::default()
+            })
+        );
+
+        let mut args = vec!["script-expression", "--any", "--sort", "-"];
+        assert!(GlobalConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_skip_and_limit_flags_provided() {
+        let mut args = vec!["key-expression", "-", "--skip", "10", "--limit", "5"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                skip: 10,
+                limit: Some(5),
+                ..GlobalConfig::default()
+            })
+        );
+
+        let mut args = vec!["key-expression", "-", "--skip", "-1"];
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "Value '-1' of '--skip' must be a non-negative integer"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_input_file_flag_provided() {
+        let mut args = vec![
+            "script-expression",
+            "--input-file",
+            "a.txt",
+            "--input-file",
+            "b.txt",
+        ];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                input_files: vec!["a.txt".to_string(), "b.txt".to_string()],
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression"]);
+    }
+
+    #[test]
+    fn test_log_file_flag_provided() {
+        let mut args = vec!["derive-key", "input", "--log-file", "bip380.log"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                log_file: Some("bip380.log".to_string()),
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "input"]);
+    }
+
+    #[test]
+    fn test_output_flag_provided() {
+        let mut args = vec!["script-expression", "-", "--output", "json"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                output: OutputFormat::Json,
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+
+        for (format, output) in [
+            ("csv", OutputFormat::Csv),
+            ("ur", OutputFormat::Ur),
+            ("qr", OutputFormat::Qr),
+        ] {
+            let mut args = vec!["script-expression", "-", "--output", format];
+            assert_eq!(
+                GlobalConfig::parse(&mut args),
+                Ok(GlobalConfig {
+                    output,
+                    ..GlobalConfig::default()
+                })
+            );
+        }
+
+        let mut args = vec!["script-expression", "-", "--output", "xml"];
+        assert!(GlobalConfig::parse(&mut args).is_err());
+
+        let mut args = vec![
+            "derive-key",
+            "-",
+            "--output",
+            "json",
+            "--template",
+            "{xpub}",
+        ];
+        assert!(GlobalConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_ur_flags_provided() {
+        let mut args = vec!["derive-key", "-", "--ur", "--ur-max-fragment", "100"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                output: OutputFormat::Ur,
+                ur_max_fragment: Some(100),
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "-"]);
+
+        let mut args = vec!["derive-key", "-", "--output", "ur", "--ur"];
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                output: OutputFormat::Ur,
+                ..GlobalConfig::default()
+            })
+        );
+
+        for args in [
+            vec!["derive-key", "-", "--ur-max-fragment", "100"],
+            vec![
+                "derive-key",
+                "-",
+                "--output",
+                "qr",
+                "--ur-max-fragment",
+                "100",
+            ],
+            vec!["derive-key", "-", "--ur", "--ur-max-fragment", "9"],
+            vec!["derive-key", "-", "--ur", "--output", "json"],
+            vec!["derive-key", "-", "--ur", "--template", "{xpub}"],
+        ] {
+            let mut args = args;
+            assert!(GlobalConfig::parse(&mut args).is_err());
+        }
+    }
+
+    #[test]
+    fn test_secret_flags_provided() {
+        let mut args = vec!["derive-key", "--no-private", "-", "--no-secret-warning"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                no_secret_warning: true,
+                no_private: true,
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "-"]);
+    }
+
+    #[test]
+    fn test_select_flag_provided() {
+        let mut args = vec![
+            "derive-key",
+            "-",
+            "--output",
+            "json",
+            "--select",
+            "xpub, fingerprint",
+        ];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                output: OutputFormat::Json,
+                select: vec!["xpub".to_string(), "fingerprint".to_string()],
+                ..GlobalConfig::default()
+            })
+        );
+
+        let mut args = vec!["derive-key", "-", "--output", "csv", "--select", "xpub"];
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                output: OutputFormat::Csv,
+                select: vec!["xpub".to_string()],
+                ..GlobalConfig::default()
+            })
+        );
+
+        for args in [
+            vec!["derive-key", "-", "--select", "xpub"],
+            vec!["derive-key", "-", "--output", "qr", "--select", "xpub"],
+            vec![
+                "derive-key",
+                "-",
+                "--output",
+                "json",
+                "--select",
+                "xpub,,fingerprint",
+            ],
+        ] {
+            let mut args = args;
+            assert!(GlobalConfig::parse(&mut args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn test_no_cache_flag_provided() {
+        let mut args = vec!["derive-key", "-", "--no-cache"];
+
+        assert_eq!(
+            GlobalConfig::parse(&mut args),
+            Ok(GlobalConfig {
+                no_cache: true,
+                ..GlobalConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["derive-key", "-"]);
+    }
+}
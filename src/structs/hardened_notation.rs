@@ -0,0 +1,40 @@
+use super::parsing_error::ParsingError;
+
+/// The marker of the hardened indexes in the printed derivation paths.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HardenedNotation {
+    /// `h`, e.g. `m/84h/0h`.
+    #[default]
+    Lowercase,
+    /// `H`, e.g. `m/84H/0H`.
+    Uppercase,
+    /// `'`, e.g. `m/84'/0'`.
+    Apostrophe,
+}
+
+impl HardenedNotation {
+    /// Returns the character appended to the hardened indexes.
+    #[must_use]
+    pub fn marker(self) -> char {
+        match self {
+            HardenedNotation::Lowercase => 'h',
+            HardenedNotation::Uppercase => 'H',
+            HardenedNotation::Apostrophe => '\'',
+        }
+    }
+}
+
+impl TryFrom<&str> for HardenedNotation {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "h" => Ok(HardenedNotation::Lowercase),
+            "H" => Ok(HardenedNotation::Uppercase),
+            "'" => Ok(HardenedNotation::Apostrophe),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported hardened notation '{value}', expected one of 'h', 'H' or '''"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use super::parsing_error::ParsingError;
+
+/// Where hardened wildcards (`/*h`) are accepted in key expressions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HardenedWildcardPolicy {
+    /// Accepted anywhere, as BIP 380 allows.
+    #[default]
+    Allow,
+    /// Accepted only after extended private keys, as they cannot be derived from extended public keys.
+    XprvOnly,
+    /// Not accepted at all (e.g. for watch-only pipelines).
+    Forbid,
+}
+
+impl TryFrom<&str> for HardenedWildcardPolicy {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "allow" => Ok(HardenedWildcardPolicy::Allow),
+            "xprv-only" => Ok(HardenedWildcardPolicy::XprvOnly),
+            "forbid" => Ok(HardenedWildcardPolicy::Forbid),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported hardened wildcard policy '{value}', expected one of 'allow', 'xprv-only' or 'forbid'"
+            ))),
+        }
+    }
+}
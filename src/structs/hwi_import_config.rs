@@ -0,0 +1,56 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flag},
+    traits::parsable::Parsable,
+};
+
+use super::{network::Network, parsing_error::ParsingError};
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct HwiImportConfig {
+    /// Whether the key expressions of the descriptors are printed instead of the descriptors.
+    pub keys: bool,
+    /// The network all extended keys must belong to, the one of the first key if not set.
+    pub network: Option<Network>,
+}
+
+impl Parsable for HwiImportConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let keys = parse_boolean_flag(args, "keys");
+
+        let network = parse_value_flag(args, "network")?
+            .map(|network| Network::try_from(network.as_str()))
+            .transpose()?;
+
+        Ok(HwiImportConfig { keys, network })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{hwi_import_config::HwiImportConfig, network::Network},
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_hwi_import_flags() {
+        let mut args = vec!["hwi-import", "-", "--keys", "--network", "testnet"];
+        assert_eq!(
+            HwiImportConfig::parse(&mut args),
+            Ok(HwiImportConfig {
+                keys: true,
+                network: Some(Network::Testnet),
+            })
+        );
+        assert_eq!(args, vec!["hwi-import", "-"]);
+
+        let mut args = vec!["hwi-import", "-"];
+        assert_eq!(
+            HwiImportConfig::parse(&mut args),
+            Ok(HwiImportConfig::default())
+        );
+
+        let mut args = vec!["hwi-import", "-", "--network", "signet"];
+        assert!(HwiImportConfig::parse(&mut args).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use super::parsing_error::ParsingError;
+
+/// The kind of an input of the process sub-command, which decides the sub-command it is routed to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InputKind {
+    /// A hexadecimal seed, derived by derive-key.
+    Seed,
+    /// An extended public or private key, derived by derive-key.
+    ExtendedKey,
+    /// A script expression (a descriptor), validated by script-expression.
+    ScriptExpression,
+}
+
+impl InputKind {
+    /// Detects the kind of the input: anything with a parenthesis is a script expression, an `xpub` or `xprv`
+    /// (optionally preceded by its key origin) is an extended key and anything made of hexadecimal digits
+    /// and whitespace only is a seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the input is none of the kinds.
+    pub fn detect(input: &str) -> Result<Self, ParsingError> {
+        let key = match input.strip_prefix('[') {
+            Some(rest) => rest.split_once(']').map_or(input, |(_, key)| key),
+            None => input,
+        };
+
+        if input.contains('(') {
+            Ok(InputKind::ScriptExpression)
+        } else if key.starts_with("xpub") || key.starts_with("xprv") {
+            Ok(InputKind::ExtendedKey)
+        } else if input.chars().any(|c| c.is_ascii_hexdigit())
+            && input
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c.is_whitespace())
+        {
+            Ok(InputKind::Seed)
+        } else {
+            Err(ParsingError::new(
+                "Unrecognized input, expected a hexadecimal seed, an extended key or a script expression",
+            ))
+        }
+    }
+}
+
+impl std::fmt::Display for InputKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            InputKind::Seed => "seed",
+            InputKind::ExtendedKey => "extended-key",
+            InputKind::ScriptExpression => "script-expression",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_input_kind() {
+        assert_eq!(
+            InputKind::detect("000102030405060708090a0b0c0d0e0f"),
+            Ok(InputKind::Seed)
+        );
+        assert_eq!(InputKind::detect("0001 0203\t0405"), Ok(InputKind::Seed));
+        assert_eq!(
+            InputKind::detect("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"),
+            Ok(InputKind::ExtendedKey)
+        );
+        assert_eq!(
+            InputKind::detect("[d34db33f/0h]xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"),
+            Ok(InputKind::ExtendedKey)
+        );
+        assert_eq!(
+            InputKind::detect("raw(deadbeef)#89f8spxm"),
+            Ok(InputKind::ScriptExpression)
+        );
+        assert!(InputKind::detect("").is_err());
+        assert!(InputKind::detect("hello world").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use super::parsing_error::ParsingError;
+
+/// The position of a key expression within a descriptor, which decides the forms of the keys allowed there.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum KeyContext {
+    /// A bare or pay-to-pubkey-hash script, any key is allowed.
+    #[default]
+    Legacy,
+    /// The redeem script of `sh(...)`, any key is allowed.
+    P2sh,
+    /// A `wpkh(...)` or `wsh(...)` script, only compressed keys are allowed (BIP 143).
+    SegwitV0,
+    /// A `tr(...)` script, only compressed or x-only keys are allowed (BIP 386).
+    Taproot,
+}
+
+impl KeyContext {
+    /// Returns whether an uncompressed public key (or the private key of one) is allowed.
+    #[must_use]
+    pub fn allows_uncompressed(self) -> bool {
+        matches!(self, KeyContext::Legacy | KeyContext::P2sh)
+    }
+
+    /// Returns whether an x-only public key (32 bytes, without the parity prefix) is allowed.
+    #[must_use]
+    pub fn allows_x_only(self) -> bool {
+        self == KeyContext::Taproot
+    }
+}
+
+impl std::fmt::Display for KeyContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            KeyContext::Legacy => "legacy",
+            KeyContext::P2sh => "p2sh",
+            KeyContext::SegwitV0 => "segwit-v0",
+            KeyContext::Taproot => "taproot",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl TryFrom<&str> for KeyContext {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "legacy" => Ok(KeyContext::Legacy),
+            "p2sh" => Ok(KeyContext::P2sh),
+            "segwit-v0" => Ok(KeyContext::SegwitV0),
+            "taproot" => Ok(KeyContext::Taproot),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported key context '{value}', expected one of 'legacy', 'p2sh', 'segwit-v0' or 'taproot'"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_context_parsing() {
+        for context in [
+            KeyContext::Legacy,
+            KeyContext::P2sh,
+            KeyContext::SegwitV0,
+            KeyContext::Taproot,
+        ] {
+            assert_eq!(
+                KeyContext::try_from(context.to_string().as_str()),
+                Ok(context)
+            );
+        }
+        assert!(KeyContext::try_from("segwit").is_err());
+    }
+}
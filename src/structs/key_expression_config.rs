@@ -1,12 +1,328 @@
-use crate::traits::parsable::Parsable;
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flag},
+    subcommands::utils::number::parse_index,
+    traits::parsable::Parsable,
+};
 
-use super::parsing_error::ParsingError;
+use super::{
+    canonical_key::CanonicalKey, hardened_wildcard_policy::HardenedWildcardPolicy,
+    key_context::KeyContext, master_key::MasterKey, network::Network, parsing_error::ParsingError,
+    script_limits::parse_limit, script_type::ScriptType,
+};
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct KeyExpressionConfig {}
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct KeyExpressionConfig {
+    pub account_descriptors: Option<ScriptType>,
+    pub verify_origin: Option<MasterKey>,
+    pub network: Option<Network>,
+    pub hardened_wildcard: HardenedWildcardPolicy,
+    /// The position within a descriptor the key is validated for.
+    pub key_context: KeyContext,
+    /// The form the hex encoded public key is printed in, as it is given if not set.
+    pub canonical_key: Option<CanonicalKey>,
+    /// Whether the surrounding whitespace and the padding around the brackets of the key origin are removed
+    /// before the validation.
+    pub lenient: bool,
+    /// Whether the keys are printed after their public keys, in the order of `sortedmulti(...)` (BIP 67).
+    pub sortedmulti_order: bool,
+    /// The index a ranged key is derived at for the `sortedmulti(...)` order.
+    pub index: Option<u32>,
+    /// Whether the derivation steps appended to an extended key are performed.
+    pub evaluate: bool,
+    /// The most derivation steps of the key origin and the appended path, the BIP 32 limit if not set.
+    pub max_origin_depth: Option<usize>,
+}
 
 impl Parsable for KeyExpressionConfig {
-    fn parse(_args: &mut Vec<&str>) -> Result<Self, ParsingError> {
-        Ok(KeyExpressionConfig {})
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let account_descriptors = parse_value_flag(args, "account-descriptors")?
+            .map(|script_type| ScriptType::try_from(script_type.as_str()))
+            .transpose()?;
+
+        let verify_origin = parse_value_flag(args, "verify-origin")?
+            .map(|master_key| MasterKey::try_from(master_key.as_str()))
+            .transpose()?;
+
+        let network = parse_value_flag(args, "network")?
+            .map(|network| Network::try_from(network.as_str()))
+            .transpose()?;
+
+        let hardened_wildcard = parse_value_flag(args, "hardened-wildcard")?
+            .map(|policy| HardenedWildcardPolicy::try_from(policy.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        let key_context = parse_value_flag(args, "key-context")?
+            .map(|context| KeyContext::try_from(context.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        let canonical_key = parse_value_flag(args, "canonical-key")?
+            .map(|form| CanonicalKey::try_from(form.as_str()))
+            .transpose()?;
+
+        let lenient = parse_boolean_flag(args, "lenient");
+
+        let sortedmulti_order = parse_boolean_flag(args, "sortedmulti-order");
+
+        let evaluate = parse_boolean_flag(args, "evaluate");
+
+        let index = parse_value_flag(args, "index")?
+            .map(|index| {
+                parse_index(&index).ok_or_else(|| {
+                    ParsingError::new(&format!(
+                        "Value '{index}' of '--index' must be from the range [0,...,2^31-1]"
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let max_origin_depth = parse_limit(
+            "max-origin-depth",
+            parse_value_flag(args, "max-origin-depth")?,
+        )?;
+
+        if index.is_some() && !sortedmulti_order {
+            return Err(ParsingError::new(
+                "'--index' can only be used with '--sortedmulti-order'",
+            ));
+        }
+        if sortedmulti_order && account_descriptors.is_some() {
+            return Err(ParsingError::new(
+                "Use only '--sortedmulti-order' or '--account-descriptors', not both",
+            ));
+        }
+
+        Ok(KeyExpressionConfig {
+            account_descriptors,
+            verify_origin,
+            network,
+            hardened_wildcard,
+            key_context,
+            canonical_key,
+            lenient,
+            sortedmulti_order,
+            index,
+            evaluate,
+            max_origin_depth,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            canonical_key::CanonicalKey, hardened_wildcard_policy::HardenedWildcardPolicy,
+            key_context::KeyContext, key_expression_config::KeyExpressionConfig,
+            master_key::MasterKey, parsing_error::ParsingError, script_type::ScriptType,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_no_flags_provided() {
+        let mut args = vec!["key-expression", "input"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args),
+            Ok(KeyExpressionConfig {
+                account_descriptors: None,
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                key_context: KeyContext::Legacy,
+                canonical_key: None,
+                lenient: false,
+                sortedmulti_order: false,
+                index: None,
+                evaluate: false,
+                max_origin_depth: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_account_descriptors_flag_provided() {
+        let mut args = vec!["key-expression", "--account-descriptors", "wpkh", "input"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args),
+            Ok(KeyExpressionConfig {
+                account_descriptors: Some(ScriptType::Wpkh),
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                key_context: KeyContext::Legacy,
+                canonical_key: None,
+                lenient: false,
+                sortedmulti_order: false,
+                index: None,
+                evaluate: false,
+                max_origin_depth: None
+            })
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+
+        assert!(KeyExpressionConfig::parse(&mut vec![
+            "key-expression",
+            "--account-descriptors",
+            "wsh"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_verify_origin_flag_provided() {
+        let mut args = vec!["key-expression", "input", "--verify-origin", "3442193e"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args),
+            Ok(KeyExpressionConfig {
+                account_descriptors: None,
+                verify_origin: Some(MasterKey::Fingerprint([0x34, 0x42, 0x19, 0x3e])),
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                key_context: KeyContext::Legacy,
+                canonical_key: None,
+                lenient: false,
+                sortedmulti_order: false,
+                index: None,
+                evaluate: false,
+                max_origin_depth: None
+            })
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+    }
+
+    #[test]
+    fn test_hardened_wildcard_flag_provided() {
+        let mut args = vec![
+            "key-expression",
+            "input",
+            "--hardened-wildcard",
+            "xprv-only",
+        ];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args),
+            Ok(KeyExpressionConfig {
+                account_descriptors: None,
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::XprvOnly,
+                key_context: KeyContext::Legacy,
+                canonical_key: None,
+                lenient: false,
+                sortedmulti_order: false,
+                index: None,
+                evaluate: false,
+                max_origin_depth: None
+            })
+        );
+
+        let mut args = vec!["key-expression", "input", "--hardened-wildcard", "never"];
+        assert!(KeyExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_key_context_flag_provided() {
+        let mut args = vec!["key-expression", "--key-context", "segwit-v0", "input"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args).map(|config| config.key_context),
+            Ok(KeyContext::SegwitV0)
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+
+        let mut args = vec!["key-expression", "input", "--key-context", "segwit"];
+        assert!(KeyExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_canonical_key_flag_provided() {
+        let mut args = vec!["key-expression", "input", "--canonical-key", "compressed"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args).map(|config| config.canonical_key),
+            Ok(Some(CanonicalKey::Compressed))
+        );
+
+        let mut args = vec!["key-expression", "input", "--canonical-key", "upper"];
+        assert!(KeyExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_lenient_flag_provided() {
+        let mut args = vec!["key-expression", "input", "--lenient"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args).map(|config| config.lenient),
+            Ok(true)
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+    }
+
+    #[test]
+    fn test_evaluate_flag_provided() {
+        let mut args = vec!["key-expression", "--evaluate", "input"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args).map(|config| config.evaluate),
+            Ok(true)
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+    }
+
+    #[test]
+    fn test_max_origin_depth_flag_provided() {
+        let mut args = vec!["key-expression", "--max-origin-depth", "512", "input"];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args).map(|config| config.max_origin_depth),
+            Ok(Some(512))
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+
+        let mut args = vec!["key-expression", "--max-origin-depth", "0", "input"];
+        assert!(KeyExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_sortedmulti_order_flags_provided() {
+        let mut args = vec![
+            "key-expression",
+            "input",
+            "--sortedmulti-order",
+            "--index",
+            "5",
+        ];
+
+        assert_eq!(
+            KeyExpressionConfig::parse(&mut args)
+                .map(|config| (config.sortedmulti_order, config.index)),
+            Ok((true, Some(5)))
+        );
+        assert_eq!(args, vec!["key-expression", "input"]);
+
+        for mut args in [
+            vec!["key-expression", "input", "--index", "5"],
+            vec![
+                "key-expression",
+                "input",
+                "--sortedmulti-order",
+                "--index",
+                "2147483648",
+            ],
+            vec![
+                "key-expression",
+                "input",
+                "--sortedmulti-order",
+                "--account-descriptors",
+                "wpkh",
+            ],
+        ] {
+            assert!(KeyExpressionConfig::parse(&mut args).is_err());
+        }
     }
 }
@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use bip32::{KeyFingerprint, XPub};
+
+use crate::subcommands::utils::{
+    extended_key::{has_extended_key_prefix, is_extended_public_key},
+    hexadecimal::decode_hex,
+};
+
+use super::parsing_error::ParsingError;
+
+/// A key of the policy, either the fingerprint of a key origin or an extended public key.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PolicyKey {
+    Fingerprint(KeyFingerprint),
+    Extended(XPub),
+}
+
+/// A rule of the policy file, the key as written in the file and whether it is required or forbidden.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PolicyRule {
+    pub required: bool,
+    pub entry: String,
+    pub key: PolicyKey,
+}
+
+/// The keys that must (`require`) or must not (`forbid`) appear in the validated descriptors, e.g. the vendor
+/// keys of a 2-of-3 wallet, loaded from the policy file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KeyPolicy {
+    pub path: String,
+    pub rules: Vec<PolicyRule>,
+}
+
+impl KeyPolicy {
+    /// Loads the policy file, one `require {key}` or `forbid {key}` rule per line, where the key is an 8 character
+    /// hex fingerprint (of a key origin) or an extended public key. Empty lines and lines starting with `#` are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the file cannot be read, or a line is not a valid rule (naming the line).
+    pub fn load(path: &str) -> Result<Self, ParsingError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ParsingError::new(&format!("Could not read the policy file '{path}': {e}"))
+        })?;
+
+        let mut rules = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = line
+                .split_once(char::is_whitespace)
+                .and_then(|(rule, entry)| parse_rule(rule, entry.trim()))
+                .ok_or_else(|| {
+                    ParsingError::new(&format!(
+                        "Line {} of '{path}': expected 'require {{key}}' or 'forbid {{key}}' with an 8 character hex fingerprint or an xpub",
+                        index + 1
+                    ))
+                })?;
+            rules.push(rule);
+        }
+
+        Ok(KeyPolicy {
+            path: path.to_string(),
+            rules,
+        })
+    }
+}
+
+fn parse_rule(rule: &str, entry: &str) -> Option<PolicyRule> {
+    let required = match rule {
+        "require" => true,
+        "forbid" => false,
+        _ => return None,
+    };
+
+    let key = if has_extended_key_prefix(entry) && is_extended_public_key(entry) {
+        PolicyKey::Extended(XPub::from_str(entry).ok()?)
+    } else if entry.len() == 8 && entry.chars().all(|c| c.is_ascii_hexdigit()) {
+        let mut fingerprint = KeyFingerprint::default();
+        fingerprint.copy_from_slice(&decode_hex(entry).ok()?);
+        PolicyKey::Fingerprint(fingerprint)
+    } else {
+        return None;
+    };
+
+    Some(PolicyRule {
+        required,
+        entry: entry.to_string(),
+        key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_policy_file() {
+        let path = std::env::temp_dir().join(format!("bip380-policy-{}", std::process::id()));
+        let path = path.to_str().expect("the temporary path is valid UTF-8");
+
+        std::fs::write(path, "# vendor keys\nrequire D34DB33F\n\nforbid deadbeef\n")
+            .expect("the policy file is written");
+        assert_eq!(
+            KeyPolicy::load(path),
+            Ok(KeyPolicy {
+                path: path.to_string(),
+                rules: vec![
+                    PolicyRule {
+                        required: true,
+                        entry: "D34DB33F".to_string(),
+                        key: PolicyKey::Fingerprint([0xd3, 0x4d, 0xb3, 0x3f]),
+                    },
+                    PolicyRule {
+                        required: false,
+                        entry: "deadbeef".to_string(),
+                        key: PolicyKey::Fingerprint([0xde, 0xad, 0xbe, 0xef]),
+                    },
+                ],
+            })
+        );
+
+        for content in [
+            "allow deadbeef",
+            "require",
+            "forbid deadbee",
+            "require xprv00",
+        ] {
+            std::fs::write(path, content).expect("the policy file is written");
+            assert!(KeyPolicy::load(path).is_err(), "{content}");
+        }
+
+        std::fs::remove_file(path).expect("the policy file is removed");
+    }
+}
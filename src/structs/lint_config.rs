@@ -0,0 +1,77 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_repeated_value_flag},
+    traits::parsable::Parsable,
+};
+
+use super::{lint_warning::LintWarning, parsing_error::ParsingError};
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct LintConfig {
+    /// The warnings turned into errors, given by `--deny {code}` (or all of them by `--deny all`).
+    pub deny: Vec<LintWarning>,
+    /// Whether the keys of the `multi` scripts are checked for the order of BIP 67 (`--check-bip67`).
+    pub check_bip67: bool,
+}
+
+impl Parsable for LintConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let mut deny: Vec<LintWarning> = Vec::new();
+        for value in parse_repeated_value_flag(args, "deny")? {
+            let warnings = if value == "all" {
+                LintWarning::ALL.to_vec()
+            } else {
+                vec![LintWarning::try_from(value.as_str())?]
+            };
+            for warning in warnings {
+                if !deny.contains(&warning) {
+                    deny.push(warning);
+                }
+            }
+        }
+
+        let check_bip67 = parse_boolean_flag(args, "check-bip67");
+
+        Ok(LintConfig { deny, check_bip67 })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{lint_config::LintConfig, lint_warning::LintWarning},
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_deny_flags() {
+        let mut args = vec!["lint", "--deny", "W003", "-", "--deny", "uncompressed-key"];
+        assert_eq!(
+            LintConfig::parse(&mut args),
+            Ok(LintConfig {
+                deny: vec![LintWarning::MultiAllKeys, LintWarning::UncompressedKey],
+                check_bip67: false,
+            })
+        );
+        assert_eq!(args, vec!["lint", "-"]);
+
+        let mut args = vec!["lint", "-", "--check-bip67", "--deny", "W006"];
+        assert_eq!(
+            LintConfig::parse(&mut args),
+            Ok(LintConfig {
+                deny: vec![LintWarning::UnsortedMulti],
+                check_bip67: true,
+            })
+        );
+
+        let mut args = vec!["lint", "--deny", "W001", "--deny", "all", "-"];
+        assert_eq!(
+            LintConfig::parse(&mut args).map(|config| config.deny.len()),
+            Ok(LintWarning::ALL.len())
+        );
+
+        for args in [vec!["lint", "--deny", "W000", "-"], vec!["lint", "--deny"]] {
+            let mut args = args;
+            assert!(LintConfig::parse(&mut args).is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::parsing_error::ParsingError;
+
+/// A non-fatal finding of the lint sub-command, identified by a stable code (e.g. `W003`) that `--deny`
+/// and the consumers of the output can rely on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LintWarning {
+    /// An uncompressed public (or WIF private) key, which makes the scripts larger and cannot be carried
+    /// over to segwit outputs.
+    UncompressedKey,
+    /// An extended key below the master key without its key origin, so signers cannot recognize it.
+    MissingOrigin,
+    /// A `multi` script requiring all of its keys, losing any of them locks the funds.
+    MultiAllKeys,
+    /// A key origin whose purpose, coin type or account step is not hardened.
+    UnhardenedAccount,
+    /// The deprecated `combo(...)`, which stands for several output types at once.
+    DeprecatedCombo,
+    /// A `multi` script whose keys are not in the order of their public keys (BIP 67), which several legacy
+    /// coordinators require. Only reported when checked for (`--check-bip67`).
+    UnsortedMulti,
+}
+
+impl LintWarning {
+    pub const ALL: [LintWarning; 6] = [
+        LintWarning::UncompressedKey,
+        LintWarning::MissingOrigin,
+        LintWarning::MultiAllKeys,
+        LintWarning::UnhardenedAccount,
+        LintWarning::DeprecatedCombo,
+        LintWarning::UnsortedMulti,
+    ];
+
+    /// The stable code of the warning.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            LintWarning::UncompressedKey => "W001",
+            LintWarning::MissingOrigin => "W002",
+            LintWarning::MultiAllKeys => "W003",
+            LintWarning::UnhardenedAccount => "W004",
+            LintWarning::DeprecatedCombo => "W005",
+            LintWarning::UnsortedMulti => "W006",
+        }
+    }
+
+    /// The human readable name of the warning.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            LintWarning::UncompressedKey => "uncompressed-key",
+            LintWarning::MissingOrigin => "missing-origin",
+            LintWarning::MultiAllKeys => "multi-all-keys",
+            LintWarning::UnhardenedAccount => "unhardened-account",
+            LintWarning::DeprecatedCombo => "deprecated-combo",
+            LintWarning::UnsortedMulti => "unsorted-multi",
+        }
+    }
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl TryFrom<&str> for LintWarning {
+    type Error = ParsingError;
+
+    /// Accepts both the code (case-insensitive) and the name of the warning.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        LintWarning::ALL
+            .into_iter()
+            .find(|warning| warning.code().eq_ignore_ascii_case(value) || warning.name() == value)
+            .ok_or_else(|| {
+                ParsingError::new(&format!(
+                    "Unknown warning '{value}', expected one of the codes W001 to W006, their names or 'all'"
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_warning_parsing() {
+        for warning in LintWarning::ALL {
+            assert_eq!(LintWarning::try_from(warning.code()), Ok(warning));
+            assert_eq!(LintWarning::try_from(warning.name()), Ok(warning));
+        }
+        assert_eq!(LintWarning::try_from("w003"), Ok(LintWarning::MultiAllKeys));
+        assert!(LintWarning::try_from("W007").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use bip32::{KeyFingerprint, XPrv, XPub};
+
+use crate::subcommands::utils::{
+    extended_key::{has_extended_key_prefix, is_extended_public_key},
+    hexadecimal::decode_hex,
+};
+
+use super::parsing_error::ParsingError;
+
+/// Master key (or just its fingerprint) the key origins of key expressions are verified against.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MasterKey {
+    Fingerprint(KeyFingerprint),
+    Public(XPub),
+    Private(XPrv),
+}
+
+impl MasterKey {
+    /// Returns the fingerprint of the master key.
+    #[must_use]
+    pub fn fingerprint(&self) -> KeyFingerprint {
+        match self {
+            MasterKey::Fingerprint(fingerprint) => *fingerprint,
+            MasterKey::Public(xpub) => xpub.fingerprint(),
+            MasterKey::Private(xprv) => xprv.public_key().fingerprint(),
+        }
+    }
+}
+
+impl TryFrom<&str> for MasterKey {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let master_key = if has_extended_key_prefix(value) && is_extended_public_key(value) {
+            MasterKey::Public(XPub::from_str(value)?)
+        } else if has_extended_key_prefix(value) {
+            MasterKey::Private(XPrv::from_str(value)?)
+        } else if value.len() == 8 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+            let mut fingerprint = KeyFingerprint::default();
+            fingerprint.copy_from_slice(&decode_hex(value)?);
+            MasterKey::Fingerprint(fingerprint)
+        } else {
+            return Err(ParsingError::new(&format!(
+                "'{value}' is neither a master xprv/xpub, nor an 8 character hex fingerprint"
+            )));
+        };
+
+        let depth = match &master_key {
+            MasterKey::Fingerprint(_) => 0,
+            MasterKey::Public(xpub) => xpub.attrs().depth,
+            MasterKey::Private(xprv) => xprv.attrs().depth,
+        };
+        if depth != 0 {
+            return Err(ParsingError::new(&format!(
+                "'{value}' is not a master key, its depth is {depth}"
+            )));
+        }
+
+        Ok(master_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_master_key_parsing() {
+        // BIP 32 test vector 1
+        let xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let fingerprint = [0x34, 0x42, 0x19, 0x3e];
+
+        for master_key in [xprv, xpub, "3442193e", "3442193E"] {
+            assert_eq!(
+                MasterKey::try_from(master_key).map(|key| key.fingerprint()),
+                Ok(fingerprint)
+            );
+        }
+
+        assert!(MasterKey::try_from("3442193").is_err());
+        assert!(MasterKey::try_from("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw").is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flags},
+    traits::parsable::Parsable,
+};
+
+use super::{
+    mnemonic_language::MnemonicLanguage, parsing_error::ParsingError, seedqr_format::SeedQrFormat,
+};
+
+/// The direction of the mnemonic sub-command.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MnemonicMode {
+    /// Encodes a mnemonic as a `SeedQR` of the format.
+    ToSeedQr(SeedQrFormat),
+    /// Decodes a standard or compact `SeedQR` back into the mnemonic.
+    FromSeedQr,
+    /// Encodes 128 to 256 bits of hex entropy as the mnemonic.
+    FromEntropy,
+    /// Decodes a mnemonic back into its hex entropy.
+    ToEntropy,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MnemonicConfig {
+    pub mode: MnemonicMode,
+    /// The language of the mnemonic, detected from its words (or English for the decoded `SeedQR`) if not given.
+    pub language: Option<MnemonicLanguage>,
+}
+
+impl Parsable for MnemonicConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [to_seedqr, language]: [Option<String>; 2] =
+            parse_value_flags(args, &["to-seedqr", "language"])?
+                .try_into()
+                .unwrap_or_default();
+        let from_seedqr = parse_boolean_flag(args, "from-seedqr");
+        let from_entropy = parse_boolean_flag(args, "from-entropy");
+        let to_entropy = parse_boolean_flag(args, "to-entropy");
+
+        let to_seedqr = to_seedqr
+            .map(|format| SeedQrFormat::try_from(format.as_str()).map(MnemonicMode::ToSeedQr))
+            .transpose()?;
+        let modes: Vec<MnemonicMode> = [
+            to_seedqr,
+            from_seedqr.then_some(MnemonicMode::FromSeedQr),
+            from_entropy.then_some(MnemonicMode::FromEntropy),
+            to_entropy.then_some(MnemonicMode::ToEntropy),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mode = match modes[..] {
+            [mode] => mode,
+            [] => {
+                return Err(ParsingError::new(
+                    "The mnemonic sub-command requires one of '--to-seedqr {format}', '--from-seedqr', \
+                     '--from-entropy' or '--to-entropy'",
+                ))
+            }
+            _ => {
+                return Err(ParsingError::new(
+                    "use only one of '--to-seedqr', '--from-seedqr', '--from-entropy' or '--to-entropy'",
+                ))
+            }
+        };
+
+        let language = language
+            .map(|language| MnemonicLanguage::try_from(language.as_str()))
+            .transpose()?;
+
+        Ok(MnemonicConfig { mode, language })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            mnemonic_config::{MnemonicConfig, MnemonicMode},
+            mnemonic_language::MnemonicLanguage,
+            parsing_error::ParsingError,
+            seedqr_format::SeedQrFormat,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_mode_flags_provided() {
+        let mut args = vec!["mnemonic", "--to-seedqr", "compact", "-"];
+        assert_eq!(
+            MnemonicConfig::parse(&mut args),
+            Ok(MnemonicConfig {
+                mode: MnemonicMode::ToSeedQr(SeedQrFormat::Compact),
+                language: None
+            })
+        );
+        assert_eq!(args, vec!["mnemonic", "-"]);
+
+        let mut args = vec!["mnemonic", "--from-seedqr", "-", "--language", "japanese"];
+        assert_eq!(
+            MnemonicConfig::parse(&mut args),
+            Ok(MnemonicConfig {
+                mode: MnemonicMode::FromSeedQr,
+                language: Some(MnemonicLanguage::Japanese)
+            })
+        );
+
+        let mut args = vec!["mnemonic", "--from-entropy", "-"];
+        assert_eq!(
+            MnemonicConfig::parse(&mut args).map(|config| config.mode),
+            Ok(MnemonicMode::FromEntropy)
+        );
+        assert_eq!(args, vec!["mnemonic", "-"]);
+    }
+
+    #[test]
+    fn test_invalid_mode_flags() {
+        let mut args = vec!["mnemonic", "--to-seedqr", "standard", "--from-seedqr", "-"];
+        assert!(MnemonicConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["mnemonic", "--to-seedqr", "tiny", "-"];
+        assert!(MnemonicConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["mnemonic", "-"];
+        assert!(MnemonicConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["mnemonic", "--from-entropy", "--to-entropy", "-"];
+        assert!(MnemonicConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["mnemonic", "--from-seedqr", "--language", "klingon", "-"];
+        assert!(MnemonicConfig::parse(&mut args).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use bip39::Language;
+
+use super::parsing_error::ParsingError;
+
+/// The official BIP 39 wordlist a mnemonic is written in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MnemonicLanguage {
+    #[default]
+    English,
+    Japanese,
+    Korean,
+    Spanish,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Czech,
+    Portuguese,
+}
+
+impl MnemonicLanguage {
+    /// Returns the wordlist of the language.
+    #[must_use]
+    pub fn wordlist(self) -> Language {
+        match self {
+            MnemonicLanguage::English => Language::English,
+            MnemonicLanguage::Japanese => Language::Japanese,
+            MnemonicLanguage::Korean => Language::Korean,
+            MnemonicLanguage::Spanish => Language::Spanish,
+            MnemonicLanguage::ChineseSimplified => Language::SimplifiedChinese,
+            MnemonicLanguage::ChineseTraditional => Language::TraditionalChinese,
+            MnemonicLanguage::French => Language::French,
+            MnemonicLanguage::Italian => Language::Italian,
+            MnemonicLanguage::Czech => Language::Czech,
+            MnemonicLanguage::Portuguese => Language::Portuguese,
+        }
+    }
+}
+
+impl TryFrom<&str> for MnemonicLanguage {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "english" => Ok(MnemonicLanguage::English),
+            "japanese" => Ok(MnemonicLanguage::Japanese),
+            "korean" => Ok(MnemonicLanguage::Korean),
+            "spanish" => Ok(MnemonicLanguage::Spanish),
+            "chinese-simplified" => Ok(MnemonicLanguage::ChineseSimplified),
+            "chinese-traditional" => Ok(MnemonicLanguage::ChineseTraditional),
+            "french" => Ok(MnemonicLanguage::French),
+            "italian" => Ok(MnemonicLanguage::Italian),
+            "czech" => Ok(MnemonicLanguage::Czech),
+            "portuguese" => Ok(MnemonicLanguage::Portuguese),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported mnemonic language '{value}', expected one of 'english', 'japanese', 'korean', 'spanish', 'chinese-simplified', 'chinese-traditional', 'french', 'italian', 'czech' or 'portuguese'"
+            ))),
+        }
+    }
+}
@@ -1,4 +1,74 @@
+#[cfg(feature = "address")]
+pub mod address_config;
+pub mod address_type;
+pub mod batch_stats;
+#[cfg(feature = "bench")]
+pub mod bench_config;
+pub mod canonical_key;
+#[cfg(feature = "coldcard-export")]
+pub mod coldcard_export_config;
+#[cfg(feature = "compose")]
+pub mod compose_config;
+#[cfg(feature = "compose")]
+pub mod compose_script;
+#[cfg(feature = "convert-key")]
+pub mod convert_key_config;
+pub mod core_import_config;
+#[cfg(feature = "demo")]
+pub mod demo_config;
 pub mod derive_key_config;
+#[cfg(feature = "derive-key")]
+pub mod derived_key;
+pub mod error_kind;
+pub mod explorer;
+#[cfg(feature = "wallet-export")]
+pub mod export_target;
+pub mod extended_key_fields;
+#[cfg(feature = "generate-seed")]
+pub mod generate_seed_config;
+pub mod global_config;
+pub mod hardened_notation;
+pub mod hardened_wildcard_policy;
+#[cfg(feature = "hwi-import")]
+pub mod hwi_import_config;
+#[cfg(feature = "process")]
+pub mod input_kind;
+pub mod key_context;
 pub mod key_expression_config;
+pub mod key_policy;
+#[cfg(feature = "lint")]
+pub mod lint_config;
+#[cfg(feature = "lint")]
+pub mod lint_warning;
+pub mod master_key;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic_config;
+pub mod mnemonic_language;
+pub mod network;
+pub mod output_format;
 pub mod parsing_error;
+pub mod path_config;
+#[cfg(feature = "process")]
+pub mod process_config;
+pub mod result_cache;
+#[cfg(feature = "script-expression")]
 pub mod script_expression_config;
+pub mod script_limits;
+pub mod script_type;
+pub mod secret_buffer;
+pub mod secret_kind;
+#[cfg(feature = "seed-xor")]
+pub mod seed_xor_config;
+#[cfg(feature = "mnemonic")]
+pub mod seedqr_format;
+#[cfg(feature = "serve")]
+pub mod serve_config;
+#[cfg(feature = "slip39")]
+pub mod slip39_config;
+#[cfg(feature = "wallet-init")]
+pub mod wallet_account;
+#[cfg(feature = "wallet-export")]
+pub mod wallet_export_config;
+#[cfg(feature = "wallet-init")]
+pub mod wallet_init_config;
+pub mod whitespace_policy;
@@ -0,0 +1,73 @@
+use bip32::Prefix;
+
+use super::parsing_error::ParsingError;
+
+/// Bitcoin network the extended keys are encoded for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    /// Returns the BIP 44 coin type of the network.
+    #[must_use]
+    pub fn coin_type(self) -> u32 {
+        match self {
+            Network::Mainnet => 0,
+            Network::Testnet => 1,
+        }
+    }
+
+    /// Returns the prefix of extended private keys on the network.
+    #[must_use]
+    pub fn private_prefix(self) -> Prefix {
+        match self {
+            Network::Mainnet => Prefix::XPRV,
+            Network::Testnet => Prefix::TPRV,
+        }
+    }
+
+    /// Returns the prefix of extended public keys on the network.
+    #[must_use]
+    pub fn public_prefix(self) -> Prefix {
+        match self {
+            Network::Mainnet => Prefix::XPUB,
+            Network::Testnet => Prefix::TPUB,
+        }
+    }
+
+    /// Returns the network of an extended key by its prefix, or `None` if it is not an extended key.
+    #[must_use]
+    pub fn of_extended_key(key: &str) -> Option<Network> {
+        match key.get(..4) {
+            Some("xpub" | "xprv") => Some(Network::Mainnet),
+            Some("tpub" | "tprv") => Some(Network::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Network {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported network '{value}', expected either 'mainnet' or 'testnet'"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use super::parsing_error::ParsingError;
+
+/// How the results and the errors of the inputs are printed, every format (but the templates) is encoded by its
+/// [`OutputEncoder`](crate::traits::output_encoder::OutputEncoder).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OutputFormat {
+    /// The usual output of the sub-commands and free-form error messages.
+    #[default]
+    Plain,
+    /// A JSON object of the result's fields per line on the standard output, and a JSON object
+    /// with the input, the kind and the message of every error on the standard error.
+    Json,
+    /// A CSV row (RFC 4180) of the values of the result's fields per line, in the order of their JSON object.
+    Csv,
+    /// The result encoded as a uniform resource (`crypto-hdkey` or `crypto-output`), one part per line.
+    Ur,
+    /// The usual output of the sub-commands drawn as a QR code.
+    Qr,
+}
+
+impl TryFrom<&str> for OutputFormat {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "plain" => Ok(OutputFormat::Plain),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "ur" => Ok(OutputFormat::Ur),
+            "qr" => Ok(OutputFormat::Qr),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported output format '{value}', expected one of 'plain', 'json', 'csv', 'ur' or 'qr'"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            OutputFormat::Plain => "plain",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Ur => "ur",
+            OutputFormat::Qr => "qr",
+        };
+        write!(f, "{name}")
+    }
+}
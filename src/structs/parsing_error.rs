@@ -1,22 +1,110 @@
-use std::num::ParseIntError;
+use std::{num::ParseIntError, ops::Range};
 
-#[derive(Debug, Eq, PartialEq)]
+use crate::subcommands::utils::json::json_string;
+
+use super::error_kind::ErrorKind;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct ParsingError {
     pub message: String,
+    pub kind: ErrorKind,
+    /// The byte range of the input at fault, if known.
+    pub span: Option<Range<usize>>,
 }
 
 impl ParsingError {
     #[must_use]
     pub fn new(message: &str) -> ParsingError {
+        ParsingError::with_kind(ErrorKind::default(), message)
+    }
+
+    #[must_use]
+    pub fn with_kind(kind: ErrorKind, message: &str) -> ParsingError {
         ParsingError {
             message: message.to_string(),
+            kind,
+            span: None,
         }
     }
+
+    /// Classifies the error as the kind, unless it is already classified more precisely than
+    /// [`ErrorKind::InvalidInput`], e.g. for the error of a decoder whose kind only its caller knows.
+    #[must_use]
+    pub fn or_kind(mut self, kind: ErrorKind) -> ParsingError {
+        if self.kind == ErrorKind::InvalidInput {
+            self.kind = kind;
+        }
+        self
+    }
+
+    /// Points the error at the byte range of the input.
+    #[must_use]
+    pub fn with_span(mut self, span: Range<usize>) -> ParsingError {
+        self.span = Some(span);
+        self
+    }
+
+    /// Moves the span (if any) by the offset, for an error of a part starting at the offset of the input.
+    #[must_use]
+    pub fn shifted(mut self, offset: usize) -> ParsingError {
+        self.span = self.span.map(|span| span.start + offset..span.end + offset);
+        self
+    }
+
+    /// Drops the span, for an error of a part whose position in the input is not known.
+    #[must_use]
+    pub fn without_span(mut self) -> ParsingError {
+        self.span = None;
+        self
+    }
+
+    /// Returns the error followed by the input with the span (if any) underlined by carets, e.g.
+    ///
+    /// ```text
+    /// Parsing error [E001]: checksum verification failed!
+    ///   raw(deadbeef)#00000000
+    ///                 ^^^^^^^^
+    /// ```
+    #[must_use]
+    pub fn render(&self, input: &str) -> String {
+        let Some((before, at_fault)) = self
+            .span
+            .as_ref()
+            .and_then(|span| Some((input.get(..span.start)?, input.get(span.clone())?)))
+        else {
+            return self.to_string();
+        };
+
+        format!(
+            "{self}\n  {input}\n  {}{}",
+            " ".repeat(before.chars().count()),
+            "^".repeat(at_fault.chars().count().max(1))
+        )
+    }
+
+    /// Returns the error of the input as a single line JSON object, e.g.
+    /// `{"input":"raw(deadbeef)#00000000","kind":"ChecksumMismatch","code":"E001","message":"checksum verification failed!","span":[14,22]}`,
+    /// the `span` is left out if not known.
+    #[must_use]
+    pub fn to_json(&self, input: &str) -> String {
+        let span = self
+            .span
+            .as_ref()
+            .map(|span| format!(",\"span\":[{},{}]", span.start, span.end))
+            .unwrap_or_default();
+        format!(
+            "{{\"input\":{},\"kind\":{},\"code\":{},\"message\":{}{span}}}",
+            json_string(input),
+            json_string(&self.kind.to_string()),
+            json_string(self.kind.code()),
+            json_string(&self.message)
+        )
+    }
 }
 
 impl std::fmt::Display for ParsingError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Parsing error: {}", self.message)
+        write!(f, "Parsing error [{}]: {}", self.kind.code(), self.message)
     }
 }
 
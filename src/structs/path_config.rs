@@ -0,0 +1,116 @@
+use bip32::DerivationPath;
+
+use crate::{parsers::flag_parser::parse_value_flags, traits::parsable::Parsable};
+
+use super::{
+    derive_key_config::parse_derivation_path, hardened_notation::HardenedNotation,
+    parsing_error::ParsingError,
+};
+
+/// What the path sub-command does with the input path.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub enum PathOperation {
+    /// Prints the path itself.
+    #[default]
+    Normalize,
+    /// Appends the relative path to the input path.
+    Join(DerivationPath),
+    /// Prints the input path relative to the base path it descends from.
+    RelativeTo(DerivationPath),
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct PathConfig {
+    pub operation: PathOperation,
+    pub notation: HardenedNotation,
+}
+
+impl Parsable for PathConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [join, relative_to, notation]: [Option<String>; 3] =
+            parse_value_flags(args, &["join", "relative-to", "notation"])?
+                .try_into()
+                .unwrap_or_default();
+
+        let operation = match (join, relative_to) {
+            (None, None) => PathOperation::Normalize,
+            (Some(suffix), None) => PathOperation::Join(parse_derivation_path(&suffix)?),
+            (None, Some(base)) => PathOperation::RelativeTo(parse_derivation_path(&base)?),
+            (Some(_), Some(_)) => {
+                return Err(ParsingError::new(
+                    "use only '--join' or '--relative-to', not both",
+                ))
+            }
+        };
+
+        let notation = notation
+            .map(|notation| HardenedNotation::try_from(notation.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(PathConfig {
+            operation,
+            notation,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            hardened_notation::HardenedNotation,
+            parsing_error::ParsingError,
+            path_config::{PathConfig, PathOperation},
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_no_flags_provided() {
+        let mut args = vec!["path", "m/84h/0h"];
+
+        assert_eq!(PathConfig::parse(&mut args), Ok(PathConfig::default()));
+        assert_eq!(args, vec!["path", "m/84h/0h"]);
+    }
+
+    #[test]
+    fn test_operation_flags_provided() {
+        let mut args = vec!["path", "m/84h/0h", "--join", "0/5", "--notation", "'"];
+
+        assert_eq!(
+            PathConfig::parse(&mut args),
+            Ok(PathConfig {
+                operation: PathOperation::Join("m/0/5".parse().unwrap()),
+                notation: HardenedNotation::Apostrophe,
+            })
+        );
+        assert_eq!(args, vec!["path", "m/84h/0h"]);
+
+        let mut args = vec!["path", "m/84h/0h/0/5", "--relative-to", "/84H/0'"];
+        assert_eq!(
+            PathConfig::parse(&mut args),
+            Ok(PathConfig {
+                operation: PathOperation::RelativeTo("m/84'/0'".parse().unwrap()),
+                notation: HardenedNotation::Lowercase,
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        let mut args = vec!["path", "m", "--join", "0", "--relative-to", "m"];
+        assert!(PathConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["path", "m", "--join", "2147483648"];
+        assert!(PathConfig::parse(&mut args).is_err());
+
+        let mut args = vec!["path", "m", "--notation", "x"];
+        assert_eq!(
+            PathConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "Unsupported hardened notation 'x', expected one of 'h', 'H' or '''"
+            ))
+        );
+    }
+}
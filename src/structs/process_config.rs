@@ -0,0 +1,59 @@
+use crate::traits::parsable::Parsable;
+
+use super::{
+    derive_key_config::DeriveKeyConfig, parsing_error::ParsingError,
+    script_expression_config::ScriptExpressionConfig,
+};
+
+/// The configurations the inputs of the process sub-command are routed to, built from the flags of
+/// derive-key and script-expression.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ProcessConfig {
+    pub derive_key: DeriveKeyConfig,
+    pub script_expression: ScriptExpressionConfig,
+}
+
+impl Parsable for ProcessConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let derive_key = DeriveKeyConfig::parse(args)?;
+        let script_expression = ScriptExpressionConfig::parse(args)?;
+
+        Ok(ProcessConfig {
+            derive_key,
+            script_expression,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            derive_key_config::DeriveKeyConfig, process_config::ProcessConfig,
+            script_expression_config::ScriptExpressionConfig,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_flags_of_both_sub_commands() {
+        let mut args = vec!["process", "--path", "0h/1", "-", "--compute-checksum"];
+        assert_eq!(
+            ProcessConfig::parse(&mut args),
+            Ok(ProcessConfig {
+                derive_key: DeriveKeyConfig {
+                    path: "m/0'/1".parse().unwrap(),
+                    ..Default::default()
+                },
+                script_expression: ScriptExpressionConfig {
+                    compute_checksum: true,
+                    ..Default::default()
+                },
+            })
+        );
+        assert_eq!(args, vec!["process", "-"]);
+
+        let mut args = vec!["process", "--verify-checksum", "--compute-checksum", "-"];
+        assert!(ProcessConfig::parse(&mut args).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::parsing_error::ParsingError;
+
+/// How many distinct inputs the cache of a batch remembers the results of.
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// A least recently used cache of the results of the inputs of a batch, so the repeated inputs (e.g. the duplicate
+/// lines of exported logs) are answered without processing them again.
+pub struct ResultCache {
+    capacity: usize,
+    /// The results by their inputs, together with the tick they were last used at.
+    entries: HashMap<String, (Result<String, ParsingError>, u64)>,
+    /// The inputs by the tick they were last used at, the least recently used first.
+    usage: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl ResultCache {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        ResultCache {
+            capacity,
+            entries: HashMap::new(),
+            usage: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the result of the input, processing it only if it is not cached yet. The least recently used
+    /// result is evicted once the cache is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns the (possibly cached) [`ParsingError`] of processing the input.
+    pub fn get_or_insert_with(
+        &mut self,
+        input: &str,
+        process: impl FnOnce() -> Result<String, ParsingError>,
+    ) -> Result<String, ParsingError> {
+        self.tick += 1;
+
+        if let Some((result, last_used)) = self.entries.get_mut(input) {
+            self.usage.remove(last_used);
+            *last_used = self.tick;
+            self.usage.insert(self.tick, input.to_string());
+            return result.clone();
+        }
+
+        let result = process();
+        if self.capacity == 0 {
+            return result;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some((_, evicted)) = self.usage.pop_first() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.entries
+            .insert(input.to_string(), (result.clone(), self.tick));
+        self.usage.insert(self.tick, input.to_string());
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_least_recently_used_eviction() {
+        let mut cache = ResultCache::new(2);
+        let mut processed = Vec::new();
+        let mut get = |cache: &mut ResultCache, input: &str| {
+            cache.get_or_insert_with(input, || {
+                processed.push(input.to_string());
+                if input == "bad" {
+                    Err(ParsingError::new("bad input"))
+                } else {
+                    Ok(input.to_uppercase())
+                }
+            })
+        };
+
+        assert_eq!(get(&mut cache, "a"), Ok("A".to_string()));
+        assert_eq!(get(&mut cache, "bad"), Err(ParsingError::new("bad input")));
+        assert_eq!(get(&mut cache, "a"), Ok("A".to_string()));
+        assert_eq!(get(&mut cache, "bad"), Err(ParsingError::new("bad input")));
+        // "a" is the least recently used, evicted by "c"
+        assert_eq!(get(&mut cache, "c"), Ok("C".to_string()));
+        assert_eq!(get(&mut cache, "bad"), Err(ParsingError::new("bad input")));
+        assert_eq!(get(&mut cache, "a"), Ok("A".to_string()));
+
+        assert_eq!(processed, vec!["a", "bad", "c", "a"]);
+    }
+}
@@ -1,11 +1,75 @@
-use crate::{parsers::flag_parser::parse_boolean_flag, traits::parsable::Parsable};
+use bip32::KeyFingerprint;
 
-use super::parsing_error::ParsingError;
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_repeated_value_flag, parse_value_flag},
+    subcommands::utils::{
+        number::{parse_index, parse_threshold},
+        template::parse_template_keys,
+    },
+    traits::parsable::Parsable,
+};
+
+use super::{
+    explorer::Explorer, hardened_wildcard_policy::HardenedWildcardPolicy, key_policy::KeyPolicy,
+    master_key::MasterKey, network::Network, parsing_error::ParsingError,
+    script_limits::ScriptLimits, whitespace_policy::WhitespacePolicy,
+};
+
+/// What the script-expression sub-command prints for a valid script expression.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub enum ScriptOutput {
+    /// The script expression itself (with the checksum, if provided or computed).
+    #[default]
+    Expression,
+    /// The compiled script printed as Bitcoin Script opcodes.
+    Disassembly,
+    /// The address of the compiled script with ranged keys derived at index 0, followed by its URL on the explorer.
+    FirstAddress(Option<Explorer>),
+    /// The verification of the `address:index` pairs of the file against the addresses the script derives.
+    VerifyAddresses(String),
+    /// The verification that the given change descriptor differs from the script only in the branch of its keys.
+    CheckChange(String),
+    /// The script expression with its keys replaced with dummy keys and its checksum recomputed.
+    Anonymized,
+    /// The positions of the keys of the script whose key origin has the fingerprint.
+    FindFingerprint(KeyFingerprint),
+    /// The script expression with the old key expression replaced with the new one and its checksum recomputed.
+    RotatedKey { old: String, new: String },
+    /// The script expression with the threshold of its `multi(...)` set and its checksum recomputed.
+    Threshold(usize),
+    /// The PSBT output metadata (BIP 174) of the script with its ranged keys derived at the index.
+    PsbtOutput(u32),
+}
 
 #[derive(Debug, PartialEq, Eq, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ScriptExpressionConfig {
     pub compute_checksum: bool,
     pub verify_checksum: bool,
+    pub verify_after: bool,
+    /// Whether the checksum is lowercased before it is verified, accepting the uppercase checksums some tools emit.
+    pub ignore_checksum_case: bool,
+    /// Whether the checksum is computed and appended if missing, and verified if present, so that the expressions
+    /// can be normalized repeatedly.
+    pub ensure_checksum: bool,
+    pub normalize: bool,
+    pub output: ScriptOutput,
+    pub keys: Vec<(String, String)>,
+    pub verify_origin: Option<MasterKey>,
+    pub network: Option<Network>,
+    pub hardened_wildcard: HardenedWildcardPolicy,
+    pub whitespace: WhitespacePolicy,
+    pub limits: ScriptLimits,
+    /// Whether the inputs name files of descriptors (one per line), which are rewritten with their checksums
+    /// computed.
+    pub rewrite: bool,
+    /// The file the rewritten descriptors are written to, instead of the file they were read from.
+    pub output_file: Option<String>,
+    /// Whether the inputs name files of descriptors (one per line, e.g. wallet backups), which are validated
+    /// together into a single report.
+    pub report: bool,
+    /// The keys that must or must not appear in the descriptors, loaded from the `--policy-file`.
+    pub policy: Option<KeyPolicy>,
 }
 
 impl Parsable for ScriptExpressionConfig {
@@ -18,19 +82,213 @@ impl Parsable for ScriptExpressionConfig {
             ));
         }
 
+        let ensure_checksum = parse_boolean_flag(args, "ensure-checksum");
+        if ensure_checksum && (compute_checksum || verify_checksum) {
+            return Err(ParsingError::new(
+                "use only '--ensure-checksum', '--verify-checksum' or '--compute-checksum', not more of them",
+            ));
+        }
+
+        let verify_after = parse_boolean_flag(args, "verify-after");
+        if verify_after && !compute_checksum {
+            return Err(ParsingError::new(
+                "'--verify-after' can only be used with '--compute-checksum'",
+            ));
+        }
+
+        let ignore_checksum_case = parse_boolean_flag(args, "ignore-checksum-case");
+        if ignore_checksum_case && compute_checksum {
+            return Err(ParsingError::new(
+                "'--ignore-checksum-case' cannot be used with '--compute-checksum', the checksum is replaced",
+            ));
+        }
+
+        let normalize = parse_boolean_flag(args, "normalize");
+
+        let output = parse_output_flags(args)?;
+
+        let keys = parse_template_keys(&parse_repeated_value_flag(args, "key")?)?;
+        if verify_checksum && !keys.is_empty() {
+            return Err(ParsingError::new(
+                "'--verify-checksum' cannot be used with '--key', the checksum of a templated script is always computed",
+            ));
+        }
+
+        let verify_origin = parse_value_flag(args, "verify-origin")?
+            .map(|master_key| MasterKey::try_from(master_key.as_str()))
+            .transpose()?;
+
+        let network = parse_value_flag(args, "network")?
+            .map(|network| Network::try_from(network.as_str()))
+            .transpose()?;
+
+        let hardened_wildcard = parse_value_flag(args, "hardened-wildcard")?
+            .map(|policy| HardenedWildcardPolicy::try_from(policy.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        let whitespace = parse_value_flag(args, "whitespace")?
+            .map(|policy| WhitespacePolicy::try_from(policy.as_str()))
+            .transpose()?
+            .unwrap_or_default();
+
+        let limits = ScriptLimits::parse(args)?;
+
+        let rewrite = parse_boolean_flag(args, "rewrite");
+        if rewrite && (verify_checksum || output != ScriptOutput::Expression || !keys.is_empty()) {
+            return Err(ParsingError::new(
+                "'--rewrite' cannot be combined with '--verify-checksum', '--disassemble', '--first-address', '--verify-addresses', '--check-change', '--anonymize', '--find-fingerprint', '--rotate-key', '--set-threshold', '--psbt-output' or '--key'",
+            ));
+        }
+        let output_file = parse_value_flag(args, "output-file")?;
+        if output_file.is_some() && !rewrite {
+            return Err(ParsingError::new(
+                "'--output-file' can only be used with '--rewrite'",
+            ));
+        }
+        // the flags are parsed, the sub-command is followed only by the files to rewrite
+        if output_file.is_some() && args.len() > 2 {
+            return Err(ParsingError::new(
+                "'--output-file' can only be used with a single file to rewrite",
+            ));
+        }
+
+        let report = parse_boolean_flag(args, "report");
+        if report
+            && (rewrite
+                || compute_checksum
+                || verify_checksum
+                || ensure_checksum
+                || output != ScriptOutput::Expression
+                || !keys.is_empty())
+        {
+            return Err(ParsingError::new(
+                "'--report' cannot be combined with '--rewrite', '--compute-checksum', '--verify-checksum', '--ensure-checksum', '--disassemble', '--first-address', '--verify-addresses', '--check-change', '--anonymize', '--find-fingerprint', '--rotate-key', '--set-threshold', '--psbt-output' or '--key'",
+            ));
+        }
+
+        let policy = parse_value_flag(args, "policy-file")?
+            .map(|path| KeyPolicy::load(&path))
+            .transpose()?;
+
         Ok(ScriptExpressionConfig {
             compute_checksum,
             verify_checksum,
+            verify_after,
+            ignore_checksum_case,
+            ensure_checksum,
+            normalize,
+            output,
+            keys,
+            verify_origin,
+            network,
+            hardened_wildcard,
+            whitespace,
+            limits,
+            rewrite,
+            output_file,
+            report,
+            policy,
         })
     }
 }
 
+/// Parses the flags selecting the output, at most one of which can be given.
+fn parse_output_flags(args: &mut Vec<&str>) -> Result<ScriptOutput, ParsingError> {
+    let explorer = parse_value_flag(args, "explorer-url")?
+        .map(|explorer| Explorer::try_from(explorer.as_str()))
+        .transpose()?;
+    let first_address = parse_boolean_flag(args, "first-address");
+    if explorer.is_some() && !first_address {
+        return Err(ParsingError::new(
+            "'--explorer-url' can only be used with '--first-address'",
+        ));
+    }
+
+    let outputs: Vec<(&str, ScriptOutput)> = [
+        parse_boolean_flag(args, "disassemble")
+            .then_some(("--disassemble", ScriptOutput::Disassembly)),
+        first_address.then_some(("--first-address", ScriptOutput::FirstAddress(explorer))),
+        parse_value_flag(args, "verify-addresses")?
+            .map(|path| ("--verify-addresses", ScriptOutput::VerifyAddresses(path))),
+        parse_value_flag(args, "check-change")?
+            .map(|change| ("--check-change", ScriptOutput::CheckChange(change))),
+        parse_boolean_flag(args, "anonymize")
+            .then_some(("--anonymize", ScriptOutput::Anonymized)),
+        parse_value_flag(args, "find-fingerprint")?
+            .map(|fingerprint| match MasterKey::try_from(fingerprint.as_str()) {
+                Ok(MasterKey::Fingerprint(fingerprint)) => Ok((
+                    "--find-fingerprint",
+                    ScriptOutput::FindFingerprint(fingerprint),
+                )),
+                _ => Err(ParsingError::new(&format!(
+                    "Value '{fingerprint}' of '--find-fingerprint' is not an 8 character hex fingerprint"
+                ))),
+            })
+            .transpose()?,
+        parse_value_flag(args, "rotate-key")?
+            .map(|rotation| match rotation.split_once('=') {
+                Some((old, new)) if !old.trim().is_empty() && !new.trim().is_empty() => Ok((
+                    "--rotate-key",
+                    ScriptOutput::RotatedKey {
+                        old: old.trim().to_string(),
+                        new: new.trim().to_string(),
+                    },
+                )),
+                _ => Err(ParsingError::new(&format!(
+                    "Value '{rotation}' of '--rotate-key' must be in the form {{old}}={{new}}"
+                ))),
+            })
+            .transpose()?,
+        parse_value_flag(args, "set-threshold")?
+            .map(|threshold| {
+                parse_threshold(&threshold)
+                    .map(|threshold| ("--set-threshold", ScriptOutput::Threshold(threshold)))
+                    .map_err(|e| {
+                        ParsingError::new(&format!(
+                            "Value '{threshold}' of '--set-threshold' is not valid: {}",
+                            e.message
+                        ))
+                    })
+            })
+            .transpose()?,
+        parse_value_flag(args, "psbt-output")?
+            .map(|index| {
+                parse_index(&index)
+                    .map(|index| ("--psbt-output", ScriptOutput::PsbtOutput(index)))
+                    .ok_or_else(|| {
+                        ParsingError::new(&format!(
+                            "Value '{index}' of '--psbt-output' must be from the range [0,...,2^31-1]"
+                        ))
+                    })
+            })
+            .transpose()?,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    match outputs.as_slice() {
+        [] => Ok(ScriptOutput::Expression),
+        [(_, output)] => Ok(output.clone()),
+        [(first, _), (second, _), ..] => Err(ParsingError::new(&format!(
+            "use only '{first}' or '{second}', not both"
+        ))),
+    }
+}
+
 mod tests {
 
     #[allow(unused_imports)]
     use crate::{
         structs::parsing_error::ParsingError,
-        structs::script_expression_config::ScriptExpressionConfig, traits::parsable::Parsable,
+        structs::{
+            explorer::Explorer,
+            hardened_wildcard_policy::HardenedWildcardPolicy,
+            script_expression_config::{ScriptExpressionConfig, ScriptOutput},
+            script_limits::ScriptLimits,
+            whitespace_policy::WhitespacePolicy,
+        },
+        traits::parsable::Parsable,
     };
 
     #[test]
@@ -41,7 +299,22 @@ mod tests {
             ScriptExpressionConfig::parse(&mut args),
             Ok(ScriptExpressionConfig {
                 compute_checksum: false,
-                verify_checksum: false
+                verify_checksum: false,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: false,
+                output: ScriptOutput::Expression,
+                keys: vec![],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
             })
         );
     }
@@ -54,7 +327,22 @@ mod tests {
             ScriptExpressionConfig::parse(&mut args),
             Ok(ScriptExpressionConfig {
                 compute_checksum: true,
-                verify_checksum: false
+                verify_checksum: false,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: false,
+                output: ScriptOutput::Expression,
+                keys: vec![],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
             })
         );
     }
@@ -67,7 +355,22 @@ mod tests {
             ScriptExpressionConfig::parse(&mut args),
             Ok(ScriptExpressionConfig {
                 compute_checksum: false,
-                verify_checksum: true
+                verify_checksum: true,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: false,
+                output: ScriptOutput::Expression,
+                keys: vec![],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
             })
         );
     }
@@ -87,4 +390,482 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_disassemble_flag_provided() {
+        let mut args = vec!["script-expression", "--disassemble", "--verify-checksum"];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                compute_checksum: false,
+                verify_checksum: true,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: false,
+                output: ScriptOutput::Disassembly,
+                keys: vec![],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_key_flags_provided() {
+        let mut args = vec![
+            "script-expression",
+            "--key",
+            "alice=02aa",
+            "--compute-checksum",
+            "--key",
+            "bob=03bb",
+        ];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                compute_checksum: true,
+                verify_checksum: false,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: false,
+                output: ScriptOutput::Expression,
+                keys: vec![
+                    ("alice".to_string(), "02aa".to_string()),
+                    ("bob".to_string(), "03bb".to_string())
+                ],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
+            })
+        );
+        assert_eq!(args, vec!["script-expression"]);
+    }
+
+    #[test]
+    fn test_key_flag_with_verify_checksum() {
+        let mut args = vec![
+            "script-expression",
+            "--key",
+            "alice=02aa",
+            "--verify-checksum",
+        ];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "'--verify-checksum' cannot be used with '--key', the checksum of a templated script is always computed"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_first_address_flag_provided() {
+        let mut args = vec!["script-expression", "--first-address"];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                compute_checksum: false,
+                verify_checksum: false,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: false,
+                output: ScriptOutput::FirstAddress(None),
+                keys: vec![],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
+            })
+        );
+
+        let mut args = vec!["script-expression", "--first-address", "--disassemble"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "use only '--disassemble' or '--first-address', not both"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_verify_addresses_flag_provided() {
+        let mut args = vec!["script-expression", "-", "--verify-addresses", "list.txt"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::VerifyAddresses("list.txt".to_string()))
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+
+        for args in [
+            vec![
+                "script-expression",
+                "--verify-addresses",
+                "list.txt",
+                "--first-address",
+            ],
+            vec![
+                "script-expression",
+                "--verify-addresses",
+                "list.txt",
+                "--rewrite",
+            ],
+            vec!["script-expression", "--verify-addresses"],
+        ] {
+            let mut args = args;
+            assert!(
+                ScriptExpressionConfig::parse(&mut args).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_change_flag_provided() {
+        let mut args = vec![
+            "script-expression",
+            "pkh(K/0/*)",
+            "--check-change",
+            "pkh(K/1/*)",
+        ];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::CheckChange("pkh(K/1/*)".to_string()))
+        );
+        assert_eq!(args, vec!["script-expression", "pkh(K/0/*)"]);
+
+        for args in [
+            vec!["script-expression", "--check-change", "C", "--disassemble"],
+            vec![
+                "script-expression",
+                "--check-change",
+                "C",
+                "--verify-addresses",
+                "list.txt",
+            ],
+            vec!["script-expression", "--check-change", "C", "--rewrite"],
+            vec!["script-expression", "--check-change", "C", "--anonymize"],
+        ] {
+            let mut args = args;
+            assert!(
+                ScriptExpressionConfig::parse(&mut args).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_fingerprint_flag_provided() {
+        let mut args = vec!["script-expression", "-", "--find-fingerprint", "DEADBEEF"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::FindFingerprint([0xde, 0xad, 0xbe, 0xef]))
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+
+        for args in [
+            vec!["script-expression", "--find-fingerprint", "deadbee"],
+            vec![
+                "script-expression",
+                "--find-fingerprint",
+                "deadbeef",
+                "--anonymize",
+            ],
+        ] {
+            let mut args = args;
+            assert!(
+                ScriptExpressionConfig::parse(&mut args).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotate_key_flag_provided() {
+        let mut args = vec!["script-expression", "-", "--rotate-key", "02aa=03bb"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::RotatedKey {
+                old: "02aa".to_string(),
+                new: "03bb".to_string()
+            })
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+
+        for args in [
+            vec!["script-expression", "--rotate-key", "02aa"],
+            vec!["script-expression", "--rotate-key", "02aa="],
+            vec![
+                "script-expression",
+                "--rotate-key",
+                "02aa=03bb",
+                "--disassemble",
+            ],
+        ] {
+            let mut args = args;
+            assert!(
+                ScriptExpressionConfig::parse(&mut args).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_threshold_flag_provided() {
+        let mut args = vec!["script-expression", "-", "--set-threshold", "3"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::Threshold(3))
+        );
+
+        let mut args = vec!["script-expression", "--set-threshold", "three"];
+        assert!(ScriptExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_explorer_url_flag_provided() {
+        let mut args = vec![
+            "script-expression",
+            "--first-address",
+            "--explorer-url",
+            "mempool",
+        ];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::FirstAddress(Some(Explorer::Mempool)))
+        );
+
+        let mut args = vec!["script-expression", "--explorer-url", "mempool"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "'--explorer-url' can only be used with '--first-address'"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_psbt_output_flag_provided() {
+        let mut args = vec!["script-expression", "-", "--psbt-output", "5"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args).map(|config| config.output),
+            Ok(ScriptOutput::PsbtOutput(5))
+        );
+
+        for args in [
+            vec!["script-expression", "--psbt-output", "2147483648"],
+            vec!["script-expression", "--psbt-output", "0", "--disassemble"],
+        ] {
+            let mut args = args;
+            assert!(
+                ScriptExpressionConfig::parse(&mut args).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_flag_provided() {
+        let mut args = vec!["script-expression", "--normalize", "--compute-checksum"];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                compute_checksum: true,
+                verify_checksum: false,
+                verify_after: false,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: true,
+                output: ScriptOutput::Expression,
+                keys: vec![],
+                verify_origin: None,
+                network: None,
+                hardened_wildcard: HardenedWildcardPolicy::Allow,
+                whitespace: WhitespacePolicy::Preserve,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_whitespace_flag_provided() {
+        let mut args = vec!["script-expression", "--whitespace", "normalize"];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                whitespace: WhitespacePolicy::Normalize,
+                limits: ScriptLimits::default(),
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: None,
+                ..ScriptExpressionConfig::default()
+            })
+        );
+
+        let mut args = vec!["script-expression", "--whitespace", "tabs"];
+        assert!(ScriptExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_verify_after_flag_provided() {
+        let mut args = vec!["script-expression", "--compute-checksum", "--verify-after"];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                compute_checksum: true,
+                verify_after: true,
+                ..ScriptExpressionConfig::default()
+            })
+        );
+
+        let mut args = vec!["script-expression", "--verify-after"];
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "'--verify-after' can only be used with '--compute-checksum'"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_ignore_checksum_case_flag_provided() {
+        let mut args = vec![
+            "script-expression",
+            "--verify-checksum",
+            "--ignore-checksum-case",
+        ];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                verify_checksum: true,
+                ignore_checksum_case: true,
+                ..ScriptExpressionConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression"]);
+
+        let mut args = vec![
+            "script-expression",
+            "--compute-checksum",
+            "--ignore-checksum-case",
+        ];
+        assert!(ScriptExpressionConfig::parse(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_ensure_checksum_flag_provided() {
+        let mut args = vec![
+            "script-expression",
+            "--ensure-checksum",
+            "--rewrite",
+            "wallets.txt",
+        ];
+
+        assert_eq!(
+            ScriptExpressionConfig::parse(&mut args),
+            Ok(ScriptExpressionConfig {
+                ensure_checksum: true,
+                rewrite: true,
+                ..ScriptExpressionConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression", "wallets.txt"]);
+
+        for flag in ["--compute-checksum", "--verify-checksum", "--report"] {
+            let mut args = vec!["script-expression", "--ensure-checksum", flag];
+            assert!(ScriptExpressionConfig::parse(&mut args).is_err(), "{flag}");
+        }
+    }
+
+    #[test]
+    fn test_report_flag() {
+        let mut args = vec!["script-expression", "--report", "backup.txt"];
+        let config = ScriptExpressionConfig::parse(&mut args).unwrap();
+        assert!(config.report);
+        assert_eq!(args, vec!["script-expression", "backup.txt"]);
+
+        for flag in ["--rewrite", "--verify-checksum", "--anonymize"] {
+            let mut args = vec!["script-expression", "--report", flag, "backup.txt"];
+            assert!(ScriptExpressionConfig::parse(&mut args).is_err(), "{flag}");
+        }
+    }
+
+    #[test]
+    fn test_rewrite_flags() {
+        let mut args = vec![
+            "script-expression",
+            "--rewrite",
+            "dump.txt",
+            "--output-file",
+            "fixed.txt",
+        ];
+        let config = ScriptExpressionConfig::parse(&mut args).unwrap();
+        assert!(config.rewrite);
+        assert_eq!(config.output_file, Some("fixed.txt".to_string()));
+        assert_eq!(args, vec!["script-expression", "dump.txt"]);
+
+        for args in [
+            vec![
+                "script-expression",
+                "--output-file",
+                "fixed.txt",
+                "dump.txt",
+            ],
+            vec![
+                "script-expression",
+                "--rewrite",
+                "--verify-checksum",
+                "dump.txt",
+            ],
+            vec![
+                "script-expression",
+                "--rewrite",
+                "--disassemble",
+                "dump.txt",
+            ],
+            vec![
+                "script-expression",
+                "--rewrite",
+                "--output-file",
+                "fixed.txt",
+                "a.txt",
+                "b.txt",
+            ],
+        ] {
+            let mut args = args;
+            assert!(ScriptExpressionConfig::parse(&mut args).is_err());
+        }
+    }
 }
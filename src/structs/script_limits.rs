@@ -0,0 +1,120 @@
+use crate::{parsers::flag_parser::parse_value_flags, traits::parsable::Parsable};
+
+use super::parsing_error::ParsingError;
+
+/// The most keys of `multi(...)`, the limit of `OP_CHECKMULTISIG`.
+pub const DEFAULT_MAX_MULTI_KEYS: usize = 20;
+/// The deepest nesting of the script functions and their parentheses.
+pub const DEFAULT_MAX_NESTING: usize = 8;
+/// The largest compiled script in bytes, the consensus limit of Bitcoin.
+pub const DEFAULT_MAX_SCRIPT_SIZE: usize = 10_000;
+/// The most derivation steps of the key origin and the path appended to the key together, the depth of BIP 32
+/// is a single byte.
+pub const DEFAULT_MAX_ORIGIN_DEPTH: usize = 255;
+
+/// The standardness limits the script expressions are checked against, adjustable for chains with other
+/// policies (e.g. Liquid/Elements).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ScriptLimits {
+    /// The most keys of `multi(...)`.
+    pub multi_keys: usize,
+    /// The deepest nesting of the parentheses.
+    pub nesting: usize,
+    /// The largest compiled script in bytes.
+    pub script_size: usize,
+    /// The most derivation steps of a key origin and the path appended to its key.
+    pub origin_depth: usize,
+}
+
+impl ScriptLimits {
+    /// The default limits, following the policy of Bitcoin.
+    pub const STANDARD: ScriptLimits = ScriptLimits {
+        multi_keys: DEFAULT_MAX_MULTI_KEYS,
+        nesting: DEFAULT_MAX_NESTING,
+        script_size: DEFAULT_MAX_SCRIPT_SIZE,
+        origin_depth: DEFAULT_MAX_ORIGIN_DEPTH,
+    };
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        ScriptLimits::STANDARD
+    }
+}
+
+impl Parsable for ScriptLimits {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [multi_keys, nesting, script_size, origin_depth]: [Option<String>; 4] =
+            parse_value_flags(
+                args,
+                &[
+                    "max-multi-keys",
+                    "max-nesting",
+                    "max-script-size",
+                    "max-origin-depth",
+                ],
+            )?
+            .try_into()
+            .unwrap_or_default();
+
+        Ok(ScriptLimits {
+            multi_keys: parse_limit("max-multi-keys", multi_keys)?
+                .unwrap_or(DEFAULT_MAX_MULTI_KEYS),
+            nesting: parse_limit("max-nesting", nesting)?.unwrap_or(DEFAULT_MAX_NESTING),
+            script_size: parse_limit("max-script-size", script_size)?
+                .unwrap_or(DEFAULT_MAX_SCRIPT_SIZE),
+            origin_depth: parse_limit("max-origin-depth", origin_depth)?
+                .unwrap_or(DEFAULT_MAX_ORIGIN_DEPTH),
+        })
+    }
+}
+
+/// Parses the value of the limit flag, which must be a positive number.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the value is not a positive number.
+pub fn parse_limit(flag: &str, value: Option<String>) -> Result<Option<usize>, ParsingError> {
+    value
+        .map(|value| {
+            value
+                .parse()
+                .ok()
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| {
+                    ParsingError::new(&format!(
+                        "Value '{value}' of '--{flag}' must be a positive number"
+                    ))
+                })
+        })
+        .transpose()
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{structs::script_limits::ScriptLimits, traits::parsable::Parsable};
+
+    #[test]
+    fn test_limit_flags() {
+        let mut args = vec!["script-expression", "--max-multi-keys", "15", "-"];
+        assert_eq!(
+            ScriptLimits::parse(&mut args),
+            Ok(ScriptLimits {
+                multi_keys: 15,
+                ..Default::default()
+            })
+        );
+        assert_eq!(args, vec!["script-expression", "-"]);
+
+        let mut args = vec!["script-expression", "--max-origin-depth", "300", "-"];
+        assert_eq!(
+            ScriptLimits::parse(&mut args).map(|limits| limits.origin_depth),
+            Ok(300)
+        );
+
+        for value in ["0", "-1", "many"] {
+            let mut args = vec!["script-expression", "--max-script-size", value];
+            assert!(ScriptLimits::parse(&mut args).is_err());
+        }
+    }
+}
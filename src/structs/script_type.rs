@@ -0,0 +1,153 @@
+use super::{address_type::AddressType, parsing_error::ParsingError};
+
+/// Output script types used for single-key wallet descriptors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScriptType {
+    /// Legacy pay-to-pubkey-hash, `pkh(KEY)` (BIP 44).
+    Pkh,
+    /// Nested segwit, `sh(wpkh(KEY))` (BIP 49).
+    ShWpkh,
+    /// Native segwit, `wpkh(KEY)` (BIP 84).
+    Wpkh,
+    /// Taproot key path, `tr(KEY)` (BIP 86).
+    Tr,
+}
+
+impl ScriptType {
+    pub const ALL: [ScriptType; 4] = [
+        ScriptType::Pkh,
+        ScriptType::ShWpkh,
+        ScriptType::Wpkh,
+        ScriptType::Tr,
+    ];
+
+    /// Wraps the key expression into the descriptor of this script type (without a checksum).
+    #[must_use]
+    pub fn wrap(self, key_expression: &str) -> String {
+        match self {
+            ScriptType::Pkh => format!("pkh({key_expression})"),
+            ScriptType::ShWpkh => format!("sh(wpkh({key_expression}))"),
+            ScriptType::Wpkh => format!("wpkh({key_expression})"),
+            ScriptType::Tr => format!("tr({key_expression})"),
+        }
+    }
+
+    /// Returns the key expression of a descriptor (without a checksum) of this script type, the reverse
+    /// of [`ScriptType::wrap`], or none if the descriptor is of another type.
+    #[must_use]
+    pub fn key_expression_of(self, descriptor: &str) -> Option<&str> {
+        let (prefix, suffix) = match self {
+            ScriptType::Pkh => ("pkh(", ")"),
+            ScriptType::ShWpkh => ("sh(wpkh(", "))"),
+            ScriptType::Wpkh => ("wpkh(", ")"),
+            ScriptType::Tr => ("tr(", ")"),
+        };
+        descriptor.strip_prefix(prefix)?.strip_suffix(suffix)
+    }
+
+    /// Returns the BIP 44 style purpose whose accounts use this script type.
+    #[must_use]
+    pub fn purpose(self) -> u32 {
+        match self {
+            ScriptType::Pkh => 44,
+            ScriptType::ShWpkh => 49,
+            ScriptType::Wpkh => 84,
+            ScriptType::Tr => 86,
+        }
+    }
+
+    /// Returns the type of the addresses the descriptors of this script type derive.
+    #[must_use]
+    pub fn address_type(self) -> AddressType {
+        match self {
+            ScriptType::Pkh => AddressType::P2pkh,
+            ScriptType::ShWpkh => AddressType::P2shP2wpkh,
+            ScriptType::Wpkh => AddressType::P2wpkh,
+            ScriptType::Tr => AddressType::P2tr,
+        }
+    }
+
+    /// Returns the script type used by accounts of the given purpose (44, 49, 84 or 86).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the purpose is not one of the supported ones.
+    pub fn from_purpose(purpose: &str) -> Result<Self, ParsingError> {
+        [
+            ScriptType::Pkh,
+            ScriptType::ShWpkh,
+            ScriptType::Wpkh,
+            ScriptType::Tr,
+        ]
+        .into_iter()
+        .find(|script_type| script_type.purpose().to_string() == purpose)
+        .ok_or_else(|| {
+            ParsingError::new(&format!(
+                "Unsupported purpose '{purpose}', expected one of 44, 49, 84 or 86"
+            ))
+        })
+    }
+}
+
+impl TryFrom<&str> for ScriptType {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "pkh" => Ok(ScriptType::Pkh),
+            "sh-wpkh" => Ok(ScriptType::ShWpkh),
+            "wpkh" => Ok(ScriptType::Wpkh),
+            "tr" => Ok(ScriptType::Tr),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported script type '{value}', expected one of 'pkh', 'sh-wpkh', 'wpkh' or 'tr'"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_type_parsing_and_wrapping() {
+        assert_eq!(
+            ScriptType::try_from("pkh").map(|t| t.wrap("K")),
+            Ok("pkh(K)".to_string())
+        );
+        assert_eq!(
+            ScriptType::try_from("sh-wpkh").map(|t| t.wrap("K")),
+            Ok("sh(wpkh(K))".to_string())
+        );
+        assert_eq!(
+            ScriptType::try_from("wpkh").map(|t| t.wrap("K")),
+            Ok("wpkh(K)".to_string())
+        );
+        assert_eq!(
+            ScriptType::try_from("tr").map(|t| t.wrap("K")),
+            Ok("tr(K)".to_string())
+        );
+        assert!(ScriptType::try_from("wsh").is_err());
+    }
+
+    #[test]
+    fn test_key_expression_of() {
+        for script_type in ScriptType::ALL {
+            assert_eq!(
+                script_type.key_expression_of(&script_type.wrap("K")),
+                Some("K")
+            );
+        }
+        assert_eq!(ScriptType::Wpkh.key_expression_of("sh(wpkh(K))"), None);
+        assert_eq!(ScriptType::Pkh.key_expression_of("wsh(pkh(K))"), None);
+    }
+
+    #[test]
+    fn test_script_type_from_purpose() {
+        assert_eq!(ScriptType::from_purpose("44"), Ok(ScriptType::Pkh));
+        assert_eq!(ScriptType::from_purpose("49"), Ok(ScriptType::ShWpkh));
+        assert_eq!(ScriptType::from_purpose("84"), Ok(ScriptType::Wpkh));
+        assert_eq!(ScriptType::from_purpose("86"), Ok(ScriptType::Tr));
+        assert!(ScriptType::from_purpose("48").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use zeroize::Zeroize;
+
+/// A buffer holding secret bytes (e.g. a seed), which is zeroed when dropped.
+///
+/// With the `mlock` feature, the buffer is also locked into memory on Unix for its whole lifetime, so it is
+/// never swapped to disk (e.g. in the long-running serve and watch modes). Failing to lock it (e.g. due to
+/// the `RLIMIT_MEMLOCK` limit) is not an error, it is only logged.
+pub struct SecretBuffer {
+    bytes: Vec<u8>,
+    locked: bool,
+}
+
+impl SecretBuffer {
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> SecretBuffer {
+        let locked = lock(&bytes);
+        SecretBuffer { bytes, locked }
+    }
+}
+
+impl AsRef<[u8]> for SecretBuffer {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl std::fmt::Debug for SecretBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SecretBuffer([redacted; {}])", self.bytes.len())
+    }
+}
+
+impl PartialEq for SecretBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for SecretBuffer {}
+
+impl Drop for SecretBuffer {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+        if self.locked {
+            unlock(&self.bytes);
+        }
+    }
+}
+
+#[cfg(all(feature = "mlock", unix))]
+fn lock(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+    // SAFETY: the pointer and the length describe the allocation of the slice
+    let locked = unsafe { libc::mlock(bytes.as_ptr().cast(), bytes.len()) } == 0;
+    if !locked {
+        tracing::warn!(
+            "Could not lock a secret buffer into memory: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+    locked
+}
+
+#[cfg(not(all(feature = "mlock", unix)))]
+fn lock(_bytes: &[u8]) -> bool {
+    false
+}
+
+#[cfg(all(feature = "mlock", unix))]
+fn unlock(bytes: &[u8]) {
+    // SAFETY: the pointer and the length describe the allocation of the slice, which was locked before
+    unsafe {
+        libc::munlock(bytes.as_ptr().cast(), bytes.len());
+    }
+}
+
+#[cfg(not(all(feature = "mlock", unix)))]
+fn unlock(_bytes: &[u8]) {}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::structs::secret_buffer::SecretBuffer;
+
+    #[test]
+    fn test_secret_buffer() {
+        let buffer = SecretBuffer::new(vec![0, 1, 2, 3]);
+
+        assert_eq!(buffer.as_ref(), &[0, 1, 2, 3]);
+        assert_eq!(format!("{buffer:?}"), "SecretBuffer([redacted; 4])");
+    }
+}
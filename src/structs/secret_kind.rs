@@ -0,0 +1,23 @@
+/// The kind of secret a value looks like, see [`classify_secret`](crate::subcommands::utils::secret::classify_secret).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SecretKind {
+    /// An extended private key (`xprv` or `tprv`), possibly within a key or script expression.
+    ExtendedPrivateKey,
+    /// A private key in the Wallet Import Format.
+    Wif,
+    /// A hexadecimal seed.
+    Seed,
+    /// A BIP 39 mnemonic phrase.
+    Mnemonic,
+}
+
+impl std::fmt::Display for SecretKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SecretKind::ExtendedPrivateKey => write!(f, "an extended private key"),
+            SecretKind::Wif => write!(f, "a WIF private key"),
+            SecretKind::Seed => write!(f, "a seed"),
+            SecretKind::Mnemonic => write!(f, "a mnemonic"),
+        }
+    }
+}
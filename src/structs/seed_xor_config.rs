@@ -0,0 +1,124 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flags},
+    traits::parsable::Parsable,
+};
+
+use super::{mnemonic_language::MnemonicLanguage, parsing_error::ParsingError};
+
+/// The most shares a seed can be split into.
+const MAX_SHARE_COUNT: u8 = 16;
+
+/// The direction of the seed-xor sub-command.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SeedXorMode {
+    /// Splits a seed into the number of random shares, all of which are needed to recover it.
+    Split(u8),
+    /// Combines the shares back into the seed.
+    Combine,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SeedXorConfig {
+    pub mode: SeedXorMode,
+    /// Whether the shares (or the recovered seed) are printed as hex entropy instead of the mnemonics.
+    pub hex: bool,
+    /// The language of the printed mnemonics, the language of the input mnemonic (or English) if not given.
+    pub language: Option<MnemonicLanguage>,
+}
+
+impl Parsable for SeedXorConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [split, language]: [Option<String>; 2] =
+            parse_value_flags(args, &["split", "language"])?
+                .try_into()
+                .unwrap_or_default();
+        let combine = parse_boolean_flag(args, "combine");
+        let hex = parse_boolean_flag(args, "hex");
+
+        let mode = match (split, combine) {
+            (Some(count), false) => SeedXorMode::Split(
+                count
+                    .parse()
+                    .ok()
+                    .filter(|count| (2..=MAX_SHARE_COUNT).contains(count))
+                    .ok_or_else(|| {
+                        ParsingError::new(&format!(
+                            "Value '{count}' of '--split' must be from the range [2,...,{MAX_SHARE_COUNT}]"
+                        ))
+                    })?,
+            ),
+            (None, true) => SeedXorMode::Combine,
+            (Some(_), true) => {
+                return Err(ParsingError::new(
+                    "use only '--split' or '--combine', not both",
+                ))
+            }
+            (None, false) => {
+                return Err(ParsingError::new(
+                    "The seed-xor sub-command requires either '--split {n}' or '--combine'",
+                ))
+            }
+        };
+
+        let language = language
+            .map(|language| MnemonicLanguage::try_from(language.as_str()))
+            .transpose()?;
+
+        Ok(SeedXorConfig {
+            mode,
+            hex,
+            language,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            mnemonic_language::MnemonicLanguage,
+            parsing_error::ParsingError,
+            seed_xor_config::{SeedXorConfig, SeedXorMode},
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_mode_flags_provided() {
+        let mut args = vec!["seed-xor", "--split", "3", "-", "--hex"];
+        assert_eq!(
+            SeedXorConfig::parse(&mut args),
+            Ok(SeedXorConfig {
+                mode: SeedXorMode::Split(3),
+                hex: true,
+                language: None
+            })
+        );
+        assert_eq!(args, vec!["seed-xor", "-"]);
+
+        let mut args = vec!["seed-xor", "--combine", "-", "--language", "czech"];
+        assert_eq!(
+            SeedXorConfig::parse(&mut args),
+            Ok(SeedXorConfig {
+                mode: SeedXorMode::Combine,
+                hex: false,
+                language: Some(MnemonicLanguage::Czech)
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        for args in [
+            vec!["seed-xor", "-"],
+            vec!["seed-xor", "--split", "2", "--combine", "-"],
+            vec!["seed-xor", "--split", "1", "-"],
+            vec!["seed-xor", "--split", "17", "-"],
+            vec!["seed-xor", "--split", "two", "-"],
+            vec!["seed-xor", "--combine", "--language", "klingon", "-"],
+        ] {
+            let mut args = args;
+            assert!(SeedXorConfig::parse(&mut args).is_err(), "{args:?}");
+        }
+    }
+}
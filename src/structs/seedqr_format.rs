@@ -0,0 +1,25 @@
+use super::parsing_error::ParsingError;
+
+/// The `SeedQR` encoding of a BIP 39 mnemonic.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SeedQrFormat {
+    /// The 4-digit (zero-padded) wordlist indexes of the words, concatenated.
+    #[default]
+    Standard,
+    /// The entropy of the mnemonic (without the checksum), printed in hexadecimal.
+    Compact,
+}
+
+impl TryFrom<&str> for SeedQrFormat {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "standard" => Ok(SeedQrFormat::Standard),
+            "compact" => Ok(SeedQrFormat::Compact),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported SeedQR format '{value}', expected one of 'standard' or 'compact'"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    parsers::flag_parser::parse_value_flags, traits::parsable::Parsable,
+    utils::error_messages::MISSING_LISTENER_ERR_MSG,
+};
+
+use super::parsing_error::ParsingError;
+
+/// Where the server listens for requests.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ServeListener {
+    /// A path of the Unix socket to create, answering newline-delimited requests.
+    Socket(String),
+    /// An address (e.g. `127.0.0.1:8380`) to serve the HTTP JSON API on.
+    Http(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServeConfig {
+    pub listener: ServeListener,
+}
+
+impl Parsable for ServeConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [socket, http]: [Option<String>; 2] = parse_value_flags(args, &["socket", "http"])?
+            .try_into()
+            .unwrap_or_default();
+
+        let listener = match (socket, http) {
+            (Some(_), Some(_)) => {
+                return Err(ParsingError::new(
+                    "use only '--socket' or '--http', not both",
+                ))
+            }
+            (Some(path), None) => ServeListener::Socket(path),
+            (None, Some(address)) => ServeListener::Http(address),
+            (None, None) => return Err(ParsingError::new(MISSING_LISTENER_ERR_MSG)),
+        };
+
+        Ok(ServeConfig { listener })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            parsing_error::ParsingError,
+            serve_config::{ServeConfig, ServeListener},
+        },
+        traits::parsable::Parsable,
+        utils::error_messages::MISSING_LISTENER_ERR_MSG,
+    };
+
+    #[test]
+    fn test_socket_flag_provided() {
+        let mut args = vec!["serve", "--socket", "/tmp/bip380.sock"];
+
+        assert_eq!(
+            ServeConfig::parse(&mut args),
+            Ok(ServeConfig {
+                listener: ServeListener::Socket("/tmp/bip380.sock".to_string())
+            })
+        );
+        assert_eq!(args, vec!["serve"]);
+    }
+
+    #[test]
+    fn test_http_flag_provided() {
+        let mut args = vec!["serve", "--http", "127.0.0.1:8380"];
+
+        assert_eq!(
+            ServeConfig::parse(&mut args),
+            Ok(ServeConfig {
+                listener: ServeListener::Http("127.0.0.1:8380".to_string())
+            })
+        );
+
+        let mut args = vec![
+            "serve",
+            "--http",
+            "127.0.0.1:8380",
+            "--socket",
+            "/tmp/bip380.sock",
+        ];
+        assert_eq!(
+            ServeConfig::parse(&mut args),
+            Err(ParsingError::new(
+                "use only '--socket' or '--http', not both"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_listener_flag_missing() {
+        let mut args = vec!["serve"];
+
+        assert_eq!(
+            ServeConfig::parse(&mut args),
+            Err(ParsingError::new(MISSING_LISTENER_ERR_MSG))
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_repeated_value_flag, parse_value_flags},
+    subcommands::utils::passphrase::parse_passphrase_flags,
+    traits::parsable::Parsable,
+};
+
+use super::{parsing_error::ParsingError, secret_buffer::SecretBuffer};
+
+/// The most groups, and the most shares in a group, SLIP-39 can encode.
+const MAX_SHARE_COUNT: u8 = 16;
+
+/// The highest iteration exponent SLIP-39 can encode.
+const MAX_ITERATION_EXPONENT: u8 = 15;
+
+/// A group of SLIP-39 shares, `threshold` of the `count` shares recover the group's share of the secret.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Slip39Group {
+    pub threshold: u8,
+    pub count: u8,
+}
+
+impl TryFrom<&str> for Slip39Group {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let group = value
+            .split_once("of")
+            .and_then(|(threshold, count)| Some((threshold.parse().ok()?, count.parse().ok()?)))
+            .map(|(threshold, count)| Slip39Group { threshold, count });
+
+        let Some(group) = group.filter(|group| {
+            (1..=group.count).contains(&group.threshold) && group.count <= MAX_SHARE_COUNT
+        }) else {
+            return Err(ParsingError::new(&format!(
+                "Unsupported group '{value}', expected '{{threshold}}of{{count}}' with 1 <= threshold <= count <= {MAX_SHARE_COUNT}"
+            )));
+        };
+        if group.threshold == 1 && group.count > 1 {
+            return Err(ParsingError::new(&format!(
+                "Group '{value}' would hand out copies of the secret, use '1of1' instead"
+            )));
+        }
+
+        Ok(group)
+    }
+}
+
+/// The direction of the slip39 sub-command.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Slip39Mode {
+    /// Splits a master secret into the shares of the groups, `group_threshold` of which are needed.
+    Split {
+        group_threshold: u8,
+        groups: Vec<Slip39Group>,
+    },
+    /// Combines the shares back into the master secret.
+    Combine,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Slip39Config {
+    pub mode: Slip39Mode,
+    /// The exponent of the PBKDF2 iterations encrypting the master secret, `10000 * 2^e` in total.
+    pub iteration_exponent: u8,
+    /// The passphrase the master secret is encrypted with, read from the environment, a file or
+    /// the terminal, empty if not given.
+    pub passphrase: Option<SecretBuffer>,
+}
+
+impl Parsable for Slip39Config {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [group_threshold, iteration_exponent]: [Option<String>; 2] =
+            parse_value_flags(args, &["group-threshold", "iteration-exponent"])?
+                .try_into()
+                .unwrap_or_default();
+        let groups = parse_repeated_value_flag(args, "group")?
+            .iter()
+            .map(|group| Slip39Group::try_from(group.as_str()))
+            .collect::<Result<Vec<Slip39Group>, ParsingError>>()?;
+
+        let mode = match (
+            parse_boolean_flag(args, "split"),
+            parse_boolean_flag(args, "combine"),
+        ) {
+            (true, false) => split_mode(group_threshold, groups)?,
+            (false, true) if groups.is_empty() && group_threshold.is_none() => Slip39Mode::Combine,
+            (false, true) => {
+                return Err(ParsingError::new(
+                    "'--group' and '--group-threshold' can only be used with '--split'",
+                ))
+            }
+            (true, true) => {
+                return Err(ParsingError::new(
+                    "use only '--split' or '--combine', not both",
+                ))
+            }
+            (false, false) => {
+                return Err(ParsingError::new(
+                    "The slip39 sub-command requires either '--split' or '--combine'",
+                ))
+            }
+        };
+
+        let iteration_exponent = match iteration_exponent {
+            None => 1,
+            Some(exponent) => exponent
+                .parse()
+                .ok()
+                .filter(|exponent| *exponent <= MAX_ITERATION_EXPONENT)
+                .ok_or_else(|| {
+                    ParsingError::new(&format!(
+                        "Value '{exponent}' of '--iteration-exponent' must be from the range [0,...,{MAX_ITERATION_EXPONENT}]"
+                    ))
+                })?,
+        };
+
+        let passphrase = parse_passphrase_flags(args, "SLIP-39")?;
+        if passphrase.as_ref().is_some_and(|passphrase| {
+            !passphrase
+                .as_ref()
+                .iter()
+                .all(|c| (b' '..=b'~').contains(c))
+        }) {
+            return Err(ParsingError::new(
+                "The SLIP-39 passphrase must consist of printable ASCII characters only",
+            ));
+        }
+
+        Ok(Slip39Config {
+            mode,
+            iteration_exponent,
+            passphrase,
+        })
+    }
+}
+
+fn split_mode(
+    group_threshold: Option<String>,
+    groups: Vec<Slip39Group>,
+) -> Result<Slip39Mode, ParsingError> {
+    if groups.is_empty() {
+        return Err(ParsingError::new(
+            "'--split' requires at least one '--group {threshold}of{count}'",
+        ));
+    }
+    if groups.len() > usize::from(MAX_SHARE_COUNT) {
+        return Err(ParsingError::new(&format!(
+            "At most {MAX_SHARE_COUNT} groups are supported"
+        )));
+    }
+
+    let group_threshold = match group_threshold {
+        None => 1,
+        Some(threshold) => threshold
+            .parse()
+            .ok()
+            .filter(|threshold| (1..=groups.len()).contains(&usize::from(*threshold)))
+            .ok_or_else(|| {
+                ParsingError::new(&format!(
+                    "Value '{threshold}' of '--group-threshold' must be from the range [1,...,{}], the number of groups",
+                    groups.len()
+                ))
+            })?,
+    };
+
+    Ok(Slip39Mode::Split {
+        group_threshold,
+        groups,
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            parsing_error::ParsingError,
+            slip39_config::{Slip39Config, Slip39Group, Slip39Mode},
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_mode_flags_provided() {
+        let mut args = vec![
+            "slip39",
+            "--split",
+            "-",
+            "--group",
+            "2of3",
+            "--group",
+            "1of1",
+            "--group-threshold",
+            "2",
+        ];
+        assert_eq!(
+            Slip39Config::parse(&mut args),
+            Ok(Slip39Config {
+                mode: Slip39Mode::Split {
+                    group_threshold: 2,
+                    groups: vec![
+                        Slip39Group {
+                            threshold: 2,
+                            count: 3
+                        },
+                        Slip39Group {
+                            threshold: 1,
+                            count: 1
+                        }
+                    ]
+                },
+                iteration_exponent: 1,
+                passphrase: None
+            })
+        );
+        assert_eq!(args, vec!["slip39", "-"]);
+
+        let mut args = vec!["slip39", "--combine", "-", "--iteration-exponent", "0"];
+        assert_eq!(
+            Slip39Config::parse(&mut args),
+            Ok(Slip39Config {
+                mode: Slip39Mode::Combine,
+                iteration_exponent: 0,
+                passphrase: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        for args in [
+            vec!["slip39", "-"],
+            vec!["slip39", "--split", "--combine", "-"],
+            vec!["slip39", "--split", "-"],
+            vec!["slip39", "--combine", "--group", "2of3", "-"],
+            vec!["slip39", "--split", "--group", "1of3", "-"],
+            vec!["slip39", "--split", "--group", "4of3", "-"],
+            vec!["slip39", "--split", "--group", "2of17", "-"],
+            vec!["slip39", "--split", "--group", "two", "-"],
+            vec![
+                "slip39",
+                "--split",
+                "--group",
+                "2of3",
+                "--group-threshold",
+                "2",
+                "-",
+            ],
+            vec![
+                "slip39",
+                "--split",
+                "--group",
+                "2of3",
+                "--iteration-exponent",
+                "16",
+                "-",
+            ],
+        ] {
+            let mut args = args;
+            assert!(Slip39Config::parse(&mut args).is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt::Display;
+
+use crate::traits::output_fields::OutputFields;
+
+/// The wallet account bootstrapped by the wallet-init sub-command.
+pub struct WalletAccount {
+    pub fingerprint: String,
+    pub xprv: String,
+    pub xpub: String,
+    pub receive: String,
+    pub change: String,
+}
+
+impl Display for WalletAccount {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let fields = self.fields();
+        let lines: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl OutputFields for WalletAccount {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("fingerprint", self.fingerprint.clone()),
+            ("xprv", self.xprv.clone()),
+            ("xpub", self.xpub.clone()),
+            ("receive", self.receive.clone()),
+            ("change", self.change.clone()),
+        ]
+    }
+}
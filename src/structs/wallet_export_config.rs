@@ -0,0 +1,120 @@
+use crate::{parsers::flag_parser::parse_value_flags, traits::parsable::Parsable};
+
+use super::{export_target::ExportTarget, parsing_error::ParsingError};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WalletExportConfig {
+    pub target: ExportTarget,
+    /// The name of the wallet shown by the wallet software.
+    pub label: String,
+    /// The height of the block the wallet is scanned from, `0` for the whole chain.
+    pub blockheight: u32,
+}
+
+impl Parsable for WalletExportConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [target, label, blockheight]: [Option<String>; 3] =
+            parse_value_flags(args, &["target", "label", "blockheight"])?
+                .try_into()
+                .unwrap_or_default();
+
+        let target = target
+            .map(|target| ExportTarget::try_from(target.as_str()))
+            .transpose()?
+            .ok_or_else(|| {
+                ParsingError::new(
+                    "The wallet-export sub-command requires '--target specter' or '--target sparrow'",
+                )
+            })?;
+
+        let label = match label {
+            Some(label) if label.trim().is_empty() => {
+                return Err(ParsingError::new("The wallet label cannot be empty"))
+            }
+            Some(label) => label,
+            None => "Multisig".to_string(),
+        };
+
+        let blockheight = blockheight
+            .map(|blockheight| {
+                blockheight.parse::<u32>().map_err(|_| {
+                    ParsingError::new(&format!(
+                        "Block height '{blockheight}' must be a non-negative integer"
+                    ))
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(WalletExportConfig {
+            target,
+            label,
+            blockheight,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            export_target::ExportTarget, parsing_error::ParsingError,
+            wallet_export_config::WalletExportConfig,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_flags_provided() {
+        let mut args = vec![
+            "wallet-export",
+            "-",
+            "--target",
+            "sparrow",
+            "--label",
+            "Family vault",
+            "--blockheight",
+            "800000",
+        ];
+
+        assert_eq!(
+            WalletExportConfig::parse(&mut args),
+            Ok(WalletExportConfig {
+                target: ExportTarget::Sparrow,
+                label: "Family vault".to_string(),
+                blockheight: 800_000,
+            })
+        );
+        assert_eq!(args, vec!["wallet-export", "-"]);
+
+        let mut args = vec!["wallet-export", "-", "--target", "specter"];
+        assert_eq!(
+            WalletExportConfig::parse(&mut args),
+            Ok(WalletExportConfig {
+                target: ExportTarget::Specter,
+                label: "Multisig".to_string(),
+                blockheight: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        for args in [
+            vec!["wallet-export", "-"],
+            vec!["wallet-export", "-", "--target", "electrum"],
+            vec!["wallet-export", "-", "--target", "specter", "--label", " "],
+            vec![
+                "wallet-export",
+                "-",
+                "--target",
+                "specter",
+                "--blockheight",
+                "-1",
+            ],
+        ] {
+            let mut args = args;
+            assert!(WalletExportConfig::parse(&mut args).is_err());
+        }
+    }
+}
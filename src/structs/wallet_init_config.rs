@@ -0,0 +1,174 @@
+use crate::{
+    parsers::flag_parser::parse_value_flags,
+    subcommands::utils::{number::parse_index, passphrase::parse_passphrase_flags},
+    traits::parsable::Parsable,
+};
+
+use super::{
+    mnemonic_language::MnemonicLanguage, network::Network, parsing_error::ParsingError,
+    script_type::ScriptType, secret_buffer::SecretBuffer,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WalletInitConfig {
+    pub script_type: ScriptType,
+    pub account: u32,
+    pub network: Network,
+    /// The language of the input mnemonic, detected from its words if not given.
+    pub language: Option<MnemonicLanguage>,
+    /// The BIP 39 passphrase of the mnemonic, read from the environment, a file or the terminal (never from
+    /// the arguments, which leak through the shell history and the process list), empty if not given.
+    pub passphrase: Option<SecretBuffer>,
+}
+
+impl Default for WalletInitConfig {
+    fn default() -> Self {
+        WalletInitConfig {
+            script_type: ScriptType::Wpkh,
+            account: 0,
+            network: Network::default(),
+            language: None,
+            passphrase: None,
+        }
+    }
+}
+
+impl Parsable for WalletInitConfig {
+    fn parse(args: &mut Vec<&str>) -> Result<Self, ParsingError> {
+        let [purpose, account, network, language]: [Option<String>; 4] =
+            parse_value_flags(args, &["purpose", "account", "network", "language"])?
+                .try_into()
+                .unwrap_or_default();
+        let defaults = WalletInitConfig::default();
+
+        let script_type = purpose
+            .map(|purpose| ScriptType::from_purpose(&purpose))
+            .transpose()?
+            .unwrap_or(defaults.script_type);
+
+        let account = account
+            .map(|account| match parse_index(&account) {
+                Some(account) => Ok(account),
+                None => Err(ParsingError::new(&format!(
+                    "Account number '{account}' must be from the range [0,...,2^31-1]"
+                ))),
+            })
+            .transpose()?
+            .unwrap_or(defaults.account);
+
+        let network = network
+            .map(|network| Network::try_from(network.as_str()))
+            .transpose()?
+            .unwrap_or(defaults.network);
+
+        let language = language
+            .map(|language| MnemonicLanguage::try_from(language.as_str()))
+            .transpose()?;
+
+        let passphrase = parse_passphrase_flags(args, "BIP 39")?;
+
+        Ok(WalletInitConfig {
+            script_type,
+            account,
+            network,
+            language,
+            passphrase,
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{
+        structs::{
+            mnemonic_language::MnemonicLanguage, network::Network, parsing_error::ParsingError,
+            script_type::ScriptType, secret_buffer::SecretBuffer,
+            wallet_init_config::WalletInitConfig,
+        },
+        traits::parsable::Parsable,
+    };
+
+    #[test]
+    fn test_no_flags_provided() {
+        let mut args = vec!["wallet-init", "input"];
+
+        assert_eq!(
+            WalletInitConfig::parse(&mut args),
+            Ok(WalletInitConfig {
+                script_type: ScriptType::Wpkh,
+                account: 0,
+                network: Network::Mainnet,
+                language: None,
+                passphrase: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_all_flags_provided() {
+        let mut args = vec![
+            "wallet-init",
+            "--network",
+            "testnet",
+            "input",
+            "--purpose",
+            "86",
+            "--account",
+            "3",
+            "--language",
+            "czech",
+        ];
+
+        assert_eq!(
+            WalletInitConfig::parse(&mut args),
+            Ok(WalletInitConfig {
+                script_type: ScriptType::Tr,
+                account: 3,
+                network: Network::Testnet,
+                language: Some(MnemonicLanguage::Czech),
+                passphrase: None
+            })
+        );
+        assert_eq!(args, vec!["wallet-init", "input"]);
+    }
+
+    #[test]
+    fn test_invalid_flag_values() {
+        assert!(WalletInitConfig::parse(&mut vec!["wallet-init", "--purpose", "45"]).is_err());
+        assert!(WalletInitConfig::parse(&mut vec!["wallet-init", "--network", "regtest"]).is_err());
+        assert!(WalletInitConfig::parse(&mut vec!["wallet-init", "--language", "latin"]).is_err());
+        assert!(WalletInitConfig::parse(&mut vec![
+            "wallet-init",
+            "--passphrase-env",
+            "PATH",
+            "--passphrase-prompt"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_passphrase_flag_provided() {
+        std::env::set_var("BIP380_TEST_WALLET_INIT_PASSPHRASE", "TREZOR");
+        let mut args = vec![
+            "wallet-init",
+            "-",
+            "--passphrase-env",
+            "BIP380_TEST_WALLET_INIT_PASSPHRASE",
+        ];
+
+        assert_eq!(
+            WalletInitConfig::parse(&mut args),
+            Ok(WalletInitConfig {
+                passphrase: Some(SecretBuffer::new(b"TREZOR".to_vec())),
+                ..WalletInitConfig::default()
+            })
+        );
+        assert_eq!(args, vec!["wallet-init", "-"]);
+        assert_eq!(
+            WalletInitConfig::parse(&mut vec!["wallet-init", "--account", "2147483648"]),
+            Err(ParsingError::new(
+                "Account number '2147483648' must be from the range [0,...,2^31-1]"
+            ))
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use super::parsing_error::ParsingError;
+
+/// How the whitespace other than spaces (e.g. tabs) is treated in script expressions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum WhitespacePolicy {
+    /// Kept as it is, so it is part of the checksum and may make the parsing fail depending on its position.
+    #[default]
+    Preserve,
+    /// Rejected anywhere in the script.
+    Strict,
+    /// Replaced with spaces before the script is parsed and its checksum computed or verified.
+    Normalize,
+}
+
+impl WhitespacePolicy {
+    /// Applies the policy to the script (without its checksum).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the policy is strict and the script contains whitespace other than spaces.
+    pub fn apply(self, script: String) -> Result<String, ParsingError> {
+        let is_non_space_whitespace = |c: char| c.is_whitespace() && c != ' ';
+        match self {
+            WhitespacePolicy::Preserve => Ok(script),
+            WhitespacePolicy::Strict => match script.chars().position(is_non_space_whitespace) {
+                Some(position) => Err(ParsingError::new(&format!(
+                    "script contains whitespace other than spaces at position {position}, which is not allowed with '--whitespace strict'"
+                ))),
+                None => Ok(script),
+            },
+            WhitespacePolicy::Normalize => Ok(script.replace(is_non_space_whitespace, " ")),
+        }
+    }
+}
+
+impl TryFrom<&str> for WhitespacePolicy {
+    type Error = ParsingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "preserve" => Ok(WhitespacePolicy::Preserve),
+            "strict" => Ok(WhitespacePolicy::Strict),
+            "normalize" => Ok(WhitespacePolicy::Normalize),
+            _ => Err(ParsingError::new(&format!(
+                "Unsupported whitespace policy '{value}', expected one of 'strict', 'normalize' or 'preserve'"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::structs::{address_config::AddressConfig, parsing_error::ParsingError};
+
+use super::{
+    key_expression::validate_key_expression,
+    utils::{
+        address::{key_output_script, script_to_address},
+        derived_public_key::derive_public_key,
+        hexadecimal::encode_hex,
+    },
+};
+
+/// Encodes the key expression as the address of the configured type, without a descriptor to be written
+/// first.
+///
+/// The key expression is validated in the context of the address type, so the segwit types refuse
+/// uncompressed keys and only P2TR accepts x-only keys. An extended key is derived along its path, the
+/// wildcard of a ranged one is replaced with `--index`. With `--explorer-url`, the URL of the address on the
+/// block explorer is printed after it, separated by a space.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The key expression is invalid or not allowed for the address type,
+/// - The key expression is ranged and no index was given.
+pub fn address(input: &str, config: &AddressConfig) -> Result<String, ParsingError> {
+    let script = address_script(input, config)?;
+    let address = script_to_address(&script, config.network)?;
+
+    Ok(match &config.explorer {
+        Some(explorer) => format!(
+            "{address} {}",
+            explorer.address_url(&address, config.network)
+        ),
+        None => address,
+    })
+}
+
+/// Encodes the key expression the same way as [`address`], returning the `address`, its `type`, the output
+/// `script` (in hexadecimal) and the explorer `url` (empty without `--explorer-url`) for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`address`].
+pub fn address_fields(
+    input: &str,
+    config: &AddressConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let script = address_script(input, config)?;
+    let address = script_to_address(&script, config.network)?;
+    let url = config
+        .explorer
+        .as_ref()
+        .map(|explorer| explorer.address_url(&address, config.network))
+        .unwrap_or_default();
+
+    Ok(vec![
+        ("address", address),
+        ("type", config.address_type.to_string()),
+        ("script", encode_hex(&script)),
+        ("url", url),
+    ])
+}
+
+fn address_script(input: &str, config: &AddressConfig) -> Result<Vec<u8>, ParsingError> {
+    let key_expression =
+        validate_key_expression(input.trim().to_string(), config.address_type.key_context())?;
+    let public_key = derive_public_key(&key_expression, config.index)?;
+
+    key_output_script(
+        &public_key,
+        config.address_type,
+        config.merkle_root.as_ref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::get_cmd;
+
+    // the generator point, whose hash160 is 751e76e8199196d454941c45d1b3a323f1433bd6
+    const KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_address_types() {
+        for (address_type, expected) in [
+            ("p2pkh", "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"),
+            ("p2wpkh", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+        ] {
+            get_cmd()
+                .args(["address", "--type", address_type, KEY])
+                .assert()
+                .success()
+                .stdout(format!("{expected}\n"));
+        }
+
+        // the first receive addresses of the BIP 49 (testnet) and BIP 86 test vectors
+        get_cmd()
+            .args([
+                "address",
+                "--type",
+                "p2sh-p2wpkh",
+                "--network",
+                "testnet",
+                "03a1af804ac108a8a51782198c2d034b28bf90c8803f5a53f76276fa69a4eae77f",
+            ])
+            .assert()
+            .success()
+            .stdout("2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2\n");
+        get_cmd()
+            .args([
+                "address",
+                "--type",
+                "p2tr",
+                "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+            ])
+            .assert()
+            .success()
+            .stdout("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr\n");
+    }
+
+    #[test]
+    fn test_merkle_root() {
+        // the output with a script tree of the BIP 341 wallet test vectors
+        get_cmd()
+            .args([
+                "address",
+                "--type",
+                "p2tr",
+                "--merkle-root",
+                "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+                "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+            ])
+            .assert()
+            .success()
+            .stdout("bc1pz37fc4cn9ah8anwm4xqqhvxygjf9rjf2resrw8h8w4tmvcs0863sa2e586\n");
+    }
+
+    #[test]
+    fn test_ranged_extended_key() {
+        // m/84h/0h/0h of the BIP 84 test vector, its first receive address
+        let xpub = "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V";
+        get_cmd()
+            .args([
+                "address",
+                "--type",
+                "p2wpkh",
+                "--index",
+                "0",
+                &format!("[73c5da0a/84h/0h/0h]{xpub}/0/*"),
+            ])
+            .assert()
+            .success()
+            .stdout("bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu\n");
+        get_cmd()
+            .args(["address", "--type", "p2wpkh", &format!("{xpub}/0/*")])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+        get_cmd()
+            .args(["address", "--type", "p2pkh", uncompressed])
+            .assert()
+            .success()
+            .stdout("1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm\n");
+        for address_type in ["p2wpkh", "p2sh-p2wpkh", "p2tr"] {
+            get_cmd()
+                .args(["address", "--type", address_type, uncompressed])
+                .assert()
+                .failure();
+        }
+
+        get_cmd()
+            .args(["address", "--type", "p2wpkh", &KEY[2..]])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_address_fields() {
+        get_cmd()
+            .args(["address", "--type", "p2wpkh", "--output", "json", KEY])
+            .assert()
+            .success()
+            .stdout("{\"address\":\"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\",\"type\":\"p2wpkh\",\"script\":\"0014751e76e8199196d454941c45d1b3a323f1433bd6\",\"url\":\"\"}\n");
+    }
+
+    #[test]
+    fn test_explorer_url() {
+        get_cmd()
+            .args([
+                "address",
+                "--type",
+                "p2wpkh",
+                "--explorer-url",
+                "blockstream",
+                KEY,
+            ])
+            .assert()
+            .success()
+            .stdout("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 https://blockstream.info/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n");
+    }
+}
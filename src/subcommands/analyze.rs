@@ -0,0 +1,200 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use crate::structs::{
+    parsing_error::ParsingError, script_expression_config::ScriptExpressionConfig,
+};
+
+use super::{
+    key_expression::split_key_expression,
+    script_expression::script_expression,
+    utils::{
+        checksum::CHECKSUM_DIVIDER_SYMBOL, extended_key::is_extended_public_key,
+        number::parse_threshold, script_compiler::collect_key_expressions,
+        script_functions::parse_script_function,
+    },
+};
+
+/// The statistics of the descriptors analyzed together by the analyze sub-command: the script types used, how many
+/// descriptors every fingerprint and extended public key appears in and the largest multisig.
+#[derive(Debug, Default)]
+pub struct DescriptorAnalysis {
+    pub descriptors: usize,
+    pub invalid: usize,
+    /// The number of the descriptors of every script type, e.g. `sh(multi)`.
+    script_types: BTreeMap<String, usize>,
+    /// The number of the descriptors every key origin fingerprint appears in.
+    fingerprints: BTreeMap<String, usize>,
+    /// The number of the descriptors every extended public key appears in.
+    extended_keys: BTreeMap<String, usize>,
+    /// The key count and the threshold of the largest `multi(...)` or `sortedmulti(...)` script.
+    largest_multisig: Option<(usize, usize)>,
+}
+
+impl DescriptorAnalysis {
+    /// Validates the descriptor (and its checksum, if present) the same way as script-expression and adds it to
+    /// the statistics. An invalid descriptor is only counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the descriptor is invalid.
+    pub fn add(&mut self, input: &str) -> Result<(), ParsingError> {
+        self.descriptors += 1;
+        let descriptor = match analyze_descriptor(input) {
+            Ok(descriptor) => descriptor,
+            Err(err) => {
+                self.invalid += 1;
+                return Err(err);
+            }
+        };
+
+        *self.script_types.entry(descriptor.script_type).or_default() += 1;
+        for fingerprint in descriptor.fingerprints {
+            *self.fingerprints.entry(fingerprint).or_default() += 1;
+        }
+        for extended_key in descriptor.extended_keys {
+            *self.extended_keys.entry(extended_key).or_default() += 1;
+        }
+        self.largest_multisig = self.largest_multisig.max(descriptor.largest_multisig);
+        Ok(())
+    }
+}
+
+impl Display for DescriptorAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Analyzed {} descriptors, {} invalid",
+            self.descriptors, self.invalid
+        )?;
+        write_counts(f, "Script types", &self.script_types)?;
+        write_counts(f, "Fingerprints", &self.fingerprints)?;
+        write_counts(f, "Extended public keys", &self.extended_keys)?;
+        match self.largest_multisig {
+            Some((keys, threshold)) => write!(f, "\nLargest multisig: {threshold} of {keys} keys"),
+            None => write!(f, "\nLargest multisig: none"),
+        }
+    }
+}
+
+/// Writes the section of the counts, the most frequent first (then in the alphabetical order), one per line.
+fn write_counts(
+    f: &mut std::fmt::Formatter,
+    title: &str,
+    counts: &BTreeMap<String, usize>,
+) -> std::fmt::Result {
+    if counts.is_empty() {
+        return write!(f, "\n{title}: none");
+    }
+
+    let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+    counts.sort_by(|(_, left), (_, right)| right.cmp(left));
+    write!(f, "\n{title}:")?;
+    for (name, count) in counts {
+        write!(f, "\n  {name}: {count}")?;
+    }
+    Ok(())
+}
+
+/// The statistics of a single valid descriptor.
+struct AnalyzedDescriptor {
+    script_type: String,
+    fingerprints: BTreeSet<String>,
+    extended_keys: BTreeSet<String>,
+    largest_multisig: Option<(usize, usize)>,
+}
+
+fn analyze_descriptor(input: &str) -> Result<AnalyzedDescriptor, ParsingError> {
+    let config = ScriptExpressionConfig {
+        verify_checksum: input.contains(CHECKSUM_DIVIDER_SYMBOL),
+        ..ScriptExpressionConfig::default()
+    };
+    script_expression(input, &config)?;
+
+    let script = input
+        .split(CHECKSUM_DIVIDER_SYMBOL)
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let mut largest_multisig = None;
+    let script_type = walk_script(script, &mut largest_multisig)?;
+
+    let (mut fingerprints, mut extended_keys) = (BTreeSet::new(), BTreeSet::new());
+    for key_expression in collect_key_expressions(script)? {
+        let (key_origin, key) = split_key_expression(key_expression.trim())?;
+        if let Some(fingerprint) = key_origin.and_then(|key_origin| key_origin.get(1..9)) {
+            fingerprints.insert(fingerprint.to_lowercase());
+        }
+        let key = key.split('/').next().unwrap_or_default();
+        if is_extended_public_key(key) {
+            extended_keys.insert(key.to_string());
+        }
+    }
+
+    Ok(AnalyzedDescriptor {
+        script_type,
+        fingerprints,
+        extended_keys,
+        largest_multisig,
+    })
+}
+
+/// Returns the type of the (previously validated) script, its functions nested in `sh(...)` or `wsh(...)` included,
+/// e.g. `sh(multi)`, keeping the largest of its `multi(...)` or `sortedmulti(...)` scripts.
+fn walk_script(
+    script: &str,
+    largest_multisig: &mut Option<(usize, usize)>,
+) -> Result<String, ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+
+    match function.name() {
+        name @ ("sh" | "wsh") => Ok(format!(
+            "{name}({})",
+            walk_script(&args[0], largest_multisig)?
+        )),
+        name @ ("multi" | "sortedmulti") => {
+            let multisig = (args.len() - 1, parse_threshold(args[0].trim())?);
+            *largest_multisig = (*largest_multisig).max(Some(multisig));
+            Ok(name.to_string())
+        }
+        name => Ok(name.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "[d34db33f/48h/0h/0h/2h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/0/*";
+    const KEY_2: &str = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+
+    #[test]
+    fn test_analysis() {
+        let mut analysis = DescriptorAnalysis::default();
+        analysis
+            .add(&format!("sh(multi(1,{KEY_1},{KEY_2}))"))
+            .unwrap();
+        analysis.add(&format!("pkh({KEY_1})")).unwrap();
+        analysis.add(&format!("pk({KEY_2})")).unwrap();
+        assert!(analysis.add("raw(xyz)").is_err());
+
+        assert_eq!(
+            analysis.to_string(),
+            "Analyzed 4 descriptors, 1 invalid\n\
+             Script types:\n  pk: 1\n  pkh: 1\n  sh(multi): 1\n\
+             Fingerprints:\n  d34db33f: 2\n\
+             Extended public keys:\n  xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL: 2\n\
+             Largest multisig: 1 of 2 keys"
+        );
+    }
+
+    #[test]
+    fn test_empty_analysis() {
+        assert_eq!(
+            DescriptorAnalysis::default().to_string(),
+            "Analyzed 0 descriptors, 0 invalid\nScript types: none\nFingerprints: none\nExtended public keys: none\nLargest multisig: none"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::time::Instant;
+
+use bip32::ChildNumber;
+
+use crate::{
+    alloc_counter::allocations,
+    structs::{
+        bench_config::BenchConfig, derive_key_config::DeriveKeyConfig,
+        hardened_notation::HardenedNotation, parsing_error::ParsingError,
+    },
+};
+
+use super::{derive_key::derive_key, path::format_path};
+
+/// The measurement of a benchmark run.
+struct BenchResult {
+    seconds: f64,
+    allocations: u64,
+}
+
+impl BenchResult {
+    fn keys_per_second(&self, count: u32) -> f64 {
+        f64::from(count) / self.seconds.max(f64::EPSILON)
+    }
+}
+
+/// Derives the children `0` to `count - 1` of the path from the seed or extended key, the same way as derive-key does
+/// (parsing the input every time), and reports the throughput in keys per second and the number of the allocations,
+/// so that the performance of the releases can be compared.
+///
+/// The allocations are counted only if the [`CountingAllocator`](crate::alloc_counter::CountingAllocator) is
+/// installed (as it is in the `bip380` binary), otherwise they are reported as not counted.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the input cannot be derived along the path (see [`derive_key`]).
+#[allow(clippy::cast_precision_loss)]
+pub fn bench(input: &str, config: &BenchConfig) -> Result<String, ParsingError> {
+    let result = run_bench(input, config)?;
+
+    let allocations = if result.allocations == 0 {
+        "allocations not counted".to_string()
+    } else {
+        format!(
+            "{} allocations ({:.1} per key)",
+            result.allocations,
+            result.allocations as f64 / f64::from(config.count)
+        )
+    };
+    Ok(format!(
+        "Derived {} keys along {}/* in {:.3} s: {:.0} keys/s, {allocations}",
+        config.count,
+        format_path(&config.path, HardenedNotation::Lowercase, true),
+        result.seconds,
+        result.keys_per_second(config.count)
+    ))
+}
+
+/// Runs the benchmark the same way as [`bench`], returning the `count` of the keys, the `seconds`, the
+/// `keys_per_second` and the `allocations` (0 if not counted) for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`bench`].
+pub fn bench_fields(
+    input: &str,
+    config: &BenchConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let result = run_bench(input, config)?;
+
+    Ok(vec![
+        ("count", config.count.to_string()),
+        ("seconds", format!("{:.6}", result.seconds)),
+        (
+            "keys_per_second",
+            format!("{:.0}", result.keys_per_second(config.count)),
+        ),
+        ("allocations", result.allocations.to_string()),
+    ])
+}
+
+fn run_bench(input: &str, config: &BenchConfig) -> Result<BenchResult, ParsingError> {
+    let allocations_before = allocations();
+    let start = Instant::now();
+
+    for index in 0..config.count {
+        let mut path = config.path.clone();
+        path.push(ChildNumber::new(index, false)?);
+        derive_key(
+            input,
+            &DeriveKeyConfig {
+                path,
+                ..DeriveKeyConfig::default()
+            },
+        )?;
+    }
+
+    Ok(BenchResult {
+        seconds: start.elapsed().as_secs_f64(),
+        allocations: allocations() - allocations_before,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::get_cmd;
+
+    #[test]
+    fn test_bench() {
+        let output = get_cmd()
+            .args([
+                "bench",
+                "000102030405060708090a0b0c0d0e0f",
+                "--count",
+                "5",
+                "--path",
+                "0h/1",
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout.starts_with("Derived 5 keys along m/0h/1/* in "),
+            "{stdout}"
+        );
+        // the binary counts the allocations
+        assert!(stdout.trim_end().ends_with("per key)"), "{stdout}");
+
+        get_cmd()
+            .args(["bench", "xpub", "--count", "5"])
+            .assert()
+            .failure();
+    }
+}
@@ -0,0 +1,162 @@
+use crate::structs::{
+    coldcard_export_config::ColdcardExportConfig, hardened_notation::HardenedNotation,
+    parsing_error::ParsingError,
+};
+
+use super::{path::format_path, utils::multisig::parse_multisig_descriptor};
+
+/// Renders the `sortedmulti` descriptor (see [`parse_multisig_descriptor`]) into the Coldcard multisig
+/// setup file.
+///
+/// The setup file consists of the `Name`, `Policy` and `Format` lines, followed by the `Derivation` of the
+/// cosigners (repeated whenever it changes, with `'` marking the hardened steps) and the
+/// `{FINGERPRINT}: {xpub}` line of every cosigner.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the descriptor is not a supported multisig descriptor.
+pub fn coldcard_export(input: &str, config: &ColdcardExportConfig) -> Result<String, ParsingError> {
+    let fields = coldcard_export_fields(input, config)?;
+    Ok(fields
+        .into_iter()
+        .find(|(name, _)| *name == "file")
+        .map(|(_, file)| file)
+        .unwrap_or_default())
+}
+
+/// Renders the setup file the same way as [`coldcard_export`], returning the `name`, the `policy`,
+/// the address `format` and the whole setup `file` for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`coldcard_export`].
+pub fn coldcard_export_fields(
+    input: &str,
+    config: &ColdcardExportConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let wallet = parse_multisig_descriptor(input)?;
+
+    let mut lines = vec![
+        "# Coldcard multisig setup file (exported by bip380)".to_string(),
+        "#".to_string(),
+        format!("Name: {}", config.name),
+        format!("Policy: {}", wallet.policy()),
+        format!("Format: {}", wallet.format.name()),
+    ];
+    let mut previous_path = None;
+    for cosigner in &wallet.cosigners {
+        if previous_path != Some(&cosigner.path) {
+            lines.push(String::new());
+            lines.push(format!(
+                "Derivation: {}",
+                format_path(&cosigner.path, HardenedNotation::Apostrophe, true)
+            ));
+            previous_path = Some(&cosigner.path);
+        }
+        lines.push(format!(
+            "{}: {}",
+            cosigner.fingerprint.to_ascii_uppercase(),
+            cosigner.xpub
+        ));
+    }
+
+    Ok(vec![
+        ("name", config.name.clone()),
+        ("policy", wallet.policy()),
+        ("format", wallet.format.name().to_string()),
+        ("file", lines.join("\n")),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{subcommands::utils::checksum::checksum_create, test_utils::get_cmd};
+
+    const XPUB_1: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const XPUB_2: &str = "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB";
+
+    fn descriptor(wrapper: &str, xpub_2_path: &str) -> String {
+        let multisig = format!(
+            "sortedmulti(2,[3442193e/48h/0h/0h/2h]{XPUB_1}/0/*,[bd16bee5{xpub_2_path}]{XPUB_2}/**)"
+        );
+        wrapper.replace("{}", &multisig)
+    }
+
+    #[test]
+    fn test_coldcard_export() {
+        let script = descriptor("wsh({})", "/48h/0h/0h/2h");
+        let input = format!("{script}#{}", checksum_create(&script).unwrap());
+
+        assert_eq!(
+            coldcard_export(
+                &input,
+                &ColdcardExportConfig {
+                    name: "Vault".to_string()
+                }
+            ),
+            Ok(format!(
+                "# Coldcard multisig setup file (exported by bip380)
+#
+Name: Vault
+Policy: 2 of 2
+Format: P2WSH
+
+Derivation: m/48'/0'/0'/2'
+3442193E: {XPUB_1}
+BD16BEE5: {XPUB_2}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_coldcard_export_formats_and_derivations() {
+        let fields = coldcard_export_fields(
+            &descriptor("sh(wsh({}))", "/48'/0'/0'/1'"),
+            &ColdcardExportConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(fields[2], ("format", "P2SH-P2WSH".to_string()));
+        assert!(fields[3].1.ends_with(&format!(
+            "Derivation: m/48'/0'/0'/2'\n3442193E: {XPUB_1}\n\nDerivation: m/48'/0'/0'/1'\nBD16BEE5: {XPUB_2}"
+        )));
+
+        let fields =
+            coldcard_export_fields(&descriptor("sh({})", ""), &ColdcardExportConfig::default())
+                .unwrap();
+        assert_eq!(fields[2], ("format", "P2SH".to_string()));
+        assert!(fields[3]
+            .1
+            .ends_with(&format!("Derivation: m\nBD16BEE5: {XPUB_2}")));
+    }
+
+    #[test]
+    fn test_coldcard_export_invalid() {
+        let valid = descriptor("wsh({})", "/48h/0h/0h/2h");
+        for invalid in [
+            descriptor("{}", "/48h/0h/0h/2h"),
+            valid.replace("sortedmulti", "multi"),
+        ] {
+            assert!(
+                coldcard_export(&invalid, &ColdcardExportConfig::default()).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_coldcard_export_template() {
+        get_cmd()
+            .args([
+                "coldcard-export",
+                &descriptor("wsh({})", "/48h/0h/0h/2h"),
+                "--name",
+                "Vault",
+                "--template",
+                "{name}: {policy} {format}",
+            ])
+            .assert()
+            .success()
+            .stdout("Vault: 2 of 2 P2WSH\n");
+    }
+}
@@ -0,0 +1,143 @@
+use crate::structs::{
+    compose_config::ComposeConfig, compose_script::ComposeScript, error_kind::ErrorKind,
+    parsing_error::ParsingError,
+};
+
+use super::{key_expression::validate_key_expressions, utils::checksum::checksum_create};
+
+/// Assembles the descriptor of the script around the key expressions, so that the keys collected from the
+/// cosigners need not be put together (and checksummed) by hand.
+///
+/// # Arguments
+///
+/// * `input` - The key expressions, separated by commas.
+/// * `config` - The configuration specifying the script and the threshold of a multisig script.
+///
+/// # Returns
+///
+/// Returns `Ok(String)` with the descriptor and its checksum. The keys of a `sortedmulti(...)` are sorted, the
+/// script sorts the public keys on its own, so the same set of keys always composes the same descriptor whatever
+/// order they are given in. The keys of a `multi(...)` are kept in the given order.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a key expression is empty or invalid in the context of the script, if the
+/// number of the keys does not fit the script or if the threshold exceeds it.
+pub fn compose(input: &str, config: &ComposeConfig) -> Result<String, ParsingError> {
+    let (descriptor, checksum) = compose_descriptor(input, config)?;
+    Ok(format!("{descriptor}#{checksum}"))
+}
+
+/// Composes the descriptor the same way as [`compose`], returning its fields for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`compose`].
+pub fn compose_fields(
+    input: &str,
+    config: &ComposeConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let (descriptor, checksum) = compose_descriptor(input, config)?;
+    let keys = input.split(',').count();
+    Ok(vec![
+        ("descriptor", format!("{descriptor}#{checksum}")),
+        ("checksum", checksum),
+        ("keys", keys.to_string()),
+    ])
+}
+
+/// Returns the descriptor (without a checksum) and its checksum.
+fn compose_descriptor(
+    input: &str,
+    config: &ComposeConfig,
+) -> Result<(String, String), ParsingError> {
+    let mut keys: Vec<String> = input.split(',').map(|key| key.trim().to_string()).collect();
+    if keys.iter().any(String::is_empty) {
+        return Err(ParsingError::new(&format!(
+            "Empty key expression in '{}', the keys must be separated by single commas",
+            input.trim()
+        )));
+    }
+    if keys.len() > config.script.max_keys() {
+        return Err(ParsingError::new(&format!(
+            "The script takes at most {} key expressions, {} given",
+            config.script.max_keys(),
+            keys.len()
+        )));
+    }
+    validate_key_expressions(&keys, config.script.key_context())?;
+
+    let descriptor = match config.script {
+        ComposeScript::Single(script_type) => script_type.wrap(&keys[0]),
+        ComposeScript::Multi { format, sorted } => {
+            let threshold = config.threshold.unwrap_or_default();
+            if !(1..=keys.len()).contains(&threshold) {
+                return Err(ParsingError::with_kind(ErrorKind::InvalidThreshold, &format!(
+                    "Threshold {threshold} is out of bounds, {} keys are given, so it must be from 1 to {}",
+                    keys.len(),
+                    keys.len()
+                )));
+            }
+            if sorted {
+                keys.sort();
+            }
+            let function = if sorted { "sortedmulti" } else { "multi" };
+            format.wrap(&format!("{function}({threshold},{})", keys.join(",")))
+        }
+    };
+
+    let checksum = checksum_create(&descriptor)?;
+    Ok((descriptor, checksum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+    const KEY_2: &str = "02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13";
+
+    fn config(script: &str, threshold: Option<usize>) -> ComposeConfig {
+        ComposeConfig {
+            script: ComposeScript::try_from(script).unwrap(),
+            threshold,
+        }
+    }
+
+    #[test]
+    fn test_compose_single_key() {
+        let composed = compose(KEY_1, &config("wpkh", None)).unwrap();
+        let descriptor = format!("wpkh({KEY_1})");
+        assert_eq!(
+            composed,
+            format!("{descriptor}#{}", checksum_create(&descriptor).unwrap())
+        );
+        assert!(compose(&format!("{KEY_1},{KEY_2}"), &config("pkh", None)).is_err());
+    }
+
+    #[test]
+    fn test_compose_multi_keeps_order() {
+        let composed = compose(&format!("{KEY_1}, {KEY_2}"), &config("sh-multi", Some(2))).unwrap();
+        assert!(composed.starts_with(&format!("sh(multi(2,{KEY_1},{KEY_2}))#")));
+    }
+
+    #[test]
+    fn test_compose_sortedmulti_sorts_keys() {
+        let config = config("sh-wsh-sortedmulti", Some(1));
+        let composed = compose(&format!("{KEY_1},{KEY_2}"), &config).unwrap();
+        assert!(composed.starts_with(&format!("sh(wsh(sortedmulti(1,{KEY_2},{KEY_1})))#")));
+        assert_eq!(compose(&format!("{KEY_2},{KEY_1}"), &config), Ok(composed));
+    }
+
+    #[test]
+    fn test_compose_errors() {
+        let config = config("wsh-multi", Some(3));
+        assert!(compose(&format!("{KEY_1},{KEY_2}"), &config)
+            .is_err_and(|err| err.message.starts_with("Threshold 3 is out of bounds")));
+        assert!(compose(&format!("{KEY_1},,{KEY_2}"), &config)
+            .is_err_and(|err| err.message.starts_with("Empty key expression")));
+        // an uncompressed key is not allowed in a witness script
+        let uncompressed = "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235";
+        assert!(compose(&format!("{KEY_1},{uncompressed}"), &config).is_err());
+    }
+}
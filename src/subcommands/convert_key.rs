@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+use bip32::{ChildNumber, ExtendedKey, ExtendedKeyAttrs, Prefix, XPrv, XPub};
+
+use crate::structs::{
+    convert_key_config::{ConvertKeyConfig, ConvertKeyMode},
+    extended_key_fields::ExtendedKeyFields,
+    parsing_error::ParsingError,
+};
+
+use super::utils::{extended_key::validate_extended_key_attrs, hexadecimal::decode_hex};
+
+/// The names of the fields accepted by the encoding, in the order of the serialization.
+const FIELD_NAMES: [&str; 6] = [
+    "version",
+    "depth",
+    "parent_fingerprint",
+    "child_number",
+    "chain_code",
+    "key",
+];
+
+/// Decomposes an extended key into its BIP 32 fields, or reassembles it from them.
+///
+/// The decoding prints the labeled `version`, `depth`, `parent_fingerprint`, `child_number`, `chain_code`
+/// and `key` fields of the extended key in hexadecimal, one per line. The encoding takes the same fields
+/// as whitespace separated `name=hex` pairs (in any order) and prints the Base58 encoded extended key.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The extended key to decode is not valid Base58 or its checksum does not match,
+/// - A field to encode is missing, repeated, unknown or does not have the expected length,
+/// - The encoded key is not a valid extended key (unknown version, invalid key bytes or attributes).
+pub fn convert_key(input: &str, config: &ConvertKeyConfig) -> Result<String, ParsingError> {
+    Ok(match config.mode {
+        ConvertKeyMode::Decode => decode_extended_key(input)?.to_string(),
+        ConvertKeyMode::Encode => encode_extended_key(input)?.key.to_string(),
+    })
+}
+
+/// Converts the key the same way as [`convert_key`], returning the fields of the key available to `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`convert_key`].
+pub fn convert_key_fields(
+    input: &str,
+    config: &ConvertKeyConfig,
+) -> Result<ExtendedKeyFields, ParsingError> {
+    match config.mode {
+        ConvertKeyMode::Decode => decode_extended_key(input),
+        ConvertKeyMode::Encode => encode_extended_key(input),
+    }
+}
+
+fn decode_extended_key(input: &str) -> Result<ExtendedKeyFields, ParsingError> {
+    Ok(ExtendedKeyFields {
+        key: ExtendedKey::from_str(input.trim())?,
+    })
+}
+
+fn encode_extended_key(input: &str) -> Result<ExtendedKeyFields, ParsingError> {
+    let mut values: [Option<&str>; 6] = [None; 6];
+    for pair in input.split_whitespace() {
+        let (name, value) = pair.split_once('=').ok_or_else(|| {
+            ParsingError::new(&format!("Field '{pair}' must be in the form 'name=hex'"))
+        })?;
+        let position = FIELD_NAMES
+            .iter()
+            .position(|field_name| *field_name == name)
+            .ok_or_else(|| ParsingError::new(&format!("Unknown extended key field '{name}'")))?;
+        if values[position].replace(value).is_some() {
+            return Err(ParsingError::new(&format!(
+                "Extended key field '{name}' is given multiple times"
+            )));
+        }
+    }
+
+    let [version, depth, parent_fingerprint, child_number, chain_code, key] = values;
+    let key = ExtendedKey {
+        prefix: Prefix::from_bytes(decode_field::<4>("version", version)?)?,
+        attrs: ExtendedKeyAttrs {
+            depth: decode_field::<1>("depth", depth)?[0],
+            parent_fingerprint: decode_field("parent_fingerprint", parent_fingerprint)?,
+            child_number: ChildNumber::from_bytes(decode_field("child_number", child_number)?),
+            chain_code: decode_field("chain_code", chain_code)?,
+        },
+        key_bytes: decode_field("key", key)?,
+    };
+
+    validate_extended_key_attrs(&key.attrs)?;
+    if key.prefix.is_private() {
+        XPrv::try_from(key.clone())?;
+    } else {
+        XPub::try_from(key.clone())?;
+    }
+
+    Ok(ExtendedKeyFields { key })
+}
+
+/// Decodes the hexadecimal value of the field, which must be exactly `N` bytes long.
+fn decode_field<const N: usize>(name: &str, value: Option<&str>) -> Result<[u8; N], ParsingError> {
+    let value =
+        value.ok_or_else(|| ParsingError::new(&format!("Missing extended key field '{name}'")))?;
+    if value.len() != 2 * N || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ParsingError::new(&format!(
+            "Extended key field '{name}' must be {N} bytes in hexadecimal, but '{value}' was given"
+        )));
+    }
+
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&decode_hex(value)?);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_cmd;
+
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const XPUB_FIELDS: &str = "version: 0488b21e
+depth: 00
+parent_fingerprint: 00000000
+child_number: 00000000
+chain_code: 873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508
+key: 0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2";
+
+    const DECODE: ConvertKeyConfig = ConvertKeyConfig {
+        mode: ConvertKeyMode::Decode,
+    };
+    const ENCODE: ConvertKeyConfig = ConvertKeyConfig {
+        mode: ConvertKeyMode::Encode,
+    };
+
+    #[test]
+    fn test_decode_extended_key() {
+        assert_eq!(convert_key(XPUB, &DECODE), Ok(XPUB_FIELDS.to_string()));
+        assert!(convert_key("xpub661MyMwAqRbc", &DECODE).is_err());
+    }
+
+    #[test]
+    fn test_encode_extended_key() {
+        let pairs = XPUB_FIELDS.replace(": ", "=").replace('\n', " ");
+        assert_eq!(convert_key(&pairs, &ENCODE), Ok(XPUB.to_string()));
+
+        for invalid in [
+            pairs.replace("depth=00", ""),
+            pairs.replace("depth=00", "depth=0"),
+            pairs.replace("depth=00", "depth=00 depth=00"),
+            pairs.replace("depth=00", "height=00"),
+            pairs.replace("depth=00", "depth"),
+            pairs.replace("parent_fingerprint=00000000", "parent_fingerprint=00000001"),
+            pairs.replace("version=0488b21e", "version=00000000"),
+            pairs.replace("key=03", "key=04"),
+        ] {
+            assert!(convert_key(&invalid, &ENCODE).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_convert_key_round_trip() {
+        let xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+
+        get_cmd()
+            .args([
+                "convert-key",
+                "--decode",
+                "--template",
+                "version={version} depth={depth} parent_fingerprint={parent_fingerprint} child_number={child_number} chain_code={chain_code} key={key}",
+                "--no-secret-warning",
+                xprv,
+            ])
+            .assert()
+            .success()
+            .stdout("version=0488ade4 depth=00 parent_fingerprint=00000000 child_number=00000000 chain_code=873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508 key=00e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35\n");
+
+        get_cmd()
+            .args(["convert-key", "--encode", "-"])
+            .write_stdin("version=0488ade4 depth=00 parent_fingerprint=00000000 child_number=00000000 chain_code=873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508 key=00e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35\n")
+            .assert()
+            .success()
+            .stdout(format!("{xprv}\n"));
+    }
+}
@@ -0,0 +1,144 @@
+use bip32::XPrv;
+
+use crate::structs::{
+    demo_config::DemoConfig, parsing_error::ParsingError, wallet_init_config::WalletInitConfig,
+};
+
+use super::{
+    utils::{
+        address::{key_output_script, script_to_address},
+        derived_public_key::derive_public_key,
+        hexadecimal::decode_hex,
+    },
+    wallet_init::wallet_account,
+};
+
+/// The seeds of the test vectors 1 to 4 of BIP 32, public and well-known, so never to be used for real funds.
+const TEST_VECTOR_SEEDS: [&str; 4] = [
+    "000102030405060708090a0b0c0d0e0f",
+    "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542",
+    "4b381541583be4423346c643850da4b320e46a87ae3d2a4e6da11eba819cd4acba45d239319ac14f863b8d5ab5a0d0c64d2e8a1e7d1457df2e5a3c51c73235be",
+    "3ddd5602285899a946114506157c7997e5444528f3003f6134712147db19b678",
+];
+
+/// Generates a complete worked example from the seed of a BIP 32 test vector, so that the training material and
+/// tutorials can be reproduced by the tool itself.
+///
+/// # Arguments
+///
+/// * `input` - The number of the BIP 32 test vector, from 1 to 4.
+/// * `config` - The configuration specifying the script type and the network of the example account.
+///
+/// # Returns
+///
+/// Returns `Ok(String)` with the labeled lines of the example: the test vector and its seed, the master extended
+/// private and public keys, the account of wallet-init (its fingerprint, keys and descriptors) and the first
+/// receive address.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the input is not the number of a test vector.
+pub fn demo(input: &str, config: &DemoConfig) -> Result<String, ParsingError> {
+    let lines: Vec<String> = demo_fields(input, config)?
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Generates the example the same way as [`demo`], returning its fields for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`demo`].
+pub fn demo_fields(
+    input: &str,
+    config: &DemoConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let vector = input.trim();
+    let seed = vector
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| TEST_VECTOR_SEEDS.get(number.checked_sub(1)?))
+        .ok_or_else(|| {
+            ParsingError::new(&format!(
+                "Unknown test vector '{vector}', expected a number from 1 to {}",
+                TEST_VECTOR_SEEDS.len()
+            ))
+        })?;
+
+    let master_xprv = XPrv::new(decode_hex(seed)?)?;
+    let account = wallet_account(
+        seed,
+        &WalletInitConfig {
+            script_type: config.script_type,
+            network: config.network,
+            ..WalletInitConfig::default()
+        },
+    )?;
+    // the first receive address, derived from the account key the same way as by the address sub-command
+    let public_key = derive_public_key(&format!("{}/0/*", account.xpub), Some(0))?;
+    let address = script_to_address(
+        &key_output_script(&public_key, config.script_type.address_type(), None)?,
+        config.network,
+    )?;
+
+    Ok(vec![
+        ("vector", vector.to_string()),
+        ("seed", (*seed).to_string()),
+        (
+            "master_xprv",
+            master_xprv
+                .to_string(config.network.private_prefix())
+                .to_string(),
+        ),
+        (
+            "master_xpub",
+            master_xprv
+                .public_key()
+                .to_string(config.network.public_prefix()),
+        ),
+        ("fingerprint", account.fingerprint),
+        ("xprv", account.xprv),
+        ("xpub", account.xpub),
+        ("receive", account.receive),
+        ("change", account.change),
+        ("address", address),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::get_cmd;
+
+    #[test]
+    fn test_demo() {
+        let output = get_cmd()
+            .args(["demo", "1"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        // the master keys of the test vector 1 of BIP 32
+        assert_eq!(lines[0], "vector: 1");
+        assert_eq!(
+            lines[2],
+            "master_xprv: xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"
+        );
+        assert_eq!(
+            lines[3],
+            "master_xpub: xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+        );
+        assert_eq!(lines[4], "fingerprint: 3442193e");
+        assert!(lines[7].starts_with("receive: wpkh([3442193e/84h/0h/0h]xpub"));
+        assert!(lines[9].starts_with("address: bc1q"));
+
+        get_cmd().args(["demo", "5"]).assert().failure().stderr(
+            "Parsing error [E000]: Unknown test vector '5', expected a number from 1 to 4\n",
+        );
+    }
+}
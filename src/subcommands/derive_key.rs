@@ -1,14 +1,25 @@
 use std::str::FromStr;
 
-use bip32::{secp256k1::elliptic_curve::zeroize::Zeroizing, Prefix, XPrv, XPub};
+use bip32::{ChildNumber, DerivationPath, Prefix, XPrv, XPub};
 
 use crate::{
-    structs::{derive_key_config::DeriveKeyConfig, parsing_error::ParsingError},
+    structs::{
+        derive_key_config::DeriveKeyConfig, derived_key::DerivedKey, error_kind::ErrorKind,
+        hardened_notation::HardenedNotation, parsing_error::ParsingError,
+        secret_buffer::SecretBuffer,
+    },
     traits::string_utils::{CharArrayUtils, StringSliceUtils},
-    utils::error_messages::invalid_seed_length_err,
+    utils::error_messages::hardened_from_xpub_err,
 };
 
-use super::utils::{extended_key::validate_extended_key_attrs, hexadecimal::decode_hex};
+use super::{
+    key_expression::split_key_expression,
+    path::format_path,
+    utils::{
+        extended_key::validate_extended_key_attrs, hexadecimal::encode_hex,
+        key_origin::validate_key_origin, seed::decode_seed,
+    },
+};
 
 /// Derives an extended public and/or private key from the given input and configuration.
 ///
@@ -24,17 +35,206 @@ use super::utils::{extended_key::validate_extended_key_attrs, hexadecimal::decod
 /// # Returns
 ///
 /// Returns `Ok(String)` containing the derived xpub and xprv (if available), separated by a colon, or an error message.
+/// With `--show-intermediate`, every key along the path is printed instead, see [`intermediate_keys`].
+/// With `--plan`, only the plan of the derivation is printed, no key is derived, see [`derivation_plan`].
+/// With `--split-at-hardened`, an extended public key is derived only up to the first hardened step of the path,
+/// the derivable prefix and the rest of the path are then printed on an additional line, separated by a space.
 ///
 /// # Errors
 ///
 /// Returns a [`ParsingError`] if:
 /// - The input is not a valid xprv, xpub, or hex seed,
 /// - The derivation path is invalid,
+/// - The derivation path has a hardened step to derive from an extended public key (without `--split-at-hardened`),
 /// - Key validation fails,
 /// - The seed is not valid hexadecimal or has an invalid length,
 /// - Any cryptographic operation fails.
 pub fn derive_key(input: &str, config: &DeriveKeyConfig) -> Result<String, ParsingError> {
-    let (xpub, xpriv) = match input.charify().as_slice() {
+    let output = if config.plan {
+        derivation_plan(input, config)?
+    } else if config.show_intermediate {
+        intermediate_keys(input, config)?
+    } else {
+        derive_extended_keys(input, config)?.to_string()
+    };
+
+    Ok(match split_at_hardened(input, config) {
+        Some((prefix, rest)) => format!(
+            "{output}\n{} {}",
+            format_path(&prefix, HardenedNotation::Lowercase, true),
+            format_path(&rest, HardenedNotation::Lowercase, false)
+        ),
+        None => output,
+    })
+}
+
+/// Splits the path before its first hardened step, if it is derived from an extended public key
+/// with `--split-at-hardened`.
+fn split_at_hardened(
+    input: &str,
+    config: &DeriveKeyConfig,
+) -> Option<(DerivationPath, DerivationPath)> {
+    if !config.split_at_hardened || !key_without_origin(input).starts_with("xpub") {
+        return None;
+    }
+    let position = config
+        .path
+        .iter()
+        .position(|child_number| child_number.is_hardened())?;
+
+    let mut prefix = DerivationPath::default();
+    prefix.extend(config.path.iter().take(position));
+    let mut rest = DerivationPath::default();
+    rest.extend(config.path.iter().skip(position));
+    Some((prefix, rest))
+}
+
+/// Returns the key of the input, without its key origin, if any.
+fn key_without_origin(input: &str) -> &str {
+    split_key_expression(input).map_or(input, |(_, key)| key)
+}
+
+/// Returns the part of the path that is derived, see [`split_at_hardened`].
+fn derived_path(input: &str, config: &DeriveKeyConfig) -> DerivationPath {
+    split_at_hardened(input, config).map_or_else(|| config.path.clone(), |(prefix, _)| prefix)
+}
+
+fn hardened_from_xpub(child_number: ChildNumber, config: &DeriveKeyConfig) -> ParsingError {
+    ParsingError::with_kind(
+        ErrorKind::HardenedFromXpub,
+        &hardened_from_xpub_err(
+            child_number.index(),
+            &format_path(&config.path, HardenedNotation::Lowercase, true),
+        ),
+    )
+}
+
+/// Prints one line per path element as `{path} {xpub} {fingerprint}`, starting with the key of the input itself (`m`),
+/// so the step at which two implementations diverge on a long path can be spotted.
+///
+/// The path prefixes are relative to the key of the input, the private keys are never printed.
+fn intermediate_keys(input: &str, config: &DeriveKeyConfig) -> Result<String, ParsingError> {
+    let mut key = derive_extended_keys(input, &DeriveKeyConfig::default())?;
+    let mut path = DerivationPath::default();
+    let mut lines = vec![intermediate_key_line(&path, &key)];
+
+    for child_number in derived_path(input, config).iter() {
+        if key.xprv.is_none() && child_number.is_hardened() {
+            return Err(hardened_from_xpub(child_number, config));
+        }
+        key = key.derive_child(child_number)?;
+        validate_extended_key_attrs(key.xpub.attrs())?;
+        path.push(child_number);
+        lines.push(intermediate_key_line(&path, &key));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Prints the plan of the derivation as `{kind} {path} {keys}`: the kind of the input (seed, xprv or xpub), the full
+/// path of the derived keys (the cumulative key origin, if the input has one) and the keys that would be derived,
+/// e.g. `seed m/44h/0h/0h xpub:xprv`. The input is only decoded, no key is derived.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the input is not a valid seed or extended key (its checksum is verified, its key is
+/// not), the key origin is invalid or the path has a hardened step to derive from an extended public key.
+fn derivation_plan(input: &str, config: &DeriveKeyConfig) -> Result<String, ParsingError> {
+    let (key_origin, key) = split_key_expression(input)?;
+    let kind = if key.starts_with("xprv") || key.starts_with("xpub") {
+        bs58::decode(key)
+            .with_check(None)
+            .into_vec()
+            .ok()
+            .filter(|bytes| bytes.len() == 78)
+            .ok_or_else(|| ParsingError::new(&format!("Invalid extended key '{key}'")))?;
+        &key[..4]
+    } else {
+        // the length of a seed is checked by the derivation of its master key otherwise
+        let seed = SecretBuffer::new(decode_seed(input)?);
+        if !(16..=64).contains(&seed.as_ref().len()) {
+            return Err(bip32::Error::SeedLength.into());
+        }
+        "seed"
+    };
+
+    let path = derived_path(key, config);
+    if kind == "xpub" {
+        if let Some(child_number) = path.iter().find(ChildNumber::is_hardened) {
+            return Err(hardened_from_xpub(child_number, config));
+        }
+    }
+
+    let full_path = match key_origin {
+        Some(key_origin) if kind != "seed" => {
+            validate_key_origin(key_origin)?;
+            cumulative_origin(key_origin, &path)
+        }
+        Some(_) => {
+            return Err(ParsingError::new(
+                "A key origin can only precede an extended key (xprv or xpub)",
+            ))
+        }
+        None => format_path(&path, HardenedNotation::Lowercase, true),
+    };
+    let keys = if kind == "xpub" { "xpub" } else { "xpub:xprv" };
+
+    Ok(format!("{kind} {full_path} {keys}"))
+}
+
+fn intermediate_key_line(path: &DerivationPath, key: &DerivedKey) -> String {
+    format!(
+        "{} {} {}",
+        format_path(path, HardenedNotation::Lowercase, true),
+        key.xpub.to_string(Prefix::XPUB),
+        encode_hex(&key.xpub.fingerprint())
+    )
+}
+
+/// Derives the extended keys the same way as [`derive_key`], returning them instead of their output.
+///
+/// An extended key with a key origin (e.g. `[d34db33f/44h/0h/0h]xprv...`) is derived along the path, which
+/// is then appended to the origin, so the derived keys carry their cumulative origin.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`derive_key`], or if the key origin is invalid or does
+/// not precede an extended key.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn derive_extended_keys(
+    input: &str,
+    config: &DeriveKeyConfig,
+) -> Result<DerivedKey, ParsingError> {
+    let (Some(key_origin), key) = split_key_expression(input)? else {
+        return derive_from_key(input, config);
+    };
+    validate_key_origin(key_origin)?;
+    if !key.starts_with("xprv") && !key.starts_with("xpub") {
+        return Err(ParsingError::new(
+            "A key origin can only precede an extended key (xprv or xpub)",
+        ));
+    }
+
+    Ok(DerivedKey {
+        origin: Some(cumulative_origin(key_origin, &derived_path(key, config))),
+        ..derive_from_key(key, config)?
+    })
+}
+
+/// Appends the derived path to the key origin, e.g. `[d34db33f/44h]` and `0/1` make `[d34db33f/44h/0/1]`.
+fn cumulative_origin(key_origin: &str, path: &DerivationPath) -> String {
+    let path = format_path(path, HardenedNotation::Lowercase, false);
+    let origin = key_origin.trim_end_matches(']');
+    if path.is_empty() {
+        format!("{origin}]")
+    } else {
+        format!("{origin}/{path}]")
+    }
+}
+
+/// Derives the extended keys from the seed or the extended key without a key origin.
+fn derive_from_key(input: &str, config: &DeriveKeyConfig) -> Result<DerivedKey, ParsingError> {
+    match input.charify().as_slice() {
         priv_key @ ['x', 'p', 'r', 'v', ..] => {
             let mut xpriv = XPrv::from_str(&priv_key.iter().collect::<String>())?;
 
@@ -48,52 +248,54 @@ pub fn derive_key(input: &str, config: &DeriveKeyConfig) -> Result<String, Parsi
 
             validate_extended_key_attrs(xpub.attrs())?;
 
-            (xpub.to_string(Prefix::XPUB), xpriv.to_string(Prefix::XPRV))
+            Ok(DerivedKey {
+                xpub,
+                xprv: Some(xpriv),
+                origin: None,
+            })
         }
         pub_key @ ['x', 'p', 'u', 'b', ..] => {
             let mut xpub = XPub::from_str(&pub_key.iter().collect::<String>())?;
 
-            for child_number in config.path.iter() {
+            for child_number in derived_path(input, config).iter() {
+                if child_number.is_hardened() {
+                    return Err(hardened_from_xpub(child_number, config));
+                }
                 xpub = xpub.derive_child(child_number)?;
             }
 
             validate_extended_key_attrs(xpub.attrs())?;
 
-            (xpub.to_string(Prefix::XPUB), Zeroizing::new(String::new()))
+            Ok(DerivedKey {
+                xpub,
+                xprv: None,
+                origin: None,
+            })
         }
         seed_input => {
-            let seed_no_whitespace = seed_input
-                .stringify()
-                .split([' ', '\t'])
-                .map(|slice| {
-                    if slice.chars().count() % 2 == 0 {
-                        Ok(slice)
-                    } else {
-                        Err(ParsingError::new(&invalid_seed_length_err(slice)))
-                    }
-                })
-                .collect::<Result<String, ParsingError>>()?;
-
-            let seed = decode_hex(&seed_no_whitespace)?;
+            let seed = SecretBuffer::new(decode_seed(&seed_input.stringify())?);
 
             let root_xprv = XPrv::derive_from_path(seed, &config.path)?;
 
-            let xpub = root_xprv.public_key();
-
-            (
-                xpub.to_string(Prefix::XPUB),
-                root_xprv.to_string(Prefix::XPRV),
-            )
+            Ok(DerivedKey {
+                xpub: root_xprv.public_key(),
+                xprv: Some(root_xprv),
+                origin: None,
+            })
         }
-    };
-    Ok(format!("{}:{}", xpub, *xpriv))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::derive_key;
     use crate::{
-        structs::derive_key_config::DeriveKeyConfig, test_utils::get_cmd,
+        structs::{
+            derive_key_config::DeriveKeyConfig, error_kind::ErrorKind, parsing_error::ParsingError,
+        },
+        test_utils::get_cmd,
         traits::parsable::Parsable,
+        utils::error_messages::hardened_from_xpub_err,
     };
 
     #[test]
@@ -214,6 +416,28 @@ mod tests {
             .stdout(expected_output);
     }
 
+    #[test]
+    fn test_derive_with_key_origin() {
+        let xprv = "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs";
+        let expected_output = "[d34db33f/0h/1/2h/2/1000000000]xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy:[d34db33f/0h/1/2h/2/1000000000]xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76\n";
+        get_cmd()
+            .args([
+                "derive-key",
+                &format!("[d34db33f/0h/1]{xprv}"),
+                "--path",
+                "2H/2/1000000000",
+            ])
+            .assert()
+            .success()
+            .stdout(expected_output);
+
+        let config = DeriveKeyConfig::default();
+        let derived = derive_key(&format!("[d34db33f]{xprv}"), &config).unwrap();
+        assert!(derived.starts_with("[d34db33f]xpub"));
+        assert!(derive_key("[d34db33f]000102030405060708090a0b0c0d0e0f", &config).is_err());
+        assert!(derive_key(&format!("[d34db33]{xprv}"), &config).is_err());
+    }
+
     #[test]
     fn test_derive_from_priv_key_with_path_at_the_beginning() {
         let expected_output = "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy:xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76\n";
@@ -226,7 +450,7 @@ mod tests {
 
     #[test]
     fn test_pubkey_and_prvkey_mismatch() {
-        let expected_stderr = "Parsing error: cryptographic error\n";
+        let expected_stderr = "Parsing error [E000]: cryptographic error\n";
         get_cmd()
             .args(["derive-key", "xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6LBpB85b3D2yc8sfvZU521AAwdZafEz7mnzBBsz4wKY5fTtTQBm"])
             .assert()
@@ -234,6 +458,100 @@ mod tests {
             .stderr(expected_stderr);
     }
 
+    #[test]
+    fn test_show_intermediate_keys() {
+        get_cmd()
+            .args([
+                "derive-key",
+                "000102030405060708090a0b0c0d0e0f",
+                "--path",
+                "0h/1",
+                "--show-intermediate",
+            ])
+            .assert()
+            .success()
+            .stdout(concat!(
+                "m xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8 3442193e\n",
+                "m/0h xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw 5c1bd648\n",
+                "m/0h/1 xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ bef5a2f9\n",
+            ));
+
+        get_cmd()
+            .args([
+                "derive-key",
+                "-",
+                "--show-intermediate",
+                "--template",
+                "{xpub}",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_derivation_plan() {
+        get_cmd()
+            .args(["derive-key", "--plan", "--path", "M/44H/0'/0h", "-"])
+            .write_stdin("000102030405060708090a0b0c0d0e0f\n")
+            .assert()
+            .success()
+            .stdout("seed m/44h/0h/0h xpub:xprv\n");
+
+        let xpub = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+        let mut config = get_config("1/2h/3");
+        config.plan = true;
+        assert_eq!(
+            derive_key(&format!("[d34db33f/0h]{xpub}"), &config),
+            Err(ParsingError::with_kind(
+                ErrorKind::HardenedFromXpub,
+                &hardened_from_xpub_err(2, "m/1/2h/3")
+            ))
+        );
+        config.split_at_hardened = true;
+        assert_eq!(
+            derive_key(&format!("[d34db33f/0h]{xpub}"), &config),
+            Ok("xpub [d34db33f/0h/1] xpub\nm/1 2h/3".to_string())
+        );
+
+        // the checksum of the extended key is verified
+        let mut corrupted = xpub.to_string();
+        corrupted.replace_range(4..5, "N");
+        assert!(derive_key(&corrupted, &config).is_err());
+        assert!(derive_key("0001", &config).is_err());
+
+        get_cmd()
+            .args(["derive-key", "--plan", "--output", "json", xpub])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_hardened_derivation_from_pub_key() {
+        let xpub = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+
+        assert_eq!(
+            derive_key(xpub, &get_config("1/2h/3")),
+            Err(ParsingError::with_kind(
+                ErrorKind::HardenedFromXpub,
+                &hardened_from_xpub_err(2, "m/1/2h/3")
+            ))
+        );
+
+        let mut split_config = get_config("1/2h/3");
+        split_config.split_at_hardened = true;
+        assert_eq!(
+            derive_key(xpub, &split_config),
+            Ok("xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ:\nm/1 2h/3".to_string())
+        );
+
+        // nothing to split without a hardened step or with a private key
+        split_config.path = "m/1".parse().unwrap();
+        assert_eq!(
+            derive_key(xpub, &split_config),
+            Ok("xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ:".to_string())
+        );
+    }
+
     fn get_config(path: &str) -> DeriveKeyConfig {
         DeriveKeyConfig::parse(&mut vec!["derive-key", "--path", path]).unwrap()
     }
@@ -669,4 +987,28 @@ mod tests {
             assert!(result.is_err());
         }
     }
+
+    #[test]
+    fn test_template() {
+        get_cmd()
+            .args([
+                "derive-key",
+                "000102030405060708090a0b0c0d0e0f",
+                "--template",
+                "{fingerprint},{depth},{parent_fingerprint}",
+            ])
+            .assert()
+            .success()
+            .stdout("3442193e,0,00000000\n");
+
+        get_cmd()
+            .args([
+                "derive-key",
+                "000102030405060708090a0b0c0d0e0f",
+                "--template",
+                "{mnemonic}",
+            ])
+            .assert()
+            .failure();
+    }
 }
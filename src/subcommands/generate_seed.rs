@@ -0,0 +1,257 @@
+use bip32::secp256k1::sha2::{Digest, Sha256};
+use bip39::Mnemonic;
+
+use crate::structs::{
+    generate_seed_config::{EntropySource, GenerateSeedConfig},
+    parsing_error::ParsingError,
+};
+
+use super::utils::hexadecimal::encode_hex;
+
+/// The entropy collected from the dice rolls or coin flips, with the estimate of how much of it there is.
+struct CollectedEntropy {
+    entropy: Vec<u8>,
+    /// The estimated entropy of the input in bits, see [`estimate_entropy_bits`].
+    bits: f64,
+    /// The pairs of coin flips discarded by the von Neumann debiasing.
+    discarded: usize,
+}
+
+/// Generates a BIP 39 mnemonic from physical entropy, the dice rolls or coin flips of the input.
+///
+/// Whitespace in the input is ignored. The dice rolls (`1` to `6`) are hashed with SHA-256, the same way as
+/// the dice rolls of Coldcard are, so the resulting mnemonic can be cross-checked. The coin flips (`0`/`T` and
+/// `1`/`H`) are first debiased by the von Neumann method, taking the flips in pairs and keeping the first flip
+/// of every pair of different flips, so a biased coin does not bias the seed, and the resulting bits are used
+/// directly as the entropy.
+///
+/// The entropy is only accepted once there is enough of it: the estimate (see [`estimate_entropy_bits`]) has
+/// to reach the 128 or 256 bits of the 12 or 24 word mnemonic.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the input contains a character that is not a roll or a flip, or it does not
+/// supply enough entropy for the mnemonic.
+pub fn generate_seed(input: &str, config: &GenerateSeedConfig) -> Result<String, ParsingError> {
+    Ok(generated_mnemonic(input, config)?.0.to_string())
+}
+
+/// Generates the mnemonic the same way as [`generate_seed`], returning the `mnemonic`, its `entropy`
+/// in hexadecimal, the estimated `bits` of the entropy supplied and the number of `discarded` pairs of coin
+/// flips for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`generate_seed`].
+pub fn generate_seed_fields(
+    input: &str,
+    config: &GenerateSeedConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let (mnemonic, collected) = generated_mnemonic(input, config)?;
+
+    Ok(vec![
+        ("mnemonic", mnemonic.to_string()),
+        ("entropy", encode_hex(&mnemonic.to_entropy())),
+        ("bits", format!("{:.1}", collected.bits)),
+        ("discarded", collected.discarded.to_string()),
+    ])
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn generated_mnemonic(
+    input: &str,
+    config: &GenerateSeedConfig,
+) -> Result<(Mnemonic, CollectedEntropy), ParsingError> {
+    let collected = match config.source {
+        EntropySource::Dice => collect_dice_entropy(input)?,
+        EntropySource::Coins => collect_coin_entropy(input, config.words)?,
+    };
+
+    let required_bits = config.words / 3 * 32;
+    if collected.bits < required_bits as f64 {
+        return Err(ParsingError::new(&format!(
+            "Not enough entropy for a {}-word mnemonic: the input supplies about {:.1} of the {required_bits} bits required, supply more {}",
+            config.words,
+            collected.bits,
+            match config.source {
+                EntropySource::Dice => "dice rolls",
+                EntropySource::Coins => "coin flips",
+            }
+        )));
+    }
+
+    let mnemonic = Mnemonic::from_entropy(&collected.entropy[..required_bits / 8])
+        .map_err(|err| ParsingError::new(&err.to_string()))?;
+    Ok((mnemonic, collected))
+}
+
+fn collect_dice_entropy(input: &str) -> Result<CollectedEntropy, ParsingError> {
+    let rolls = symbols(
+        input,
+        |c| c.to_digit(10).filter(|roll| (1..=6).contains(roll)),
+        "dice roll",
+    )?;
+    let digits: String = rolls.iter().map(ToString::to_string).collect();
+
+    Ok(CollectedEntropy {
+        entropy: Sha256::digest(digits.as_bytes()).to_vec(),
+        bits: estimate_entropy_bits(&rolls, 6),
+        discarded: 0,
+    })
+}
+
+fn collect_coin_entropy(input: &str, words: usize) -> Result<CollectedEntropy, ParsingError> {
+    let flips = symbols(
+        input,
+        |c| match c.to_ascii_uppercase() {
+            '0' | 'T' => Some(0),
+            '1' | 'H' => Some(1),
+            _ => None,
+        },
+        "coin flip",
+    )?;
+
+    let pairs = flips.chunks_exact(2);
+    let discarded = pairs.clone().filter(|pair| pair[0] == pair[1]).count();
+    let bits: Vec<u32> = pairs
+        .filter(|pair| pair[0] != pair[1])
+        .map(|pair| pair[0])
+        .collect();
+
+    // only as many bits as the mnemonic needs are used, the estimate is made on the same bits
+    let used_bits = &bits[..bits.len().min(words / 3 * 32)];
+    let entropy = used_bits
+        .chunks(8)
+        .map(|byte| {
+            byte.iter().enumerate().fold(0u8, |acc, (position, bit)| {
+                acc | (u8::from(*bit == 1) << (7 - position))
+            })
+        })
+        .collect();
+
+    Ok(CollectedEntropy {
+        entropy,
+        bits: estimate_entropy_bits(used_bits, 2),
+        discarded,
+    })
+}
+
+/// Maps every non-whitespace character of the input to its symbol (the roll or the flip).
+fn symbols(
+    input: &str,
+    symbol: impl Fn(char) -> Option<u32>,
+    label: &str,
+) -> Result<Vec<u32>, ParsingError> {
+    input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| symbol(c).ok_or_else(|| ParsingError::new(&format!("Invalid {label} '{c}'"))))
+        .collect()
+}
+
+/// Estimates the entropy of the symbols (of the base) in bits, conservatively.
+///
+/// Every symbol supplies at most `log2(base)` bits, and less if the symbols are not evenly distributed:
+/// the estimate is the number of the symbols times the Shannon entropy of their observed frequencies,
+/// so e.g. a run of the same roll supplies no entropy at all.
+#[allow(clippy::cast_precision_loss)]
+fn estimate_entropy_bits(symbols: &[u32], base: u32) -> f64 {
+    let total = symbols.len() as f64;
+    let shannon_entropy: f64 = (0..=base)
+        .map(|value| symbols.iter().filter(|symbol| **symbol == value).count() as f64 / total)
+        .filter(|frequency| *frequency > 0.0)
+        .map(|frequency| -frequency * frequency.log2())
+        .sum();
+
+    total * shannon_entropy.min(f64::from(base).log2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_cmd;
+
+    fn config(source: EntropySource, words: usize) -> GenerateSeedConfig {
+        GenerateSeedConfig { source, words }
+    }
+
+    #[test]
+    fn test_dice_entropy() {
+        // the mnemonic of the SHA-256 of the rolls
+        let rolls = "123456 ".repeat(17);
+        let fields = generate_seed_fields(&rolls, &config(EntropySource::Dice, 24)).unwrap();
+
+        assert_eq!(
+            fields[1],
+            (
+                "entropy",
+                "8fd128918b2e29d6dcbfa5b9a118e5c16d60498c7ba107922a8eb6eb1d36c112".to_string()
+            )
+        );
+        assert_eq!(fields[2], ("bits", "263.7".to_string()));
+
+        let fields = generate_seed_fields(&rolls, &config(EntropySource::Dice, 12)).unwrap();
+        assert_eq!(
+            fields[1],
+            ("entropy", "8fd128918b2e29d6dcbfa5b9a118e5c1".to_string())
+        );
+        assert_eq!(fields[0].1.split(' ').count(), 12);
+    }
+
+    #[test]
+    fn test_coin_entropy() {
+        // "HT" is 1, "TH" is 0, the equal pairs are discarded
+        let flips = format!("{}HH TT", "HTTHTHHTTHHTHTTH".repeat(16));
+        let fields = generate_seed_fields(&flips, &config(EntropySource::Coins, 12)).unwrap();
+
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "mnemonic",
+                    generate_seed(&flips, &config(EntropySource::Coins, 12)).unwrap()
+                ),
+                ("entropy", "96".repeat(16)),
+                ("bits", "128.0".to_string()),
+                ("discarded", "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_not_enough_entropy() {
+        // too few rolls, the same roll over and over and too few flips after the debiasing
+        for (input, source) in [
+            ("123456".repeat(16), EntropySource::Dice),
+            ("1".repeat(200), EntropySource::Dice),
+            ("HH".repeat(200), EntropySource::Coins),
+            ("HT".repeat(200), EntropySource::Coins),
+        ] {
+            assert!(
+                generate_seed(&input, &config(source, 24)).is_err(),
+                "{input}"
+            );
+        }
+
+        assert!(generate_seed("1234567", &config(EntropySource::Dice, 12)).is_err());
+        assert!(generate_seed("HTX", &config(EntropySource::Coins, 12)).is_err());
+    }
+
+    #[test]
+    fn test_generate_seed_stdin() {
+        get_cmd()
+            .args([
+                "generate-seed",
+                "--dice",
+                "--words",
+                "12",
+                "-",
+                "--template",
+                "{entropy}",
+            ])
+            .write_stdin(format!("{}\n", "123456".repeat(17)))
+            .assert()
+            .success()
+            .stdout("8fd128918b2e29d6dcbfa5b9a118e5c1\n");
+    }
+}
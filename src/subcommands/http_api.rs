@@ -0,0 +1,394 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Take, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+use crate::{
+    structs::{error_kind::ErrorKind, parsing_error::ParsingError},
+    utils::signals::shutdown_requested,
+};
+
+use super::{
+    serve::{
+        run_args, ConnectionLimit, ACCEPT_POLL_INTERVAL, CONNECTION_TIMEOUT, MAX_CONNECTIONS,
+        MAX_REQUEST_LENGTH,
+    },
+    utils::json::{json_string, parse_json, JsonValue},
+};
+
+/// The sub-commands exposed as `POST /{sub-command}` endpoints.
+const ENDPOINTS: [&str; 3] = ["derive-key", "key-expression", "script-expression"];
+
+/// Upper bound of the request line and the headers together.
+const MAX_HEADER_LENGTH: u64 = 8 * 1024;
+
+static CONNECTIONS: ConnectionLimit = ConnectionLimit::new(MAX_CONNECTIONS);
+
+const OK: &str = "200 OK";
+const BAD_REQUEST: &str = "400 Bad Request";
+const NOT_FOUND: &str = "404 Not Found";
+const METHOD_NOT_ALLOWED: &str = "405 Method Not Allowed";
+
+/// Keeps answering HTTP requests on the address, until the process is killed or a signal requests the shutdown.
+///
+/// Every connection is handled on its own thread and answers a single request. At most [`MAX_CONNECTIONS`] are
+/// handled at once, and a connection is closed if it does not send the request within [`CONNECTION_TIMEOUT`].
+/// See [`respond`] for the endpoints and the format of the requests and responses.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the address cannot be bound.
+pub fn serve_http(address: &str) -> Result<(), ParsingError> {
+    let listener = TcpListener::bind(address)
+        .map_err(|e| ParsingError::new(&format!("Could not bind the address '{address}': {e}")))?;
+    // the listener is polled, so that a requested shutdown is noticed between the connections
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| ParsingError::new(&format!("Could not bind the address '{address}': {e}")))?;
+
+    while !shutdown_requested() {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_read_timeout(Some(CONNECTION_TIMEOUT));
+                let _ = stream.set_write_timeout(Some(CONNECTION_TIMEOUT));
+                CONNECTIONS.spawn(stream, handle_connection);
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+            Err(e) => eprintln!("Error accepting a connection: {e}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream) {
+    let (status, body) = match read_request(&stream) {
+        Ok((method, path, body)) => respond(&method, &path, &body),
+        Err(err) => (BAD_REQUEST, error_body(&err)),
+    };
+
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+}
+
+/// Reads the method, the path and the body of an HTTP/1.1 request.
+fn read_request(stream: &TcpStream) -> Result<(String, String, String), ParsingError> {
+    let read_err =
+        |e: std::io::Error| ParsingError::new(&format!("Could not read the request: {e}"));
+    let mut reader = BufReader::new(stream);
+    let mut head = (&mut reader).take(MAX_HEADER_LENGTH);
+
+    let request_line = read_head_line(&mut head)?;
+    let mut request_line = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(ParsingError::new("Invalid HTTP request line"));
+    };
+
+    let mut content_length = 0;
+    loop {
+        let header = read_head_line(&mut head)?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| ParsingError::new("Invalid Content-Length header"))?;
+            }
+        }
+    }
+    if content_length > MAX_REQUEST_LENGTH {
+        return Err(ParsingError::new("Request body is too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(read_err)?;
+    let body = String::from_utf8(body)
+        .map_err(|_| ParsingError::new("Request body is not valid UTF-8"))?;
+
+    Ok((method.to_string(), path.to_string(), body))
+}
+
+/// Reads a line of the request line and the headers, which are limited to [`MAX_HEADER_LENGTH`] bytes together.
+fn read_head_line<R: BufRead>(head: &mut Take<R>) -> Result<String, ParsingError> {
+    let mut line = String::new();
+    head.read_line(&mut line)
+        .map_err(|e| ParsingError::new(&format!("Could not read the request: {e}")))?;
+    if !line.ends_with('\n') && head.limit() == 0 {
+        return Err(ParsingError::new(&format!(
+            "Request headers are larger than {MAX_HEADER_LENGTH} bytes"
+        )));
+    }
+    Ok(line)
+}
+
+/// Answers a request of the HTTP JSON API, returning the status and the JSON body of the response.
+///
+/// The endpoints are `POST /derive-key`, `POST /key-expression` and `POST /script-expression`, each taking
+/// a JSON object with the `input` string and optionally the `options` array of the sub-command's flags,
+/// e.g. `{"input": "000102030405060708090a0b0c0d0e0f", "options": ["--path", "0h/1"]}`.
+///
+/// A successful response is `{"output": "..."}`, a failed one `{"error": "...", "code": "..."}` with the stable code of
+/// the error kind.
+fn respond(method: &str, path: &str, body: &str) -> (&'static str, String) {
+    let Some(command) = path
+        .strip_prefix('/')
+        .filter(|command| ENDPOINTS.contains(command))
+    else {
+        return (
+            NOT_FOUND,
+            error_body(&ParsingError::new(&format!("Unknown endpoint '{path}'"))),
+        );
+    };
+    if method != "POST" {
+        return (
+            METHOD_NOT_ALLOWED,
+            error_body(&ParsingError::new(&format!(
+                "Endpoint '{path}' only accepts POST requests"
+            ))),
+        );
+    }
+
+    match parse_request_args(command, body).and_then(|(args, input)| run_args(&args, Some(&input)))
+    {
+        Ok(outputs) => (
+            OK,
+            format!("{{\"output\":{}}}", json_string(&outputs.join("\n"))),
+        ),
+        Err(err) => (BAD_REQUEST, error_body(&err)),
+    }
+}
+
+/// Builds the command-line arguments of the sub-command from the JSON request body, returning them apart from the
+/// input, so that the input is never taken for a flag.
+fn parse_request_args(command: &str, body: &str) -> Result<(Vec<String>, String), ParsingError> {
+    let request = parse_json(body)?;
+
+    let Some(JsonValue::String(input)) = request.get("input") else {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            "The request must be a JSON object with the 'input' string",
+        ));
+    };
+
+    let options = match request.get("options") {
+        None => vec![],
+        Some(JsonValue::Array(options)) => options
+            .iter()
+            .map(|option| match option {
+                JsonValue::String(option) => Ok(option.clone()),
+                _ => Err(ParsingError::with_kind(
+                    ErrorKind::InvalidJson,
+                    "The 'options' must be an array of strings",
+                )),
+            })
+            .collect::<Result<Vec<String>, ParsingError>>()?,
+        Some(_) => {
+            return Err(ParsingError::with_kind(
+                ErrorKind::InvalidJson,
+                "The 'options' must be an array of strings",
+            ))
+        }
+    };
+
+    Ok((
+        std::iter::once(command.to_string())
+            .chain(options)
+            .collect(),
+        input.clone(),
+    ))
+}
+
+fn error_body(err: &ParsingError) -> String {
+    format!(
+        "{{\"error\":{},\"code\":{}}}",
+        json_string(&err.message),
+        json_string(err.kind.code())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "derive-key")]
+    fn test_respond_derive_key() {
+        assert_eq!(
+            respond(
+                "POST",
+                "/derive-key",
+                r#"{"input": "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8", "options": ["--path", "0"]}"#
+            ),
+            (
+                OK,
+                r#"{"output":"xpub68Gmy5EVb2BdFbj2LpWrk1M7obNuaPTpT5oh9QCCo5sRfqSHVYWex97WpDZzszdzHzxXDAzPLVSwybe4uPYkSk4G3gnrPqqkV9RyNzAcNJ1:"}"#
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_respond_errors() {
+        assert_eq!(respond("POST", "/wallet", "{}").0, NOT_FOUND);
+        assert_eq!(respond("GET", "/key-expression", "").0, METHOD_NOT_ALLOWED);
+        for body in [
+            "not json",
+            r#"{"options": []}"#,
+            r#"{"input": "-", "options": [1]}"#,
+        ] {
+            let (status, body) = respond("POST", "/key-expression", body);
+            assert_eq!(status, BAD_REQUEST);
+            assert!(body.ends_with(r#""code":"E015"}"#), "{body}");
+        }
+        assert_eq!(
+            respond("POST", "/key-expression", r#"{"input": "-"}"#).0,
+            BAD_REQUEST
+        );
+
+        let (status, body) = respond("POST", "/key-expression", r#"{"input": "invalid"}"#);
+        assert_eq!(status, BAD_REQUEST);
+        assert!(body.starts_with(r#"{"error":"#));
+
+        // a deeply nested body is refused, not overflowing the stack of the handler
+        assert_eq!(
+            respond("POST", "/key-expression", &"[".repeat(1_000_000)).0,
+            BAD_REQUEST
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_input_not_taken_for_flag() {
+        // the input is a key to parse even if it looks like a flag, stdin or a file
+        for input in ["--help", "--path", "--input-file", "-", "@/etc/hostname"] {
+            let (status, body) = respond(
+                "POST",
+                "/key-expression",
+                &format!(r#"{{"input": "{input}", "options": ["--network", "mainnet"]}}"#),
+            );
+            assert_eq!(status, BAD_REQUEST, "{input}");
+            // an invalid key or an invalid character of the key
+            assert!(
+                body.ends_with(r#""code":"E014"}"#) || body.ends_with(r#""code":"E012"}"#),
+                "{input}: {body}"
+            );
+        }
+
+        assert_eq!(
+            respond(
+                "POST",
+                "/key-expression",
+                r#"{"input": "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600", "options": ["--help"]}"#
+            ),
+            (
+                BAD_REQUEST,
+                error_body(&ParsingError::new(
+                    "Argument '--help' is not supported in requests sent to the server."
+                ))
+            )
+        );
+        assert_eq!(
+            respond(
+                "POST",
+                "/key-expression",
+                r#"{"input": "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600", "options": ["--network", "mainnet", "invalid"]}"#
+            ),
+            (
+                BAD_REQUEST,
+                error_body(&ParsingError::new(
+                    "Unexpected argument 'invalid' among the options, the input is given on its own."
+                ))
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_serve_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                handle_connection(stream.unwrap());
+            }
+        });
+
+        let body =
+            r#"{"input": "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"}"#;
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /key-expression HTTP/1.1\r\nHost: localhost\r\ncontent-length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(
+            r#"{"output":"0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"}"#
+        ));
+    }
+
+    /// Reads the request the client sends (and keeps sending, if it is incomplete) on the server side.
+    fn read_sent_request(request: &[u8]) -> Result<(String, String, String), ParsingError> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(request).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        stream
+            .set_read_timeout(Some(std::time::Duration::from_millis(200)))
+            .unwrap();
+        read_request(&stream)
+    }
+
+    #[test]
+    fn test_request_limits() {
+        assert_eq!(
+            read_sent_request(b"POST /key-expression HTTP/1.1\r\ncontent-length: 2\r\n\r\n{}"),
+            Ok((
+                "POST".to_string(),
+                "/key-expression".to_string(),
+                "{}".to_string()
+            ))
+        );
+        assert_eq!(
+            read_sent_request(
+                format!(
+                    "POST /key-expression HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+                    "a".repeat(9000)
+                )
+                .as_bytes()
+            ),
+            Err(ParsingError::new(
+                "Request headers are larger than 8192 bytes"
+            ))
+        );
+        assert_eq!(
+            read_sent_request(
+                format!(
+                    "POST /key-expression HTTP/1.1\r\ncontent-length: {}\r\n\r\n",
+                    MAX_REQUEST_LENGTH + 1
+                )
+                .as_bytes()
+            ),
+            Err(ParsingError::new("Request body is too large"))
+        );
+        // a client that stops sending does not keep the connection open
+        assert!(
+            read_sent_request(b"POST /key-expression HTTP/1.1\r\nHost: loc")
+                .unwrap_err()
+                .message
+                .starts_with("Could not read the request: ")
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use crate::structs::{
+    error_kind::ErrorKind, hwi_import_config::HwiImportConfig, parsing_error::ParsingError,
+    script_type::ScriptType,
+};
+
+use super::{
+    key_expression::validate_key_expression,
+    utils::{
+        checksum::{checksum_check, checksum_create, split_checksum},
+        extended_key::check_network_consistency,
+        json::{parse_json, JsonValue},
+    },
+};
+
+/// A descriptor read from the output of HWI, validated and with its checksum.
+struct HwiDescriptor {
+    /// Whether the descriptor is of the change (internal) addresses.
+    internal: bool,
+    descriptor: String,
+    key_expression: String,
+}
+
+/// Converts the JSON output of HWI's `getdescriptors` or `getkeypool` into the validated descriptors (or with
+/// `--keys`, their key expressions), so that the keys of a hardware wallet can be brought over to this utility.
+///
+/// # Arguments
+///
+/// * `input` - The JSON printed by HWI: the object of the `receive` and `internal` descriptors of
+///   `getdescriptors`, or the array of the keypool entries of `getkeypool` (their `desc` and `internal` members).
+/// * `config` - The configuration specifying whether the key expressions are printed and the network.
+///
+/// # Returns
+///
+/// Returns `Ok(String)` with a line for every descriptor, labeled `receive` or `change` the same way as the
+/// descriptors of wallet-init, e.g. `receive: wpkh([d34db33f/84h/0h/0h]xpub.../0/*)#checksum`. A descriptor
+/// without a checksum gets it computed.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The input is not JSON of either form, or it is the error HWI reported,
+/// - A descriptor is not a single key `pkh`, `sh(wpkh)`, `wpkh` or `tr` descriptor, or its checksum does not match,
+/// - A key expression is invalid in its descriptor, or the extended keys do not all belong to the network.
+pub fn hwi_import(input: &str, config: &HwiImportConfig) -> Result<String, ParsingError> {
+    let lines: Vec<String> = hwi_descriptors(input, config)?
+        .iter()
+        .map(|descriptor| {
+            let label = if descriptor.internal {
+                "change"
+            } else {
+                "receive"
+            };
+            let value = if config.keys {
+                &descriptor.key_expression
+            } else {
+                &descriptor.descriptor
+            };
+            format!("{label}: {value}")
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+/// Converts the output of HWI the same way as [`hwi_import`], returning the comma separated `receive` and
+/// `change` descriptors (or key expressions) and their `count` for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`hwi_import`].
+pub fn hwi_import_fields(
+    input: &str,
+    config: &HwiImportConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let descriptors = hwi_descriptors(input, config)?;
+    let joined = |internal: bool| -> String {
+        descriptors
+            .iter()
+            .filter(|descriptor| descriptor.internal == internal)
+            .map(|descriptor| {
+                if config.keys {
+                    descriptor.key_expression.as_str()
+                } else {
+                    descriptor.descriptor.as_str()
+                }
+            })
+            .collect::<Vec<&str>>()
+            .join(",")
+    };
+
+    Ok(vec![
+        ("receive", joined(false)),
+        ("change", joined(true)),
+        ("count", descriptors.len().to_string()),
+    ])
+}
+
+fn hwi_descriptors(
+    input: &str,
+    config: &HwiImportConfig,
+) -> Result<Vec<HwiDescriptor>, ParsingError> {
+    let descriptors: Vec<HwiDescriptor> = hwi_entries(&parse_json(input.trim())?)?
+        .into_iter()
+        .map(|(internal, descriptor)| validate_hwi_descriptor(internal, descriptor))
+        .collect::<Result<_, _>>()?;
+
+    let key_expressions: Vec<String> = descriptors
+        .iter()
+        .map(|descriptor| descriptor.key_expression.clone())
+        .collect();
+    check_network_consistency(&key_expressions, config.network)?;
+
+    Ok(descriptors)
+}
+
+/// Returns the descriptors of the HWI output, each with whether it is of the change addresses.
+fn hwi_entries(json: &JsonValue) -> Result<Vec<(bool, &str)>, ParsingError> {
+    if let Some(error) = json.get("error") {
+        let message = match error {
+            JsonValue::String(message) => message.clone(),
+            error => error.to_string(),
+        };
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            &format!("HWI reported an error: {message}"),
+        ));
+    }
+
+    let unexpected = || {
+        ParsingError::with_kind(ErrorKind::InvalidJson,
+            "Expected the output of HWI getdescriptors (an object of the 'receive' and 'internal' descriptors) \
+             or getkeypool (an array of the entries with their 'desc')",
+        )
+    };
+
+    let mut entries = Vec::new();
+    match json {
+        JsonValue::Object(_) => {
+            for (name, internal) in [("receive", false), ("internal", true)] {
+                let Some(JsonValue::Array(descriptors)) = json.get(name) else {
+                    return Err(unexpected());
+                };
+                for descriptor in descriptors {
+                    let JsonValue::String(descriptor) = descriptor else {
+                        return Err(unexpected());
+                    };
+                    entries.push((internal, descriptor.as_str()));
+                }
+            }
+        }
+        JsonValue::Array(keypool) => {
+            for entry in keypool {
+                let Some(JsonValue::String(descriptor)) = entry.get("desc") else {
+                    return Err(unexpected());
+                };
+                let internal = entry.get("internal") == Some(&JsonValue::Bool(true));
+                entries.push((internal, descriptor.as_str()));
+            }
+        }
+        _ => return Err(unexpected()),
+    }
+
+    if entries.is_empty() {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            "The output of HWI holds no descriptors",
+        ));
+    }
+
+    Ok(entries)
+}
+
+fn validate_hwi_descriptor(internal: bool, input: &str) -> Result<HwiDescriptor, ParsingError> {
+    let (script, checksum) = split_checksum(input)?;
+    let checksum = match checksum {
+        Some(checksum) if !checksum_check(script, checksum)? => {
+            return Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                &format!("The checksum of the descriptor '{input}' does not match"),
+            ))
+        }
+        Some(checksum) => checksum.to_string(),
+        None => checksum_create(script)?,
+    };
+
+    let (script_type, key_expression) = ScriptType::ALL
+        .into_iter()
+        .find_map(|script_type| {
+            script_type
+                .key_expression_of(script)
+                .map(|key_expression| (script_type, key_expression))
+        })
+        .ok_or_else(|| {
+            ParsingError::new(&format!(
+                "Unsupported descriptor '{input}', expected a single key pkh, sh(wpkh), wpkh or tr descriptor"
+            ))
+        })?;
+    let key_expression = validate_key_expression(
+        key_expression.to_string(),
+        script_type.address_type().key_context(),
+    )?;
+
+    Ok(HwiDescriptor {
+        internal,
+        descriptor: format!("{script}#{checksum}"),
+        key_expression,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{structs::network::Network, test_utils::get_cmd};
+
+    const TPUB: &str = "tpubDDNRbZGvdA33cgpY5uy2mmphT7sK4uciRjcQScSd64S5KRyZDxHcPuzs24or84Hywugb2JbEEt2jWH8fduiN9cmZzkSj8sSSx6txXkhXyZs";
+
+    fn receive() -> String {
+        format!("wpkh([3442193e/84h/1h/0h]{TPUB}/0/*)#0s0dqh6s")
+    }
+
+    fn change() -> String {
+        format!("wpkh([3442193e/84h/1h/0h]{TPUB}/1/*)#7y2vaz2g")
+    }
+
+    #[test]
+    fn test_getdescriptors() {
+        let input = format!(
+            r#"{{"receive": ["{}"], "internal": ["{}"]}}"#,
+            receive(),
+            change()
+        );
+
+        assert_eq!(
+            hwi_import(&input, &HwiImportConfig::default()),
+            Ok(format!("receive: {}\nchange: {}", receive(), change()))
+        );
+        assert_eq!(
+            hwi_import(
+                &input,
+                &HwiImportConfig {
+                    keys: true,
+                    network: None,
+                }
+            ),
+            Ok(format!(
+                "receive: [3442193e/84h/1h/0h]{TPUB}/0/*\nchange: [3442193e/84h/1h/0h]{TPUB}/1/*"
+            ))
+        );
+        assert!(hwi_import(
+            &input,
+            &HwiImportConfig {
+                keys: false,
+                network: Some(Network::Mainnet),
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_getkeypool() {
+        // a descriptor without its checksum gets it computed
+        let change = change();
+        let change_script = change.split('#').next().unwrap();
+        let input = format!(
+            r#"[{{"desc": "{}", "range": [0, 1000], "timestamp": "now", "internal": false, "keypool": true, "active": true, "watchonly": true}}, {{"desc": "{change_script}", "internal": true}}]"#,
+            receive()
+        );
+
+        get_cmd()
+            .args(["hwi-import", "-"])
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(format!("receive: {}\nchange: {change}\n", receive()));
+    }
+
+    #[test]
+    fn test_hwi_import_errors() {
+        let config = HwiImportConfig::default();
+        for input in [
+            r#"{"error": "No device path found", "code": -1}"#.to_string(),
+            "[]".to_string(),
+            r#"{"receive": []}"#.to_string(),
+            format!(r#"[{{"desc": "{}"}}]"#, receive().replace("#0s0dqh6s", "#00000000")),
+            format!(r#"[{{"desc": "wsh(pk([3442193e/84h/1h/0h]{TPUB}/0/*))"}}]"#),
+            r#"[{"desc": "wpkh(04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235)"}]"#.to_string(),
+            "[".repeat(200_000),
+        ] {
+            assert!(hwi_import(&input, &config).is_err(), "{input}");
+        }
+        assert_eq!(
+            hwi_import(r#"{"error": "No device path found", "code": -1}"#, &config)
+                .map_err(|err| err.message),
+            Err("HWI reported an error: No device path found".to_string())
+        );
+    }
+}
@@ -1,17 +1,34 @@
 use std::str::FromStr;
 
-use bip32::ExtendedKey;
+use bip32::{DerivationPath, ExtendedKey, Prefix};
 
-use crate::structs::{key_expression_config::KeyExpressionConfig, parsing_error::ParsingError};
+use crate::structs::{
+    canonical_key::CanonicalKey, derive_key_config::DeriveKeyConfig, error_kind::ErrorKind,
+    key_context::KeyContext, key_expression_config::KeyExpressionConfig,
+    parsing_error::ParsingError, script_limits::DEFAULT_MAX_ORIGIN_DEPTH, script_type::ScriptType,
+};
 use crate::subcommands::utils::{
-    extended_key, hex_encoded_public_key, key_origin, wallet_import_format,
+    descriptor::receive_and_change_descriptors, extended_key, hex_encoded_public_key, key_origin,
+    wallet_import_format,
+};
+
+#[cfg(feature = "derive-key")]
+use super::derive_key::derive_extended_keys;
+use super::utils::derived_public_key::{derive_public_key, resolve_derivation_steps};
+use super::utils::extended_key::{
+    check_hardened_wildcards, check_network_consistency, has_extended_key_prefix,
+    validate_extended_key_attrs,
+};
+use super::utils::hex_encoded_public_key::{
+    compress_public_key, has_hex_encoded_public_key_prefix, is_x_only_public_key,
 };
+use super::utils::hexadecimal::encode_hex;
 
-use super::utils::extended_key::{has_extended_key_prefix, validate_extended_key_attrs};
-use super::utils::hex_encoded_public_key::has_hex_encoded_public_key_prefix;
+/// The characters of the derivation steps of a key origin or of the path appended to an extended key.
+const PATH_CHARS: &str = "0123456789/hH'*";
 
-const ALLOWED_CHAR_SET: &str =
-    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`# ";
+/// A component of a key expression: its name, its text and whether a character is allowed in it.
+type Component<'a> = (&'static str, &'a str, fn(char) -> bool);
 
 /// Parses and validates a key expression according to the provided configuration.
 ///
@@ -20,29 +37,261 @@ const ALLOWED_CHAR_SET: &str =
 /// # Arguments
 ///
 /// * `input` - The key expression as a `String`.
-/// * `_config` - The configuration for key expression parsing (currently unused).
+/// * `config` - The configuration for key expression parsing.
 ///
 /// # Returns
 ///
 /// Returns `Ok(String)` with the validated key expression, or `Err(ParsingError)` if validation fails.
+/// If account descriptors are requested, the output is the receive (`/0/*`) and change (`/1/*`) descriptors
+/// of the requested script type on two lines instead.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the input is empty, contains invalid characters, or fails key format validation
+/// (including the form of the key not allowed in the configured key context), if the key origin does not match the master key to verify it against (see [`key_origin::verify_key_origin`]),
+/// if an extended key does not belong to the selected network, if a hardened wildcard is not accepted by the policy,
+/// or if account descriptors are requested for a key that is not a non-ranged extended key.
+///
+/// With a canonical key form configured, a hex encoded public key is printed in that form (see
+/// [`canonical_key_expression`]) and a note is printed to the standard error if the input was not canonical.
+/// In the lenient mode, the stray whitespace is removed first (see [`lenient_key_expression`]).
+/// In the sortedmulti order mode, the key is printed after its public key (see [`sortedmulti_order_line`]).
+/// With `--evaluate`, the derivation steps appended to an extended key are performed first (see
+/// [`evaluate_key_expression`]).
+pub fn key_expression(input: String, config: &KeyExpressionConfig) -> Result<String, ParsingError> {
+    let input = if config.lenient {
+        lenient_key_expression(&input)
+    } else {
+        input
+    };
+    let mut key_expression = validate_key_expression(input, config.key_context)?;
+
+    if let Some(form) = config.canonical_key {
+        let canonical = canonical_key_expression(&key_expression, form)?;
+        if canonical != key_expression {
+            eprintln!("Note: the key expression '{key_expression}' is not canonical, printed as '{canonical}'");
+            key_expression = canonical;
+        }
+    }
+
+    if let Some(master_key) = &config.verify_origin {
+        key_origin::verify_key_origin(&key_expression, master_key)?;
+    }
+
+    let key_expressions = std::slice::from_ref(&key_expression);
+    check_network_consistency(key_expressions, config.network)?;
+    check_hardened_wildcards(key_expressions, config.hardened_wildcard)?;
+    key_origin::check_origin_depths(
+        key_expressions,
+        config.max_origin_depth.unwrap_or(DEFAULT_MAX_ORIGIN_DEPTH),
+    )?;
+
+    #[cfg(feature = "derive-key")]
+    if config.evaluate {
+        key_expression = evaluate_key_expression(&key_expression)?;
+    }
+
+    match config.account_descriptors {
+        Some(script_type) => account_descriptors(&key_expression, script_type),
+        None if config.sortedmulti_order => sortedmulti_order_line(&key_expression, config.index),
+        None => Ok(key_expression),
+    }
+}
+
+/// Parses and validates a key expression the same way as [`key_expression`], returning the fields
+/// available to `--template`: the `expression`, the `fingerprint` and the `origin_path` of the key origin
+/// (empty without a key origin), the `key` and the `receive` and `change` account descriptors (if requested).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`key_expression`].
+pub fn key_expression_fields(
+    input: &str,
+    config: &KeyExpressionConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let input = if config.lenient {
+        lenient_key_expression(input)
+    } else {
+        input.to_string()
+    };
+    let output = key_expression(input.clone(), config)?;
+
+    let expression = match config.canonical_key {
+        Some(form) => canonical_key_expression(input.trim(), form)?,
+        None => input.trim().to_string(),
+    };
+    let (key_origin, key) = split_key_expression(&expression)?;
+    let (fingerprint, origin_path) = key_origin
+        .map(|key_origin| key_origin.trim_start_matches('[').trim_end_matches(']'))
+        .map_or(("", ""), |key_origin| {
+            key_origin.split_once('/').unwrap_or((key_origin, ""))
+        });
+
+    let mut fields = vec![
+        ("expression", expression.clone()),
+        ("fingerprint", fingerprint.to_string()),
+        ("origin_path", origin_path.to_string()),
+        ("key", key.to_string()),
+    ];
+    if config.account_descriptors.is_some() {
+        if let Some((receive, change)) = output.split_once('\n') {
+            fields.extend([
+                ("receive", receive.to_string()),
+                ("change", change.to_string()),
+            ]);
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Returns the (previously validated) key expression with its hex encoded public key (including an x-only one)
+/// in the canonical form: lowercase, and with [`CanonicalKey::Compressed`] an uncompressed key compressed.
+/// Other keys and the key origin are kept as they are.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key origin is not closed or an uncompressed key cannot be compressed.
+pub fn canonical_key_expression(
+    key_expression: &str,
+    form: CanonicalKey,
+) -> Result<String, ParsingError> {
+    let (key_origin, key) = split_key_expression(key_expression)?;
+    if !has_hex_encoded_public_key_prefix(key) && !is_x_only_public_key(key) {
+        return Ok(key_expression.to_string());
+    }
+
+    let key = match form {
+        CanonicalKey::Compressed if key.starts_with("04") && !is_x_only_public_key(key) => {
+            compress_public_key(key)?
+        }
+        _ => key.to_lowercase(),
+    };
+
+    Ok(format!("{}{key}", key_origin.unwrap_or_default()))
+}
+
+/// Removes the whitespace surrounding the key expression and padding the brackets of its key origin, e.g.
+/// ` [ deadbeef/0h ] xpub...` becomes `[deadbeef/0h]xpub...`. The whitespace within the components is kept, so
+/// that a key broken by a space is still rejected.
+#[must_use]
+pub fn lenient_key_expression(input: &str) -> String {
+    let input = input.trim();
+    match input
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        Some((key_origin, key)) => format!("[{}]{}", key_origin.trim(), key.trim_start()),
+        None => input.to_string(),
+    }
+}
+
+/// Performs the derivation steps appended to the extended key of the (previously validated) key expression the
+/// same way as derive-key, returning the key expression of the derived key with the steps appended to its key
+/// origin, if any, e.g. `[d34db33f/44h]xprv.../0h/1` evaluates to `[d34db33f/44h/0h/1]xprv...`. An extended
+/// private key evaluates to the derived private key, as only it can perform the hardened steps.
 ///
 /// # Errors
 ///
-/// Returns a [`ParsingError`] if the input is empty, contains invalid characters, or fails key format validation.
-pub fn key_expression(
-    input: String,
-    _config: &KeyExpressionConfig,
+/// Returns a [`ParsingError`] if the key is not a mainnet extended key (the same as with derive-key), the key
+/// expression is ranged, or a hardened step is to be derived from an extended public key.
+#[cfg(feature = "derive-key")]
+pub fn evaluate_key_expression(key_expression: &str) -> Result<String, ParsingError> {
+    let (key_origin, key) = split_key_expression(key_expression)?;
+    if !key.starts_with("xprv") && !key.starts_with("xpub") {
+        return Err(ParsingError::new(
+            "Only the derivation of an extended key (xprv or xpub) can be evaluated",
+        ));
+    }
+    let (encoded, path) = key.split_at(key.find('/').unwrap_or(key.len()));
+    if path.contains('*') {
+        return Err(ParsingError::new(
+            "A ranged key expression cannot be evaluated, it stands for many keys",
+        ));
+    }
+
+    let mut derivation_path = DerivationPath::default();
+    derivation_path.extend(resolve_derivation_steps(path, None)?);
+    if encoded.starts_with("xpub") && derivation_path.iter().any(|step| step.is_hardened()) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::HardenedFromXpub,
+            &format!(
+                "The hardened steps of '{path}' cannot be evaluated from an extended public key, they require the extended private key"
+            ),
+        ));
+    }
+    let derived = derive_extended_keys(
+        &format!("{}{encoded}", key_origin.unwrap_or_default()),
+        &DeriveKeyConfig {
+            path: derivation_path,
+            ..DeriveKeyConfig::default()
+        },
+    )?;
+
+    let derived_key = match &derived.xprv {
+        Some(xprv) => xprv.to_string(Prefix::XPRV).to_string(),
+        None => derived.xpub.to_string(Prefix::XPUB),
+    };
+    Ok(format!(
+        "{}{derived_key}",
+        derived.origin.unwrap_or_default()
+    ))
+}
+
+/// Returns the (previously validated) key expression preceded by the public key it stands for, an extended key
+/// derived along its path (a ranged one at the `index`), e.g. `02e493...cd13 [deadbeef/0h]xpub.../0/*`.
+///
+/// The public keys are lowercase hex of the same length, so sorting the lines sorts the keys in the lexicographic
+/// order of their public keys, which `sortedmulti(...)` places them in (BIP 67).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key is ranged and no `index` is given, or its public key cannot be derived.
+pub fn sortedmulti_order_line(
+    key_expression: &str,
+    index: Option<u32>,
+) -> Result<String, ParsingError> {
+    if index.is_none() && key_expression.contains('*') {
+        return Err(ParsingError::new(
+            "A ranged key has no single public key, use '--index {i}' to select the one derived at the index",
+        ));
+    }
+    let public_key = encode_hex(&derive_public_key(key_expression, index)?);
+
+    Ok(format!("{public_key} {key_expression}"))
+}
+
+/// Builds the checksummed receive and change descriptors for an account level extended key expression.
+fn account_descriptors(
+    key_expression: &str,
+    script_type: ScriptType,
 ) -> Result<String, ParsingError> {
-    validate_key_expression(input)
+    let (_, key) = split_key_expression(key_expression)?;
+    if !has_extended_key_prefix(key) {
+        return Err(ParsingError::new(
+            "Account descriptors can only be generated from an extended key",
+        ));
+    }
+    if key.contains('*') {
+        return Err(ParsingError::new(
+            "Account descriptors cannot be generated from a ranged key expression",
+        ));
+    }
+
+    let descriptors = receive_and_change_descriptors(key_expression, script_type)?;
+
+    Ok(descriptors.join("\n"))
 }
 
 /// Validates a key expression string for correct format and allowed characters.
 ///
-/// This function checks for empty input, invalid characters, key origin format, and key type validity (public key, extended key, or WIF).
+/// This function checks for empty input, invalid characters of its components, key origin format, and key type validity (public key, extended key, or WIF),
+/// and whether the form of the key is allowed in the context it appears in.
 ///
 /// # Arguments
 ///
 /// * `input` - The key expression as a `String`.
+/// * `context` - The position of the key expression within a descriptor.
 ///
 /// # Returns
 ///
@@ -52,30 +301,104 @@ pub fn key_expression(
 ///
 /// Returns a [`ParsingError`] if:
 /// - The input is empty,
-/// - The input contains invalid characters,
+/// - The key origin, the key or its derivation path contains a character it cannot consist of,
 /// - The key origin or key format is invalid,
-/// - The key fails type-specific validation.
-pub fn validate_key_expression(input: String) -> Result<String, ParsingError> {
-    if input.is_empty() {
-        return Err(ParsingError::new("Input is empty"));
+/// - The key fails type-specific validation,
+/// - The key is uncompressed in a segwit or taproot context, or x-only outside of a taproot context.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn validate_key_expression(input: String, context: KeyContext) -> Result<String, ParsingError> {
+    validate_key_expressions(std::slice::from_ref(&input), context)?;
+
+    Ok(input)
+}
+
+/// Validates a batch of key expression strings (e.g. all keys of a `multi` script) appearing in the context.
+///
+/// Every expression is syntax-checked first, while the hex encoded public keys found along the way are collected
+/// and verified to lie on the secp256k1 curve together, so the whole batch shares a single validation pass.
+///
+/// # Arguments
+///
+/// * `inputs` - The key expressions to be validated.
+/// * `context` - The position of the key expressions within a descriptor.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] for the first invalid key expression (see [`validate_key_expression`]),
+/// or if any of the hex encoded public keys is not a valid point on the curve.
+pub fn validate_key_expressions(
+    inputs: &[String],
+    context: KeyContext,
+) -> Result<(), ParsingError> {
+    let mut hex_public_keys: Vec<&str> = Vec::new();
+
+    for input in inputs {
+        if let Some(hex_public_key) = validate_key_expression_syntax(input, context)? {
+            hex_public_keys.push(hex_public_key);
+        }
     }
 
-    if input.chars().any(|c| !ALLOWED_CHAR_SET.contains(c)) {
-        return Err(ParsingError::new("Input contains invalid characters"));
+    hex_encoded_public_key::validate_public_keys_on_curve(&hex_public_keys)
+        .map_err(|err| err.or_kind(ErrorKind::InvalidKey))
+}
+
+/// Checks the format of a single key expression, returning the hex encoded public key (if the expression
+/// contains one) so that its curve membership can be checked afterwards.
+fn validate_key_expression_syntax(
+    input: &str,
+    context: KeyContext,
+) -> Result<Option<&str>, ParsingError> {
+    if input.is_empty() {
+        return Err(ParsingError::new("Input is empty"));
     }
 
-    let (key_origin, key) = split_key_expression(input.as_str())?;
+    let (key_origin, key) = split_key_expression(input)?;
+    check_component_chars(input, key_origin, key)?;
 
     if let Some(key_origin) = key_origin {
         key_origin::validate_key_origin(key_origin)?;
     }
 
-    validate_key(key)?;
+    validate_key(key, context).map_err(|err| err.or_kind(ErrorKind::InvalidKey))
+}
 
-    Ok(input)
+/// Checks the characters of every component of the key expression on its own: the key origin may only contain
+/// the hex digits of the fingerprint and the path characters, the key itself no whitespace or non-ASCII characters
+/// (the rest is left to its hex, base58 or WIF decoder) and the path appended to it only the path characters. The
+/// characters of the enclosing script are left to the script parser.
+fn check_component_chars(
+    input: &str,
+    key_origin: Option<&str>,
+    key: &str,
+) -> Result<(), ParsingError> {
+    let origin_content = key_origin.map_or("", |key_origin| {
+        key_origin.trim_start_matches('[').trim_end_matches(']')
+    });
+    let (key, path) = key.split_at(key.find('/').unwrap_or(key.len()));
+
+    let components: [Component; 3] = [
+        ("key origin", origin_content, |c| {
+            c.is_ascii_hexdigit() || PATH_CHARS.contains(c)
+        }),
+        ("key", key, |c| c.is_ascii_graphic()),
+        ("derivation path", path, |c| PATH_CHARS.contains(c)),
+    ];
+    for (component, value, allowed) in components {
+        if let Some(c) = value.chars().find(|c| !allowed(*c)) {
+            return Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                &format!(
+                    "Invalid character {c:?} in the {component} of the key expression '{}'",
+                    input.escape_debug()
+                ),
+            ));
+        }
+    }
+
+    Ok(())
 }
 
-fn validate_key(key: &str) -> Result<(), ParsingError> {
+fn validate_key(key: &str, context: KeyContext) -> Result<Option<&str>, ParsingError> {
     if key.is_empty() {
         return Err(ParsingError::new("Key is empty"));
     }
@@ -84,21 +407,43 @@ fn validate_key(key: &str) -> Result<(), ParsingError> {
         return Err(ParsingError::new("Key can not include key origin"));
     }
 
-    if has_hex_encoded_public_key_prefix(key) {
+    // an x-only key may start with any of the prefixes of the other hex encoded public keys
+    if is_x_only_public_key(key) {
+        if !context.allows_x_only() {
+            return Err(ParsingError::new(&format!(
+                "X-only public key '{key}' is only allowed in a taproot context, not in a {context} one"
+            )));
+        }
+        return Ok(Some(key));
+    }
+
+    let compressed = if has_hex_encoded_public_key_prefix(key) {
         hex_encoded_public_key::parse_hex_encoded_public_key(key)?;
+        !key.starts_with("04")
     } else if has_extended_key_prefix(key) {
         let key_str = extended_key::validate_extended_key(key)?;
-        let key = ExtendedKey::from_str(&key_str)?;
-        validate_extended_key_attrs(&key.attrs)?;
+        let extended_key = ExtendedKey::from_str(&key_str)?;
+        validate_extended_key_attrs(&extended_key.attrs)?;
+        true
     } else {
-        wallet_import_format::validate_wif_private_key(key)?;
+        wallet_import_format::validate_wif_private_key(key)?
+    };
+
+    if !compressed && !context.allows_uncompressed() {
+        return Err(ParsingError::new(&format!(
+            "Uncompressed key '{key}' is not allowed in a {context} context"
+        )));
     }
 
-    Ok(())
+    Ok(has_hex_encoded_public_key_prefix(key).then_some(key))
 }
 
 /// Split the key expression subcommand input into key origin and key
-fn split_key_expression(input: &str) -> Result<(Option<&str>, &str), ParsingError> {
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key origin is not closed by a bracket.
+pub fn split_key_expression(input: &str) -> Result<(Option<&str>, &str), ParsingError> {
     if input.starts_with('[') {
         let end_index = input
             .find(']')
@@ -115,7 +460,10 @@ fn split_key_expression(input: &str) -> Result<(Option<&str>, &str), ParsingErro
 
 #[cfg(test)]
 mod tests {
-    use crate::test_utils::get_cmd;
+    use crate::{
+        structs::hardened_wildcard_policy::HardenedWildcardPolicy,
+        subcommands::utils::checksum::checksum_create, test_utils::get_cmd,
+    };
 
     use super::*;
 
@@ -152,7 +500,7 @@ mod tests {
         ];
 
         for &key_origin in &valid_key_origins {
-            let result = validate_key_expression(key_origin.to_string());
+            let result = validate_key_expression(key_origin.to_string(), KeyContext::Legacy);
             println!("{:?}", result);
             assert!(
                 result.is_ok(),
@@ -184,7 +532,7 @@ mod tests {
         ];
 
         for &key_origin in &invalid_key_origins {
-            let result = validate_key_expression(key_origin.to_string());
+            let result = validate_key_expression(key_origin.to_string(), KeyContext::Legacy);
             assert!(
                 result.is_err(),
                 "Expected invalid key origin '{}' not to pass validation",
@@ -193,8 +541,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_component_chars() {
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+        // the characters of the enclosing script belong to none of the components
+        for (key_expression, message) in [
+            (
+                format!("[deadbeef/0h{{]{xpub}"),
+                format!("Invalid character '{{' in the key origin of the key expression '[deadbeef/0h{{]{xpub}'"),
+            ),
+            (
+                format!("{xpub}/<0;1>/*"),
+                format!("Invalid character '<' in the derivation path of the key expression '{xpub}/<0;1>/*'"),
+            ),
+            (
+                format!("{xpub}\t"),
+                format!("Invalid character '\\t' in the key of the key expression '{xpub}\\t'"),
+            ),
+        ] {
+            assert_eq!(
+                validate_key_expression(key_expression, KeyContext::Legacy),
+                Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, &message))
+            );
+        }
+
+        // the other characters of a key are left to its decoder
+        assert_eq!(
+            validate_key_expression("xpub_invalid".to_string(), KeyContext::Legacy),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidKey,
+                "Invalid xpub key: base58 error"
+            ))
+        );
+    }
+
     // integration test
     #[test]
+    #[cfg(feature = "key-expression")]
     fn test_key_expression() {
         let input_string = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600";
         get_cmd()
@@ -215,6 +599,7 @@ mod tests {
     fn valid_compressed_public_key() {
         let result = validate_key_expression(
             "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
@@ -223,6 +608,7 @@ mod tests {
     fn valid_compressed_public_key_other_prefix() {
         let result = validate_key_expression(
             "0360b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
@@ -231,6 +617,7 @@ mod tests {
     fn invalid_compressed_public_too_short() {
         let result = validate_key_expression(
             "0360b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce60".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_err());
     }
@@ -239,6 +626,7 @@ mod tests {
     fn invalid_compressed_public_too_long() {
         let result = validate_key_expression(
             "0360b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce6000".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_err());
     }
@@ -246,131 +634,138 @@ mod tests {
     #[test]
     fn valid_uncompressed_public_key() {
         let result = validate_key_expression(
-            "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235".into()
-        );
+            "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_public_key() {
-        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL".into());
+        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_public_key_with_derivation() {
-        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3/4/5".into());
+        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3/4/5".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_public_key_with_derivation_and_children() {
-        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3/4/5/*".into());
+        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3/4/5/*".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_public_key_with_hardened_derivation_and_unhardened_children() {
-        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/4h/5h/*".into());
+        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/4h/5h/*".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_public_key_with_hardened_derivation_and_children() {
-        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/4h/5h/*h".into());
+        let result = validate_key_expression("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/4h/5h/*h".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_private_key() {
-        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc".into());
+        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_private_key_with_derivation() {
-        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3/4/5".into());
+        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3/4/5".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_private_key_with_derivation_and_children() {
-        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3/4/5/*".into());
+        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3/4/5/*".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_private_key_with_hardened_derivation_and_unhardened_children() {
-        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*".into());
+        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_extended_private_key_with_hardened_derivation_and_children() {
-        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*h".into());
+        let result = validate_key_expression("xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*h".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn invalid_extended_key_random_bytes() {
-        let result = validate_key_expression("xprv123".into());
+        let result = validate_key_expression("xprv123".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn invalid_derivation_index_out_of_range() {
-        let result = validate_key_expression("xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483648".into());
+        let result = validate_key_expression("xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483648".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn valid_wif_uncompressed() {
-        let result =
-            validate_key_expression("5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss".into());
+        let result = validate_key_expression(
+            "5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss".into(),
+            KeyContext::Legacy,
+        );
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_wif_compressed() {
-        let result =
-            validate_key_expression("L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1".into());
+        let result = validate_key_expression(
+            "L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1".into(),
+            KeyContext::Legacy,
+        );
         assert!(result.is_ok());
     }
 
     #[test]
     fn wif_invalid_checksum() {
-        let result =
-            validate_key_expression("L2yR7WsFoYmeZqch8ScZ6J2YqrJ7N9JGVd56jz17WAWWm3coAJza".into());
+        let result = validate_key_expression(
+            "L2yR7WsFoYmeZqch8ScZ6J2YqrJ7N9JGVd56jz17WAWWm3coAJza".into(),
+            KeyContext::Legacy,
+        );
         assert!(result.is_err());
     }
 
     #[test]
     fn wif_invalid_prefix() {
-        let result =
-            validate_key_expression("5wGuZuPGhWjR8d1J3zfVFS6c1tM1gKZX2VZeu4fz248QepEppupV".into());
+        let result = validate_key_expression(
+            "5wGuZuPGhWjR8d1J3zfVFS6c1tM1gKZX2VZeu4fz248QepEppupV".into(),
+            KeyContext::Legacy,
+        );
         assert!(result.is_err());
     }
 
     #[test]
     fn xpub_zero_depth_with_non_zero_parent_fingerprint_invalid() {
-        let result = validate_key_expression("xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ".into());
+        let result = validate_key_expression("xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn xpub_zero_depth_with_non_zero_index_invalid() {
-        let result = validate_key_expression("xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8".into());
+        let result = validate_key_expression("xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
     #[test]
     fn xprv_zero_depth_non_zero_parent_invalid() {
-        let result = validate_key_expression("xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv".into());
+        let result = validate_key_expression("xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn xprv_zero_depth_non_zero_index_invalid() {
-        let result = validate_key_expression("xprv9s21ZrQH4r4TsiLvyLXqM9P7k1K3EYhA1kkD6xuquB5i39AU8KF42acDyL3qsDbU9NmZn6MsGSUYZEsuoePmjzsB3eFKSUEh3Gu1N3cqVUN".into());
+        let result = validate_key_expression("xprv9s21ZrQH4r4TsiLvyLXqM9P7k1K3EYhA1kkD6xuquB5i39AU8KF42acDyL3qsDbU9NmZn6MsGSUYZEsuoePmjzsB3eFKSUEh3Gu1N3cqVUN".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
@@ -378,6 +773,7 @@ mod tests {
     fn valid_public_key_02() {
         let result = validate_key_expression(
             "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
@@ -386,12 +782,13 @@ mod tests {
     fn valid_public_key_03() {
         let result = validate_key_expression(
             "0360b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
     #[test]
     fn valid_public_key_04() {
-        let result = validate_key_expression("04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235".into());
+        let result = validate_key_expression("04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
@@ -400,6 +797,7 @@ mod tests {
         let result = validate_key_expression(
             "[deadbeef/0h/0h/0h]0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"
                 .into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
@@ -409,6 +807,7 @@ mod tests {
         let result = validate_key_expression(
             "[deadbeef/0'/0'/0']0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"
                 .into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
@@ -418,38 +817,41 @@ mod tests {
         let result = validate_key_expression(
             "[deadbeef/0'/0h/0']0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"
                 .into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_wif_key() {
-        let result =
-            validate_key_expression("5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss".into());
+        let result = validate_key_expression(
+            "5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss".into(),
+            KeyContext::Legacy,
+        );
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_public_extended_key_with_every_optional_part() {
-        let result = validate_key_expression("[deadbeef/0h/1h/2]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/4h/5h/*h".into());
+        let result = validate_key_expression("[deadbeef/0h/1h/2]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/4h/5h/*h".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn valid_private_extended_key_with_every_optional_part() {
-        let result = validate_key_expression("[deadbeef/0h/1h/2]xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*h".into());
+        let result = validate_key_expression("[deadbeef/0h/1h/2]xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*h".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
 
     #[test]
     fn invalid_empty() {
-        let result = validate_key_expression("".into());
+        let result = validate_key_expression("".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn invalid_no_key() {
-        let result = validate_key_expression("[deadbeef]".into());
+        let result = validate_key_expression("[deadbeef]".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
@@ -458,25 +860,26 @@ mod tests {
         let result = validate_key_expression(
             "[deadbef/0h/0h/0h]0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"
                 .into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_err());
     }
 
     #[test]
     fn invalid_multiple_origins() {
-        let result = validate_key_expression("[aaaaaaaa][aaaaaaaa]xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483647'/0".into());
+        let result = validate_key_expression("[aaaaaaaa][aaaaaaaa]xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483647'/0".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn invalid_missing_origin_start() {
-        let result = validate_key_expression("aaaaaaaa]xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483647'/0".into());
+        let result = validate_key_expression("aaaaaaaa]xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483647'/0".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
     #[test]
     fn invalid_origin_non_hex() {
-        let result = validate_key_expression("[gaaaaaaa]xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483647'/0".into());
+        let result = validate_key_expression("[gaaaaaaa]xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/2147483647'/0".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
@@ -484,13 +887,14 @@ mod tests {
     fn invalid_wif_key_with_derivation() {
         let result = validate_key_expression(
             "L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1/0".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_err());
     }
 
     #[test]
     fn invalid_derivation() {
-        let result = validate_key_expression("xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/1aa".into());
+        let result = validate_key_expression("xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U/1aa".into(), KeyContext::Legacy);
         assert!(result.is_err());
     }
 
@@ -498,13 +902,349 @@ mod tests {
     fn invalid_missing_origin_end() {
         let result = validate_key_expression(
             "[deadbeef0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            KeyContext::Legacy,
         );
         assert!(result.is_err());
     }
 
     #[test]
     fn valid_from_project_desription() {
-        let result = validate_key_expression("[deadbeef/0h/1h/2]xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*h".into());
+        let result = validate_key_expression("[deadbeef/0h/1h/2]xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/4h/5h/*h".into(), KeyContext::Legacy);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn invalid_compressed_public_key_not_on_curve() {
+        let result = validate_key_expression(
+            "020000000000000000000000000000000000000000000000000000000000000005".into(),
+            KeyContext::Legacy,
+        );
+        assert_eq!(
+            result,
+            Err(ParsingError::with_kind(ErrorKind::InvalidKey,
+                "Hex encoded public key '020000000000000000000000000000000000000000000000000000000000000005' is not a valid point on the secp256k1 curve"
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_uncompressed_public_key_not_on_curve() {
+        let result = validate_key_expression(
+            "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea236".into(), KeyContext::Legacy);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn batch_validation_reports_off_curve_key() {
+        let result = validate_key_expressions(&[
+            "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            "[deadbeef/0h]020000000000000000000000000000000000000000000000000000000000000005".into(),
+            "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL".into(),
+        ], KeyContext::Legacy);
+        assert!(result.is_err());
+
+        let result = validate_key_expressions(&[
+            "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".into(),
+            "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235".into(),
+        ], KeyContext::Legacy);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn key_context_rules() {
+        let uncompressed = "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235";
+        let uncompressed_wif = "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ";
+        let x_only = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let compressed = format!("02{x_only}");
+
+        for context in [KeyContext::Legacy, KeyContext::P2sh] {
+            assert!(validate_key_expression(uncompressed.into(), context).is_ok());
+            assert!(validate_key_expression(uncompressed_wif.into(), context).is_ok());
+            assert!(validate_key_expression(x_only.into(), context).is_err());
+        }
+        for context in [KeyContext::SegwitV0, KeyContext::Taproot] {
+            assert!(validate_key_expression(uncompressed.into(), context).is_err());
+            assert!(validate_key_expression(uncompressed_wif.into(), context).is_err());
+            assert!(validate_key_expression(compressed.clone(), context).is_ok());
+        }
+        assert!(validate_key_expression(x_only.into(), KeyContext::SegwitV0).is_err());
+        assert!(
+            validate_key_expression(format!("[deadbeef/86h]{x_only}"), KeyContext::Taproot).is_ok()
+        );
+        // the x-coordinate of the x-only key must be on the curve
+        assert!(validate_key_expression("00".repeat(32), KeyContext::Taproot).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_key_expression_account_descriptors() {
+        let account_key = "[deadbeef/84h/0h/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+        let receive = format!("wpkh({account_key}/0/*)");
+        let change = format!("wpkh({account_key}/1/*)");
+
+        get_cmd()
+            .args([
+                "key-expression",
+                "--account-descriptors",
+                "wpkh",
+                account_key,
+            ])
+            .assert()
+            .success()
+            .stdout(format!(
+                "{receive}#{}\n{change}#{}\n",
+                checksum_create(&receive).unwrap(),
+                checksum_create(&change).unwrap()
+            ));
+
+        let config = KeyExpressionConfig {
+            account_descriptors: Some(ScriptType::Pkh),
+            verify_origin: None,
+            network: None,
+            hardened_wildcard: HardenedWildcardPolicy::Allow,
+            key_context: KeyContext::Legacy,
+            canonical_key: None,
+            lenient: false,
+            sortedmulti_order: false,
+            index: None,
+            evaluate: false,
+            max_origin_depth: None,
+        };
+        assert!(key_expression(format!("{account_key}/*"), &config).is_err());
+        assert!(key_expression(
+            "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".to_string(),
+            &config
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "key-expression", feature = "script-expression"))]
+    fn test_account_descriptors_round_trip() {
+        let account_key = "[deadbeef/86h/0h/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+
+        for script_type in ["pkh", "sh-wpkh", "wpkh", "tr"] {
+            let descriptors = get_cmd()
+                .args([
+                    "key-expression",
+                    "--account-descriptors",
+                    script_type,
+                    account_key,
+                ])
+                .assert()
+                .success()
+                .get_output()
+                .stdout
+                .clone();
+            let descriptors = String::from_utf8(descriptors).unwrap();
+
+            // the descriptors are checked and their checksums computed again by script-expression
+            get_cmd()
+                .args(["script-expression", "--compute-checksum", "-"])
+                .write_stdin(descriptors.clone())
+                .assert()
+                .success()
+                .stdout(descriptors.clone());
+            get_cmd()
+                .args(["script-expression", "--verify-checksum", "-"])
+                .write_stdin(descriptors.clone())
+                .assert()
+                .success();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_key_expression_verify_origin() {
+        let key = "[3442193e/0h]xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/1";
+
+        get_cmd()
+            .args(["key-expression", "--verify-origin", "3442193e", key])
+            .assert()
+            .success()
+            .stdout(format!("{key}\n"));
+
+        get_cmd()
+            .args(["key-expression", "--verify-origin", "3442193f", key])
+            .assert()
+            .failure()
+            .stderr(format!(
+                "Parsing error [E005]: Key origin fingerprint of '{key}' does not match the master key fingerprint '3442193f'.\n"
+            ));
+    }
+
+    #[test]
+    fn test_key_expression_fields() {
+        let fields = key_expression_fields(
+            "[deadbeef/0h/1h/2]0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600",
+            &KeyExpressionConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fields[1..],
+            [
+                ("fingerprint", "deadbeef".to_string()),
+                ("origin_path", "0h/1h/2".to_string()),
+                (
+                    "key",
+                    "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_key_expression_lenient() {
+        let key = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600";
+        let padded = format!("  [ deadbeef/0h ] {key} \t");
+
+        get_cmd()
+            .args(["key-expression", &padded])
+            .assert()
+            .failure();
+        get_cmd()
+            .args(["key-expression", "--lenient", &padded])
+            .assert()
+            .success()
+            .stdout(format!("[deadbeef/0h]{key}\n"));
+
+        // the whitespace within a component is not padding
+        assert_eq!(
+            lenient_key_expression(&format!("[dead beef]{key}")),
+            format!("[dead beef]{key}")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_canonical_key_expression() {
+        let compressed = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let uncompressed = "0479BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8";
+
+        assert_eq!(
+            canonical_key_expression(
+                &format!("[DEADBEEF/0h]{}", compressed.to_uppercase()),
+                CanonicalKey::Lowercase
+            ),
+            Ok(format!("[DEADBEEF/0h]{compressed}"))
+        );
+        assert_eq!(
+            canonical_key_expression(uncompressed, CanonicalKey::Lowercase),
+            Ok(uncompressed.to_lowercase())
+        );
+        assert_eq!(
+            canonical_key_expression(uncompressed, CanonicalKey::Compressed),
+            Ok(compressed.to_string())
+        );
+        let wif = "L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1";
+        assert_eq!(
+            canonical_key_expression(wif, CanonicalKey::Compressed),
+            Ok(wif.to_string())
+        );
+
+        get_cmd()
+            .args(["key-expression", "--canonical-key", "compressed", uncompressed])
+            .assert()
+            .success()
+            .stdout(format!("{compressed}\n"))
+            .stderr(format!(
+                "Note: the key expression '{uncompressed}' is not canonical, printed as '{compressed}'\n"
+            ));
+        get_cmd()
+            .args(["key-expression", "--canonical-key", "lowercase", compressed])
+            .assert()
+            .success()
+            .stdout(format!("{compressed}\n"))
+            .stderr("");
+    }
+
+    #[cfg(feature = "derive-key")]
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_evaluate_key_expression() {
+        // the chain m/0h/1 of the BIP 32 test vector 1
+        let master = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+        let child = "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs";
+        assert_eq!(
+            evaluate_key_expression(&format!("[3442193e]{master}/0h/1")),
+            Ok(format!("[3442193e/0h/1]{child}"))
+        );
+        assert_eq!(
+            evaluate_key_expression(&format!("{master}/0H/1")),
+            Ok(child.to_string())
+        );
+        assert_eq!(evaluate_key_expression(master), Ok(master.to_string()));
+
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        assert_eq!(
+            evaluate_key_expression(&format!("{xpub}/0h")).map_err(|err| err.kind),
+            Err(ErrorKind::HardenedFromXpub)
+        );
+        assert!(evaluate_key_expression(&format!("{xpub}/0/*")).is_err());
+        assert!(evaluate_key_expression(
+            "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd"
+        )
+        .is_err());
+
+        get_cmd()
+            .args(["key-expression", "--evaluate", &format!("{xpub}/0")])
+            .assert()
+            .success()
+            .stdout("xpub68Gmy5EVb2BdFbj2LpWrk1M7obNuaPTpT5oh9QCCo5sRfqSHVYWex97WpDZzszdzHzxXDAzPLVSwybe4uPYkSk4G3gnrPqqkV9RyNzAcNJ1\n");
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_key_expression_sortedmulti_order() {
+        let xpub = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+        let ranged = format!("[deadbeef/0h]{xpub}/*");
+        let public_key = encode_hex(&derive_public_key(&ranged, Some(1)).unwrap());
+        assert_eq!(
+            sortedmulti_order_line(&ranged, Some(1)),
+            Ok(format!("{public_key} {ranged}"))
+        );
+        assert!(sortedmulti_order_line(&ranged, None).is_err());
+
+        let key_1 = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let key_2 = "02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13";
+        get_cmd()
+            .args(["key-expression", "--sortedmulti-order", "-"])
+            .write_stdin(format!("{key_1}\n[deadbeef/1]{key_2}\n"))
+            .assert()
+            .success()
+            .stdout(format!("{key_2} [deadbeef/1]{key_2}\n{key_1} {key_1}\n"));
+        get_cmd()
+            .args([
+                "key-expression",
+                "--sortedmulti-order",
+                "--template",
+                "{key}",
+                key_1,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    #[cfg(feature = "key-expression")]
+    fn test_key_expression_origin_depth() {
+        let xpub = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+        let deep = format!("[deadbeef{}]{xpub}/0/*", "/0h".repeat(254));
+
+        get_cmd()
+            .args(["key-expression", &deep])
+            .assert()
+            .failure()
+            .stderr(format!(
+                "Parsing error [E000]: Key '{deep}' is 256 derivation steps deep (254 of the key origin and 2 appended to the key), more than the limit of 255. The limit can be changed with '--max-origin-depth'.\n"
+            ));
+        get_cmd()
+            .args(["key-expression", "--max-origin-depth", "300", &deep])
+            .assert()
+            .success()
+            .stdout(format!("{deep}\n"));
+    }
 }
@@ -0,0 +1,414 @@
+use std::{fmt, str::FromStr};
+
+use bip32::ExtendedKey;
+
+use crate::{
+    structs::{
+        error_kind::ErrorKind, key_context::KeyContext, lint_config::LintConfig,
+        lint_warning::LintWarning, parsing_error::ParsingError,
+    },
+    traits::script_function::ScriptContext,
+};
+
+use super::{
+    key_expression::{split_key_expression, validate_key_expression},
+    utils::{
+        checksum::{checksum_check, split_checksum},
+        derived_public_key::derive_public_key,
+        extended_key::has_extended_key_prefix,
+        hex_encoded_public_key::{has_hex_encoded_public_key_prefix, is_x_only_public_key},
+        number::parse_threshold,
+        script_functions::{parse_script_function, validate_script},
+        wallet_import_format::validate_wif_private_key,
+    },
+};
+
+/// The number of the leading steps of a derivation path making up the account (purpose, coin type and account).
+const ACCOUNT_PATH_DEPTH: usize = 3;
+
+/// A warning found in a descriptor, with the explanation of the part at fault.
+#[derive(Clone)]
+struct Finding {
+    warning: LintWarning,
+    message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}",
+            self.warning.code(),
+            self.warning.name(),
+            self.message
+        )
+    }
+}
+
+/// Validates the descriptor and lists its warnings one per line, or prints `No warnings`.
+///
+/// The warnings point out the valid but questionable constructs (see [`LintWarning`]): uncompressed keys
+/// (every script of this utility is a legacy one), extended keys without their key origin, `multi` scripts
+/// requiring all of their keys, key origins with an unhardened account path and the deprecated `combo(...)`.
+/// If configured, the keys of the `multi` scripts are also checked for the order of BIP 67.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the descriptor is invalid or its checksum does not match, or (of the
+/// [`ErrorKind::DeniedWarning`] kind) if any of the warnings is denied by the configuration, listing them.
+pub fn lint(input: &str, config: &LintConfig) -> Result<String, ParsingError> {
+    let findings = lint_findings(input, config)?;
+    if findings.is_empty() {
+        return Ok("No warnings".to_string());
+    }
+
+    Ok(join_findings(&findings))
+}
+
+/// Lints the descriptor the same way as [`lint`], returning the comma separated codes of the `warnings` and
+/// their `count` for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`lint`].
+pub fn lint_fields(
+    input: &str,
+    config: &LintConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let findings = lint_findings(input, config)?;
+    let codes: Vec<&str> = findings
+        .iter()
+        .map(|finding| finding.warning.code())
+        .collect();
+
+    Ok(vec![
+        ("warnings", codes.join(",")),
+        ("count", findings.len().to_string()),
+    ])
+}
+
+fn lint_findings(input: &str, config: &LintConfig) -> Result<Vec<Finding>, ParsingError> {
+    let findings = collect_findings(input.trim(), config.check_bip67)?;
+
+    let denied: Vec<Finding> = findings
+        .iter()
+        .filter(|finding| config.deny.contains(&finding.warning))
+        .cloned()
+        .collect();
+    if !denied.is_empty() {
+        return Err(ParsingError::with_kind(
+            ErrorKind::DeniedWarning,
+            &format!(
+                "{} denied warning(s) of the descriptor:\n{}",
+                denied.len(),
+                join_findings(&denied)
+            ),
+        ));
+    }
+
+    Ok(findings)
+}
+
+fn join_findings(findings: &[Finding]) -> String {
+    findings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn collect_findings(input: &str, check_bip67: bool) -> Result<Vec<Finding>, ParsingError> {
+    let (script, checksum) = split_checksum(input)?;
+    if let Some(checksum) = checksum {
+        if !checksum_check(script, checksum)? {
+            return Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                "checksum verification failed!",
+            ));
+        }
+    }
+
+    let mut findings = Vec::new();
+
+    // combo() is not a supported script function, its key is linted on its own
+    if let Some(key_expression) = script
+        .trim()
+        .strip_prefix("combo(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        findings.push(Finding {
+            warning: LintWarning::DeprecatedCombo,
+            message: "combo() stands for several output types at once, spell out the one in use (e.g. pkh(...))"
+                .to_string(),
+        });
+        let key_expression =
+            validate_key_expression(key_expression.trim().to_string(), KeyContext::Legacy)?;
+        lint_key_expression(&key_expression, &mut findings)?;
+        return Ok(findings);
+    }
+
+    validate_script(script, ScriptContext::TopLevel)?;
+    lint_script(script, check_bip67, &mut findings)?;
+
+    Ok(findings)
+}
+
+/// Walks the (previously validated) script expression, collecting the warnings of its scripts and keys.
+fn lint_script(
+    script: &str,
+    check_bip67: bool,
+    findings: &mut Vec<Finding>,
+) -> Result<(), ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+
+    match function.name() {
+        "sh" | "wsh" => lint_script(&args[0], check_bip67, findings),
+        "pk" | "pkh" | "wpkh" | "tr" => lint_key_expression(&args[0], findings),
+        name @ ("multi" | "sortedmulti") => {
+            let (threshold, keys) = (&args[0], &args[1..]);
+            // a 1-of-1 multi locks the funds with its only key just the same
+            if !keys.is_empty() && parse_threshold(threshold.trim()) == Ok(keys.len()) {
+                let message = if keys.len() == 1 {
+                    format!(
+                        "{name}({threshold},...) requires its only key, losing it locks the funds"
+                    )
+                } else {
+                    format!(
+                        "{name}({threshold},...) requires all of its {} keys, losing any of them locks the funds",
+                        keys.len()
+                    )
+                };
+                findings.push(Finding {
+                    warning: LintWarning::MultiAllKeys,
+                    message,
+                });
+            }
+            // the keys of sortedmulti(...) are sorted when it is compiled
+            if check_bip67 && name == "multi" {
+                if let Some(message) = bip67_order_violation(threshold, keys)? {
+                    findings.push(Finding {
+                        warning: LintWarning::UnsortedMulti,
+                        message,
+                    });
+                }
+            }
+            keys.iter()
+                .try_for_each(|key_expression| lint_key_expression(key_expression, findings))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Explains why the (previously validated) keys of a `multi` script are not in the lexicographic order of their
+/// public keys required by BIP 67, none if they are.
+///
+/// The public keys of the ranged keys differ from one index to another, so the keys of a `multi` script with
+/// any of them cannot be relied on to stay in the order.
+fn bip67_order_violation(threshold: &str, keys: &[String]) -> Result<Option<String>, ParsingError> {
+    if keys
+        .iter()
+        .any(|key_expression| key_expression.contains('*'))
+    {
+        return Ok(Some(format!(
+            "multi({threshold},...) has ranged keys, so the order of their public keys (BIP 67) changes from one index to another, use sortedmulti(...)"
+        )));
+    }
+
+    let public_keys = keys
+        .iter()
+        .map(|key_expression| derive_public_key(key_expression, None))
+        .collect::<Result<Vec<Vec<u8>>, ParsingError>>()?;
+    if public_keys.is_sorted() {
+        return Ok(None);
+    }
+
+    let mut order: Vec<usize> = (1..=keys.len()).collect();
+    order.sort_by(|a, b| public_keys[a - 1].cmp(&public_keys[b - 1]));
+    let order: Vec<String> = order.iter().map(ToString::to_string).collect();
+    Ok(Some(format!(
+        "the keys of multi({threshold},...) are not in the order of their public keys (BIP 67), sort them as keys {}",
+        order.join(", ")
+    )))
+}
+
+/// Collects the warnings of a single (previously validated) key expression.
+fn lint_key_expression(
+    key_expression: &str,
+    findings: &mut Vec<Finding>,
+) -> Result<(), ParsingError> {
+    let (key_origin, key) = split_key_expression(key_expression.trim())?;
+
+    // an x-only key may start with the prefix of an uncompressed one
+    let uncompressed = if has_extended_key_prefix(key) || is_x_only_public_key(key) {
+        false
+    } else if has_hex_encoded_public_key_prefix(key) {
+        key.starts_with("04")
+    } else {
+        !validate_wif_private_key(key)?
+    };
+    if uncompressed {
+        findings.push(Finding {
+            warning: LintWarning::UncompressedKey,
+            message: format!(
+                "the key '{key}' is uncompressed, which makes the scripts larger and cannot be used in segwit outputs"
+            ),
+        });
+    }
+
+    if has_extended_key_prefix(key) && key_origin.is_none() {
+        let encoded = key.split('/').next().unwrap_or(key);
+        if ExtendedKey::from_str(encoded)?.attrs.depth > 0 {
+            findings.push(Finding {
+                warning: LintWarning::MissingOrigin,
+                message: format!(
+                    "the extended key '{encoded}' has no key origin, so signers cannot tell where it was derived from"
+                ),
+            });
+        }
+    }
+
+    if let Some(key_origin) = key_origin {
+        let steps: Vec<&str> = key_origin
+            .trim_end_matches(']')
+            .split('/')
+            .skip(1)
+            .collect();
+        if steps.len() >= ACCOUNT_PATH_DEPTH
+            && steps[..ACCOUNT_PATH_DEPTH]
+                .iter()
+                .any(|step| !step.ends_with(['h', 'H', '\'']))
+        {
+            findings.push(Finding {
+                warning: LintWarning::UnhardenedAccount,
+                message: format!(
+                    "the account path of the key origin '{key_origin}' is not hardened, a leaked child private key and the xpub reveal the whole account"
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::get_cmd;
+
+    const KEY: &str = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+    const UNCOMPRESSED_KEY: &str = "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235";
+    const XPUB: &str = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+
+    #[test]
+    fn test_lint_warnings() {
+        for (descriptor, expected) in [
+            (format!("pkh({KEY})"), "No warnings".to_string()),
+            (
+                format!("pk([deadbeef/44h/0h/0h]{XPUB}/0/*)"),
+                "No warnings".to_string(),
+            ),
+            (
+                format!("pkh({UNCOMPRESSED_KEY})"),
+                format!("W001 (uncompressed-key): the key '{UNCOMPRESSED_KEY}' is uncompressed, which makes the scripts larger and cannot be used in segwit outputs"),
+            ),
+            (
+                format!("pkh({XPUB}/0)"),
+                format!("W002 (missing-origin): the extended key '{XPUB}' has no key origin, so signers cannot tell where it was derived from"),
+            ),
+            (
+                format!("sh(multi(2,{KEY},[deadbeef/44h/0h/0]{XPUB}))"),
+                "W003 (multi-all-keys): multi(2,...) requires all of its 2 keys, losing any of them locks the funds\n\
+                W004 (unhardened-account): the account path of the key origin '[deadbeef/44h/0h/0]' is not hardened, a leaked child private key and the xpub reveal the whole account".to_string(),
+            ),
+            (
+                format!("multi(1,{KEY})"),
+                "W003 (multi-all-keys): multi(1,...) requires its only key, losing it locks the funds".to_string(),
+            ),
+            (
+                format!("multi(1,{KEY},{KEY})"),
+                "No warnings".to_string(),
+            ),
+            (
+                format!("wsh(sortedmulti(2,{KEY},[deadbeef/48h/0h/0h/2h]{XPUB}))"),
+                "W003 (multi-all-keys): sortedmulti(2,...) requires all of its 2 keys, losing any of them locks the funds".to_string(),
+            ),
+            (
+                format!("tr({})", &KEY[2..]),
+                "No warnings".to_string(),
+            ),
+            (
+                format!("combo({KEY})"),
+                "W005 (deprecated-combo): combo() stands for several output types at once, spell out the one in use (e.g. pkh(...))".to_string(),
+            ),
+        ] {
+            get_cmd()
+                .args(["lint", &descriptor])
+                .assert()
+                .success()
+                .stdout(format!("{expected}\n"));
+        }
+    }
+
+    #[test]
+    fn test_denied_warnings() {
+        let descriptor = format!("multi(1,{KEY},{UNCOMPRESSED_KEY})");
+        get_cmd()
+            .args(["lint", &descriptor, "--deny", "W003"])
+            .assert()
+            .success();
+
+        let output = get_cmd()
+            .args(["lint", &descriptor, "--deny", "all", "--output", "json"])
+            .output()
+            .expect("the command runs");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("\"kind\":\"DeniedWarning\""));
+    }
+
+    #[test]
+    fn test_bip67_order() {
+        let lower_key = "02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13";
+        let sorted = format!("sh(multi(1,{lower_key},{KEY}))");
+        let unsorted = format!("sh(multi(1,{KEY},{lower_key}))");
+        let warning = "W006 (unsorted-multi): the keys of multi(1,...) are not in the order of their public keys (BIP 67), sort them as keys 2, 1";
+
+        get_cmd()
+            .args(["lint", &unsorted])
+            .assert()
+            .success()
+            .stdout("No warnings\n");
+        get_cmd()
+            .args(["lint", &sorted, "--check-bip67"])
+            .assert()
+            .success()
+            .stdout("No warnings\n");
+        get_cmd()
+            .args(["lint", &unsorted, "--check-bip67"])
+            .assert()
+            .success()
+            .stdout(format!("{warning}\n"));
+        get_cmd()
+            .args([
+                "lint",
+                &unsorted,
+                "--check-bip67",
+                "--deny",
+                "unsorted-multi",
+            ])
+            .assert()
+            .failure();
+
+        let ranged = format!("multi(1,{KEY},[deadbeef/44h/0h/0h]{XPUB}/0/*)");
+        get_cmd()
+            .args(["lint", &ranged, "--check-bip67"])
+            .assert()
+            .success()
+            .stdout("W006 (unsorted-multi): multi(1,...) has ranged keys, so the order of their public keys (BIP 67) changes from one index to another, use sortedmulti(...)\n");
+    }
+
+    #[test]
+    fn test_invalid_descriptors() {
+        for descriptor in ["pkh(00)", "combo(00)", "pkh(02)#00000000", "wpkh(00)"] {
+            get_cmd().args(["lint", descriptor]).assert().failure();
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use std::fmt::Write;
+
+use bip39::Mnemonic;
+
+use crate::structs::{
+    error_kind::ErrorKind,
+    mnemonic_config::{MnemonicConfig, MnemonicMode},
+    parsing_error::ParsingError,
+    seedqr_format::SeedQrFormat,
+};
+
+use super::utils::{
+    hexadecimal::{decode_hex, encode_hex},
+    seed::{decode_entropy, parse_mnemonic},
+};
+
+/// The number of words of the mnemonics `SeedQR` is defined for.
+const SEEDQR_WORD_COUNTS: [usize; 2] = [12, 24];
+
+/// Encodes a BIP 39 mnemonic as a `SeedQR`, or decodes a `SeedQR` back into the mnemonic. With `--from-entropy`
+/// or `--to-entropy`, converts between the hex entropy and the mnemonic instead, so that the backups kept in
+/// either format can be cross-checked.
+///
+/// The standard `SeedQR` is the 4-digit (zero-padded) wordlist index of every word, concatenated into
+/// 48 or 96 digits. The compact `SeedQR` is the 16 or 32 bytes of the entropy, which is printed (and read)
+/// in hexadecimal, as its QR code holds the raw bytes. The decoding tells the formats apart by their length.
+///
+/// The word indexes refer to the wordlist of the language of the mnemonic, which is detected from its words
+/// unless given by `--language`. The decoded mnemonic is printed in the given language (English by default).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The mnemonic has an invalid word count, contains unknown words or its checksum does not match,
+/// - The mnemonic does not have 12 or 24 words,
+/// - The `SeedQR` is neither 48 or 96 digits nor 32 or 64 hex digits, contains a word index out of
+///   the range [0,...,2047] or its mnemonic checksum does not match,
+/// - The entropy is not hexadecimal or not 128, 160, 192, 224 or 256 bits long.
+pub fn mnemonic(input: &str, config: &MnemonicConfig) -> Result<String, ParsingError> {
+    match config.mode {
+        MnemonicMode::ToSeedQr(format) => {
+            Ok(encode_seedqr(&seedqr_mnemonic(input, config)?, format))
+        }
+        MnemonicMode::FromSeedQr => Ok(decode_seedqr(input, config)?.to_string()),
+        MnemonicMode::FromEntropy => Ok(entropy_mnemonic(input, config)?.to_string()),
+        MnemonicMode::ToEntropy => Ok(encode_hex(
+            &parse_mnemonic(input.trim(), config.language)?.to_entropy(),
+        )),
+    }
+}
+
+/// Converts the mnemonic the same way as [`mnemonic`], returning the `mnemonic`, its `entropy`, its standard
+/// `seedqr` and its `compact_seedqr` (both empty unless the mnemonic has 12 or 24 words) for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`mnemonic`].
+pub fn mnemonic_fields(
+    input: &str,
+    config: &MnemonicConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let mnemonic = match config.mode {
+        MnemonicMode::ToSeedQr(_) => seedqr_mnemonic(input, config)?,
+        MnemonicMode::FromSeedQr => decode_seedqr(input, config)?,
+        MnemonicMode::FromEntropy => entropy_mnemonic(input, config)?,
+        MnemonicMode::ToEntropy => parse_mnemonic(input.trim(), config.language)?,
+    };
+    let seedqr = |format| {
+        check_word_count(&mnemonic)
+            .map(|()| encode_seedqr(&mnemonic, format))
+            .unwrap_or_default()
+    };
+
+    Ok(vec![
+        ("mnemonic", mnemonic.to_string()),
+        ("entropy", encode_hex(&mnemonic.to_entropy())),
+        ("seedqr", seedqr(SeedQrFormat::Standard)),
+        ("compact_seedqr", seedqr(SeedQrFormat::Compact)),
+    ])
+}
+
+fn entropy_mnemonic(input: &str, config: &MnemonicConfig) -> Result<Mnemonic, ParsingError> {
+    let entropy = decode_entropy(input.trim())?;
+    Mnemonic::from_entropy_in(config.language.unwrap_or_default().wordlist(), &entropy).map_err(
+        |err| ParsingError::with_kind(ErrorKind::InvalidSeed, &format!("Invalid entropy: {err}")),
+    )
+}
+
+fn seedqr_mnemonic(input: &str, config: &MnemonicConfig) -> Result<Mnemonic, ParsingError> {
+    let mnemonic = parse_mnemonic(input.trim(), config.language)?;
+    check_word_count(&mnemonic)?;
+    Ok(mnemonic)
+}
+
+fn check_word_count(mnemonic: &Mnemonic) -> Result<(), ParsingError> {
+    if SEEDQR_WORD_COUNTS.contains(&mnemonic.word_count()) {
+        Ok(())
+    } else {
+        Err(ParsingError::with_kind(
+            ErrorKind::InvalidSeed,
+            &format!(
+                "SeedQR supports only 12 or 24 word mnemonics, the mnemonic has {} words",
+                mnemonic.word_count()
+            ),
+        ))
+    }
+}
+
+fn encode_seedqr(mnemonic: &Mnemonic, format: SeedQrFormat) -> String {
+    match format {
+        SeedQrFormat::Standard => {
+            mnemonic
+                .word_indices()
+                .fold(String::new(), |mut digits, index| {
+                    let _ = write!(digits, "{index:04}");
+                    digits
+                })
+        }
+        SeedQrFormat::Compact => encode_hex(&mnemonic.to_entropy()),
+    }
+}
+
+fn decode_seedqr(input: &str, config: &MnemonicConfig) -> Result<Mnemonic, ParsingError> {
+    let input = input.trim();
+    let language = config.language.unwrap_or_default().wordlist();
+    let invalid_seedqr_err = || {
+        ParsingError::with_kind(ErrorKind::InvalidSeed, &format!(
+            "Invalid SeedQR '{input}', expected 48 or 96 digits (standard) or 32 or 64 hex digits (compact)"
+        ))
+    };
+
+    let mnemonic = match input.len() {
+        48 | 96 if input.chars().all(|c| c.is_ascii_digit()) => {
+            let wordlist = language.word_list();
+            let words = input
+                .as_bytes()
+                .chunks(4)
+                .map(|digits| {
+                    let index: usize = std::str::from_utf8(digits)
+                        .ok()
+                        .and_then(|digits| digits.parse().ok())
+                        .ok_or_else(invalid_seedqr_err)?;
+                    wordlist.get(index).copied().ok_or_else(|| {
+                        ParsingError::with_kind(
+                            ErrorKind::InvalidSeed,
+                            &format!(
+                            "Word index '{index}' of the SeedQR is out of the range [0,...,2047]"
+                        ),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<&str>, ParsingError>>()?;
+            Mnemonic::parse_in(language, words.join(" "))
+        }
+        32 | 64 => Mnemonic::from_entropy_in(
+            language,
+            &decode_hex(input).map_err(|_| invalid_seedqr_err())?,
+        ),
+        _ => return Err(invalid_seedqr_err()),
+    }
+    .map_err(|err| {
+        ParsingError::with_kind(
+            ErrorKind::InvalidSeed,
+            &format!("Invalid SeedQR mnemonic: {err}"),
+        )
+    })?;
+
+    check_word_count(&mnemonic)?;
+    Ok(mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{structs::mnemonic_language::MnemonicLanguage, test_utils::get_cmd};
+
+    const ABANDON_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const LEGAL_MNEMONIC: &str =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+    fn config(mode: MnemonicMode) -> MnemonicConfig {
+        MnemonicConfig {
+            mode,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_to_standard_seedqr() {
+        let config = config(MnemonicMode::ToSeedQr(SeedQrFormat::Standard));
+
+        assert_eq!(
+            mnemonic(ABANDON_MNEMONIC, &config),
+            Ok(format!("{}0003", "0000".repeat(11)))
+        );
+        assert_eq!(
+            mnemonic(LEGAL_MNEMONIC, &config),
+            Ok("101920151790203919831533203119191019201517902040".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_compact_seedqr() {
+        let config = config(MnemonicMode::ToSeedQr(SeedQrFormat::Compact));
+
+        assert_eq!(mnemonic(LEGAL_MNEMONIC, &config), Ok("7f".repeat(16)));
+        assert_eq!(
+            mnemonic(&format!("{} art", "abandon ".repeat(23)), &config),
+            Ok("00".repeat(32))
+        );
+    }
+
+    #[test]
+    fn test_from_seedqr() {
+        let config = config(MnemonicMode::FromSeedQr);
+
+        assert_eq!(
+            mnemonic("101920151790203919831533203119191019201517902040", &config),
+            Ok(LEGAL_MNEMONIC.to_string())
+        );
+        assert_eq!(
+            mnemonic(&"7F".repeat(16), &config),
+            Ok(LEGAL_MNEMONIC.to_string())
+        );
+        assert_eq!(
+            mnemonic(&format!("{}0003\n", "0000".repeat(11)), &config),
+            Ok(ABANDON_MNEMONIC.to_string())
+        );
+    }
+
+    #[test]
+    fn test_seedqr_languages() {
+        let spanish = format!("{}abierto", "ábaco ".repeat(11));
+        let standard_seedqr = format!("{}0003", "0000".repeat(11));
+
+        assert_eq!(
+            mnemonic(
+                &spanish,
+                &config(MnemonicMode::ToSeedQr(SeedQrFormat::Standard))
+            ),
+            Ok(standard_seedqr.clone())
+        );
+
+        let mut from_seedqr = config(MnemonicMode::FromSeedQr);
+        from_seedqr.language = Some(MnemonicLanguage::Spanish);
+        assert_eq!(
+            mnemonic(&standard_seedqr, &from_seedqr)
+                .map(|phrase| parse_mnemonic(&phrase, None).unwrap().to_entropy()),
+            Ok(vec![0; 16])
+        );
+        assert_ne!(
+            mnemonic(&standard_seedqr, &from_seedqr),
+            Ok(ABANDON_MNEMONIC.to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_seedqr() {
+        let config = config(MnemonicMode::FromSeedQr);
+
+        for input in [
+            "",
+            "0000",
+            // an index out of the wordlist
+            &format!("{}2048", "0000".repeat(11)),
+            // a checksum mismatch
+            &"0000".repeat(12),
+            // 20 bytes of entropy (a 15 word mnemonic) are not a compact SeedQR
+            &"00".repeat(20),
+            &"zz".repeat(16),
+        ] {
+            assert!(mnemonic(input, &config).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_unsupported_mnemonic() {
+        let config = config(MnemonicMode::ToSeedQr(SeedQrFormat::Standard));
+
+        assert!(mnemonic("abandon abandon", &config).is_err());
+        // a valid 15 word mnemonic
+        assert!(mnemonic(&format!("{} address", "abandon ".repeat(14)), &config).is_err());
+    }
+
+    #[test]
+    fn test_entropy_conversion() {
+        // BIP 39 test vectors
+        let from_entropy = config(MnemonicMode::FromEntropy);
+        assert_eq!(
+            mnemonic(&"7f".repeat(16), &from_entropy),
+            Ok(LEGAL_MNEMONIC.to_string())
+        );
+        assert_eq!(
+            mnemonic(&"80".repeat(24), &from_entropy),
+            Ok("letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter always".to_string())
+        );
+
+        let to_entropy = config(MnemonicMode::ToEntropy);
+        assert_eq!(mnemonic(LEGAL_MNEMONIC, &to_entropy), Ok("7f".repeat(16)));
+        assert_eq!(
+            mnemonic(&format!("{} address", "abandon ".repeat(14)), &to_entropy),
+            Ok("00".repeat(20))
+        );
+
+        for input in [
+            "",
+            &"00".repeat(15),
+            &"00".repeat(18),
+            &"00".repeat(33),
+            &"zz".repeat(16),
+            "0",
+            "000",
+            "m",
+            &format!("{}č", "7f".repeat(15)),
+        ] {
+            assert!(mnemonic(input, &from_entropy).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_entropy_cli() {
+        for input in ["0", "000", "m", "7fč"] {
+            get_cmd()
+                .args(["mnemonic", "--from-entropy", input])
+                .assert()
+                .failure()
+                .stderr(format!(
+                    "Parsing error [E016]: Invalid entropy '{input}', expected 32, 40, 48, 56 or 64 hex digits (128 to 256 bits)\n"
+                ));
+        }
+    }
+
+    #[test]
+    fn test_mnemonic_fields() {
+        assert_eq!(
+            mnemonic_fields(&"7f".repeat(16), &config(MnemonicMode::FromSeedQr)),
+            Ok(vec![
+                ("mnemonic", LEGAL_MNEMONIC.to_string()),
+                ("entropy", "7f".repeat(16)),
+                (
+                    "seedqr",
+                    "101920151790203919831533203119191019201517902040".to_string()
+                ),
+                ("compact_seedqr", "7f".repeat(16)),
+            ])
+        );
+        assert_eq!(
+            mnemonic_fields(&"00".repeat(20), &config(MnemonicMode::FromEntropy))
+                .map(|fields| fields[3].clone()),
+            Ok(("compact_seedqr", String::new()))
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_stdin() {
+        get_cmd()
+            .args(["mnemonic", "--to-seedqr", "compact", "-"])
+            .write_stdin(format!("{LEGAL_MNEMONIC}\n"))
+            .assert()
+            .success()
+            .stdout(format!("{}\n", "7f".repeat(16)));
+    }
+}
@@ -1,4 +1,46 @@
+#[cfg(feature = "address")]
+pub mod address;
+#[cfg(feature = "analyze")]
+pub mod analyze;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "coldcard-export")]
+pub mod coldcard_export;
+#[cfg(feature = "compose")]
+pub mod compose;
+#[cfg(feature = "convert-key")]
+pub mod convert_key;
+pub mod core_import;
+#[cfg(feature = "demo")]
+pub mod demo;
+#[cfg(feature = "derive-key")]
 pub mod derive_key;
+#[cfg(feature = "generate-seed")]
+pub mod generate_seed;
+#[cfg(feature = "http")]
+pub mod http_api;
+#[cfg(feature = "hwi-import")]
+pub mod hwi_import;
 pub mod key_expression;
+#[cfg(feature = "lint")]
+pub mod lint;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+pub mod path;
+#[cfg(feature = "process")]
+pub mod process;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "script-expression")]
 pub mod script_expression;
-mod utils;
+#[cfg(feature = "seed-xor")]
+pub mod seed_xor;
+#[cfg(any(feature = "serve", feature = "rpc"))]
+pub mod serve;
+#[cfg(feature = "slip39")]
+pub mod slip39;
+pub(crate) mod utils;
+#[cfg(feature = "wallet-export")]
+pub mod wallet_export;
+#[cfg(feature = "wallet-init")]
+pub mod wallet_init;
@@ -0,0 +1,175 @@
+use bip32::DerivationPath;
+
+use crate::structs::{
+    derive_key_config::parse_derivation_path,
+    hardened_notation::HardenedNotation,
+    parsing_error::ParsingError,
+    path_config::{PathConfig, PathOperation},
+};
+
+/// Normalizes, joins or relativizes the derivation path.
+///
+/// The input path is accepted in the same notation as the `--path` of derive-key, with or without the
+/// leading `m/` and with any of `h`, `H` or `'` marking the hardened indexes. The output is printed with
+/// the configured hardened notation:
+/// - normalized as the absolute path, e.g. `m/84h/0h/0h`,
+/// - joined with the relative path of `--join`, as the absolute path,
+/// - relative to the base path of `--relative-to` it must descend from, without the leading `m/`
+///   (empty for the base path itself).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The path is malformed or an index is out of the range [0,...,2^31-1],
+/// - The path does not descend from the base path of `--relative-to`.
+pub fn derivation_path(input: &str, config: &PathConfig) -> Result<String, ParsingError> {
+    let path = parse_derivation_path(input.trim())?;
+
+    Ok(match &config.operation {
+        PathOperation::Normalize => format_path(&path, config.notation, true),
+        PathOperation::Join(suffix) => {
+            let mut joined = path;
+            joined.extend(suffix.iter());
+            format_path(&joined, config.notation, true)
+        }
+        PathOperation::RelativeTo(base) => {
+            if base.len() > path.len() || base.iter().zip(path.iter()).any(|(a, b)| a != b) {
+                return Err(ParsingError::new(&format!(
+                    "Path '{}' does not descend from the base path '{}'",
+                    format_path(&path, config.notation, true),
+                    format_path(base, config.notation, true)
+                )));
+            }
+            let mut relative = DerivationPath::default();
+            relative.extend(path.iter().skip(base.len()));
+            format_path(&relative, config.notation, false)
+        }
+    })
+}
+
+/// Converts the path the same way as [`derivation_path`], returning the `path` and its `depth` (the number
+/// of the indexes of the resulting path) for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`derivation_path`].
+pub fn derivation_path_fields(
+    input: &str,
+    config: &PathConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let path = derivation_path(input, config)?;
+    let depth = path
+        .split('/')
+        .filter(|index| !matches!(*index, "m" | ""))
+        .count();
+
+    Ok(vec![("path", path), ("depth", depth.to_string())])
+}
+
+/// Formats the path with the hardened notation, with the leading `m/` if it is `absolute`.
+#[must_use]
+pub fn format_path(path: &DerivationPath, notation: HardenedNotation, absolute: bool) -> String {
+    let indexes = path.iter().map(|child_number| {
+        if child_number.is_hardened() {
+            format!("{}{}", child_number.index(), notation.marker())
+        } else {
+            child_number.index().to_string()
+        }
+    });
+
+    if absolute {
+        std::iter::once("m".to_string())
+            .chain(indexes)
+            .collect::<Vec<_>>()
+            .join("/")
+    } else {
+        indexes.collect::<Vec<_>>().join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_cmd;
+
+    fn config(operation: PathOperation, notation: HardenedNotation) -> PathConfig {
+        PathConfig {
+            operation,
+            notation,
+        }
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        let normalize = config(PathOperation::Normalize, HardenedNotation::Lowercase);
+        assert_eq!(
+            derivation_path("84'/0H/0h/1", &normalize),
+            Ok("m/84h/0h/0h/1".to_string())
+        );
+        assert_eq!(derivation_path("m", &normalize), Ok("m".to_string()));
+        assert_eq!(
+            derivation_path(
+                "/84h/0h",
+                &config(PathOperation::Normalize, HardenedNotation::Apostrophe)
+            ),
+            Ok("m/84'/0'".to_string())
+        );
+
+        for invalid in ["", "m/", "84h//0", "2147483648", "0x", "m/-1"] {
+            assert!(derivation_path(invalid, &normalize).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_join_paths() {
+        assert_eq!(
+            derivation_path(
+                "m/84h/0h",
+                &config(
+                    PathOperation::Join("m/0/5".parse().unwrap()),
+                    HardenedNotation::Uppercase
+                )
+            ),
+            Ok("m/84H/0H/0/5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let relative_to = |base: &str| {
+            config(
+                PathOperation::RelativeTo(parse_derivation_path(base).unwrap()),
+                HardenedNotation::Lowercase,
+            )
+        };
+
+        assert_eq!(
+            derivation_path("m/84h/0h/0h/0/5", &relative_to("m/84h/0h/0h")),
+            Ok("0/5".to_string())
+        );
+        assert_eq!(
+            derivation_path("m/84h/0h", &relative_to("84'/0'")),
+            Ok(String::new())
+        );
+        assert!(derivation_path("m/84h/0h", &relative_to("m/84h/1h")).is_err());
+        assert!(derivation_path("m/84h", &relative_to("m/84h/0h")).is_err());
+        assert!(derivation_path("m/84h/0h", &relative_to("m/84/0h")).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "path")]
+    fn test_path_template() {
+        get_cmd()
+            .args([
+                "path",
+                "m/84'/0'",
+                "--join",
+                "0/5",
+                "--template",
+                "{depth} {path}",
+            ])
+            .assert()
+            .success()
+            .stdout("4 m/84h/0h/0/5\n");
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    structs::{input_kind::InputKind, parsing_error::ParsingError, process_config::ProcessConfig},
+    traits::output_fields::OutputFields,
+};
+
+use super::{
+    derive_key::{derive_extended_keys, derive_key},
+    script_expression::{script_expression, script_expression_fields},
+};
+
+/// Routes the input to the sub-command of its kind, see [`InputKind::detect`]: a seed or an extended key
+/// is derived by derive-key, a script expression is validated by script-expression, each with its flags
+/// from the configuration.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the kind of the input is not recognized or the sub-command fails on it.
+pub fn process(input: &str, config: &ProcessConfig) -> Result<String, ParsingError> {
+    match InputKind::detect(input.trim())? {
+        InputKind::Seed | InputKind::ExtendedKey => derive_key(input, &config.derive_key),
+        InputKind::ScriptExpression => script_expression(input, &config.script_expression),
+    }
+}
+
+/// Processes the input the same way as [`process`], returning the `kind` of the input followed by the fields
+/// of the sub-command it is routed to.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`process`].
+pub fn process_fields(
+    input: &str,
+    config: &ProcessConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let kind = InputKind::detect(input.trim())?;
+    let fields = match kind {
+        InputKind::Seed | InputKind::ExtendedKey => {
+            derive_extended_keys(input, &config.derive_key)?.fields()
+        }
+        InputKind::ScriptExpression => script_expression_fields(input, &config.script_expression)?,
+    };
+
+    Ok([vec![("kind", kind.to_string())], fields].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::get_cmd;
+
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+
+    #[test]
+    fn test_mixed_inputs() {
+        get_cmd()
+            .args(["process", "--compute-checksum", "-"])
+            .write_stdin(format!("{SEED}\nraw(deadbeef)\n{XPUB}\n"))
+            .assert()
+            .success()
+            .stdout(format!("{XPUB}:{XPRV}\nraw(deadbeef)#89f8spxm\n{XPUB}:\n"));
+    }
+
+    #[test]
+    fn test_flags_routed_to_sub_commands() {
+        let derived = get_cmd()
+            .args(["derive-key", SEED, "--path", "0"])
+            .output()
+            .unwrap();
+        get_cmd()
+            .args([
+                "process",
+                SEED,
+                "--path",
+                "0",
+                "raw(deadbeef)#89f8spxm",
+                "--verify-checksum",
+            ])
+            .assert()
+            .success()
+            .stdout(format!(
+                "{}Veritification of the 'raw(deadbeef)#89f8spxm' script succeeded!\n",
+                String::from_utf8(derived.stdout).unwrap()
+            ));
+    }
+
+    #[test]
+    fn test_unrecognized_input() {
+        get_cmd()
+            .args(["process", SEED, "not a key"])
+            .assert()
+            .failure()
+            .stdout(format!("{XPUB}:{XPRV}\n"));
+    }
+
+    #[test]
+    fn test_json_output_kind() {
+        let output = get_cmd()
+            .args(["process", "--output", "json", "raw(deadbeef)", XPUB])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert!(lines[0].starts_with("{\"kind\":\"script-expression\""));
+        assert!(lines[1].starts_with("{\"kind\":\"extended-key\""));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+use super::{
+    serve::run_args,
+    utils::json::{json_string, parse_json, JsonValue},
+};
+
+/// Answers a single JSON-RPC style request line, always returning a single line JSON response.
+///
+/// The request is a JSON object `{"id": ..., "cmd": "script-expression", "input": "...", "flags": {...}}`.
+/// The `id` (any JSON value, `null` if missing) is echoed back in the response, so the responses can be
+/// correlated with the requests. The `flags` are optional, see [`flag_args`] for how they are translated.
+///
+/// The response is `{"id": ..., "output": "..."}` on success and `{"id": ..., "error": "...", "code": "..."}` on
+/// failure, with the stable code of the error kind.
+#[must_use]
+pub fn handle_rpc_request(request: &str) -> String {
+    let request = parse_json(request);
+    let id = request
+        .as_ref()
+        .ok()
+        .and_then(|request| request.get("id"))
+        .map_or_else(|| "null".to_string(), ToString::to_string);
+
+    match request.and_then(|request| run_rpc_request(&request)) {
+        Ok(output) => format!("{{\"id\":{id},\"output\":{}}}", json_string(&output)),
+        Err(err) => format!(
+            "{{\"id\":{id},\"error\":{},\"code\":{}}}",
+            json_string(&err.message),
+            json_string(err.kind.code())
+        ),
+    }
+}
+
+fn run_rpc_request(request: &JsonValue) -> Result<String, ParsingError> {
+    let (Some(JsonValue::String(cmd)), Some(JsonValue::String(input))) =
+        (request.get("cmd"), request.get("input"))
+    else {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            "The request must be a JSON object with the 'cmd' and 'input' strings",
+        ));
+    };
+
+    let mut args = vec![cmd.clone()];
+    if let Some(flags) = request.get("flags") {
+        args.extend(flag_args(flags)?);
+    }
+
+    Ok(run_args(&args, Some(input))?.join("\n"))
+}
+
+/// Translates the `flags` object into command-line flags.
+///
+/// A `true` member is a boolean flag (`false` and `null` members are left out), a string or a number
+/// member is a value flag and an array member is a flag repeated with each of its values,
+/// e.g. `{"compute-checksum": true, "network": "testnet", "key": ["a=02aa", "b=03bb"]}`.
+fn flag_args(flags: &JsonValue) -> Result<Vec<String>, ParsingError> {
+    let JsonValue::Object(members) = flags else {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            "The 'flags' must be a JSON object",
+        ));
+    };
+
+    let mut args = Vec::new();
+    for (name, value) in members {
+        let flag = format!("--{name}");
+        let values = match value {
+            JsonValue::Bool(false) | JsonValue::Null => continue,
+            JsonValue::Bool(true) => {
+                args.push(flag);
+                continue;
+            }
+            JsonValue::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                JsonValue::String(value) | JsonValue::Number(value) => value.clone(),
+                _ => {
+                    return Err(ParsingError::with_kind(
+                        ErrorKind::InvalidJson,
+                        &format!(
+                            "Invalid value of the flag '{name}', expected a string or a number"
+                        ),
+                    ))
+                }
+            };
+            args.extend([flag.clone(), value]);
+        }
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_cmd;
+
+    #[test]
+    fn test_flag_args() {
+        assert_eq!(
+            flag_args(
+                &parse_json(
+                    r#"{"compute-checksum": true, "verify-checksum": false, "network": "testnet", "account": 1, "key": ["a=02aa", "b=03bb"]}"#
+                )
+                .unwrap()
+            ),
+            Ok(vec![
+                "--compute-checksum".to_string(),
+                "--network".to_string(),
+                "testnet".to_string(),
+                "--account".to_string(),
+                "1".to_string(),
+                "--key".to_string(),
+                "a=02aa".to_string(),
+                "--key".to_string(),
+                "b=03bb".to_string(),
+            ])
+        );
+        assert!(flag_args(&parse_json(r#"{"path": {}}"#).unwrap()).is_err());
+        assert!(flag_args(&parse_json("[]").unwrap()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_handle_rpc_request() {
+        assert_eq!(
+            handle_rpc_request(
+                r#"{"id": 7, "cmd": "script-expression", "input": "raw(deadbeef)", "flags": {"compute-checksum": true}}"#
+            ),
+            r#"{"id":7,"output":"raw(deadbeef)#89f8spxm"}"#
+        );
+        assert!(
+            handle_rpc_request(r#"{"id": "a", "cmd": "key-expression", "input": "invalid"}"#)
+                .starts_with(r#"{"id":"a","error":"#)
+        );
+        assert!(handle_rpc_request("not json").starts_with(r#"{"id":null,"error":"#));
+        assert!(handle_rpc_request(&"[".repeat(200_000)).starts_with(r#"{"id":null,"error":"#));
+        assert!(handle_rpc_request(
+            r#"{"id": 2, "cmd": "script-expression", "input": "raw(deadbeef)#00000000", "flags": {"verify-checksum": true}}"#
+        )
+        .ends_with(r#""code":"E001"}"#));
+        assert!(handle_rpc_request(r#"{"id": 1, "cmd": "key-expression"}"#)
+            .starts_with(r#"{"id":1,"error":"#));
+        // the input is not taken for a flag
+        assert_eq!(
+            handle_rpc_request(r#"{"id": 3, "cmd": "script-expression", "input": "--help"}"#),
+            r#"{"id":3,"error":"parsing of the script failed!","code":"E000"}"#
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "derive-key", feature = "key-expression"))]
+    fn test_rpc_stdin() {
+        let output = get_cmd()
+            .arg("--rpc")
+            .write_stdin(concat!(
+                r#"{"id": 1, "cmd": "key-expression", "input": "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"}"#,
+                "\n",
+                r#"{"id": 2, "cmd": "derive-key", "input": "-"}"#,
+                "\n"
+            ))
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let lines: Vec<String> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            lines[0],
+            r#"{"id":1,"output":"0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"}"#
+        );
+        assert!(lines[1].starts_with(r#"{"id":2,"error":"#));
+    }
+}
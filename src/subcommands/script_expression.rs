@@ -1,22 +1,45 @@
+use std::collections::BTreeMap;
+
+use bip32::KeyFingerprint;
+
 use crate::{
-    structs::{parsing_error::ParsingError, script_expression_config::ScriptExpressionConfig},
-    traits::string_utils::{CharArrayUtils, StringSliceUtils, Trimifiable},
-    utils::error_messages::script_sh_unsupported_arg_err,
+    structs::{
+        error_kind::ErrorKind,
+        master_key::MasterKey,
+        network::Network,
+        parsing_error::ParsingError,
+        script_expression_config::{ScriptExpressionConfig, ScriptOutput},
+    },
+    subcommands::key_expression::split_key_expression,
+    traits::script_function::ScriptContext,
 };
 
-use super::{
-    key_expression::validate_key_expression,
-    utils::{
-        checksum::{
-            checksum_check, checksum_create, checksum_length_check, CHECKSUM_DIVIDER_SYMBOL,
-        },
-        hexadecimal::assert_hexadecimal_format,
+use super::utils::{
+    address::script_to_address,
+    anonymize::anonymize_script,
+    checksum::{
+        checksum_check, checksum_create, checksum_length_check, split_checksum,
+        CHECKSUM_DIVIDER_SYMBOL,
     },
+    descriptor::change_descriptor_differences,
+    extended_key::{check_hardened_wildcards, check_network_consistency},
+    hexadecimal::encode_hex,
+    key_origin::{check_origin_depths, verify_key_origin},
+    key_policy::check_key_policy,
+    number::parse_index,
+    opcodes::disassemble_script,
+    psbt::psbt_output,
+    rotate_key::rotate_key,
+    script_compiler::{collect_key_expressions, compile_script_expression, normalize_hex_case},
+    script_functions::{check_script_limits, parse_script_function, validate_script},
+    template::{substitute_key_placeholders, PLACEHOLDER_SYMBOL},
+    threshold::set_multi_threshold,
 };
 
 /// Parses and processes a script expression according to the provided configuration.
 ///
-/// This function supports various script types such as `raw`, `multi`, `pk`, `pkh`, and `sh`.
+/// This function supports the script types of the registry of script functions (see
+/// [`SCRIPT_FUNCTIONS`](super::utils::script_functions::SCRIPT_FUNCTIONS)), `raw`, `multi`, `sortedmulti`, `pk`, `pkh`, `wpkh`, `sh`, `wsh` and `tr`, the timelocks `after` and `older` are refused on their own.
 /// It validates the script format, checks or computes checksums as requested, and returns the processed script string or an error.
 ///
 /// # Arguments
@@ -27,6 +50,37 @@ use super::{
 /// # Returns
 ///
 /// Returns `Ok(String)` with the processed script or an error message, or `Err(ParsingError)` if parsing or validation fails.
+/// If disassembling is requested, the output is the compiled script printed as Bitcoin Script opcodes instead.
+/// If the first address is requested, the output is the address of the script with ranged keys derived at index 0
+/// (encoded for the selected network, or the network of its extended keys, mainnet by default).
+/// If the addresses of a file are to be verified, the output is the number of the verified addresses, all of which
+/// must match the addresses the script derives at their indexes.
+/// If the change descriptor is to be checked, the output is the number of its ranged keys, which must differ from
+/// the keys of the script only in their branch.
+/// If anonymizing is requested, the output is the script with its keys replaced with dummy keys (see
+/// [`anonymize_script`]) and its checksum recomputed.
+/// If a fingerprint is to be found, the output is the input followed by the positions of the keys with the
+/// fingerprint (see [`find_fingerprint`]), or by `not found`.
+/// If a key is to be rotated, the output is the script with the old key expression replaced with the new one (see
+/// [`rotate_key`]), validated again, and its checksum recomputed.
+/// If the threshold is to be set, the output is the script with the threshold of its `multi(...)` set (see
+/// [`set_multi_threshold`]) and its checksum recomputed.
+///
+/// If the input contains `$name` placeholders or template keys are configured, the placeholders are first
+/// substituted with the configured key expressions and the checksum of the resulting script is computed
+/// (any checksum provided with the template is ignored).
+///
+/// The whitespace other than spaces in the script is kept, rejected or replaced with spaces according to
+/// the configured whitespace policy, before anything else is done with the script.
+///
+/// If the verification after the computation is requested, the computed `SCRIPT#CHECKSUM` is verified again
+/// the same way as with checksum verification, failing if it does not pass.
+///
+/// If normalization is requested, the hex encoded public keys and `raw` payloads are lowercased before the checksum
+/// is computed or verified, and the normalized script is output.
+///
+/// If rewriting is requested, the input is the path of a file of descriptors instead, see [`rewrite_descriptor_file`].
+/// The same holds for a report, see [`report_descriptor_file`].
 ///
 /// # Errors
 ///
@@ -34,79 +88,491 @@ use super::{
 /// - The script expression is invalid or not recognized,
 /// - Arguments are missing or in the wrong format,
 /// - Checksum verification fails or is missing when required,
-/// - The script contains unsupported or invalid content.
-///
-/// # Panics
-///
-/// Panics if conversion from a positive `i32` to `usize` fails (should not occur in practice).
+/// - The script contains unsupported or invalid content,
+/// - Disassembling is requested, but the script cannot be compiled (e.g. it contains ranged keys),
+/// - The first address is requested, but the script cannot be compiled or has no address format,
+/// - The addresses of a file are to be verified, but the file is malformed or an address does not match,
+/// - The change descriptor is to be checked, but it is invalid or differs in more than the branch of its keys,
+/// - A placeholder has no template key defined or a template key is not used,
+/// - A key origin does not match the master key to verify it against,
+/// - The keys violate the key policy (a required key is missing or a forbidden one appears),
+/// - The extended keys belong to different networks, or to another than the selected one,
+/// - A hardened wildcard is not accepted by the configured policy,
+/// - The script contains whitespace other than spaces and the whitespace policy is strict,
+/// - The script exceeds the configured limits (the keys of multi, the nesting or the compiled size).
+#[tracing::instrument(level = "debug", skip_all)]
 pub fn script_expression(
     input: &str,
     config: &ScriptExpressionConfig,
 ) -> Result<String, ParsingError> {
-    let (script, checksum) = divide_script_and_checksum(input);
-    match script.charify().trimify().as_slice() {
-        ['r', 'a', 'w', rest @ ..] => match rest.extract_args("raw")?.as_slice() {
-            [arg] => {
-                assert_hexadecimal_format(arg, "raw function argument")?;
-            }
-            _ => return Err(ParsingError::new("script parsing failed!")),
-        },
-        ['m', 'u', 'l', 't', 'i', rest @ ..] => match rest.extract_args("multi")?.as_slice() {
-            [arg_count, rest_of_args @ ..] => match arg_count.parse::<i32>()? {
-                val if val < 0 => {
-                    return Err(ParsingError::new("arg count indicator cannot be negative"))
-                }
-                val => {
-                    let val_usize: usize = val.try_into().expect("value is positive");
-                    if val_usize <= rest_of_args.len() {
-                        for arg in rest_of_args {
-                            validate_key_expression(arg.clone())?;
-                        }
-                    } else {
-                        return Err(ParsingError::new(
-                            "arg count indicator cannot be higher than actual args count",
-                        ));
-                    }
-                }
+    if config.rewrite {
+        return rewrite_descriptor_file(input, config);
+    }
+    if config.report {
+        return report_descriptor_file(input, config);
+    }
+    if !config.keys.is_empty() || input.contains(PLACEHOLDER_SYMBOL) {
+        let (template, _) = divide_script_and_checksum(input);
+        let script = substitute_key_placeholders(&template, &config.keys)?;
+        return script_expression(
+            &script,
+            &ScriptExpressionConfig {
+                compute_checksum: true,
+                verify_checksum: false,
+                verify_after: config.verify_after,
+                ignore_checksum_case: false,
+                ensure_checksum: false,
+                normalize: config.normalize,
+                output: config.output.clone(),
+                keys: vec![],
+                verify_origin: config.verify_origin.clone(),
+                network: config.network,
+                hardened_wildcard: config.hardened_wildcard,
+                whitespace: config.whitespace,
+                limits: config.limits,
+                rewrite: false,
+                output_file: None,
+                report: false,
+                policy: config.policy.clone(),
             },
-            _ => return Err(ParsingError::new("at least two arguments needed")),
-        },
-        ['p', 'k', 'h', rest @ ..] => match rest.extract_args("pkh")?.as_slice() {
-            [arg] => {
-                validate_key_expression(arg.clone())?;
-            }
-            _ => {
-                return Err(ParsingError::new(
-                    "exactly one argument is needed for pkh script",
-                ))
-            }
-        },
-        ['p', 'k', rest @ ..] => match rest.extract_args("pk")?.as_slice() {
-            [arg] => {
-                validate_key_expression(arg.clone())?;
-            }
-            _ => {
-                return Err(ParsingError::new(
-                    "exactly one argument is needed for pk script",
-                ))
-            }
-        },
-        ['s', 'h', rest @ ..] => match rest.extract_args("sh")?.as_slice() {
-            [arg]
-                if arg.starts_with("pkh") || arg.starts_with("pk") || arg.starts_with("multi") =>
-            {
-                script_expression(&arg.clone(), &ScriptExpressionConfig::default())?;
-            }
-            [arg] => return Err(ParsingError::new(&script_sh_unsupported_arg_err(arg))),
-            _ => {
-                return Err(ParsingError::new(
-                    "exactly one argument is needed for sh script",
-                ))
-            }
-        },
-        _ => return Err(ParsingError::new("parsing of the script failed!")),
+        )
+        // the spans point into the substituted script rather than the template
+        .map_err(ParsingError::without_span);
+    }
+
+    // the checksum is replaced by the computed one, so anything after the first separator is dropped
+    if !config.compute_checksum {
+        split_checksum(input)?;
+    }
+    let (script, mut checksum) = divide_script_and_checksum(input);
+    if config.ignore_checksum_case {
+        checksum = checksum.as_deref().map(lowercase_checksum);
+    }
+    let script_length = script.len();
+    let script = prepare_script(script, config)?;
+    // the spans point into the prepared script, which is only the input if its length is kept
+    let keep_span = |err: ParsingError| {
+        if script.len() == script_length {
+            err
+        } else {
+            err.without_span()
+        }
+    };
+    validate_script(&script, ScriptContext::TopLevel).map_err(keep_span)?;
+    check_script_limits(&script, &config.limits)?;
+    let output = script_operation(&script, checksum.as_ref(), config).map_err(keep_span)?;
+
+    let key_expressions = collect_key_expressions(&script)?;
+    let network = check_network_consistency(&key_expressions, config.network)?;
+    check_hardened_wildcards(&key_expressions, config.hardened_wildcard)?;
+    check_origin_depths(&key_expressions, config.limits.origin_depth)?;
+
+    if let Some(master_key) = &config.verify_origin {
+        for key_expression in &key_expressions {
+            verify_key_origin(key_expression, master_key)?;
+        }
+    }
+
+    if let Some(policy) = &config.policy {
+        check_key_policy(&key_expressions, policy)?;
+    }
+
+    match &config.output {
+        ScriptOutput::Expression => Ok(output),
+        ScriptOutput::Disassembly => disassemble_script(&compile_script_expression(&script, None)?),
+        ScriptOutput::FirstAddress(explorer) => {
+            let network = network.unwrap_or_default();
+            let address =
+                script_to_address(&compile_script_expression(&script, Some(0))?, network)?;
+            Ok(match explorer {
+                Some(explorer) => format!("{address} {}", explorer.address_url(&address, network)),
+                None => address,
+            })
+        }
+        ScriptOutput::VerifyAddresses(path) => {
+            verify_addresses(&script, path, network.unwrap_or_default())
+        }
+        ScriptOutput::CheckChange(change) => check_change_descriptor(&script, change),
+        ScriptOutput::Anonymized => {
+            let anonymized = anonymize_script(&script)?;
+            let checksum = checksum_create(&anonymized)?;
+            Ok(format!("{anonymized}{CHECKSUM_DIVIDER_SYMBOL}{checksum}"))
+        }
+        ScriptOutput::FindFingerprint(fingerprint) => Ok(format!(
+            "{}: {}",
+            input.trim(),
+            find_fingerprint(&key_expressions, *fingerprint)
+        )),
+        ScriptOutput::RotatedKey { old, new } => rotate_descriptor_key(&script, old, new, config),
+        ScriptOutput::Threshold(threshold) => {
+            let updated = set_multi_threshold(&script, *threshold)?;
+            check_script_limits(&updated, &config.limits)?;
+            let checksum = checksum_create(&updated)?;
+            Ok(format!("{updated}{CHECKSUM_DIVIDER_SYMBOL}{checksum}"))
+        }
+        ScriptOutput::PsbtOutput(index) => Ok(psbt_output(&script, *index)?.to_string()),
+    }
+}
+
+/// Replaces the old key expression of the script with the new one (see [`rotate_key`]) and validates the rotated
+/// script the same way as the input: its syntax and the context rules of its keys, the limits, the networks of its
+/// keys, the hardened wildcards and the key policy. Returns the rotated script with its checksum recomputed.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the old key expression does not appear in the script or the rotated script is
+/// invalid (keeping the kind of the error).
+fn rotate_descriptor_key(
+    script: &str,
+    old: &str,
+    new: &str,
+    config: &ScriptExpressionConfig,
+) -> Result<String, ParsingError> {
+    let (rotated, _) = rotate_key(script, old, new)?;
+    let invalid = |err: ParsingError| {
+        ParsingError::with_kind(
+            err.kind,
+            &format!("The rotated descriptor is invalid: {}", err.message),
+        )
+    };
+
+    validate_script(&rotated, ScriptContext::TopLevel).map_err(invalid)?;
+    check_script_limits(&rotated, &config.limits).map_err(invalid)?;
+    let key_expressions = collect_key_expressions(&rotated)?;
+    check_network_consistency(&key_expressions, config.network).map_err(invalid)?;
+    check_hardened_wildcards(&key_expressions, config.hardened_wildcard).map_err(invalid)?;
+    check_origin_depths(&key_expressions, config.limits.origin_depth).map_err(invalid)?;
+    if let Some(policy) = &config.policy {
+        check_key_policy(&key_expressions, policy).map_err(invalid)?;
+    }
+
+    let checksum = checksum_create(&rotated)?;
+    Ok(format!("{rotated}{CHECKSUM_DIVIDER_SYMBOL}{checksum}"))
+}
+
+/// Finds the (previously validated) key expressions of a descriptor with the fingerprint, e.g. of a compromised
+/// signer: the ones whose key origin starts with the fingerprint, and the master extended keys (without a key
+/// origin) of the fingerprint. Returns their positions (from 1), e.g. `keys 1, 3`, or `not found`.
+fn find_fingerprint(key_expressions: &[String], fingerprint: KeyFingerprint) -> String {
+    let target = encode_hex(&fingerprint);
+    let positions: Vec<String> = key_expressions
+        .iter()
+        .enumerate()
+        .filter(
+            |(_, key_expression)| match split_key_expression(key_expression.trim()) {
+                Ok((Some(key_origin), _)) => key_origin
+                    .get(1..9)
+                    .is_some_and(|origin| origin.eq_ignore_ascii_case(&target)),
+                Ok((None, key)) => MasterKey::try_from(key.split('/').next().unwrap_or(key))
+                    .is_ok_and(|master_key| master_key.fingerprint() == fingerprint),
+                Err(_) => false,
+            },
+        )
+        .map(|(index, _)| (index + 1).to_string())
+        .collect();
+
+    match positions.as_slice() {
+        [] => "not found".to_string(),
+        [position] => format!("key {position}"),
+        _ => format!("keys {}", positions.join(", ")),
+    }
+}
+
+/// Verifies that the change descriptor (validated, with its checksum verified if present) differs from the script
+/// only in the branch of its ranged keys, `.../0/*` of the script against `.../1/*` of the change descriptor, with
+/// the same script functions, thresholds and keys otherwise. Returns a summary of the number of the ranged keys.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the change descriptor is invalid or its checksum does not match, or (of the
+/// [`ErrorKind::ChangeMismatch`] kind) if the descriptors differ in anything else, listing all the differences.
+fn check_change_descriptor(script: &str, change: &str) -> Result<String, ParsingError> {
+    let (change_script, checksum) = split_checksum(change.trim())?;
+    if let Some(checksum) = checksum {
+        if !checksum_check(change_script, checksum)? {
+            return Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                "checksum verification of the change descriptor failed!",
+            ));
+        }
+    }
+    validate_script(change_script, ScriptContext::TopLevel).map_err(|err| {
+        ParsingError::with_kind(
+            err.kind,
+            &format!("The change descriptor is invalid: {}", err.message),
+        )
+    })?;
+
+    let (branches, differences) = change_descriptor_differences(script, change_script)?;
+    if !differences.is_empty() {
+        return Err(ParsingError::with_kind(
+            ErrorKind::ChangeMismatch,
+            &format!(
+                "The change descriptor does not match the receive descriptor:\n{}",
+                differences.join("\n")
+            ),
+        ));
+    }
+
+    Ok(format!(
+        "The change descriptor differs from the receive descriptor only in the branch of its {branches} ranged keys"
+    ))
+}
+
+/// Verifies the `address:index` pairs of the file, one per line, against the addresses the script derives at
+/// those indexes (encoded for the network), e.g. to check that an address list really derives from the descriptor.
+/// The empty lines are skipped. Returns a summary of the number of the verified addresses.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the file cannot be read, a line is not an `address:index` pair, the script has no
+/// address format, or (of the [`ErrorKind::AddressMismatch`] kind) any of the addresses does not match, listing
+/// all the mismatches with their lines.
+fn verify_addresses(script: &str, path: &str, network: Network) -> Result<String, ParsingError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ParsingError::new(&format!("Could not read the address file '{path}': {e}"))
+    })?;
+
+    let mut mismatches = Vec::new();
+    let mut addresses = 0;
+    for (line_index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_err = |message: &str| {
+            ParsingError::new(&format!("Line {} of '{path}': {message}", line_index + 1))
+        };
+        let (address, index) = line
+            .trim()
+            .rsplit_once(':')
+            .and_then(|(address, index)| Some((address.trim(), parse_index(index.trim())?)))
+            .ok_or_else(|| {
+                line_err("expected 'address:index' with the index from the range [0,...,2^31-1]")
+            })?;
+
+        let derived = script_to_address(&compile_script_expression(script, Some(index))?, network)?;
+        addresses += 1;
+        if derived != address {
+            mismatches.push(format!(
+                "Line {}: the address '{address}' does not match '{derived}' derived at index {index}",
+                line_index + 1
+            ));
+        }
+    }
+
+    if !mismatches.is_empty() {
+        return Err(ParsingError::with_kind(
+            ErrorKind::AddressMismatch,
+            &format!(
+                "{} of {addresses} addresses of '{path}' do not match the descriptor:\n{}",
+                mismatches.len(),
+                mismatches.join("\n")
+            ),
+        ));
+    }
+
+    Ok(format!(
+        "All {addresses} addresses of '{path}' match the descriptor"
+    ))
+}
+
+/// Processes a script expression the same way as [`script_expression`], returning the fields available
+/// to `--template`: the `output` and the `script` and its `checksum` (empty without one). These are taken
+/// from the output expression if it is printed, otherwise from the input.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`script_expression`].
+pub fn script_expression_fields(
+    input: &str,
+    config: &ScriptExpressionConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    if config.rewrite {
+        return Err(ParsingError::new(
+            "'--rewrite' cannot be combined with '--template' or '--output json', 'csv' or 'ur'",
+        ));
+    }
+    if config.report {
+        return Err(ParsingError::new(
+            "'--report' cannot be combined with '--template' or '--output json', 'csv' or 'ur'",
+        ));
+    }
+    let output = script_expression(input, config)?;
+
+    let expression = if matches!(
+        config.output,
+        ScriptOutput::Expression
+            | ScriptOutput::Anonymized
+            | ScriptOutput::RotatedKey { .. }
+            | ScriptOutput::Threshold(_)
+    ) && !config.verify_checksum
+    {
+        output.as_str()
+    } else {
+        input.trim()
+    };
+    let (script, checksum) = divide_script_and_checksum(expression);
+
+    Ok(vec![
+        ("script", script),
+        ("checksum", checksum.unwrap_or_default()),
+        ("output", output),
+    ])
+}
+
+/// Computes the checksums of the script expressions of the file, one per line, and writes them back to the file
+/// (or to the output file, if configured) with the checksums appended, any previous checksum replaced. The empty
+/// lines are kept as they are. Returns a summary of the number of the descriptors and of the changed lines.
+///
+/// Nothing is written unless all the script expressions are valid.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the file cannot be read or written, or a script expression is invalid (naming its
+/// line).
+pub fn rewrite_descriptor_file(
+    path: &str,
+    config: &ScriptExpressionConfig,
+) -> Result<String, ParsingError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ParsingError::new(&format!("Could not read the descriptor file '{path}': {e}"))
+    })?;
+    let line_config = ScriptExpressionConfig {
+        compute_checksum: !config.ensure_checksum,
+        verify_checksum: false,
+        verify_after: config.verify_after,
+        ignore_checksum_case: config.ignore_checksum_case,
+        ensure_checksum: config.ensure_checksum,
+        normalize: config.normalize,
+        output: ScriptOutput::Expression,
+        keys: vec![],
+        verify_origin: config.verify_origin.clone(),
+        network: config.network,
+        hardened_wildcard: config.hardened_wildcard,
+        whitespace: config.whitespace,
+        limits: config.limits,
+        rewrite: false,
+        output_file: None,
+        report: false,
+        policy: config.policy.clone(),
+    };
+
+    let mut lines = Vec::new();
+    let (mut descriptors, mut changed) = (0, 0);
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            lines.push(line.to_string());
+            continue;
+        }
+        let rewritten = script_expression(line.trim(), &line_config).map_err(|err| {
+            ParsingError::with_kind(
+                err.kind,
+                &format!("Line {} of '{path}': {}", index + 1, err.message),
+            )
+        })?;
+        descriptors += 1;
+        if rewritten != line {
+            changed += 1;
+        }
+        lines.push(rewritten);
+    }
+
+    let target = config.output_file.as_deref().unwrap_or(path);
+    let trailing_newline = if content.ends_with('\n') { "\n" } else { "" };
+    std::fs::write(target, lines.join("\n") + trailing_newline).map_err(|e| {
+        ParsingError::new(&format!(
+            "Could not write the descriptor file '{target}': {e}"
+        ))
+    })?;
+
+    Ok(format!(
+        "Rewrote {descriptors} descriptors of '{path}' to '{target}', {changed} of them changed"
+    ))
+}
+
+/// Validates the script expressions of the file, one per line (e.g. a wallet backup), and returns a single report:
+/// the number of the descriptors, the valid ones counted by their top-level function and the invalid ones with
+/// their line numbers and errors. The checksums are verified where present. The empty lines and the comments
+/// (starting with `#`) are skipped.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the file cannot be read, the invalid descriptors are only reported.
+pub fn report_descriptor_file(
+    path: &str,
+    config: &ScriptExpressionConfig,
+) -> Result<String, ParsingError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ParsingError::new(&format!("Could not read the descriptor file '{path}': {e}"))
+    })?;
+
+    let mut types: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut failures = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_config = ScriptExpressionConfig {
+            compute_checksum: false,
+            verify_checksum: line.contains(CHECKSUM_DIVIDER_SYMBOL),
+            verify_after: false,
+            ignore_checksum_case: config.ignore_checksum_case,
+            ensure_checksum: false,
+            normalize: config.normalize,
+            output: ScriptOutput::Expression,
+            keys: vec![],
+            verify_origin: config.verify_origin.clone(),
+            network: config.network,
+            hardened_wildcard: config.hardened_wildcard,
+            whitespace: config.whitespace,
+            limits: config.limits,
+            rewrite: false,
+            output_file: None,
+            report: false,
+            policy: config.policy.clone(),
+        };
+        let validated = script_expression(line, &line_config).and_then(|_| {
+            let (script, _) = divide_script_and_checksum(line);
+            parse_script_function(&script).map(|(function, _)| function.name())
+        });
+        match validated {
+            Ok(name) => *types.entry(name).or_default() += 1,
+            Err(err) => failures.push(format!("Line {}: {}", index + 1, err.message)),
+        }
+    }
+
+    let valid: usize = types.values().sum();
+    let mut report = vec![format!(
+        "Report of '{path}': {} descriptors, {valid} valid, {} invalid",
+        valid + failures.len(),
+        failures.len()
+    )];
+    report.extend(types.iter().map(|(name, count)| format!("{name}: {count}")));
+    report.extend(failures);
+    Ok(report.join("\n"))
+}
+
+/// Applies the whitespace policy and the normalization (if requested) to the script.
+fn prepare_script(script: String, config: &ScriptExpressionConfig) -> Result<String, ParsingError> {
+    let script = config.whitespace.apply(script)?;
+    Ok(if config.normalize {
+        normalize_hex_case(&script)
+    } else {
+        script
+    })
+}
+
+/// Lowercases the checksum for `--ignore-checksum-case`, noting on the standard error if it was not lowercase, as the
+/// output shows the lowercased one then. The checksum characters are all ASCII, so the spans are kept.
+fn lowercase_checksum(checksum: &str) -> String {
+    let lowercase = checksum.to_ascii_lowercase();
+    if lowercase != checksum {
+        eprintln!(
+            "Note: the checksum '{checksum}' is lowercased to '{lowercase}' before it is verified"
+        );
     }
-    script_operation(&script, checksum.as_ref(), config)
+    lowercase
 }
 
 fn divide_script_and_checksum(input: &str) -> (String, Option<String>) {
@@ -116,36 +582,75 @@ fn divide_script_and_checksum(input: &str) -> (String, Option<String>) {
     (script, checksum)
 }
 
+/// Re-verifies the computed `SCRIPT#CHECKSUM` through the verification path, as a self-check.
+fn verify_computed_checksum(expression: &str) -> Result<(), ParsingError> {
+    let (script, checksum) = divide_script_and_checksum(expression);
+    let config = ScriptExpressionConfig {
+        verify_checksum: true,
+        ..ScriptExpressionConfig::default()
+    };
+
+    script_operation(&script, checksum.as_ref(), &config)
+        .map(|_| ())
+        .map_err(|err| {
+            ParsingError::with_kind(
+                err.kind,
+                &format!(
+                    "self-check of the computed checksum of '{expression}' failed: {}",
+                    err.message
+                ),
+            )
+        })
+}
+
 fn script_operation(
     script: &str,
     checksum: Option<&String>,
     config: &ScriptExpressionConfig,
 ) -> Result<String, ParsingError> {
-    if config.compute_checksum {
+    if config.compute_checksum || (config.ensure_checksum && checksum.is_none()) {
         // ignores checksum
-        return Ok(format!("{}#{}", script, checksum_create(script)));
+        let expression = format!("{}#{}", script, checksum_create(script)?);
+        if config.verify_after {
+            verify_computed_checksum(&expression)?;
+        }
+        return Ok(expression);
     }
     match checksum {
         Some(checksum) => {
+            let checksum_span = script.len() + 1..script.len() + 1 + checksum.len();
             if checksum_length_check(checksum) {
-                if config.verify_checksum {
-                    if checksum_check(script, checksum) {
+                if config.verify_checksum || config.ensure_checksum {
+                    if !checksum_check(script, checksum)? {
+                        Err(ParsingError::with_kind(
+                            ErrorKind::ChecksumMismatch,
+                            "checksum verification failed!",
+                        )
+                        .with_span(checksum_span))
+                    } else if config.ensure_checksum {
+                        Ok(format!("{script}#{checksum}"))
+                    } else {
                         Ok(format!(
                             "Veritification of the '{script}#{checksum}' script succeeded!"
                         ))
-                    } else {
-                        Err(ParsingError::new("checksum verification failed!"))
                     }
                 } else {
                     Ok(format!("{script}#{checksum}"))
                 }
             } else {
-                Err(ParsingError::new("checksum length is incorrect!"))
+                Err(ParsingError::with_kind(
+                    ErrorKind::InvalidChecksum,
+                    "checksum length is incorrect!",
+                )
+                .with_span(checksum_span))
             }
         }
         None => {
             if config.verify_checksum {
-                Err(ParsingError::new("checksum is required for verification!"))
+                Err(ParsingError::with_kind(
+                    ErrorKind::MissingChecksum,
+                    "checksum is required for verification!",
+                ))
             } else {
                 Ok(script.to_string())
             }
@@ -158,23 +663,76 @@ mod tests {
 
     use super::*;
     use crate::{
-        structs::script_expression_config::ScriptExpressionConfig, test_utils::get_cmd,
-        utils::error_messages::script_arg_extraction_err,
+        structs::{
+            hardened_wildcard_policy::HardenedWildcardPolicy, network::Network,
+            script_expression_config::ScriptExpressionConfig, script_limits::ScriptLimits,
+            whitespace_policy::WhitespacePolicy,
+        },
+        test_utils::get_cmd,
+        utils::error_messages::{
+            script_arg_extraction_err, script_sh_unsupported_arg_err,
+            script_top_level_unsupported_err,
+        },
     };
 
     const CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY: ScriptExpressionConfig = ScriptExpressionConfig {
         compute_checksum: false,
         verify_checksum: false,
+        verify_after: false,
+        ignore_checksum_case: false,
+        ensure_checksum: false,
+        normalize: false,
+        output: ScriptOutput::Expression,
+        keys: vec![],
+        verify_origin: None,
+        network: None,
+        hardened_wildcard: HardenedWildcardPolicy::Allow,
+        whitespace: WhitespacePolicy::Preserve,
+        limits: ScriptLimits::STANDARD,
+        rewrite: false,
+        output_file: None,
+        report: false,
+        policy: None,
     };
 
     const CONFIG_WITH_TRUE_VERIFY: ScriptExpressionConfig = ScriptExpressionConfig {
         compute_checksum: false,
         verify_checksum: true,
+        verify_after: false,
+        ignore_checksum_case: false,
+        ensure_checksum: false,
+        normalize: false,
+        output: ScriptOutput::Expression,
+        keys: vec![],
+        verify_origin: None,
+        network: None,
+        hardened_wildcard: HardenedWildcardPolicy::Allow,
+        whitespace: WhitespacePolicy::Preserve,
+        limits: ScriptLimits::STANDARD,
+        rewrite: false,
+        output_file: None,
+        report: false,
+        policy: None,
     };
 
     const CONFIG_WITH_TRUE_COMPUTE: ScriptExpressionConfig = ScriptExpressionConfig {
         compute_checksum: true,
         verify_checksum: false,
+        verify_after: false,
+        ignore_checksum_case: false,
+        ensure_checksum: false,
+        normalize: false,
+        output: ScriptOutput::Expression,
+        keys: vec![],
+        verify_origin: None,
+        network: None,
+        hardened_wildcard: HardenedWildcardPolicy::Allow,
+        whitespace: WhitespacePolicy::Preserve,
+        limits: ScriptLimits::STANDARD,
+        rewrite: false,
+        output_file: None,
+        report: false,
+        policy: None,
     };
 
     #[test]
@@ -208,33 +766,43 @@ mod tests {
         );
         assert_eq!(
             script_expression("raw(\tDEADBEEF)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new(
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
                 "raw function argument '\tDEADBEEF' is not a valid hexadecimal string!"
-            ))
+            )
+            .with_span(4..5))
         );
         assert_eq!(
             script_expression("raw(\nDEADBEEF)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new(
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
                 "raw function argument '\nDEADBEEF' is not a valid hexadecimal string!"
-            ))
+            )
+            .with_span(4..5))
         );
         assert_eq!(
             script_expression("raw(\u{a0}DEADBEEF)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new(
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
                 "raw function argument '\u{a0}DEADBEEF' is not a valid hexadecimal string!"
-            ))
+            )
+            .with_span(4..6))
         );
         assert_eq!(
             script_expression("raw(nothexadecimal)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new(
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
                 "raw function argument 'nothexadecimal' is not a valid hexadecimal string!"
-            ))
+            )
+            .with_span(4..5))
         );
         assert_eq!(
             script_expression("raw(nothexadecimal)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new(
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
                 "raw function argument 'nothexadecimal' is not a valid hexadecimal string!"
-            ))
+            )
+            .with_span(4..5))
         );
 
         assert_eq!(
@@ -249,21 +817,33 @@ mod tests {
         );
         assert_eq!(
             script_expression("raw(deadbeef)#", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new("checksum length is incorrect!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidChecksum,
+                "checksum length is incorrect!"
+            )
+            .with_span(14..14))
         );
         assert_eq!(
             script_expression(
                 "raw(deadbeef)#89f8spxmx",
                 &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
             ),
-            Err(ParsingError::new("checksum length is incorrect!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidChecksum,
+                "checksum length is incorrect!"
+            )
+            .with_span(14..23))
         );
         assert_eq!(
             script_expression(
                 "raw(deadbeef)#89f8spx",
                 &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
             ),
-            Err(ParsingError::new("checksum length is incorrect!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidChecksum,
+                "checksum length is incorrect!"
+            )
+            .with_span(14..21))
         );
         assert_eq!(
             script_expression(
@@ -277,7 +857,11 @@ mod tests {
                 "raw(deadbeef)##89f8spxm",
                 &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
             ),
-            Err(ParsingError::new("checksum length is incorrect!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidChecksum,
+                "the script expression has 2 checksum separators '#', only one is allowed"
+            )
+            .with_span(14..15))
         );
         assert_eq!(
             script_expression("rawraw)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
@@ -302,15 +886,26 @@ mod tests {
         );
         assert_eq!(
             script_expression("raw(DEA D BEEF)#agaaa9aa", &CONFIG_WITH_TRUE_VERIFY),
-            Err(ParsingError::new("checksum verification failed!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                "checksum verification failed!"
+            )
+            .with_span(16..24))
         );
         assert_eq!(
             script_expression("raw(deedbeef)#89f8spxm", &CONFIG_WITH_TRUE_VERIFY),
-            Err(ParsingError::new("checksum verification failed!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                "checksum verification failed!"
+            )
+            .with_span(14..22))
         );
         assert_eq!(
             script_expression("raw(DEA D BEEF)", &CONFIG_WITH_TRUE_VERIFY),
-            Err(ParsingError::new("checksum is required for verification!"))
+            Err(ParsingError::with_kind(
+                ErrorKind::MissingChecksum,
+                "checksum is required for verification!"
+            ))
         );
 
         assert_eq!(
@@ -342,31 +937,69 @@ mod tests {
     #[test]
     fn test_multi_script() {
         assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)#5jlj4shz", &CONFIG_WITH_TRUE_COMPUTE), Ok("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)#5jlj4shz".to_string()));
-        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("arg count indicator cannot be higher than actual args count")));
+        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidThreshold, "arg count indicator cannot be higher than actual args count")));
         assert_eq!(script_expression("multi(1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Ok("multi(1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)".to_string()));
-        assert_eq!(script_expression("multi(-1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("arg count indicator cannot be negative")));
-        assert_eq!(script_expression("multi(1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8Nqtwyb \t GhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
-        assert_eq!(script_expression("multi(1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybčGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
+        assert_eq!(script_expression("multi(-1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidThreshold, "arg count indicator cannot be negative")));
+        assert_eq!(script_expression("multi(1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8Nqtwyb \t GhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character ' ' in the key of the key expression 'xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8Nqtwyb \\t GhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8'")));
+        assert_eq!(script_expression("multi(1, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybčGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character 'č' in the key of the key expression 'xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybčGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8'")));
         assert_eq!(
             script_expression("multi(0)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
             Ok("multi(0)".to_string())
         );
         assert_eq!(
             script_expression("multi(1)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new(
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidThreshold,
                 "arg count indicator cannot be higher than actual args count"
             ))
         );
         assert_eq!(script_expression(" \t \t \t multi \t \t \t (\t \t \t 2 \t \t \t, \t \t \t xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, \t \t \t xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)\t \t \t", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("parsing of the script failed!")));
-        assert_eq!(script_expression("multi(\t2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("invalid digit found in string")));
-        assert_eq!(script_expression("multi(2,\txpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
-        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8,\txpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
-        assert_eq!(script_expression("multi(\n2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("invalid digit found in string")));
-        assert_eq!(script_expression("multi(2,\nxpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
-        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8,\nxpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
-        assert_eq!(script_expression("multi(\u{a0}2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("invalid digit found in string")));
-        assert_eq!(script_expression("multi(2,\u{a0}xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
-        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8,\u{a0}xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("Input contains invalid characters")));
+        assert_eq!(script_expression("multi(\t2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidThreshold, "The threshold '\\t2' must be a decimal number without a sign")));
+        assert_eq!(script_expression("multi(2,\txpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character '\\t' in the key of the key expression '\\txpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8'")));
+        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8,\txpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character '\\t' in the key of the key expression '\\txpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB'")));
+        assert_eq!(script_expression("multi(\n2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidThreshold, "The threshold '\\n2' must be a decimal number without a sign")));
+        assert_eq!(script_expression("multi(2,\nxpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character '\\n' in the key of the key expression '\\nxpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8'")));
+        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8,\nxpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character '\\n' in the key of the key expression '\\nxpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB'")));
+        assert_eq!(script_expression("multi(\u{a0}2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidThreshold, "The threshold '\\u{a0}2' must be a decimal number without a sign")));
+        assert_eq!(script_expression("multi(2,\u{a0}xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character '\\u{a0}' in the key of the key expression '\\u{a0}xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8'")));
+        assert_eq!(script_expression("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8,\u{a0}xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidCharacter, "Invalid character '\\u{a0}' in the key of the key expression '\\u{a0}xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB'")));
+    }
+
+    #[test]
+    fn test_multi_script_strict_threshold() {
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        for (threshold, message) in [
+            (
+                "+1",
+                "The threshold '+1' must be a decimal number without a sign",
+            ),
+            ("01", "The threshold '01' must not have leading zeros"),
+            (
+                "1.0",
+                "The threshold '1.0' must be a decimal number without a sign",
+            ),
+        ] {
+            assert_eq!(
+                script_expression(
+                    &format!("multi({threshold},{key})"),
+                    &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
+                ),
+                Err(ParsingError::with_kind(
+                    ErrorKind::InvalidThreshold,
+                    message
+                )),
+                "{threshold}"
+            );
+        }
+        assert_eq!(
+            script_expression(
+                &format!("pk([deadbeef/044h]{key})"),
+                &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
+            ),
+            Err(ParsingError::new(
+                "Invalid derivation path: The derivation index '044' must not have leading zeros"
+            ))
+        );
     }
 
     #[test]
@@ -375,7 +1008,10 @@ mod tests {
         assert_eq!(script_expression("pk(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_TRUE_COMPUTE), Ok("pk(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#yjz8lyzk".to_string()));
         assert_eq!(
             script_expression("pk(xpub_invalid_format)", &CONFIG_WITH_TRUE_COMPUTE),
-            Err(ParsingError::new("Invalid xpub key: base58 error"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidKey,
+                "Invalid xpub key: base58 error"
+            ))
         );
         assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid_checksum", &CONFIG_WITH_TRUE_COMPUTE), Ok("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#axav5m0j".to_string()));
         assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)extra", &CONFIG_WITH_TRUE_COMPUTE), Err(ParsingError::new(&script_arg_extraction_err("pk"))));
@@ -393,14 +1029,17 @@ mod tests {
         assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Ok("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)".to_string()));
         assert_eq!(
             script_expression("pk(invalid_xpub)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new("Could not convert WIF from base58"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidKey,
+                "Could not convert WIF from base58"
+            ))
         );
-        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("checksum length is incorrect!")));
+        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidChecksum, "checksum length is incorrect!").with_span(116..123)));
         assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Ok("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh".to_string()));
         assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#axav5m0j", &CONFIG_WITH_TRUE_VERIFY), Ok("Veritification of the 'pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#axav5m0j' script succeeded!".to_string()));
-        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::new("checksum length is incorrect!")));
-        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::new("checksum verification failed!")));
-        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::new("checksum is required for verification!")));
+        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidChecksum, "checksum length is incorrect!").with_span(116..123)));
+        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::with_kind(ErrorKind::ChecksumMismatch, "checksum verification failed!").with_span(116..124)));
+        assert_eq!(script_expression("pk(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::with_kind(ErrorKind::MissingChecksum, "checksum is required for verification!")));
     }
 
     #[test]
@@ -409,7 +1048,10 @@ mod tests {
         assert_eq!(script_expression("pkh(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_TRUE_COMPUTE), Ok("pkh(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#ujpe9npc".to_string()));
         assert_eq!(
             script_expression("pkh(xpub_invalid_format)", &CONFIG_WITH_TRUE_COMPUTE),
-            Err(ParsingError::new("Invalid xpub key: base58 error"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidKey,
+                "Invalid xpub key: base58 error"
+            ))
         );
         assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid_checksum", &CONFIG_WITH_TRUE_COMPUTE), Ok("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#vm4xc4ed".to_string()));
         assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)extra", &CONFIG_WITH_TRUE_COMPUTE), Err(ParsingError::new("Could not extract arguments from 'pkh' expression.")));
@@ -428,9 +1070,12 @@ mod tests {
         assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Ok("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)".to_string()));
         assert_eq!(
             script_expression("pkh(invalid_xpub)", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
-            Err(ParsingError::new("Could not convert WIF from base58"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidKey,
+                "Could not convert WIF from base58"
+            ))
         );
-        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::new("checksum length is incorrect!")));
+        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidChecksum, "checksum length is incorrect!").with_span(117..124)));
         assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY), Ok("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh".to_string()));
         assert_eq!(
             script_expression("pkh()", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
@@ -438,9 +1083,41 @@ mod tests {
         );
 
         assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#vm4xc4ed", &CONFIG_WITH_TRUE_VERIFY), Ok("Veritification of the 'pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#vm4xc4ed' script succeeded!".to_string()));
-        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::new("checksum length is incorrect!")));
-        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::new("checksum verification failed!")));
-        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::new("checksum is required for verification!")));
+        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#invalid", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::with_kind(ErrorKind::InvalidChecksum, "checksum length is incorrect!").with_span(117..124)));
+        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)#abcdefgh", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::with_kind(ErrorKind::ChecksumMismatch, "checksum verification failed!").with_span(117..125)));
+        assert_eq!(script_expression("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", &CONFIG_WITH_TRUE_VERIFY), Err(ParsingError::with_kind(ErrorKind::MissingChecksum, "checksum is required for verification!")));
+    }
+
+    #[test]
+    fn test_key_contexts() {
+        let x_only = "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115";
+        let uncompressed = "04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235";
+
+        // the keys are validated by the position they appear in
+        for script in [
+            format!("pkh({uncompressed})"),
+            format!("sh(multi(1,{uncompressed}))"),
+            format!("tr({x_only})"),
+            format!("tr(02{x_only})"),
+            format!("sh(wsh(pk(02{x_only})))"),
+        ] {
+            assert_eq!(
+                script_expression(&script, &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
+                Ok(script)
+            );
+        }
+        for script in [
+            format!("wpkh({uncompressed})"),
+            format!("sh(wpkh({uncompressed}))"),
+            format!("wsh(multi(1,{uncompressed}))"),
+            format!("sh(wsh(pkh({uncompressed})))"),
+            format!("tr({uncompressed})"),
+            format!("pk({x_only})"),
+            format!("wpkh({x_only})"),
+            format!("wsh(pk({x_only}))"),
+        ] {
+            assert!(script_expression(&script, &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY).is_err());
+        }
     }
 
     #[test]
@@ -481,7 +1158,7 @@ mod tests {
         assert_eq!(
             script_expression("sh( )", &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY),
             Err(ParsingError::new(
-                "'sh' script's argument must be either 'pk', 'pkh' or 'multi' scripts, but '' was given."
+                "'sh' script's argument must be either 'pk', 'pkh', 'multi', 'sortedmulti', 'wpkh' or 'wsh' scripts, but '' was given."
             ))
         );
         assert_eq!(
@@ -499,7 +1176,7 @@ mod tests {
                 &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
             ),
             Err(ParsingError::new(
-                "'sh' script's argument must be either 'pk', 'pkh' or 'multi' scripts, but 'invalid_start' was given."
+                "'sh' script's argument must be either 'pk', 'pkh', 'multi', 'sortedmulti', 'wpkh' or 'wsh' scripts, but 'invalid_start' was given."
             ))
         );
         assert_eq!(
@@ -507,14 +1184,20 @@ mod tests {
                 "sh(pkh(invalid key))",
                 &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
             ),
-            Err(ParsingError::new("Could not convert WIF from base58"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                "Invalid character ' ' in the key of the key expression 'invalid key'"
+            ))
         );
         assert_eq!(
             script_expression(
                 "sh(multi(1, invalid key))",
                 &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY
             ),
-            Err(ParsingError::new("Could not convert WIF from base58"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                "Invalid character ' ' in the key of the key expression 'invalid key'"
+            ))
         );
 
         assert_eq!(
@@ -522,7 +1205,10 @@ mod tests {
                 "sh(multi(2, xpub1, xpub2))#checksum",
                 &CONFIG_WITH_TRUE_VERIFY
             ),
-            Err(ParsingError::new("Invalid xpub key: base58 error"))
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidKey,
+                "Invalid xpub key: base58 error"
+            ))
         );
     }
 
@@ -583,7 +1269,7 @@ mod tests {
             .args(["script-expression", "--verify-checksum", "raw(deadbeef)"])
             .assert()
             .failure()
-            .stderr("Parsing error: checksum is required for verification!\n");
+            .stderr("Parsing error [E003]: checksum is required for verification!\n");
     }
 
     #[test]
@@ -598,6 +1284,17 @@ mod tests {
             .success()
             .stdout("raw(deadbeef)#89f8spxm\n");
 
+        // every checksum after the first separator is replaced
+        get_cmd()
+            .args([
+                "script-expression",
+                "--compute-checksum",
+                "raw(deadbeef)#89f8spxm#89f8spxm",
+            ])
+            .assert()
+            .success()
+            .stdout("raw(deadbeef)#89f8spxm\n");
+
         get_cmd()
             .args([
                 "script-expression",
@@ -629,6 +1326,88 @@ mod tests {
             .stdout("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)#5jlj4shz\n");
     }
 
+    #[test]
+    fn test_script_expression_ignore_checksum_case() {
+        assert_eq!(
+            script_expression("raw(deadbeef)#89F8SPXM", &CONFIG_WITH_TRUE_VERIFY),
+            Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                "checksum verification failed!"
+            )
+            .with_span(14..22))
+        );
+
+        let config = ScriptExpressionConfig {
+            verify_checksum: true,
+            ignore_checksum_case: true,
+            ..ScriptExpressionConfig::default()
+        };
+        assert_eq!(
+            script_expression("raw(deadbeef)#89F8SPXM", &config),
+            Ok("Veritification of the 'raw(deadbeef)#89f8spxm' script succeeded!".to_string())
+        );
+        assert!(script_expression("raw(deadbeef)#89F8SPXX", &config).is_err());
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--verify-checksum",
+                "--ignore-checksum-case",
+                "raw(deadbeef)#89F8SPXM",
+                "raw(deadbeef)#89f8spxm",
+            ])
+            .assert()
+            .success()
+            .stdout(
+                "Veritification of the 'raw(deadbeef)#89f8spxm' script succeeded!\n\
+                 Veritification of the 'raw(deadbeef)#89f8spxm' script succeeded!\n",
+            )
+            .stderr(
+                "Note: the checksum '89F8SPXM' is lowercased to '89f8spxm' before it is verified\n",
+            );
+    }
+
+    #[test]
+    fn test_script_expression_ensure_checksum() {
+        let config = ScriptExpressionConfig {
+            ensure_checksum: true,
+            ..ScriptExpressionConfig::default()
+        };
+
+        for input in ["raw(deadbeef)", "raw(deadbeef)#89f8spxm"] {
+            assert_eq!(
+                script_expression(input, &config),
+                Ok("raw(deadbeef)#89f8spxm".to_string())
+            );
+        }
+        assert_eq!(
+            script_expression("raw(deadbeef)#00000000", &config),
+            Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                "checksum verification failed!"
+            )
+            .with_span(14..22))
+        );
+        assert!(script_expression("raw(deadbeef)#89f8", &config).is_err());
+    }
+
+    #[test]
+    fn test_script_expression_multiple_checksum_separators() {
+        for config in [
+            &CONFIG_WITH_FALSE_COMPUTE_AND_VERIFY,
+            &CONFIG_WITH_TRUE_VERIFY,
+        ] {
+            assert_eq!(
+                script_expression("raw(deadbeef)#abc#def", config),
+                Err(ParsingError::with_kind(
+                    ErrorKind::InvalidChecksum,
+                    "the script expression has 2 checksum separators '#', only one is allowed"
+                )
+                .with_span(17..18))
+            );
+        }
+    }
+
     #[test]
     fn test_script_expression_compute_and_verify() {
         get_cmd()
@@ -641,7 +1420,776 @@ mod tests {
             .assert()
             .failure()
             .stderr(
-                "Parsing error: use only '--verify-checksum' or '--compute-checksum', not both\n",
+                "Parsing error [E000]: use only '--verify-checksum' or '--compute-checksum', not both\n",
+            );
+    }
+
+    #[test]
+    fn test_script_expression_disassemble() {
+        get_cmd()
+            .args([
+                "script-expression",
+                "--disassemble",
+                "raw(76a914751e76e8199196d454941c45d1b3a323f1433bd688ac)",
+            ])
+            .assert()
+            .success()
+            .stdout("OP_DUP OP_HASH160 751e76e8199196d454941c45d1b3a323f1433bd6 OP_EQUALVERIFY OP_CHECKSIG\n");
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--disassemble",
+                "--verify-checksum",
+                "pk(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)#gn28ywm7",
+            ])
+            .assert()
+            .success()
+            .stdout(
+                "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 OP_CHECKSIG\n",
+            );
+
+        get_cmd()
+            .args(["script-expression", "--disassemble", "raw(6a04dead)"])
+            .assert()
+            .failure()
+            .stderr(
+                "Parsing error [E000]: Script contains a truncated data push at byte offset 1\n",
             );
+
+        get_cmd()
+            .args(["script-expression", "--disassemble", "pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/*)"])
+            .assert()
+            .failure()
+            .stderr("Parsing error [E000]: Key expression is ranged (contains a wildcard), a derivation index is required\n");
+    }
+
+    #[test]
+    fn test_script_expression_template() {
+        let alice = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let bob = "[deadbeef/0h]xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*";
+        let substituted = format!("sh(multi(2,{alice},{bob}))");
+        let expected = script_expression(&substituted, &CONFIG_WITH_TRUE_COMPUTE).unwrap();
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "sh(multi(2,$alice,$bob))#ignored1",
+                "--key",
+                &format!("alice={alice}"),
+                "--key",
+                &format!("bob={bob}"),
+            ])
+            .assert()
+            .success()
+            .stdout(format!("{expected}\n"));
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "sh(multi(2,$alice,$carol))",
+                "--key",
+                &format!("alice={alice}"),
+            ])
+            .assert()
+            .failure()
+            .stderr("Parsing error [E000]: Placeholder '$carol' has no value. Provide it using '--key carol=KEY'.\n");
+
+        get_cmd()
+            .args(["script-expression", "pk($alice)", "--key", "alice=02aa"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_script_expression_first_address() {
+        get_cmd()
+            .args([
+                "script-expression",
+                "--first-address",
+                "pkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+            ])
+            .assert()
+            .success()
+            .stdout("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH\n");
+        get_cmd()
+            .args([
+                "script-expression",
+                "--first-address",
+                "--explorer-url",
+                "mempool",
+                "pkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+            ])
+            .assert()
+            .success()
+            .stdout("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH https://mempool.space/address/1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH\n");
+        for (script, address) in [
+            (
+                "wpkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            // the first receive address of the BIP 86 test vectors
+            (
+                "tr(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+        ] {
+            get_cmd()
+                .args(["script-expression", "--first-address", script])
+                .assert()
+                .success()
+                .stdout(format!("{address}\n"));
+        }
+
+        // BIP 32 test vector 1 chain m/0h/1, its first address is derived at m/0h/1/0
+        let ranged = "pkh(xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs/*)";
+        let expected = script_to_address(
+            &compile_script_expression(
+                "pkh(xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ/0)",
+                None,
+            )
+            .unwrap(),
+            Network::Mainnet,
+        )
+        .unwrap();
+        get_cmd()
+            .args(["script-expression", "--first-address", ranged])
+            .assert()
+            .success()
+            .stdout(format!("{expected}\n"));
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--first-address",
+                "pk(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_script_expression_non_ascii() {
+        // the non-ASCII characters are reported as errors, the multibyte characters are not split
+        get_cmd()
+            .args(["script-expression", "pk(č)"])
+            .assert()
+            .failure()
+            .stderr("Parsing error [E012]: Invalid character 'č' in the key of the key expression 'č'\n");
+        get_cmd()
+            .args(["script-expression", "raw(deadbeefč)"])
+            .assert()
+            .failure()
+            .stderr("Parsing error [E012]: raw function argument 'deadbeefč' is not a valid hexadecimal string!\n  raw(deadbeefč)\n              ^\n");
+    }
+
+    #[test]
+    fn test_script_expression_timelock_on_its_own() {
+        // a timelock without any key would be spendable by anyone
+        for (script, name) in [("after(1)", "after"), ("older(144)", "older")] {
+            get_cmd()
+                .args(["script-expression", "--compute-checksum", script])
+                .assert()
+                .failure()
+                .stderr(format!(
+                    "Parsing error [E000]: {}\n",
+                    script_top_level_unsupported_err(name)
+                ));
+        }
+        for (args, redeem_script) in [
+            (&["--compute-checksum", "sh(after(1))"][..], "after(1)"),
+            (&["--first-address", "sh(after(1))"], "after(1)"),
+            (&["--psbt-output", "0", "sh(older(144))"], "older(144)"),
+        ] {
+            get_cmd()
+                .arg("script-expression")
+                .args(args)
+                .assert()
+                .failure()
+                .stderr(format!(
+                    "Parsing error [E000]: {}\n",
+                    script_sh_unsupported_arg_err(redeem_script)
+                ));
+        }
+    }
+
+    #[test]
+    fn test_script_expression_verify_origin() {
+        // BIP 32 test vector 1, master key and chain m/0h
+        let master_xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+        let key = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+        let script = format!(
+            "sh(multi(1,[3442193e/0h]{key}/0/*,0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798))"
+        );
+
+        get_cmd()
+            .args(["script-expression", "--verify-origin", master_xprv, &script])
+            .assert()
+            .success()
+            .stdout(format!("{script}\n"));
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--verify-origin",
+                master_xprv,
+                &script.replace("/0h]", "/1h]"),
+            ])
+            .assert()
+            .failure();
+
+        get_cmd()
+            .args(["script-expression", "--verify-origin", "deadbeef", &script])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_script_expression_policy_file() {
+        // BIP 32 test vector 1, master key and chain m/0h
+        let master_xprv = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+        let master_xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let key = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+        let script = format!(
+            "sh(multi(1,[3442193e/0h]{key}/0/*,0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798))"
+        );
+        let path = std::env::temp_dir().join(format!("bip380-policy-file-{}", std::process::id()));
+        let path = path.to_str().expect("the temporary path is valid UTF-8");
+
+        std::fs::write(
+            path,
+            format!("# vendor keys\nrequire 3442193E\nrequire {key}\nforbid deadbeef\n"),
+        )
+        .expect("the policy file is written");
+        get_cmd()
+            .args(["script-expression", "--policy-file", path, &script])
+            .assert()
+            .success()
+            .stdout(format!("{script}\n"));
+
+        std::fs::write(
+            path,
+            format!("require deadbeef\nforbid {master_xpub}\nforbid {key}\n"),
+        )
+        .expect("the policy file is written");
+        get_cmd()
+            .args([
+                "script-expression",
+                "--policy-file",
+                path,
+                "--no-secret-warning",
+                &format!("multi(1,{master_xprv},{key})"),
+            ])
+            .assert()
+            .failure()
+            .stderr(format!(
+                "Parsing error [E009]: The descriptor violates the key policy of '{path}':\n\
+                the required key 'deadbeef' does not appear in the descriptor\n\
+                the forbidden key '{master_xpub}' appears as the key 1 of the descriptor\n\
+                the forbidden key '{key}' appears as the key 2 of the descriptor\n"
+            ));
+
+        std::fs::remove_file(path).expect("the policy file is removed");
+    }
+
+    #[test]
+    fn test_script_expression_network_mixing() {
+        let xpub = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*";
+        let tpub = "tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp/*";
+
+        get_cmd()
+            .args(["script-expression", &format!("multi(1,{xpub},{tpub})")])
+            .assert()
+            .failure()
+            .stderr(format!(
+                "Parsing error [E004]: Key '{tpub}' is a testnet key, but the descriptor network is mainnet.\n"
+            ));
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--network",
+                "testnet",
+                &format!("pkh({xpub})"),
+            ])
+            .assert()
+            .failure()
+            .stderr(format!(
+                "Parsing error [E004]: Key '{xpub}' is a mainnet key, but the selected network is testnet.\n"
+            ));
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--network",
+                "testnet",
+                &format!("sh(multi(1,{tpub},{tpub}))"),
+            ])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_script_expression_normalize() {
+        let upper = "pk(0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798)";
+        let lower = "pk(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)";
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--compute-checksum",
+                "--normalize",
+                upper,
+            ])
+            .assert()
+            .success()
+            .stdout(format!("{lower}#gn28ywm7\n"));
+
+        let upper_with_lower_checksum = format!("{upper}#gn28ywm7");
+        get_cmd()
+            .args([
+                "script-expression",
+                "--verify-checksum",
+                "--normalize",
+                &upper_with_lower_checksum,
+            ])
+            .assert()
+            .success();
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--verify-checksum",
+                &upper_with_lower_checksum,
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_script_expression_hardened_wildcard_policy() {
+        let script = "pkh([deadbeef/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3/*h)";
+
+        get_cmd()
+            .args(["script-expression", script])
+            .assert()
+            .success();
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--hardened-wildcard",
+                "xprv-only",
+                script,
+            ])
+            .assert()
+            .failure();
+
+        get_cmd()
+            .args(["script-expression", "--hardened-wildcard", "forbid", script])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_script_expression_verify_after() {
+        let config = ScriptExpressionConfig {
+            compute_checksum: true,
+            verify_after: true,
+            ..ScriptExpressionConfig::default()
+        };
+
+        assert_eq!(
+            script_expression("raw(deadbeef)#00000000", &config),
+            Ok("raw(deadbeef)#89f8spxm".to_string())
+        );
+        assert!(verify_computed_checksum("raw(deadbeef)#89f8spxm").is_ok());
+        assert_eq!(
+            verify_computed_checksum("raw(deadbeef)#00000000")
+                .unwrap_err()
+                .kind,
+            ErrorKind::ChecksumMismatch
+        );
+    }
+
+    #[test]
+    fn test_script_expression_whitespace_policy() {
+        let config = |whitespace| ScriptExpressionConfig {
+            compute_checksum: true,
+            whitespace,
+            ..ScriptExpressionConfig::default()
+        };
+
+        assert!(script_expression("raw(dead\tbeef)", &config(WhitespacePolicy::Preserve)).is_err());
+        assert_eq!(
+            script_expression("raw(dead\tbeef)", &config(WhitespacePolicy::Strict)),
+            Err(ParsingError::new(
+                "script contains whitespace other than spaces at position 8, which is not allowed with '--whitespace strict'"
+            ))
+        );
+        assert_eq!(
+            script_expression("raw(dead\tbeef)", &config(WhitespacePolicy::Normalize)),
+            script_expression("raw(dead beef)", &config(WhitespacePolicy::Preserve))
+        );
+        assert_eq!(
+            script_expression("raw(dead beef)", &config(WhitespacePolicy::Strict)),
+            Ok("raw(dead beef)#v63m402s".to_string())
+        );
+    }
+
+    #[test]
+    fn test_script_expression_output_template() {
+        get_cmd()
+            .args([
+                "script-expression",
+                "raw(deadbeef)",
+                "--compute-checksum",
+                "--template",
+                "{checksum} {script}",
+            ])
+            .assert()
+            .success()
+            .stdout("89f8spxm raw(deadbeef)\n");
+    }
+
+    #[test]
+    fn test_rewrite_descriptor_file() {
+        let path = std::env::temp_dir().join(format!("bip380-rewrite-{}", std::process::id()));
+        let output_path = path.with_extension("out");
+        let (path, output_path) = (path.to_str().unwrap(), output_path.to_str().unwrap());
+        std::fs::write(path, "raw(deadbeef)#00000000\n\nraw(deadbeef)#89f8spxm\n").unwrap();
+
+        get_cmd()
+            .args([
+                "script-expression",
+                "--rewrite",
+                path,
+                "--output-file",
+                output_path,
+            ])
+            .assert()
+            .success()
+            .stdout(format!(
+                "Rewrote 2 descriptors of '{path}' to '{output_path}', 1 of them changed\n"
+            ));
+        assert_eq!(
+            std::fs::read_to_string(output_path).unwrap(),
+            "raw(deadbeef)#89f8spxm\n\nraw(deadbeef)#89f8spxm\n"
+        );
+
+        // nothing is written if any of the descriptors is invalid
+        std::fs::write(path, "raw(deadbeef)\nraw(xyz)\n").unwrap();
+        get_cmd()
+            .args(["script-expression", "--rewrite", path])
+            .assert()
+            .failure();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "raw(deadbeef)\nraw(xyz)\n"
+        );
+
+        std::fs::write(path, "raw(deadbeef)\n").unwrap();
+        get_cmd()
+            .args(["script-expression", "--rewrite", path])
+            .assert()
+            .success();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "raw(deadbeef)#89f8spxm\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(output_path).unwrap();
+    }
+
+    #[test]
+    fn test_rewrite_ensure_checksum() {
+        let path = std::env::temp_dir().join(format!("bip380-ensure-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "raw(deadbeef)\nraw(deadbeef)#89f8spxm\n").unwrap();
+
+        // the operation is idempotent
+        for changed in [1, 0] {
+            get_cmd()
+                .args(["script-expression", "--ensure-checksum", "--rewrite", path])
+                .assert()
+                .success()
+                .stdout(format!(
+                    "Rewrote 2 descriptors of '{path}' to '{path}', {changed} of them changed\n"
+                ));
+            assert_eq!(
+                std::fs::read_to_string(path).unwrap(),
+                "raw(deadbeef)#89f8spxm\nraw(deadbeef)#89f8spxm\n"
+            );
+        }
+
+        // unlike --compute-checksum, a mismatching checksum is not replaced
+        std::fs::write(path, "raw(deadbeef)#00000000\n").unwrap();
+        get_cmd()
+            .args(["script-expression", "--ensure-checksum", "--rewrite", path])
+            .assert()
+            .failure();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "raw(deadbeef)#00000000\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_report_descriptor_file() {
+        let path = std::env::temp_dir().join(format!("bip380-report-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "# wallet backup\nraw(deadbeef)#89f8spxm\n\nraw(deadbeef)#00000000\npk(03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd)\nraw(xyz)\n",
+        )
+        .unwrap();
+
+        get_cmd()
+            .args(["script-expression", "--report", path])
+            .assert()
+            .success()
+            .stdout(format!(
+                "Report of '{path}': 4 descriptors, 2 valid, 2 invalid\npk: 1\nraw: 1\nLine 4: checksum verification failed!\nLine 6: raw function argument 'xyz' is not a valid hexadecimal string!\n"
+            ));
+
+        get_cmd()
+            .args(["script-expression", "--report", "missing-backup.txt"])
+            .assert()
+            .failure();
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_verify_addresses() {
+        let xpub = "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ";
+        let [first, second] = [0, 1].map(|index| {
+            script_to_address(
+                &compile_script_expression(&format!("pkh({xpub}/{index})"), None).unwrap(),
+                Network::Mainnet,
+            )
+            .unwrap()
+        });
+        let path = std::env::temp_dir().join(format!("bip380-addresses-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let descriptor = format!("pkh({xpub}/*)");
+
+        std::fs::write(path, format!("{first}:0\n\n{second}:1\n")).unwrap();
+        get_cmd()
+            .args(["script-expression", &descriptor, "--verify-addresses", path])
+            .assert()
+            .success()
+            .stdout(format!(
+                "All 2 addresses of '{path}' match the descriptor\n"
+            ));
+
+        std::fs::write(path, format!("{first}:0\n{first}:1\n")).unwrap();
+        get_cmd()
+            .args(["script-expression", &descriptor, "--verify-addresses", path])
+            .assert()
+            .failure()
+            .stderr(format!(
+                "Parsing error [E007]: 1 of 2 addresses of '{path}' do not match the descriptor:\nLine 2: the address '{first}' does not match '{second}' derived at index 1\n"
+            ));
+
+        std::fs::write(path, format!("{first}\n")).unwrap();
+        get_cmd()
+            .args(["script-expression", &descriptor, "--verify-addresses", path])
+            .assert()
+            .failure();
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_check_change() {
+        let xpub = "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ";
+        let key = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+        let descriptor = |branch: &str, threshold: u8| {
+            format!("sh(multi({threshold},[d34db33f/48h/0h/0h]{xpub}/{branch}/*,{key}))")
+        };
+
+        get_cmd()
+            .args([
+                "script-expression",
+                &descriptor("0", 1),
+                "--check-change",
+                &descriptor("1", 1),
+            ])
+            .assert()
+            .success()
+            .stdout(
+                "The change descriptor differs from the receive descriptor only in the branch of its 1 ranged keys\n",
+            );
+
+        get_cmd()
+            .args([
+                "script-expression",
+                &descriptor("0", 1),
+                "--check-change",
+                &descriptor("1", 2),
+            ])
+            .assert()
+            .failure()
+            .stderr(
+                "Parsing error [E010]: The change descriptor does not match the receive descriptor:\n\
+                '1' of the receive descriptor does not match '2' of the change descriptor, only '.../0/*' and '.../1/*' may differ\n",
+            );
+
+        for change in [
+            format!("pkh({xpub}/1/*)"),
+            descriptor("1", 1).replace("d34db33f", "deadbeef"),
+            descriptor("0", 1),
+            format!("{}#00000000", descriptor("1", 1)),
+        ] {
+            get_cmd()
+                .args([
+                    "script-expression",
+                    &descriptor("0", 1),
+                    "--check-change",
+                    &change,
+                ])
+                .assert()
+                .failure();
+        }
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let script = "pkh([DEADBEEF/44h/0h/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/0/*)";
+        let output = get_cmd()
+            .args(["script-expression", "--anonymize", script])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let anonymized = String::from_utf8(output.stdout).unwrap();
+        let anonymized = anonymized.trim_end();
+
+        assert!(anonymized.starts_with("pkh([00000001/44h/0h/0h]xpub"));
+        assert!(!anonymized.contains("xpub6ERApfZwUNrh"));
+        // the anonymized descriptor is valid, with its checksum recomputed
+        get_cmd()
+            .args(["script-expression", "--verify-checksum", anonymized])
+            .assert()
+            .success();
+        // and the same for every run
+        get_cmd()
+            .args(["script-expression", "--anonymize", script])
+            .assert()
+            .success()
+            .stdout(format!("{anonymized}\n"));
+    }
+
+    #[test]
+    fn test_find_fingerprint() {
+        let xpub = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+        // the master key of the fingerprint 3442193e (the test vector 1 of BIP 32)
+        let master = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let descriptors = [
+            format!("pkh([3442193E/44h/0h/0h]{xpub}/0/*)"),
+            format!("multi(1,[deadbeef/0h]{xpub},{master}/0,[3442193e/1h]{xpub})"),
+            format!("pk([deadbeef]{xpub})"),
+        ];
+
+        get_cmd()
+            .args(["script-expression", "--find-fingerprint", "3442193e", "-"])
+            .write_stdin(descriptors.join("\n"))
+            .assert()
+            .success()
+            .stdout(format!(
+                "{}: key 1\n{}: keys 2, 3\n{}: not found\n",
+                descriptors[0], descriptors[1], descriptors[2]
+            ));
+    }
+
+    #[test]
+    fn test_rotate_key() {
+        let old = "[deadbeef/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+        let new = "[3442193e/0h]xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let other = "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB";
+        let script = format!("sh(multi(2,{old}/0/*,{other}/0/*))");
+        let rotated = format!("sh(multi(2,{new}/0/*,{other}/0/*))");
+
+        get_cmd()
+            .args([
+                "script-expression",
+                &format!("{script}#{}", checksum_create(&script).unwrap()),
+                "--verify-checksum",
+                "--rotate-key",
+                &format!("{old}={new}"),
+            ])
+            .assert()
+            .success()
+            .stdout(format!(
+                "{rotated}#{}\n",
+                checksum_create(&rotated).unwrap()
+            ));
+
+        // the rotated descriptor is validated again, a testnet key does not fit
+        let testnet = "tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp";
+        let output = get_cmd()
+            .args([
+                "script-expression",
+                &script,
+                "--rotate-key",
+                &format!("{old}={testnet}"),
+            ])
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("Parsing error [E004]: The rotated descriptor is invalid: "));
+    }
+
+    #[test]
+    fn test_set_threshold() {
+        let keys = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600,03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let updated = format!("sh(multi(2,{keys}))");
+
+        get_cmd()
+            .args([
+                "script-expression",
+                &format!("sh(multi(1,{keys}))"),
+                "--set-threshold",
+                "2",
+            ])
+            .assert()
+            .success()
+            .stdout(format!(
+                "{updated}#{}\n",
+                checksum_create(&updated).unwrap()
+            ));
+        get_cmd()
+            .args([
+                "script-expression",
+                &format!("sh(multi(1,{keys}))"),
+                "--set-threshold",
+                "3",
+            ])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_psbt_output() {
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+
+        get_cmd()
+            .args([
+                "script-expression",
+                &format!("pkh([deadbeef/0h/1]{key})"),
+                "--psbt-output",
+                "0",
+            ])
+            .assert()
+            .success()
+            .stdout(format!(
+                r#"{{"bip32_derivs":[{{"pubkey":"{key}","master_fingerprint":"deadbeef","path":"m/0h/1"}}],"hex":"2202{key}0cdeadbeef000000800100000000"}}
+"#
+            ));
     }
 }
@@ -0,0 +1,315 @@
+use bip39::{Language, Mnemonic};
+
+use crate::structs::{
+    error_kind::ErrorKind,
+    mnemonic_language::MnemonicLanguage,
+    parsing_error::ParsingError,
+    seed_xor_config::{SeedXorConfig, SeedXorMode},
+};
+
+use super::utils::{
+    hexadecimal::encode_hex,
+    seed::{decode_entropy, parse_mnemonic},
+    shamir::random_bytes,
+};
+
+/// A seed or a share of it, given as a mnemonic or as its hex entropy.
+struct Entropy {
+    bytes: Vec<u8>,
+    /// The language of the mnemonic, none for the hex entropy.
+    language: Option<Language>,
+}
+
+/// Splits a BIP 39 seed into `SeedXOR` shares, or combines the shares back into the seed.
+///
+/// `SeedXOR` splits the entropy of the mnemonic into random shares of the same length, all of which are needed to
+/// recover it: all the shares but the last are random and the last one is the XOR of the entropy and all of them.
+/// Every share is a valid BIP 39 mnemonic of the same word count on its own, so it can be kept (and even used as a
+/// decoy wallet) the same way as any other mnemonic, and the seed can be recovered with pen and paper.
+///
+/// With `--split {n}`, the input is the mnemonic (or its hex entropy, 128 to 256 bits) and the output are the
+/// mnemonics of the `n` shares, one per line. With `--combine`, the input are the shares separated by commas and
+/// the output is the mnemonic of the seed. With `--hex`, the hex entropy is printed instead of the mnemonics.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - A mnemonic has an invalid word count, contains unknown words or its checksum does not match,
+/// - The hex entropy is not 32, 40, 48, 56 or 64 hex digits long,
+/// - Fewer than two shares are combined or the shares are not of the same length,
+/// - The random shares cannot be generated.
+pub fn seed_xor(input: &str, config: &SeedXorConfig) -> Result<String, ParsingError> {
+    let (seed, shares) = seed_and_shares(input, config)?;
+    match config.mode {
+        SeedXorMode::Split(_) => Ok(shares.join("\n")),
+        SeedXorMode::Combine => Ok(seed),
+    }
+}
+
+/// Splits or combines the shares the same way as [`seed_xor`], returning the `seed` (its mnemonic, or with
+/// `--hex` its entropy), its `entropy` in hexadecimal, the `shares` (separated by commas, the same as the input of
+/// `--combine`) and their `count` for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`seed_xor`].
+pub fn seed_xor_fields(
+    input: &str,
+    config: &SeedXorConfig,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let (seed, shares) = seed_and_shares(input, config)?;
+    let entropy = match config.mode {
+        SeedXorMode::Split(_) => parse_entropy(input, config)?.bytes,
+        SeedXorMode::Combine => combine_shares(&parse_shares(input, config)?)?,
+    };
+
+    Ok(vec![
+        ("seed", seed),
+        ("entropy", encode_hex(&entropy)),
+        ("shares", shares.join(",")),
+        ("count", shares.len().to_string()),
+    ])
+}
+
+/// Returns the seed and its shares, both as printed.
+fn seed_and_shares(
+    input: &str,
+    config: &SeedXorConfig,
+) -> Result<(String, Vec<String>), ParsingError> {
+    match config.mode {
+        SeedXorMode::Split(count) => {
+            let seed = parse_entropy(input, config)?;
+            let language = output_language(config, seed.language);
+            let shares = split_entropy(&seed.bytes, count)?
+                .iter()
+                .map(|share| encode_entropy(share, language, config.hex))
+                .collect::<Result<Vec<String>, ParsingError>>()?;
+            Ok((encode_entropy(&seed.bytes, language, config.hex)?, shares))
+        }
+        SeedXorMode::Combine => {
+            let shares = parse_shares(input, config)?;
+            let language = output_language(config, shares[0].language);
+            let seed = encode_entropy(&combine_shares(&shares)?, language, config.hex)?;
+            let shares = shares
+                .iter()
+                .map(|share| encode_entropy(&share.bytes, language, config.hex))
+                .collect::<Result<Vec<String>, ParsingError>>()?;
+            Ok((seed, shares))
+        }
+    }
+}
+
+/// Returns the language of the printed mnemonics: the given one, or the one of the input mnemonic, or English.
+fn output_language(config: &SeedXorConfig, input_language: Option<Language>) -> Language {
+    config
+        .language
+        .map(MnemonicLanguage::wordlist)
+        .or(input_language)
+        .unwrap_or_default()
+}
+
+fn parse_entropy(input: &str, config: &SeedXorConfig) -> Result<Entropy, ParsingError> {
+    let input = input.trim();
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(Entropy {
+            bytes: decode_entropy(input)?,
+            language: None,
+        });
+    }
+
+    let mnemonic = parse_mnemonic(input, config.language)?;
+    Ok(Entropy {
+        bytes: mnemonic.to_entropy(),
+        language: Some(mnemonic.language()),
+    })
+}
+
+fn parse_shares(input: &str, config: &SeedXorConfig) -> Result<Vec<Entropy>, ParsingError> {
+    let shares = input
+        .split(',')
+        .map(|share| parse_entropy(share, config))
+        .collect::<Result<Vec<Entropy>, ParsingError>>()?;
+
+    if shares.len() < 2 {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            "At least two shares separated by commas are needed to recover the seed",
+        ));
+    }
+    if shares
+        .iter()
+        .any(|share| share.bytes.len() != shares[0].bytes.len())
+    {
+        return Err(ParsingError::with_kind(ErrorKind::InvalidShare,
+            "The shares are not of the same length, all the shares of a seed have the same number of words",
+        ));
+    }
+
+    Ok(shares)
+}
+
+/// Returns the `count` shares of the entropy: the random ones and the last one, which XORs them into the entropy.
+fn split_entropy(entropy: &[u8], count: u8) -> Result<Vec<Vec<u8>>, ParsingError> {
+    let mut shares = (1..count)
+        .map(|_| random_bytes(entropy.len()))
+        .collect::<Result<Vec<Vec<u8>>, ParsingError>>()?;
+
+    let last = shares
+        .iter()
+        .fold(entropy.to_vec(), |last, share| xor(&last, share));
+    shares.push(last);
+    Ok(shares)
+}
+
+fn combine_shares(shares: &[Entropy]) -> Result<Vec<u8>, ParsingError> {
+    let (first, rest) = shares
+        .split_first()
+        .ok_or_else(|| ParsingError::with_kind(ErrorKind::InvalidShare, "No shares to combine"))?;
+    Ok(rest
+        .iter()
+        .fold(first.bytes.clone(), |seed, share| xor(&seed, &share.bytes)))
+}
+
+fn xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    left.iter().zip(right).map(|(l, r)| l ^ r).collect()
+}
+
+fn encode_entropy(entropy: &[u8], language: Language, hex: bool) -> Result<String, ParsingError> {
+    if hex {
+        return Ok(encode_hex(entropy));
+    }
+    Mnemonic::from_entropy_in(language, entropy)
+        .map(|mnemonic| mnemonic.to_string())
+        .map_err(|err| {
+            ParsingError::with_kind(ErrorKind::InvalidSeed, &format!("Invalid entropy: {err}"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_cmd;
+
+    const LEGAL_MNEMONIC: &str =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    const LETTER_MNEMONIC: &str =
+        "letter advice cage absurd amount doctor acoustic avoid letter advice cage above";
+
+    fn config(mode: SeedXorMode) -> SeedXorConfig {
+        SeedXorConfig {
+            mode,
+            hex: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_combine_shares() {
+        // 7f.. XOR 80.. is ff..
+        let combine = config(SeedXorMode::Combine);
+        assert_eq!(
+            seed_xor(&format!("{LEGAL_MNEMONIC}, {LETTER_MNEMONIC}"), &combine),
+            Ok("zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong".to_string())
+        );
+        assert_eq!(
+            seed_xor(&format!("{},{LETTER_MNEMONIC}", "7f".repeat(16)), &combine),
+            Ok("zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong".to_string())
+        );
+        assert_eq!(
+            seed_xor(
+                &format!("{LEGAL_MNEMONIC},{LEGAL_MNEMONIC}"),
+                &SeedXorConfig {
+                    hex: true,
+                    ..combine
+                }
+            ),
+            Ok("00".repeat(16))
+        );
+    }
+
+    #[test]
+    fn test_split_recombines() {
+        for input in [LEGAL_MNEMONIC.to_string(), "ab".repeat(32)] {
+            let shares = seed_xor(&input, &config(SeedXorMode::Split(3))).unwrap();
+            let shares: Vec<&str> = shares.lines().collect();
+            assert_eq!(shares.len(), 3);
+            assert!(shares
+                .iter()
+                .all(|share| parse_mnemonic(share, None).is_ok()));
+
+            let seed = seed_xor(&shares.join(","), &config(SeedXorMode::Combine)).unwrap();
+            assert_eq!(
+                parse_mnemonic(&seed, None).unwrap().to_entropy(),
+                parse_entropy(&input, &config(SeedXorMode::Combine))
+                    .unwrap()
+                    .bytes
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_keeps_language() {
+        let spanish = format!("{}abierto", "ábaco ".repeat(11));
+        let shares = seed_xor(&spanish, &config(SeedXorMode::Split(2))).unwrap();
+        assert!(shares
+            .lines()
+            .all(|share| parse_mnemonic(share, Some(MnemonicLanguage::Spanish)).is_ok()));
+    }
+
+    #[test]
+    fn test_seed_xor_errors() {
+        let combine = config(SeedXorMode::Combine);
+        for input in [
+            LEGAL_MNEMONIC.to_string(),
+            format!("{LEGAL_MNEMONIC},{}", "00".repeat(32)),
+            format!("{LEGAL_MNEMONIC},,{LETTER_MNEMONIC}"),
+            format!("{LEGAL_MNEMONIC},{}", "00".repeat(15)),
+        ] {
+            assert!(seed_xor(&input, &combine).is_err(), "{input}");
+        }
+        assert!(seed_xor("abandon abandon", &config(SeedXorMode::Split(2))).is_err());
+        assert!(seed_xor(&format!("{LEGAL_MNEMONIC},000"), &combine).is_err());
+    }
+
+    #[test]
+    fn test_odd_length_entropy() {
+        for input in ["0", "000", &"7f".repeat(16)[1..]] {
+            get_cmd()
+                .args(["seed-xor", "--split", "2", input])
+                .assert()
+                .failure()
+                .stderr(format!(
+                    "Parsing error [E016]: Invalid entropy '{input}', expected 32, 40, 48, 56 or 64 hex digits (128 to 256 bits)\n"
+                ));
+        }
+    }
+
+    #[test]
+    fn test_seed_xor_fields() {
+        assert_eq!(
+            seed_xor_fields(
+                &format!("{LEGAL_MNEMONIC},{LETTER_MNEMONIC}"),
+                &config(SeedXorMode::Combine)
+            ),
+            Ok(vec![
+                (
+                    "seed",
+                    "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong".to_string()
+                ),
+                ("entropy", "ff".repeat(16)),
+                ("shares", format!("{LEGAL_MNEMONIC},{LETTER_MNEMONIC}")),
+                ("count", "2".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_seed_xor_stdin() {
+        get_cmd()
+            .args(["seed-xor", "--combine", "--hex", "--no-secret-warning", "-"])
+            .write_stdin(format!("{LEGAL_MNEMONIC},{LETTER_MNEMONIC}\n"))
+            .assert()
+            .success()
+            .stdout(format!("{}\n", "ff".repeat(16)));
+    }
+}
@@ -0,0 +1,663 @@
+#[cfg(feature = "serve")]
+use crate::structs::serve_config::{ServeConfig, ServeListener};
+use crate::{
+    parsers::arg_parser::{parse_args, parse_args_with_inputs, INPUT_FILE_PREFIX},
+    structs::parsing_error::ParsingError,
+    utils::{error_messages::unsupported_request_arg_err, signals::shutdown_requested},
+};
+
+#[cfg(feature = "http")]
+use super::http_api::serve_http;
+
+/// How long to wait for a new connection before checking whether a signal requested the shutdown.
+#[cfg(feature = "serve")]
+pub(crate) const ACCEPT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// How long a connection may stay silent (or not take the response) before it is closed.
+#[cfg(feature = "serve")]
+pub(crate) const CONNECTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Upper bound of a single request, the inputs are never anywhere near this long.
+#[cfg(feature = "serve")]
+pub(crate) const MAX_REQUEST_LENGTH: usize = 1 << 20;
+
+/// How many connections are handled at once, the listeners close the ones over the limit right away.
+#[cfg(feature = "serve")]
+pub(crate) const MAX_CONNECTIONS: usize = 64;
+
+/// Bounds the number of the connections handled at once, each on its own thread.
+#[cfg(feature = "serve")]
+pub(crate) struct ConnectionLimit {
+    active: std::sync::atomic::AtomicUsize,
+    max: usize,
+}
+
+#[cfg(feature = "serve")]
+impl ConnectionLimit {
+    pub(crate) const fn new(max: usize) -> ConnectionLimit {
+        ConnectionLimit {
+            active: std::sync::atomic::AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    /// Handles the connection on a new thread, or drops (and so closes) it if the limit is reached.
+    /// Returns whether the connection is handled.
+    pub(crate) fn spawn<S, F>(&'static self, stream: S, handle: F) -> bool
+    where
+        S: Send + 'static,
+        F: FnOnce(S) + Send + 'static,
+    {
+        use std::sync::atomic::Ordering;
+
+        /// Frees the slot of the connection even if its handler panics.
+        struct Slot(&'static std::sync::atomic::AtomicUsize);
+        impl Drop for Slot {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        if self
+            .active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                (active < self.max).then_some(active + 1)
+            })
+            .is_err()
+        {
+            return false;
+        }
+        let slot = Slot(&self.active);
+        std::thread::spawn(move || {
+            let _slot = slot;
+            handle(stream);
+        });
+        true
+    }
+}
+
+/// The flags a request sent to the server may use, the ones only shaping the response. Every other flag is refused,
+/// e.g. the ones reading from the server's own standard input, files, environment or terminal, writing its files,
+/// not producing a response or reaching out to Bitcoin Core, so a new flag must be allowed explicitly.
+const SERVER_REQUEST_FLAGS: [&str; 83] = [
+    "--account",
+    "--account-descriptors",
+    "--anonymize",
+    "--any",
+    "--blockheight",
+    "--canonical-key",
+    "--check",
+    "--check-bip67",
+    "--check-change",
+    "--coins",
+    "--combine",
+    "--compute-checksum",
+    "--count",
+    "--decode",
+    "--deny",
+    "--dice",
+    "--disassemble",
+    "--encode",
+    "--ensure-checksum",
+    "--evaluate",
+    "--explorer-url",
+    "--find-fingerprint",
+    "--first-address",
+    "--from-entropy",
+    "--from-seedqr",
+    "--group",
+    "--group-threshold",
+    "--hardened-wildcard",
+    "--hex",
+    "--ignore-checksum-case",
+    "--index",
+    "--iteration-exponent",
+    "--join",
+    "--key",
+    "--key-context",
+    "--keys",
+    "--label",
+    "--language",
+    "--lenient",
+    "--limit",
+    "--max-multi-keys",
+    "--max-nesting",
+    "--max-origin-depth",
+    "--max-script-size",
+    "--merkle-root",
+    "--name",
+    "--network",
+    "--no-cache",
+    "--no-private",
+    "--no-secret-warning",
+    "--normalize",
+    "--notation",
+    "--output",
+    "--path",
+    "--plan",
+    "--psbt-output",
+    "--purpose",
+    "--relative-to",
+    "--rotate-key",
+    "--script",
+    "--select",
+    "--set-threshold",
+    "--show-intermediate",
+    "--skip",
+    "--sort",
+    "--sortedmulti-order",
+    "--split",
+    "--split-at-hardened",
+    "--stats",
+    "--target",
+    "--template",
+    "--threshold",
+    "--timing",
+    "--to-entropy",
+    "--to-seedqr",
+    "--type",
+    "--ur",
+    "--ur-max-fragment",
+    "--verify-after",
+    "--verify-checksum",
+    "--verify-origin",
+    "--whitespace",
+    "--words",
+];
+
+#[cfg(feature = "serve")]
+/// Keeps answering the requests sent to the configured listener, until the process is killed or a signal requests
+/// the shutdown (see [`install_shutdown_handlers`](crate::utils::signals::install_shutdown_handlers)).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the listener cannot be bound, or the HTTP API is not compiled in.
+pub fn serve(config: &ServeConfig) -> Result<(), ParsingError> {
+    match &config.listener {
+        ServeListener::Socket(path) => serve_socket(path),
+        ServeListener::Http(address) => serve_http(address),
+    }
+}
+
+#[cfg(all(feature = "serve", not(feature = "http")))]
+fn serve_http(_address: &str) -> Result<(), ParsingError> {
+    Err(ParsingError::new(
+        "The HTTP API is not available, the utility was built without the 'http' feature",
+    ))
+}
+
+/// Keeps answering the requests sent to the Unix socket at the path.
+///
+/// Every connection is handled on its own thread and may send any number of requests, one per line, each at most
+/// [`MAX_REQUEST_LENGTH`] bytes long. At most [`MAX_CONNECTIONS`] are handled at once, and a connection is closed once
+/// it sends no request for [`CONNECTION_TIMEOUT`].
+/// See [`handle_request`] for the format of the requests and responses.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the socket cannot be bound (e.g. the path already exists).
+#[cfg(all(feature = "serve", unix))]
+fn serve_socket(path: &str) -> Result<(), ParsingError> {
+    use std::{io::ErrorKind, os::unix::net::UnixListener, thread};
+
+    static CONNECTIONS: ConnectionLimit = ConnectionLimit::new(MAX_CONNECTIONS);
+
+    let listener = UnixListener::bind(path)
+        .map_err(|e| ParsingError::new(&format!("Could not bind the socket '{path}': {e}")))?;
+    // the listener is polled, so that a requested shutdown is noticed between the connections
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| ParsingError::new(&format!("Could not bind the socket '{path}': {e}")))?;
+
+    while !shutdown_requested() {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_read_timeout(Some(CONNECTION_TIMEOUT));
+                let _ = stream.set_write_timeout(Some(CONNECTION_TIMEOUT));
+                CONNECTIONS.spawn(stream, handle_connection);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+            Err(e) => eprintln!("Error accepting a connection: {e}"),
+        }
+    }
+
+    // the path has to be free for the socket to be bound again
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+/// Unix sockets are not available on this platform.
+///
+/// # Errors
+///
+/// Always returns a [`ParsingError`].
+#[cfg(all(feature = "serve", not(unix)))]
+fn serve_socket(_path: &str) -> Result<(), ParsingError> {
+    Err(ParsingError::new(
+        "The serve sub-command is only supported on Unix",
+    ))
+}
+
+#[cfg(all(feature = "serve", unix))]
+fn handle_connection(stream: std::os::unix::net::UnixStream) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(reader);
+    let mut writer = stream;
+
+    loop {
+        let mut line = String::new();
+        // a line over the limit is not read any further, the connection is closed after the error
+        match (&mut reader)
+            .take(MAX_REQUEST_LENGTH as u64 + 1)
+            .read_line(&mut line)
+        {
+            Ok(0) | Err(_) => return,
+            Ok(length) if length > MAX_REQUEST_LENGTH => {
+                let _ = writer.write_all(
+                    format!("error: The request is longer than {MAX_REQUEST_LENGTH} bytes\n\n")
+                        .as_bytes(),
+                );
+                return;
+            }
+            Ok(_) => {}
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        if writer.write_all(handle_request(&line).as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(feature = "serve")]
+/// Answers a single request, which is a command line without the program name,
+/// e.g. `derive-key 000102030405060708090a0b0c0d0e0f --path 0h/1`.
+///
+/// Arguments are separated by whitespace, an argument containing whitespace (such as a mnemonic)
+/// can be enclosed in double quotes. Reading from the standard input, `--watch`, `--input-file`, `--help`, `--import-to-core`
+/// and the passphrase sources of wallet-init are not supported.
+///
+/// The response consists of the output of every input on its own line, or of a single
+/// `error: {message}` line if any of the inputs fails, and is terminated by an empty line.
+#[must_use]
+pub fn handle_request(request: &str) -> String {
+    match run_request(request) {
+        Ok(outputs) => {
+            outputs
+                .into_iter()
+                .fold(String::new(), |mut response, output| {
+                    response.push_str(&output);
+                    response.push('\n');
+                    response
+                })
+                + "\n"
+        }
+        Err(err) => format!("error: {}\n\n", err.message),
+    }
+}
+
+#[cfg(feature = "serve")]
+fn run_request(request: &str) -> Result<Vec<String>, ParsingError> {
+    run_args(&split_request(request)?, None)
+}
+
+/// Runs the sub-command given by the arguments (without the program name) on all of its inputs,
+/// refusing the arguments that make no sense for a server.
+///
+/// The inputs are the positional arguments, unless the input is given on its own (as by the JSON requests), which
+/// is then taken as it is, even if it starts with `--`, `@` or is `-`, and the arguments must be only the options.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if an argument is not supported, parsing the arguments fails or any of the inputs fails.
+pub(crate) fn run_args(args: &[String], input: Option<&str>) -> Result<Vec<String>, ParsingError> {
+    let unsupported_arg = args.first().filter(|arg| *arg == "serve").or_else(|| {
+        // an '@path' input would send the content of the server's file back to the client
+        args.iter().find(|arg| {
+            *arg == "-"
+                || arg.starts_with(INPUT_FILE_PREFIX)
+                || (arg.starts_with("--") && !SERVER_REQUEST_FLAGS.contains(&arg.as_str()))
+        })
+    });
+    if let Some(arg) = unsupported_arg {
+        return Err(ParsingError::new(&unsupported_request_arg_err(arg)));
+    }
+
+    let args = args.iter().map(String::as_str).collect();
+    let (command, global_config, inputs) = match input {
+        Some(input) => parse_args_with_inputs(args, vec![input.to_string()])?,
+        None => parse_args(args)?,
+    };
+    let mut outputs = inputs
+        .map(|input| command.output(&input, &global_config))
+        .collect::<Result<Vec<String>, ParsingError>>()?;
+    if global_config.check {
+        outputs.clear();
+    } else if global_config.sort {
+        outputs.sort();
+    }
+
+    Ok(outputs)
+}
+
+#[cfg(feature = "serve")]
+/// Splits the request into arguments on whitespace, keeping double quoted arguments together.
+///
+/// Only a double quote at the start of an argument opens a quoted argument, so the single quote
+/// (used as the hardened indicator in paths) and double quotes within an argument are kept as they are.
+fn split_request(request: &str) -> Result<Vec<String>, ParsingError> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+
+    for c in request.chars() {
+        match c {
+            '"' if quoted => quoted = false,
+            '"' if current.is_none() => {
+                quoted = true;
+                current = Some(String::new());
+            }
+            c if c.is_whitespace() && !quoted => args.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quoted {
+        return Err(ParsingError::new(
+            "Unterminated double quote in the request",
+        ));
+    }
+    args.extend(current);
+
+    Ok(args)
+}
+
+#[cfg(all(test, feature = "serve"))]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::utils::info_messages::HELP_MESSAGE;
+
+    #[test]
+    fn test_split_request() {
+        assert_eq!(
+            split_request("wallet-init  \"abandon abandon about\" --purpose 44"),
+            Ok(vec![
+                "wallet-init".to_string(),
+                "abandon abandon about".to_string(),
+                "--purpose".to_string(),
+                "44".to_string()
+            ])
+        );
+        assert_eq!(
+            split_request("derive-key xprv --path 0'/1h"),
+            Ok(vec![
+                "derive-key".to_string(),
+                "xprv".to_string(),
+                "--path".to_string(),
+                "0'/1h".to_string()
+            ])
+        );
+        assert!(split_request("key-expression \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_handle_request() {
+        #[cfg(feature = "key-expression")]
+        assert_eq!(
+            handle_request(
+                "key-expression 0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600"
+            ),
+            "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600\n\n"
+        );
+        assert!(handle_request("key-expression invalid").starts_with("error: "));
+        assert_eq!(
+            handle_request("key-expression -"),
+            format!("error: {}\n\n", unsupported_request_arg_err("-"))
+        );
+        assert_eq!(
+            handle_request("script-expression @/tmp/victim.txt"),
+            format!(
+                "error: {}\n\n",
+                unsupported_request_arg_err("@/tmp/victim.txt")
+            )
+        );
+        assert_eq!(
+            handle_request("script-expression --rewrite /tmp/victim.txt"),
+            format!("error: {}\n\n", unsupported_request_arg_err("--rewrite"))
+        );
+        assert_eq!(
+            handle_request("script-expression --output-file /tmp/victim.txt"),
+            format!(
+                "error: {}\n\n",
+                unsupported_request_arg_err("--output-file")
+            )
+        );
+        assert_eq!(
+            handle_request("script-expression --report /tmp/victim.txt"),
+            format!("error: {}\n\n", unsupported_request_arg_err("--report"))
+        );
+        assert_eq!(
+            handle_request("script-expression raw(deadbeef) --policy-file /etc/passwd"),
+            format!(
+                "error: {}\n\n",
+                unsupported_request_arg_err("--policy-file")
+            )
+        );
+        assert_eq!(
+            handle_request("script-expression raw(deadbeef) --verify-addresses /tmp/victim.txt"),
+            format!(
+                "error: {}\n\n",
+                unsupported_request_arg_err("--verify-addresses")
+            )
+        );
+        assert_eq!(
+            handle_request("serve --socket /tmp/other.sock"),
+            format!("error: {}\n\n", unsupported_request_arg_err("serve"))
+        );
+    }
+
+    /// The flags refused in the requests sent to the server, see [`SERVER_REQUEST_FLAGS`].
+    const REFUSED_REQUEST_FLAGS: [&str; 19] = [
+        "--watch",
+        "--input-file",
+        "--args-file",
+        "--help",
+        "--rpc",
+        "--import-to-core",
+        "--rpc-url",
+        "--rpc-cookie",
+        "--passphrase-env",
+        "--passphrase-file",
+        "--passphrase-prompt",
+        "--rewrite",
+        "--output-file",
+        "--report",
+        "--policy-file",
+        "--verify-addresses",
+        "--log-file",
+        "--socket",
+        "--http",
+    ];
+
+    /// Collects the flags documented in the help and the ones parsed by the sources, e.g.
+    /// `parse_boolean_flag(args, "sort")` or `parse_value_flags(args, &["skip", "limit"])`.
+    fn known_flags() -> BTreeSet<String> {
+        let mut flags: BTreeSet<String> = HELP_MESSAGE
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .filter(|word| word.starts_with("--") && word.len() > 2)
+            .map(ToString::to_string)
+            .collect();
+
+        let mut directories = vec![std::path::PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src"
+        ))];
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(directory).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+                let source = std::fs::read_to_string(&path).unwrap();
+                for call in ["_flag(args,", "_flags(args,", "_flags(\n"] {
+                    for (start, _) in source.match_indices(call) {
+                        // skips the patterns themselves, only the calls follow a function name
+                        if !source[..start].ends_with(|c: char| c.is_ascii_alphabetic()) {
+                            continue;
+                        }
+                        let rest = &source[start + call.len()..];
+                        // the names end with the arguments of the call
+                        let arguments = &rest[..rest.find(')').unwrap_or(rest.len())];
+                        flags.extend(
+                            arguments
+                                .split('"')
+                                .skip(1)
+                                .step_by(2)
+                                .filter(|name| {
+                                    name.starts_with(|c: char| c.is_ascii_lowercase())
+                                        && name.bytes().all(|byte| {
+                                            byte.is_ascii_lowercase()
+                                                || byte.is_ascii_digit()
+                                                || byte == b'-'
+                                        })
+                                })
+                                .map(|name| format!("--{name}")),
+                        );
+                    }
+                }
+            }
+        }
+        flags
+    }
+
+    #[test]
+    fn test_request_flags_classified() {
+        for flag in known_flags() {
+            assert!(
+                SERVER_REQUEST_FLAGS.contains(&flag.as_str())
+                    || REFUSED_REQUEST_FLAGS.contains(&flag.as_str()),
+                "the flag '{flag}' must be added to SERVER_REQUEST_FLAGS or REFUSED_REQUEST_FLAGS"
+            );
+        }
+
+        for flag in REFUSED_REQUEST_FLAGS {
+            assert!(!SERVER_REQUEST_FLAGS.contains(&flag), "{flag}");
+            assert_eq!(
+                run_args(&["script-expression".to_string(), flag.to_string()], None),
+                Err(ParsingError::new(&unsupported_request_arg_err(flag)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        use std::sync::mpsc;
+
+        static LIMIT: ConnectionLimit = ConnectionLimit::new(1);
+
+        let (release, released) = mpsc::channel::<()>();
+        let (done, finished) = mpsc::channel();
+        assert!(LIMIT.spawn(released, move |released| {
+            released.recv().unwrap();
+            done.send(()).unwrap();
+        }));
+        assert!(!LIMIT.spawn((), |()| {}));
+
+        release.send(()).unwrap();
+        finished.recv().unwrap();
+        // the slot is freed once the handler returns
+        while !LIMIT.spawn((), |()| {}) {
+            std::thread::yield_now();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_request_length_limit() {
+        use std::{
+            io::{Read, Write},
+            os::unix::net::UnixStream,
+            thread,
+        };
+
+        let (mut client, server) = UnixStream::pair().unwrap();
+        let handler = thread::spawn(move || handle_connection(server));
+
+        let mut request = format!("key-expression {}", "a".repeat(MAX_REQUEST_LENGTH));
+        request.push('\n');
+        // the handler stops reading the request, so the rest of it may not be sent at all
+        let _ = client.write_all(request.as_bytes());
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        handler.join().unwrap();
+
+        assert_eq!(
+            response,
+            format!("error: The request is longer than {MAX_REQUEST_LENGTH} bytes\n\n")
+        );
+    }
+
+    #[cfg(not(feature = "http"))]
+    #[test]
+    fn test_serve_http_without_feature() {
+        crate::test_utils::get_cmd()
+            .args(["serve", "--http", "127.0.0.1:0"])
+            .assert()
+            .failure();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[cfg(all(feature = "derive-key", feature = "serve"))]
+    fn test_serve_socket() {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            os::unix::net::UnixStream,
+            process::Command,
+            thread,
+            time::Duration,
+        };
+
+        let socket = std::env::temp_dir().join(format!("bip380-serve-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let mut server = Command::new(assert_cmd::cargo::cargo_bin(env!("CARGO_PKG_NAME")))
+            .args(["serve", "--socket", socket.to_str().unwrap()])
+            .spawn()
+            .unwrap();
+
+        let mut stream = (0..50)
+            .find_map(|_| {
+                thread::sleep(Duration::from_millis(100));
+                UnixStream::connect(&socket).ok()
+            })
+            .unwrap();
+        stream
+            .write_all(
+                b"derive-key 000102030405060708090a0b0c0d0e0f --path 0h\nkey-expression invalid\n",
+            )
+            .unwrap();
+
+        let lines: Vec<String> = BufReader::new(stream)
+            .lines()
+            .take(4)
+            .map(Result::unwrap)
+            .collect();
+        server.kill().unwrap();
+        server.wait().unwrap();
+        std::fs::remove_file(&socket).unwrap();
+
+        assert!(lines[0].starts_with("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw"));
+        assert_eq!(lines[1], "");
+        assert!(lines[2].starts_with("error: "));
+        assert_eq!(lines[3], "");
+    }
+}
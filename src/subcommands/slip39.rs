@@ -0,0 +1,757 @@
+use std::collections::BTreeMap;
+
+use bip32::{secp256k1::sha2::Sha256, Prefix, XPrv};
+
+use crate::structs::{
+    error_kind::ErrorKind,
+    parsing_error::ParsingError,
+    slip39_config::{Slip39Config, Slip39Group, Slip39Mode},
+};
+
+use super::utils::{
+    hexadecimal::{decode_hex, encode_hex},
+    shamir::{random_bytes, recover_secret, split_secret},
+    slip39_wordlist::SLIP39_WORDLIST,
+};
+
+/// The bits encoded by a single word.
+const RADIX_BITS: usize = 10;
+/// The words of the identifier, the extendable flag and the iteration exponent.
+const ID_EXP_LENGTH_WORDS: usize = 2;
+/// The words of the group and member indexes, thresholds and counts.
+const SHARE_PARAMS_LENGTH_WORDS: usize = 2;
+/// The words of the RS1024 checksum.
+const CHECKSUM_LENGTH_WORDS: usize = 3;
+/// The shortest master secret (128 bits), in bytes.
+const MIN_SECRET_LENGTH: usize = 16;
+/// The PBKDF2 iterations of all the rounds of the encryption together, multiplied by `2^e`.
+const BASE_ITERATION_COUNT: u32 = 10_000;
+/// The rounds of the Feistel network encrypting the master secret.
+const ROUND_COUNT: u8 = 4;
+/// The generator of the RS1024 checksum.
+const CHECKSUM_GENERATOR: [u32; 10] = [
+    0x00E0_E040,
+    0x01C1_C080,
+    0x0383_8100,
+    0x0707_0200,
+    0x0E0E_0009,
+    0x1C0C_2412,
+    0x3808_6C24,
+    0x3090_FC48,
+    0x21B1_F890,
+    0x03F3_F120,
+];
+
+/// A single SLIP-39 share, as encoded by its mnemonic.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Vec<u8>,
+}
+
+impl Share {
+    /// The parameters every share of the same master secret has in common.
+    fn common_parameters(&self) -> (u16, bool, u8, u8, u8) {
+        (
+            self.identifier,
+            self.extendable,
+            self.iteration_exponent,
+            self.group_threshold,
+            self.group_count,
+        )
+    }
+}
+
+/// Splits a master secret into SLIP-39 (Shamir's secret sharing) mnemonic shares, or combines the shares back
+/// into the master secret.
+///
+/// With `--split`, the input is the master secret in hexadecimal (16 to 64 bytes, an even number of them, e.g. a
+/// BIP 32 seed) and the output are the mnemonics of all the shares, one per line, group by group. The secret is
+/// encrypted with the passphrase (empty if not given) before it is split.
+///
+/// With `--combine`, the input are the mnemonics of the shares separated by commas, and the output is the
+/// recovered master secret in hexadecimal. The secret is the BIP 32 seed of the wallet, so it can be passed
+/// to derive-key as it is. A wrong passphrase cannot be detected, it recovers a different secret.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The master secret is not hexadecimal, shorter than 16 bytes or of an odd number of bytes,
+/// - A mnemonic contains an unknown word, has an invalid length or padding, or its checksum does not match,
+/// - The shares are not of the same master secret, or there are not enough of them,
+/// - The recovered secret does not match its digest (some of the shares do not belong together).
+pub fn slip39(input: &str, config: &Slip39Config) -> Result<String, ParsingError> {
+    match &config.mode {
+        Slip39Mode::Split {
+            group_threshold,
+            groups,
+        } => Ok(split_mnemonics(input, *group_threshold, groups, config)?.join("\n")),
+        Slip39Mode::Combine => Ok(encode_hex(&combine_mnemonics(input, config)?.1)),
+    }
+}
+
+/// Splits or combines the shares the same way as [`slip39`], returning the master `secret` in hexadecimal, its
+/// `identifier`, the `shares` (the mnemonics separated by commas, the same as the input of `--combine`) and the
+/// BIP 32 master `xprv` of the secret for `--template`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`slip39`].
+pub fn slip39_fields(
+    input: &str,
+    config: &Slip39Config,
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    let (identifier, secret, shares) = match &config.mode {
+        Slip39Mode::Split {
+            group_threshold,
+            groups,
+        } => {
+            let shares = split_mnemonics(input, *group_threshold, groups, config)?;
+            let identifier = decode_share(&shares[0])?.identifier;
+            (identifier, decode_secret(input)?, shares)
+        }
+        Slip39Mode::Combine => {
+            let (identifier, secret) = combine_mnemonics(input, config)?;
+            let shares = input.split(',').map(normalize_mnemonic).collect();
+            (identifier, secret, shares)
+        }
+    };
+
+    Ok(vec![
+        ("secret", encode_hex(&secret)),
+        ("identifier", identifier.to_string()),
+        ("shares", shares.join(",")),
+        (
+            "xprv",
+            XPrv::new(&secret)?.to_string(Prefix::XPRV).to_string(),
+        ),
+    ])
+}
+
+fn decode_secret(input: &str) -> Result<Vec<u8>, ParsingError> {
+    let input = input.trim();
+    if !input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidSeed,
+            "The master secret must be in hexadecimal",
+        ));
+    }
+    // validated on the hex digits, an odd number of them is not a whole number of bytes to decode
+    if input.len() < 2 * MIN_SECRET_LENGTH || !input.len().is_multiple_of(4) {
+        return Err(ParsingError::with_kind(ErrorKind::InvalidSeed, &format!(
+            "The master secret must be at least {MIN_SECRET_LENGTH} bytes long and of an even number of bytes, got {} hex digits",
+            input.len()
+        )));
+    }
+    decode_hex(input)
+}
+
+fn split_mnemonics(
+    input: &str,
+    group_threshold: u8,
+    groups: &[Slip39Group],
+    config: &Slip39Config,
+) -> Result<Vec<String>, ParsingError> {
+    let secret = decode_secret(input)?;
+    let random = random_bytes(2)?;
+    let identifier = u16::from_be_bytes([random[0], random[1]]) & 0x7FFF;
+    let encrypted = encrypt(
+        &secret,
+        passphrase(config),
+        config.iteration_exponent,
+        identifier,
+        true,
+    );
+
+    let group_count = u8::try_from(groups.len()).expect("at most 16 groups are accepted");
+    let mut mnemonics = Vec::new();
+    for ((group_index, group_share), group) in
+        split_secret(group_threshold, group_count, &encrypted)?
+            .into_iter()
+            .zip(groups)
+    {
+        for (member_index, value) in split_secret(group.threshold, group.count, &group_share)? {
+            mnemonics.push(encode_share(&Share {
+                identifier,
+                extendable: true,
+                iteration_exponent: config.iteration_exponent,
+                group_index,
+                group_threshold,
+                group_count,
+                member_index,
+                member_threshold: group.threshold,
+                value,
+            }));
+        }
+    }
+
+    Ok(mnemonics)
+}
+
+/// Combines the comma separated mnemonics into the identifier and the decrypted master secret.
+fn combine_mnemonics(input: &str, config: &Slip39Config) -> Result<(u16, Vec<u8>), ParsingError> {
+    let mut shares: Vec<Share> = Vec::new();
+    for mnemonic in input
+        .split(',')
+        .filter(|mnemonic| !mnemonic.trim().is_empty())
+    {
+        let share = decode_share(mnemonic)?;
+        // the same share given twice is not an error
+        if !shares.contains(&share) {
+            shares.push(share);
+        }
+    }
+    let Some(first) = shares.first().cloned() else {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            "No SLIP-39 shares given",
+        ));
+    };
+    if shares
+        .iter()
+        .any(|share| share.common_parameters() != first.common_parameters())
+    {
+        return Err(ParsingError::with_kind(ErrorKind::InvalidShare,
+            "The shares are not of the same master secret, they must all begin with the same words and have the same group threshold and count",
+        ));
+    }
+
+    let mut groups: BTreeMap<u8, Vec<Share>> = BTreeMap::new();
+    for share in shares {
+        groups.entry(share.group_index).or_default().push(share);
+    }
+
+    let mut group_shares = Vec::new();
+    for (group_index, members) in groups {
+        let member_threshold = members[0].member_threshold;
+        if members
+            .iter()
+            .any(|member| member.member_threshold != member_threshold)
+        {
+            return Err(ParsingError::with_kind(
+                ErrorKind::InvalidShare,
+                &format!(
+                    "The shares of group {} have different thresholds",
+                    group_index + 1
+                ),
+            ));
+        }
+        let mut member_indexes: Vec<u8> =
+            members.iter().map(|member| member.member_index).collect();
+        member_indexes.sort_unstable();
+        member_indexes.dedup();
+        if member_indexes.len() != members.len() {
+            return Err(ParsingError::with_kind(
+                ErrorKind::InvalidShare,
+                &format!(
+                    "Group {} contains different shares of the same index",
+                    group_index + 1
+                ),
+            ));
+        }
+        if members.len() < usize::from(member_threshold) {
+            return Err(ParsingError::with_kind(
+                ErrorKind::InvalidShare,
+                &format!(
+                    "Not enough shares of group {}: {} of the {member_threshold} required",
+                    group_index + 1,
+                    members.len()
+                ),
+            ));
+        }
+
+        let members: Vec<(u8, Vec<u8>)> = members
+            .into_iter()
+            .map(|member| (member.member_index, member.value))
+            .collect();
+        group_shares.push((group_index, recover_secret(member_threshold, &members)?));
+    }
+
+    if group_shares.len() < usize::from(first.group_threshold) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            &format!(
+                "Not enough groups: {} of the {} required",
+                group_shares.len(),
+                first.group_threshold
+            ),
+        ));
+    }
+
+    let encrypted = recover_secret(first.group_threshold, &group_shares)?;
+    let secret = decrypt(
+        &encrypted,
+        passphrase(config),
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+    );
+
+    Ok((first.identifier, secret))
+}
+
+fn passphrase(config: &Slip39Config) -> &[u8] {
+    config
+        .passphrase
+        .as_ref()
+        .map_or(&[], |passphrase| passphrase.as_ref())
+}
+
+fn normalize_mnemonic(mnemonic: &str) -> String {
+    mnemonic.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn encode_share(share: &Share) -> String {
+    let id_exp = u32::from(share.identifier) << 5
+        | u32::from(share.extendable) << 4
+        | u32::from(share.iteration_exponent);
+    let share_params = u32::from(share.group_index) << 16
+        | u32::from(share.group_threshold - 1) << 12
+        | u32::from(share.group_count - 1) << 8
+        | u32::from(share.member_index) << 4
+        | u32::from(share.member_threshold - 1);
+
+    let mut indexes = Vec::new();
+    indexes.extend(int_to_indexes(id_exp, ID_EXP_LENGTH_WORDS));
+    indexes.extend(int_to_indexes(share_params, SHARE_PARAMS_LENGTH_WORDS));
+    indexes.extend(bytes_to_indexes(&share.value));
+    indexes.extend(create_checksum(&indexes, share.extendable));
+
+    indexes
+        .iter()
+        .map(|&index| SLIP39_WORDLIST[usize::from(index)])
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn decode_share(mnemonic: &str) -> Result<Share, ParsingError> {
+    let indexes = mnemonic
+        .split_whitespace()
+        .map(|word| {
+            SLIP39_WORDLIST
+                .binary_search(&word.to_lowercase().as_str())
+                .map(|index| u16::try_from(index).expect("the wordlist has 1024 words"))
+                .map_err(|_| {
+                    ParsingError::with_kind(
+                        ErrorKind::InvalidShare,
+                        &format!("Unknown SLIP-39 word '{word}'"),
+                    )
+                })
+        })
+        .collect::<Result<Vec<u16>, ParsingError>>()?;
+
+    let metadata_length = ID_EXP_LENGTH_WORDS + SHARE_PARAMS_LENGTH_WORDS + CHECKSUM_LENGTH_WORDS;
+    let min_length = metadata_length + (MIN_SECRET_LENGTH * 8).div_ceil(RADIX_BITS);
+    if indexes.len() < min_length {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            &format!(
+                "Invalid SLIP-39 mnemonic length, at least {min_length} words are required, got {}",
+                indexes.len()
+            ),
+        ));
+    }
+
+    let id_exp = indexes_to_int(&indexes[..ID_EXP_LENGTH_WORDS]);
+    let extendable = id_exp >> 4 & 1 == 1;
+    if !verify_checksum(&indexes, extendable) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::ChecksumMismatch,
+            &format!(
+                "Invalid checksum of the SLIP-39 mnemonic '{}...'",
+                mnemonic
+                    .split_whitespace()
+                    .take(2)
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            ),
+        ));
+    }
+
+    let share_params = indexes_to_int(
+        &indexes[ID_EXP_LENGTH_WORDS..ID_EXP_LENGTH_WORDS + SHARE_PARAMS_LENGTH_WORDS],
+    );
+    let nibble =
+        |shift: u32| u8::try_from(share_params >> shift & 0xF).expect("a nibble fits into u8");
+    let share = Share {
+        identifier: u16::try_from(id_exp >> 5).expect("the identifier has 15 bits"),
+        extendable,
+        iteration_exponent: u8::try_from(id_exp & 0xF).expect("the exponent has 4 bits"),
+        group_index: nibble(16),
+        group_threshold: nibble(12) + 1,
+        group_count: nibble(8) + 1,
+        member_index: nibble(4),
+        member_threshold: nibble(0) + 1,
+        value: indexes_to_bytes(
+            &indexes[ID_EXP_LENGTH_WORDS + SHARE_PARAMS_LENGTH_WORDS
+                ..indexes.len() - CHECKSUM_LENGTH_WORDS],
+        )?,
+    };
+    if share.group_count < share.group_threshold {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            "Invalid SLIP-39 mnemonic, its group threshold exceeds the group count",
+        ));
+    }
+
+    Ok(share)
+}
+
+fn int_to_indexes(value: u32, length: usize) -> impl Iterator<Item = u16> {
+    (0..length).rev().map(move |position| {
+        u16::try_from(value >> (position * RADIX_BITS) & 0x3FF).expect("a word index has 10 bits")
+    })
+}
+
+fn indexes_to_int(indexes: &[u16]) -> u32 {
+    indexes
+        .iter()
+        .fold(0, |value, &index| value << RADIX_BITS | u32::from(index))
+}
+
+/// Converts the share value into the word indexes, padded by zero bits at the start to the multiple of 10 bits.
+fn bytes_to_indexes(bytes: &[u8]) -> Vec<u16> {
+    let word_count = (bytes.len() * 8).div_ceil(RADIX_BITS);
+    let mut accumulator: u32 = 0;
+    let mut bits = word_count * RADIX_BITS - bytes.len() * 8;
+    let mut indexes = Vec::with_capacity(word_count);
+
+    for &byte in bytes {
+        accumulator = accumulator << 8 | u32::from(byte);
+        bits += 8;
+        while bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            indexes.push(
+                u16::try_from(accumulator >> bits & 0x3FF).expect("a word index has 10 bits"),
+            );
+        }
+        accumulator &= (1 << bits) - 1;
+    }
+
+    indexes
+}
+
+/// Converts the word indexes back into the share value, verifying the padding is at most 8 zero bits.
+fn indexes_to_bytes(indexes: &[u16]) -> Result<Vec<u8>, ParsingError> {
+    let padding = indexes.len() * RADIX_BITS % 16;
+    if padding > 8 {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            "Invalid SLIP-39 mnemonic length",
+        ));
+    }
+
+    let mut accumulator: u32 = 0;
+    let mut bits = 0;
+    let mut padding_left = padding;
+    let mut bytes = Vec::new();
+
+    for &index in indexes {
+        accumulator = accumulator << RADIX_BITS | u32::from(index);
+        bits += RADIX_BITS;
+        if padding_left > 0 {
+            bits -= padding_left;
+            if accumulator >> bits != 0 {
+                return Err(ParsingError::with_kind(
+                    ErrorKind::InvalidShare,
+                    "Invalid SLIP-39 mnemonic, its padding is not zero",
+                ));
+            }
+            padding_left = 0;
+        }
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push(u8::try_from(accumulator >> bits & 0xFF).expect("a byte has 8 bits"));
+        }
+        accumulator &= (1 << bits) - 1;
+    }
+
+    Ok(bytes)
+}
+
+fn customization_string(extendable: bool) -> &'static [u8] {
+    if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    }
+}
+
+fn polymod(values: impl Iterator<Item = u32>) -> u32 {
+    values.fold(1, |checksum, value| {
+        let top = checksum >> 20;
+        CHECKSUM_GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| top >> position & 1 == 1)
+            .fold(
+                (checksum & 0xF_FFFF) << 10 ^ value,
+                |checksum, (_, generator)| checksum ^ generator,
+            )
+    })
+}
+
+fn checksum_values(indexes: &[u16], extendable: bool) -> impl Iterator<Item = u32> + '_ {
+    customization_string(extendable)
+        .iter()
+        .map(|&c| u32::from(c))
+        .chain(indexes.iter().map(|&index| u32::from(index)))
+}
+
+fn create_checksum(indexes: &[u16], extendable: bool) -> Vec<u16> {
+    let checksum =
+        polymod(checksum_values(indexes, extendable).chain([0; CHECKSUM_LENGTH_WORDS])) ^ 1;
+    int_to_indexes(checksum, CHECKSUM_LENGTH_WORDS).collect()
+}
+
+fn verify_checksum(indexes: &[u16], extendable: bool) -> bool {
+    polymod(checksum_values(indexes, extendable)) == 1
+}
+
+fn encrypt(
+    secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    let salt = salt(identifier, extendable);
+    feistel(secret, 0..ROUND_COUNT, |round, half| {
+        round_function(round, passphrase, iteration_exponent, &salt, half)
+    })
+}
+
+fn decrypt(
+    encrypted: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    let salt = salt(identifier, extendable);
+    feistel(encrypted, (0..ROUND_COUNT).rev(), |round, half| {
+        round_function(round, passphrase, iteration_exponent, &salt, half)
+    })
+}
+
+/// Runs the rounds of the 4-round Feistel network of SLIP-39, returning the halves swapped.
+fn feistel(
+    input: &[u8],
+    rounds: impl Iterator<Item = u8>,
+    round_function: impl Fn(u8, &[u8]) -> Vec<u8>,
+) -> Vec<u8> {
+    let (left, right) = input.split_at(input.len() / 2);
+    let (mut left, mut right) = (left.to_vec(), right.to_vec());
+
+    for round in rounds {
+        let mixed: Vec<u8> = left
+            .iter()
+            .zip(round_function(round, &right))
+            .map(|(byte, key)| byte ^ key)
+            .collect();
+        left = std::mem::replace(&mut right, mixed);
+    }
+
+    [right, left].concat()
+}
+
+fn salt(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        Vec::new()
+    } else {
+        [customization_string(false), &identifier.to_be_bytes()].concat()
+    }
+}
+
+fn round_function(
+    round: u8,
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    salt: &[u8],
+    half: &[u8],
+) -> Vec<u8> {
+    let mut output = vec![0; half.len()];
+    pbkdf2::pbkdf2_hmac::<Sha256>(
+        &[&[round][..], passphrase].concat(),
+        &[salt, half].concat(),
+        (BASE_ITERATION_COUNT << iteration_exponent) / u32::from(ROUND_COUNT),
+        &mut output,
+    );
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{structs::secret_buffer::SecretBuffer, test_utils::get_cmd};
+
+    const SINGLE_SHARE: &str = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+    const TWO_OF_THREE_SHARES: &str = "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed, shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking";
+
+    fn config(mode: Slip39Mode, passphrase: &str) -> Slip39Config {
+        Slip39Config {
+            mode,
+            iteration_exponent: 0,
+            passphrase: Some(SecretBuffer::new(passphrase.as_bytes().to_vec())),
+        }
+    }
+
+    #[test]
+    fn test_combine_vectors() {
+        let combine = config(Slip39Mode::Combine, "TREZOR");
+
+        assert_eq!(
+            slip39(SINGLE_SHARE, &combine),
+            Ok("bb54aac4b89dc868ba37d9cc21b2cece".to_string())
+        );
+        assert_eq!(
+            slip39(TWO_OF_THREE_SHARES, &combine),
+            Ok("b43ceb7e57a0ea8766221624d01b0864".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_shares() {
+        let combine = config(Slip39Mode::Combine, "TREZOR");
+
+        // the last word is changed, breaking the checksum
+        assert!(slip39(&SINGLE_SHARE.replace("keyboard", "kidney"), &combine).is_err());
+        // only one of the two required shares
+        assert!(slip39(TWO_OF_THREE_SHARES.split(',').next().unwrap(), &combine).is_err());
+        assert!(slip39("duckling enlarge academic", &combine).is_err());
+        assert!(slip39("bitcoin enlarge academic", &combine).is_err());
+        assert!(slip39(&format!("{SINGLE_SHARE},{TWO_OF_THREE_SHARES}"), &combine).is_err());
+    }
+
+    #[test]
+    fn test_split_and_combine() {
+        let secret = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+        let split = config(
+            Slip39Mode::Split {
+                group_threshold: 2,
+                groups: vec![
+                    Slip39Group {
+                        threshold: 1,
+                        count: 1,
+                    },
+                    Slip39Group {
+                        threshold: 2,
+                        count: 3,
+                    },
+                    Slip39Group {
+                        threshold: 3,
+                        count: 5,
+                    },
+                ],
+            },
+            "passphrase",
+        );
+        let shares: Vec<String> = slip39(secret, &split)
+            .unwrap()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(shares.len(), 9);
+        assert!(shares.iter().all(|share| share.split(' ').count() == 33));
+
+        let combine = config(Slip39Mode::Combine, "passphrase");
+        for subset in [[0, 1, 3].as_slice(), &[2, 3, 4, 6, 8], &[3, 0, 1]] {
+            let input = subset
+                .iter()
+                .map(|&i| shares[i].as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            assert_eq!(slip39(&input, &combine), Ok(secret.to_string()));
+        }
+
+        assert_ne!(
+            slip39(
+                &[&shares[0][..], &shares[1]].join(","),
+                &config(Slip39Mode::Combine, "")
+            ),
+            Ok(secret.to_string())
+        );
+        // only one share of the 2 groups required, and an incomplete group
+        assert!(slip39(&shares[1..3].join(","), &combine).is_err());
+        assert!(slip39(
+            &[&shares[1][..], &shares[0], &shares[4]].join(","),
+            &combine
+        )
+        .is_err());
+        assert!(slip39("000102030405060708090a0b0c0d0e", &split).is_err());
+        for input in [
+            "0",
+            "000",
+            &secret[1..],
+            "zz",
+            &format!("{}č", &secret[2..]),
+        ] {
+            assert!(slip39(input, &split).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_secret_cli() {
+        for input in ["0", "000", "000102030405060708090a0b0c0d0e0f1"] {
+            get_cmd()
+                .args(["slip39", "--split", "--group", "2of3", input])
+                .assert()
+                .failure()
+                .stderr(format!(
+                    "Parsing error [E016]: The master secret must be at least 16 bytes long and of an even number of bytes, got {} hex digits\n",
+                    input.len()
+                ));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "derive-key", feature = "slip39"))]
+    fn test_combine_into_derive_key() {
+        let secret = get_cmd()
+            .args([
+                "slip39",
+                "--combine",
+                "--passphrase-env",
+                "BIP380_TEST_SLIP39_PASSPHRASE",
+                SINGLE_SHARE,
+            ])
+            .env("BIP380_TEST_SLIP39_PASSPHRASE", "TREZOR")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let secret = String::from_utf8(secret).unwrap();
+        assert_eq!(secret.trim(), "bb54aac4b89dc868ba37d9cc21b2cece");
+
+        let xprv = get_cmd()
+            .args([
+                "slip39",
+                "--combine",
+                "--passphrase-env",
+                "BIP380_TEST_SLIP39_PASSPHRASE",
+                "--template",
+                "{xprv}",
+                SINGLE_SHARE,
+            ])
+            .env("BIP380_TEST_SLIP39_PASSPHRASE", "TREZOR")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        get_cmd()
+            .args(["derive-key", "--template", "{xprv}", "-"])
+            .write_stdin(secret)
+            .assert()
+            .success()
+            .stdout(String::from_utf8(xprv).unwrap());
+    }
+}
@@ -0,0 +1,216 @@
+use bip32::secp256k1::sha2::{Digest, Sha256};
+
+use crate::structs::{address_type::AddressType, network::Network, parsing_error::ParsingError};
+
+use super::{
+    hashes::hash160,
+    opcodes::{OP_0, OP_1, OP_CHECKSIG, OP_DUP, OP_EQUAL, OP_EQUALVERIFY, OP_HASH160},
+    taproot::taproot_output_key,
+};
+
+const HASH160_PUSH: u8 = 20;
+const X_ONLY_PUSH: u8 = 32;
+const SHA256_PUSH: u8 = 32;
+
+/// The characters of the bech32 encoding (BIP 173), indexed by their 5-bit values.
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The final XOR constant of the checksum of a witness version 0 address (BIP 173).
+const BECH32_CONST: u32 = 1;
+
+/// The final XOR constant of the checksum of a witness version 1+ address (BIP 350).
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+fn base58_check_encode(version: u8, payload: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(payload.len() + 5);
+    bytes.push(version);
+    bytes.extend_from_slice(payload);
+
+    let checksum = Sha256::digest(Sha256::digest(&bytes));
+    bytes.extend_from_slice(&checksum[..4]);
+
+    bs58::encode(bytes).into_string()
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+
+    values.iter().fold(1, |checksum, value| {
+        let top = checksum >> 25;
+        let checksum = ((checksum & 0x01ff_ffff) << 5) ^ u32::from(*value);
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (top >> i) & 1 == 1)
+            .fold(checksum, |checksum, (_, generator)| checksum ^ generator)
+    })
+}
+
+/// Encodes the witness program as a segwit address, bech32 for version 0 and bech32m for the later versions.
+fn segwit_encode(hrp: &str, version: u8, program: &[u8]) -> String {
+    // the program regrouped from 8-bit bytes into 5-bit values, the last one padded with zeros
+    let mut data = vec![version];
+    let (mut accumulator, mut bits) = (0u32, 0u32);
+    for byte in program {
+        accumulator = (accumulator << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            data.push(((accumulator >> bits) & 0x1f) as u8);
+        }
+    }
+    if bits > 0 {
+        data.push(((accumulator << (5 - bits)) & 0x1f) as u8);
+    }
+
+    let mut values: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|c| c & 0x1f));
+    values.extend(&data);
+    values.extend([0; 6]);
+    let constant = if version == 0 {
+        BECH32_CONST
+    } else {
+        BECH32M_CONST
+    };
+    let checksum = bech32_polymod(&values) ^ constant;
+    data.extend((0..6).map(|i| ((checksum >> (5 * (5 - i))) & 0x1f) as u8));
+
+    let encoded: String = data
+        .iter()
+        .map(|value| char::from(BECH32_CHARSET[usize::from(*value)]))
+        .collect();
+    format!("{hrp}1{encoded}")
+}
+
+/// Encodes an output script as its address on the given network.
+///
+/// Only the scripts with a standard address format are supported, i.e. P2PKH (`1...` on mainnet),
+/// P2SH (`3...` on mainnet), P2WPKH and P2WSH (`bc1q...` on mainnet) and P2TR (`bc1p...` on mainnet).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script does not have an address format (e.g. bare `pk` or `multi` scripts).
+pub fn script_to_address(script: &[u8], network: Network) -> Result<String, ParsingError> {
+    let (p2pkh_version, p2sh_version, hrp) = match network {
+        Network::Mainnet => (0x00, 0x05, "bc"),
+        Network::Testnet => (0x6f, 0xc4, "tb"),
+    };
+
+    match script {
+        [OP_DUP, OP_HASH160, HASH160_PUSH, hash @ .., OP_EQUALVERIFY, OP_CHECKSIG]
+            if hash.len() == 20 =>
+        {
+            Ok(base58_check_encode(p2pkh_version, hash))
+        }
+        [OP_HASH160, HASH160_PUSH, hash @ .., OP_EQUAL] if hash.len() == 20 => {
+            Ok(base58_check_encode(p2sh_version, hash))
+        }
+        [OP_0, HASH160_PUSH, hash @ ..] if hash.len() == 20 => Ok(segwit_encode(hrp, 0, hash)),
+        [OP_0, SHA256_PUSH, hash @ ..] if hash.len() == 32 => Ok(segwit_encode(hrp, 0, hash)),
+        [OP_1, X_ONLY_PUSH, key @ ..] if key.len() == 32 => Ok(segwit_encode(hrp, 1, key)),
+        _ => Err(ParsingError::new(
+            "Script has no address format, only pkh, sh, wpkh, wsh and tr scripts can be encoded as addresses",
+        )),
+    }
+}
+
+/// Builds the output script paying to the serialized public key (33 or 65 bytes, or 32 bytes x-only for
+/// P2TR) by the address type.
+///
+/// The output key of P2TR is the internal key tweaked with the merkle root of its script tree, see
+/// [`taproot_output_key`]. Without a merkle root, the output commits to the key alone (BIP 86).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key is not on the curve, or is uncompressed for a segwit address type.
+pub fn key_output_script(
+    public_key: &[u8],
+    address_type: AddressType,
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<Vec<u8>, ParsingError> {
+    if public_key.len() == 65 && !address_type.key_context().allows_uncompressed() {
+        return Err(ParsingError::new(&format!(
+            "The {address_type} address type requires a compressed public key"
+        )));
+    }
+
+    let witness_v0_script = |public_key: &[u8]| {
+        let mut script = vec![OP_0, HASH160_PUSH];
+        script.extend(hash160(public_key));
+        script
+    };
+
+    Ok(match address_type {
+        AddressType::P2pkh => {
+            let mut script = vec![OP_DUP, OP_HASH160, HASH160_PUSH];
+            script.extend(hash160(public_key));
+            script.extend([OP_EQUALVERIFY, OP_CHECKSIG]);
+            script
+        }
+        AddressType::P2wpkh => witness_v0_script(public_key),
+        AddressType::P2shP2wpkh => {
+            let mut script = vec![OP_HASH160, HASH160_PUSH];
+            script.extend(hash160(&witness_v0_script(public_key)));
+            script.push(OP_EQUAL);
+            script
+        }
+        AddressType::P2tr => {
+            let mut script = vec![OP_1, X_ONLY_PUSH];
+            // the x coordinate of a compressed key is the internal key
+            let internal_key = public_key
+                .get(public_key.len().saturating_sub(32)..)
+                .filter(|_| matches!(public_key.len(), 32 | 33))
+                .ok_or_else(|| ParsingError::new("Invalid public key for a taproot output"))?;
+            script.extend(taproot_output_key(internal_key, merkle_root)?);
+            script
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::decode_hex;
+
+    #[test]
+    fn test_script_to_address() {
+        // hash160 of the generator point public key
+        let p2pkh = decode_hex("76a914751e76e8199196d454941c45d1b3a323f1433bd688ac").unwrap();
+        assert_eq!(
+            script_to_address(&p2pkh, Network::Mainnet),
+            Ok("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".to_string())
+        );
+        assert_eq!(
+            script_to_address(&p2pkh, Network::Testnet),
+            Ok("mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r".to_string())
+        );
+
+        let p2sh = decode_hex("a914751e76e8199196d454941c45d1b3a323f1433bd687").unwrap();
+        assert!(script_to_address(&p2sh, Network::Mainnet)
+            .is_ok_and(|address| address.starts_with('3')));
+
+        let p2wpkh = decode_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        assert_eq!(
+            script_to_address(&p2wpkh, Network::Testnet),
+            Ok("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".to_string())
+        );
+
+        // test vector from BIP 173
+        let p2wsh =
+            decode_hex("00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262")
+                .unwrap();
+        assert_eq!(
+            script_to_address(&p2wsh, Network::Testnet),
+            Ok("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7".to_string())
+        );
+
+        assert!(script_to_address(&[OP_CHECKSIG], Network::Mainnet).is_err());
+    }
+}
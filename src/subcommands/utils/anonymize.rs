@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use bip32::{
+    secp256k1::{elliptic_curve::sec1::ToEncodedPoint, SecretKey},
+    ExtendedKey, ExtendedKeyAttrs,
+};
+
+use crate::{
+    structs::parsing_error::ParsingError, subcommands::key_expression::split_key_expression,
+};
+
+use super::{
+    extended_key::has_extended_key_prefix,
+    hex_encoded_public_key::{has_hex_encoded_public_key_prefix, is_x_only_public_key},
+    hexadecimal::encode_hex,
+    script_compiler::collect_key_expressions,
+    wallet_import_format::validate_wif_private_key,
+};
+
+/// The version byte of a mainnet WIF private key.
+const WIF_VERSION: u8 = 0x80;
+
+/// The dummy keys and fingerprints assigned so far, in the order of their first appearance.
+#[derive(Default)]
+struct Dummies {
+    keys: Vec<String>,
+    fingerprints: Vec<String>,
+}
+
+impl Dummies {
+    /// Returns the (1-based) number of the dummy standing for the value, assigning the next one to a new value.
+    fn number(values: &mut Vec<String>, value: &str) -> u32 {
+        let position = values
+            .iter()
+            .position(|known| known == value)
+            .unwrap_or_else(|| {
+                values.push(value.to_string());
+                values.len() - 1
+            });
+        u32::try_from(position + 1).expect("a script has fewer than 2^32 keys")
+    }
+}
+
+/// Replaces the keys of a (previously validated) script expression, without its checksum, with dummy keys, so that
+/// it can be shared publicly (e.g. in a bug report) without revealing the wallet.
+///
+/// The n-th distinct key is replaced with the key of the private key n (the first one with the generator point) in
+/// the same form: a compressed, uncompressed or x-only public key, a WIF private key or an extended key of the same
+/// kind, depth and child number. The n-th distinct fingerprint of the key origins is replaced with `n` (e.g.
+/// `00000001`). Everything else is kept: the script functions, the origin paths, the derivation paths, the wildcards
+/// and the `raw` payloads. A key appearing multiple times is replaced with the same dummy key each time.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression or any of its keys is malformed.
+pub fn anonymize_script(script: &str) -> Result<String, ParsingError> {
+    let key_expressions = collect_key_expressions(script)?;
+    let mut dummies = Dummies::default();
+
+    let mut anonymized = String::with_capacity(script.len());
+    for token in script.split_inclusive(['(', ')', ',']) {
+        let (argument, delimiter) = match token.char_indices().last() {
+            Some((index, '(' | ')' | ',')) => token.split_at(index),
+            _ => (token, ""),
+        };
+        let trimmed = argument.trim();
+        if key_expressions
+            .iter()
+            .any(|key_expression| key_expression.trim() == trimmed)
+        {
+            let dummy = anonymize_key_expression(trimmed, &mut dummies)?;
+            anonymized.push_str(&argument.replace(trimmed, &dummy));
+        } else {
+            anonymized.push_str(argument);
+        }
+        anonymized.push_str(delimiter);
+    }
+
+    Ok(anonymized)
+}
+
+fn anonymize_key_expression(
+    key_expression: &str,
+    dummies: &mut Dummies,
+) -> Result<String, ParsingError> {
+    let (key_origin, key) = split_key_expression(key_expression)?;
+
+    let key_origin = match key_origin {
+        Some(key_origin) => {
+            let (fingerprint, path) = key_origin[1..].split_at(8);
+            let number =
+                Dummies::number(&mut dummies.fingerprints, &fingerprint.to_ascii_lowercase());
+            format!("[{number:08x}{path}")
+        }
+        None => String::new(),
+    };
+
+    let (encoded, path) = key.split_at(key.find('/').unwrap_or(key.len()));
+    let identity = if has_hex_encoded_public_key_prefix(encoded) || is_x_only_public_key(encoded) {
+        encoded.to_ascii_lowercase()
+    } else {
+        encoded.to_string()
+    };
+    let number = Dummies::number(&mut dummies.keys, &identity);
+
+    Ok(format!("{key_origin}{}{path}", dummy_key(encoded, number)?))
+}
+
+/// Returns the key of the private key `number` in the same form as the key.
+fn dummy_key(key: &str, number: u32) -> Result<String, ParsingError> {
+    let mut secret = [0; 32];
+    secret[28..].copy_from_slice(&number.to_be_bytes());
+    let public_key = SecretKey::from_slice(&secret)
+        .map_err(|_| ParsingError::new("Could not create a dummy key"))?
+        .public_key();
+
+    if is_x_only_public_key(key) {
+        return Ok(encode_hex(
+            &public_key.to_encoded_point(true).as_bytes()[1..],
+        ));
+    }
+    if has_hex_encoded_public_key_prefix(key) {
+        let compressed = !key.starts_with("04");
+        return Ok(encode_hex(
+            public_key.to_encoded_point(compressed).as_bytes(),
+        ));
+    }
+    if has_extended_key_prefix(key) {
+        let extended_key = ExtendedKey::from_str(key)?;
+        let mut key_bytes = [0; 33];
+        if extended_key.prefix.is_private() {
+            key_bytes[1..].copy_from_slice(&secret);
+        } else {
+            key_bytes.copy_from_slice(public_key.to_encoded_point(true).as_bytes());
+        }
+        let depth = extended_key.attrs.depth;
+        let dummy = ExtendedKey {
+            prefix: extended_key.prefix,
+            attrs: ExtendedKeyAttrs {
+                depth,
+                parent_fingerprint: if depth == 0 {
+                    [0; 4]
+                } else {
+                    number.to_be_bytes()
+                },
+                child_number: extended_key.attrs.child_number,
+                chain_code: secret,
+            },
+            key_bytes,
+        };
+        return Ok(dummy.to_string());
+    }
+
+    let compressed = validate_wif_private_key(key)?;
+    let mut bytes = vec![WIF_VERSION];
+    bytes.extend_from_slice(&secret);
+    if compressed {
+        bytes.push(0x01);
+    }
+    Ok(bs58::encode(bytes).with_check().into_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the generator point, the public key of the private key 1
+    const DUMMY_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_anonymize_script() {
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let xpub = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+
+        let anonymized = anonymize_script(&format!(
+            "sh(multi(2,{key},[deadbeef/48h/0h/0h/2h]{xpub}/0/*,{}))",
+            key.to_ascii_uppercase()
+        ))
+        .unwrap();
+        let (prefix, rest) = anonymized.split_at(anonymized.find("xpub").unwrap());
+        assert_eq!(
+            prefix,
+            format!("sh(multi(2,{DUMMY_KEY},[00000001/48h/0h/0h/2h]")
+        );
+        assert!(rest.ends_with(&format!("/0/*,{DUMMY_KEY}))")));
+
+        let dummy_xpub = ExtendedKey::from_str(&rest[..rest.find('/').unwrap()]).unwrap();
+        let xpub = ExtendedKey::from_str(xpub).unwrap();
+        assert_eq!(dummy_xpub.attrs.depth, xpub.attrs.depth);
+        assert_eq!(dummy_xpub.attrs.child_number, xpub.attrs.child_number);
+        assert_ne!(dummy_xpub.key_bytes, xpub.key_bytes);
+
+        // the private key 1 in both of the WIF forms
+        assert_eq!(
+            anonymize_script("pkh(5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss)"),
+            Ok("pkh(5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf)".to_string())
+        );
+        assert_eq!(
+            anonymize_script("pkh(L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1)"),
+            Ok("pkh(KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn)".to_string())
+        );
+        assert_eq!(
+            anonymize_script("raw(deadbeef)"),
+            Ok("raw(deadbeef)".to_string())
+        );
+    }
+}
@@ -0,0 +1,127 @@
+/// A minimal CBOR (RFC 8949) encoder of the data items used by the uniform resources.
+///
+/// Only definite lengths are written, the maps are written in the order the entries are added,
+/// so the keys are to be added in the canonical (ascending) order.
+#[derive(Debug, Default)]
+pub struct CborEncoder {
+    bytes: Vec<u8>,
+}
+
+const UNSIGNED: u8 = 0;
+const BYTES: u8 = 2;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+
+impl CborEncoder {
+    #[must_use]
+    pub fn new() -> Self {
+        CborEncoder::default()
+    }
+
+    /// Returns the encoded data items.
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Writes the head of a data item of the major type with its argument, in the shortest form.
+    fn head(&mut self, major_type: u8, argument: u64) -> &mut Self {
+        let bytes = argument.to_be_bytes();
+        let (additional_info, length) = match argument {
+            0..=0x17 => (bytes[7], 0),
+            0x18..=0xff => (0x18, 1),
+            0x100..=0xffff => (0x19, 2),
+            0x1_0000..=0xffff_ffff => (0x1a, 4),
+            _ => (0x1b, 8),
+        };
+        self.bytes.push(major_type << 5 | additional_info);
+        self.bytes.extend(&bytes[bytes.len() - length..]);
+        self
+    }
+
+    pub fn unsigned(&mut self, value: u64) -> &mut Self {
+        self.head(UNSIGNED, value)
+    }
+
+    pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.head(BYTES, value.len() as u64);
+        self.bytes.extend(value);
+        self
+    }
+
+    pub fn bool(&mut self, value: bool) -> &mut Self {
+        self.bytes.push(if value { TRUE } else { FALSE });
+        self
+    }
+
+    /// Starts an array of `length` items, which are to be written next.
+    pub fn array(&mut self, length: usize) -> &mut Self {
+        self.head(ARRAY, length as u64)
+    }
+
+    /// Starts a map of `length` entries, whose keys and values are to be written next.
+    pub fn map(&mut self, length: usize) -> &mut Self {
+        self.head(MAP, length as u64)
+    }
+
+    /// Writes the already encoded data item(s).
+    pub fn encoded(&mut self, item: &[u8]) -> &mut Self {
+        self.bytes.extend(item);
+        self
+    }
+
+    /// Tags the data item to be written next.
+    pub fn tag(&mut self, tag: u64) -> &mut Self {
+        self.head(TAG, tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::encode_hex;
+
+    #[test]
+    fn test_cbor_encoder() {
+        // examples from RFC 8949, Appendix A
+        let mut encoder = CborEncoder::new();
+        encoder
+            .unsigned(10)
+            .unsigned(24)
+            .unsigned(1000)
+            .unsigned(1_000_000)
+            .unsigned(1_000_000_000_000)
+            .bool(false)
+            .bool(true)
+            .bytes(&[1, 2, 3, 4])
+            .tag(1)
+            .unsigned(1_363_896_240)
+            .array(2)
+            .unsigned(1)
+            .array(0)
+            .map(1)
+            .unsigned(1)
+            .unsigned(2);
+
+        assert_eq!(
+            encode_hex(&encoder.into_bytes()),
+            [
+                "0a",
+                "1818",
+                "1903e8",
+                "1a000f4240",
+                "1b000000e8d4a51000",
+                "f4",
+                "f5",
+                "4401020304",
+                "c11a514b67b0",
+                "820180",
+                "a10102",
+            ]
+            .concat()
+        );
+    }
+}
@@ -1,5 +1,8 @@
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
 pub const CHECKSUM_DIVIDER_SYMBOL: &str = "#";
 const CHECKSUM_LENGTH: usize = 8;
+/// The characters a script expression may consist of (BIP 380), the escaped `\"` and `\\` being two of them.
 const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
 const CHECKSUM_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
 const GENERATOR: [u64; 5] = [
@@ -35,14 +38,26 @@ fn checksum_polymod(symbols: Vec<usize>) -> u64 {
     checksum
 }
 
-fn checksum_expand(script: &str) -> Vec<usize> {
+fn charset_index(kind: &CharsetKind, character: char) -> Result<usize, ParsingError> {
+    let set = match kind {
+        CharsetKind::Input => INPUT_CHARSET,
+        CharsetKind::Checksum => CHECKSUM_CHARSET,
+    };
+    set.find(character).ok_or_else(|| {
+        ParsingError::with_kind(
+            ErrorKind::InvalidCharacter,
+            &invalid_char_err_msg(kind, character),
+        )
+    })
+}
+
+fn checksum_expand(script: &str) -> Result<Vec<usize>, ParsingError> {
     let mut groups = Vec::new();
     let mut symbols = Vec::new();
 
-    for character in script.chars() {
-        let index = INPUT_CHARSET
-            .find(character)
-            .unwrap_or_else(|| panic!("{}", invalid_char_err_msg(&CharsetKind::Input, character)));
+    for (position, character) in script.char_indices() {
+        let index = charset_index(&CharsetKind::Input, character)
+            .map_err(|err| err.with_span(position..position + character.len_utf8()))?;
         symbols.push(index & 31);
         groups.push(index >> 5);
 
@@ -57,49 +72,90 @@ fn checksum_expand(script: &str) -> Vec<usize> {
         2 => symbols.push(groups[0] * 3 + groups[1]),
         _ => {}
     }
-    symbols
+    Ok(symbols)
+}
+
+/// Splits the script expression into the script and its checksum (if any) at the checksum separator.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] of the [`ErrorKind::InvalidChecksum`] kind if the expression has more than one
+/// separator (e.g. `raw(00)#abc#def`), spanning the second one, as it is unclear which part is the checksum.
+pub fn split_checksum(input: &str) -> Result<(&str, Option<&str>), ParsingError> {
+    let mut separators = input.match_indices(CHECKSUM_DIVIDER_SYMBOL);
+    if let (Some(_), Some((second, _))) = (separators.next(), separators.next()) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidChecksum,
+            &format!(
+                "the script expression has {} checksum separators '{CHECKSUM_DIVIDER_SYMBOL}', only one is allowed",
+                separators.count() + 2
+            ),
+        )
+        .with_span(second..second + CHECKSUM_DIVIDER_SYMBOL.len()));
+    }
+
+    Ok(input
+        .split_once(CHECKSUM_DIVIDER_SYMBOL)
+        .map_or((input, None), |(script, checksum)| (script, Some(checksum))))
 }
 
 pub fn checksum_length_check(checksum: &str) -> bool {
     checksum.chars().count() == CHECKSUM_LENGTH
 }
 
-pub fn checksum_check(script: &str, checksum: &str) -> bool {
-    checksum_length_check(checksum)
-        && checksum.chars().all(|c| CHECKSUM_CHARSET.find(c).is_some())
-        && script.chars().all(|c| INPUT_CHARSET.find(c).is_some())
-        && checksum_polymod(
-            checksum_expand(script)
-                .into_iter()
-                .chain(
-                    checksum
-                        .chars()
-                        .map(|c| {
-                            CHECKSUM_CHARSET.find(c).unwrap_or_else(|| {
-                                panic!("{}", invalid_char_err_msg(&CharsetKind::Checksum, c))
-                            })
-                        })
-                        .collect::<Vec<usize>>(),
-                )
-                .collect::<Vec<usize>>(),
-        ) == 1
+/// Verifies that `checksum` is the valid BIP 380 checksum of `script`.
+///
+/// Returns `Ok(false)` if the checksum has an incorrect length, contains a character outside
+/// of the checksum charset (such a checksum can never match) or does not match the script.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script contains a character outside of the input charset,
+/// spanning the character.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn checksum_check(script: &str, checksum: &str) -> Result<bool, ParsingError> {
+    if !checksum_length_check(checksum) {
+        return Ok(false);
+    }
+
+    let Ok(checksum_symbols) = checksum
+        .chars()
+        .map(|c| charset_index(&CharsetKind::Checksum, c))
+        .collect::<Result<Vec<usize>, ParsingError>>()
+    else {
+        return Ok(false);
+    };
+
+    let symbols = checksum_expand(script)?
+        .into_iter()
+        .chain(checksum_symbols)
+        .collect::<Vec<usize>>();
+
+    Ok(checksum_polymod(symbols) == 1)
 }
 
-pub fn checksum_create(script: &str) -> String {
-    let symbols = checksum_expand(script)
+/// Computes the BIP 380 checksum of `script`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script contains a character outside of the input charset,
+/// spanning the character.
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn checksum_create(script: &str) -> Result<String, ParsingError> {
+    let symbols = checksum_expand(script)?
         .into_iter()
         .chain([0; CHECKSUM_LENGTH])
         .collect::<Vec<usize>>();
     let checksum = checksum_polymod(symbols) ^ 1;
 
-    (0..CHECKSUM_LENGTH)
+    Ok((0..CHECKSUM_LENGTH)
         .map(|i| {
             CHECKSUM_CHARSET
                 .chars()
                 .nth(((checksum >> (5 * 7_usize.saturating_sub(i))) & 31) as usize)
                 .unwrap_or_default()
         })
-        .collect()
+        .collect())
 }
 
 #[cfg(test)]
@@ -108,23 +164,109 @@ mod tests {
 
     #[test]
     fn test_checksum_create() {
-        assert_eq!(checksum_create("raw(deadbeef)"), "89f8spxm");
-        assert_eq!(checksum_create("raw( deadbeef )"), "985dv2zl");
-        assert_eq!(checksum_create("raw(DEAD BEEF)"), "qqn7ll2h");
-        assert_eq!(checksum_create("raw(DEA D BEEF)"), "egs9fwsr");
-        assert_eq!(checksum_create("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)"), "vm4xc4ed");
-        assert_eq!(checksum_create("pkh(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)"), "ujpe9npc");
-        assert_eq!(checksum_create("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)"), "5jlj4shz");
+        assert_eq!(checksum_create("raw(deadbeef)"), Ok("89f8spxm".to_string()));
+        assert_eq!(
+            checksum_create("raw( deadbeef )"),
+            Ok("985dv2zl".to_string())
+        );
+        assert_eq!(
+            checksum_create("raw(DEAD BEEF)"),
+            Ok("qqn7ll2h".to_string())
+        );
+        assert_eq!(
+            checksum_create("raw(DEA D BEEF)"),
+            Ok("egs9fwsr".to_string())
+        );
+        assert_eq!(checksum_create("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)"), Ok("vm4xc4ed".to_string()));
+        assert_eq!(checksum_create("pkh(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)"), Ok("ujpe9npc".to_string()));
+        assert_eq!(checksum_create("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)"), Ok("5jlj4shz".to_string()));
     }
 
     #[test]
     fn test_checksum_check() {
-        assert!(checksum_check("raw(deadbeef)", "89f8spxm"));
-        assert!(checksum_check("raw( deadbeef )", "985dv2zl"));
-        assert!(checksum_check("raw(DEAD BEEF)", "qqn7ll2h"));
-        assert!(checksum_check("raw(DEA D BEEF)", "egs9fwsr"));
-        assert!(checksum_check("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", "vm4xc4ed"));
-        assert!(checksum_check("pkh(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", "ujpe9npc"));
-        assert!(checksum_check("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", "5jlj4shz"));
+        assert_eq!(checksum_check("raw(deadbeef)", "89f8spxm"), Ok(true));
+        assert_eq!(checksum_check("raw( deadbeef )", "985dv2zl"), Ok(true));
+        assert_eq!(checksum_check("raw(DEAD BEEF)", "qqn7ll2h"), Ok(true));
+        assert_eq!(checksum_check("raw(DEA D BEEF)", "egs9fwsr"), Ok(true));
+        assert_eq!(checksum_check("pkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", "vm4xc4ed"), Ok(true));
+        assert_eq!(checksum_check("pkh(   xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8)", "ujpe9npc"), Ok(true));
+        assert_eq!(checksum_check("multi(2, xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8, xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB)", "5jlj4shz"), Ok(true));
+    }
+
+    #[test]
+    fn test_full_input_charset() {
+        // every character of the input charset, including '"', '\\', '#' and the space
+        let script = format!("raw({INPUT_CHARSET})");
+        assert_eq!(checksum_create(&script), Ok("r7r28t8w".to_string()));
+        assert_eq!(checksum_check(&script, "r7r28t8w"), Ok(true));
+        assert_eq!(checksum_check(&script, "r7r28t8q"), Ok(false));
+
+        for (script, checksum) in [("raw(de\"ad)", "slfa7ly5"), ("raw(de\\ad)", "ptvur25v")] {
+            assert_eq!(checksum_create(script), Ok(checksum.to_string()));
+            assert_eq!(checksum_check(script, checksum), Ok(true));
+        }
+    }
+
+    #[test]
+    fn test_split_checksum() {
+        assert_eq!(split_checksum("raw(00)"), Ok(("raw(00)", None)));
+        assert_eq!(split_checksum("raw(00)#abc"), Ok(("raw(00)", Some("abc"))));
+        assert_eq!(split_checksum("raw(00)#"), Ok(("raw(00)", Some(""))));
+        assert_eq!(
+            split_checksum("raw(00)#abc#def#"),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidChecksum,
+                "the script expression has 3 checksum separators '#', only one is allowed"
+            )
+            .with_span(11..12))
+        );
+    }
+
+    #[test]
+    fn test_checksum_check_mismatch() {
+        assert_eq!(checksum_check("raw(deedbeef)", "89f8spxm"), Ok(false));
+        assert_eq!(checksum_check("raw(deadbeef)", "89f8spx"), Ok(false));
+        assert_eq!(checksum_check("raw(deadbeef)", "89f8spxmx"), Ok(false));
+    }
+
+    #[test]
+    fn test_invalid_input_characters() {
+        assert_eq!(
+            checksum_create("raw(deadbeef\u{a0})"),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                &invalid_char_err_msg(&CharsetKind::Input, '\u{a0}')
+            )
+            .with_span(12..14))
+        );
+        assert_eq!(
+            checksum_create("pk(č)"),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                &invalid_char_err_msg(&CharsetKind::Input, 'č')
+            )
+            .with_span(3..5))
+        );
+        assert_eq!(
+            checksum_check("raw(dead\tbeef)", "89f8spxm"),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                &invalid_char_err_msg(&CharsetKind::Input, '\t')
+            )
+            .with_span(8..9))
+        );
+    }
+
+    #[test]
+    fn test_invalid_checksum_characters() {
+        assert_eq!(checksum_check("raw(deadbeef)", "89f8spxb"), Ok(false));
+        assert_eq!(checksum_check("raw(deadbeef)", "89F8SPXM"), Ok(false));
+        assert_eq!(
+            charset_index(&CharsetKind::Checksum, 'b'),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidCharacter,
+                &invalid_char_err_msg(&CharsetKind::Checksum, 'b')
+            ))
+        );
     }
 }
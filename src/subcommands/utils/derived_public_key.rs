@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+use bip32::{
+    secp256k1::{elliptic_curve::sec1::ToEncodedPoint, SecretKey},
+    ChildNumber, XPrv, XPub,
+};
+
+use crate::{
+    structs::parsing_error::ParsingError, subcommands::key_expression::split_key_expression,
+};
+
+use super::{
+    extended_key::{has_extended_key_prefix, is_extended_public_key},
+    hex_encoded_public_key::{has_hex_encoded_public_key_prefix, is_x_only_public_key},
+    hexadecimal::decode_hex,
+    number::parse_child_number,
+};
+
+const WILDCARDS: [&str; 4] = ["*", "*h", "*H", "*'"];
+
+/// Resolves a (previously validated) key expression into the serialized public key it stands for.
+///
+/// Extended keys are derived along the path appended to them. A trailing wildcard is replaced
+/// by `index`, so ranged key expressions can only be resolved when an index is provided.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The key expression is ranged and no `index` was given,
+/// - A hardened derivation step is requested from an extended public key,
+/// - The key itself cannot be decoded.
+pub fn derive_public_key(
+    key_expression: &str,
+    index: Option<u32>,
+) -> Result<Vec<u8>, ParsingError> {
+    let (_, key) = split_key_expression(key_expression.trim())?;
+
+    if has_hex_encoded_public_key_prefix(key) || is_x_only_public_key(key) {
+        return decode_hex(key);
+    }
+
+    if has_extended_key_prefix(key) {
+        return derive_extended_public_key(key, index);
+    }
+
+    wif_public_key(key)
+}
+
+/// Parses the path appended to an extended key (e.g. `/1/2h/*`) into child numbers,
+/// substituting the wildcard step with `index`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a path segment is invalid or the path is ranged and `index` is `None`.
+pub fn resolve_derivation_steps(
+    path: &str,
+    index: Option<u32>,
+) -> Result<Vec<ChildNumber>, ParsingError> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if WILDCARDS.contains(&segment) {
+                let index = index.ok_or_else(|| {
+                    ParsingError::new(
+                        "Key expression is ranged (contains a wildcard), a derivation index is required",
+                    )
+                })?;
+                Ok(ChildNumber::new(index, segment != "*")?)
+            } else {
+                parse_child_number(segment).map_err(|e| {
+                    ParsingError::new(&format!(
+                        "Invalid derivation segment '{segment}': {}",
+                        e.message
+                    ))
+                })
+            }
+        })
+        .collect()
+}
+
+fn derive_extended_public_key(key: &str, index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+    let (key, path) = key.split_at(key.find('/').unwrap_or(key.len()));
+    let steps = resolve_derivation_steps(path, index)?;
+
+    let xpub = if is_extended_public_key(key) {
+        let mut xpub = XPub::from_str(key)?;
+        for step in steps {
+            if step.is_hardened() {
+                return Err(ParsingError::new(
+                    "Hardened derivation steps require an extended private key",
+                ));
+            }
+            xpub = xpub.derive_child(step)?;
+        }
+        xpub
+    } else {
+        let mut xprv = XPrv::from_str(key)?;
+        for step in steps {
+            xprv = xprv.derive_child(step)?;
+        }
+        xprv.public_key()
+    };
+
+    Ok(xpub.to_bytes().to_vec())
+}
+
+fn wif_public_key(key: &str) -> Result<Vec<u8>, ParsingError> {
+    let bytes = bs58::decode(key)
+        .into_vec()
+        .map_err(|_| ParsingError::new("Could not convert WIF from base58"))?;
+
+    // 0x80 prefix, 32 bytes of the private key, optional 0x01 compression flag, 4 checksum bytes
+    let compressed = bytes.len() == 38;
+    let secret_key = bytes
+        .get(1..33)
+        .and_then(|secret| SecretKey::from_slice(secret).ok())
+        .ok_or_else(|| ParsingError::new("Invalid WIF format"))?;
+
+    Ok(secret_key
+        .public_key()
+        .to_encoded_point(compressed)
+        .as_bytes()
+        .to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_public_key() {
+        assert_eq!(
+            derive_public_key(
+                "[deadbeef/0h]0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600",
+                None
+            ),
+            Ok(
+                decode_hex("0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600")
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_wif_public_key() {
+        // private key 1 (compressed and uncompressed) maps to the generator point
+        assert_eq!(
+            derive_public_key("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn", None),
+            Ok(
+                decode_hex("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            derive_public_key("5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf", None)
+                .unwrap()
+                .len(),
+            65
+        );
+    }
+
+    #[test]
+    fn test_extended_key_derivation() {
+        // BIP 32 test vector 1, chain m/0h/1 derived from m/0h
+        let from_xprv = derive_public_key("xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7/1", None);
+        let from_xpub = derive_public_key("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*", Some(1));
+        let expected = XPub::from_str("xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ").unwrap().to_bytes().to_vec();
+
+        assert_eq!(from_xprv, Ok(expected.clone()));
+        assert_eq!(from_xpub, Ok(expected));
+    }
+
+    #[test]
+    fn test_ranged_key_without_index() {
+        assert!(derive_public_key("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*", None).is_err());
+        assert!(derive_public_key("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/1h", None).is_err());
+    }
+}
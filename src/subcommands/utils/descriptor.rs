@@ -0,0 +1,112 @@
+use crate::structs::{parsing_error::ParsingError, script_type::ScriptType};
+
+use super::{
+    checksum::checksum_create,
+    script_compiler::normalize_hex_case,
+    script_functions::{find_script_function, parse_script_function},
+};
+
+/// The branches of the receive and the change addresses, the last but one step of their key expressions.
+const RECEIVE_BRANCH: &str = "0";
+const CHANGE_BRANCH: &str = "1";
+
+/// Builds the checksummed receive (`/0/*`) and change (`/1/*`) descriptors of the given script type
+/// for an account level key expression.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the checksum of a descriptor cannot be computed.
+pub fn receive_and_change_descriptors(
+    account_key_expression: &str,
+    script_type: ScriptType,
+) -> Result<[String; 2], ParsingError> {
+    let descriptor = |chain: u8| -> Result<String, ParsingError> {
+        let descriptor = script_type.wrap(&format!("{account_key_expression}/{chain}/*"));
+        Ok(format!("{descriptor}#{}", checksum_create(&descriptor)?))
+    };
+
+    Ok([descriptor(0)?, descriptor(1)?])
+}
+
+/// Compares a receive and a change descriptor (both previously validated, without their checksums), which must
+/// differ only in the branch of their ranged keys, `.../0/*` of the receive descriptor against `.../1/*` of the
+/// change one, while their script functions, thresholds and keys are identical. Returns the number of the key
+/// pairs differing in the branch and the differences found (any other difference, or no key differing in the
+/// branch at all), none if the change descriptor matches.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a script expression is not supported or malformed.
+pub fn change_descriptor_differences(
+    receive: &str,
+    change: &str,
+) -> Result<(usize, Vec<String>), ParsingError> {
+    let mut differences = Vec::new();
+    let branches = compare_branches(
+        &canonical_script(receive),
+        &canonical_script(change),
+        &mut differences,
+    )?;
+
+    if differences.is_empty() && branches == 0 {
+        differences.push(
+            "no key of the descriptors differs in the branch, '.../0/*' and '.../1/*'".to_string(),
+        );
+    }
+
+    Ok((branches, differences))
+}
+
+fn canonical_script(script: &str) -> String {
+    let script: String = script.chars().filter(|c| !c.is_whitespace()).collect();
+    normalize_hex_case(&script)
+}
+
+fn compare_branches(
+    receive: &str,
+    change: &str,
+    differences: &mut Vec<String>,
+) -> Result<usize, ParsingError> {
+    let (receive_function, receive_args) = parse_script_function(receive)?;
+    let (change_function, change_args) = parse_script_function(change)?;
+    if receive_function.name() != change_function.name() || receive_args.len() != change_args.len()
+    {
+        differences.push(format!(
+            "'{receive}' of the receive descriptor does not have the shape of '{change}' of the change descriptor"
+        ));
+        return Ok(0);
+    }
+
+    let mut branches = 0;
+    for (receive_arg, change_arg) in receive_args.iter().zip(&change_args) {
+        if receive_arg.contains('(') && find_script_function(receive_arg).is_some() {
+            branches += compare_branches(receive_arg, change_arg, differences)?;
+        } else if let (Some(receive_prefix), Some(change_prefix)) = (
+            branch_prefix(receive_arg, RECEIVE_BRANCH),
+            branch_prefix(change_arg, CHANGE_BRANCH),
+        ) {
+            if receive_prefix == change_prefix {
+                branches += 1;
+            } else {
+                differences.push(format!(
+                    "the key '{receive_arg}' of the receive descriptor and the key '{change_arg}' of the change descriptor differ before the branch"
+                ));
+            }
+        } else if receive_arg != change_arg {
+            differences.push(format!(
+                "'{receive_arg}' of the receive descriptor does not match '{change_arg}' of the change descriptor, only '.../0/*' and '.../1/*' may differ"
+            ));
+        }
+    }
+
+    Ok(branches)
+}
+
+/// Returns the key expression without its branch, if it ends with the branch followed by a wildcard, e.g.
+/// `[d34db33f/84h/0h/0h]xpub.../*` (with the wildcard kept) for `[d34db33f/84h/0h/0h]xpub.../0/*`.
+fn branch_prefix(key_expression: &str, branch: &str) -> Option<String> {
+    let (rest, wildcard) = key_expression.rsplit_once('/')?;
+    let (prefix, key_branch) = rest.rsplit_once('/')?;
+
+    (wildcard.starts_with('*') && key_branch == branch).then(|| format!("{prefix}/{wildcard}"))
+}
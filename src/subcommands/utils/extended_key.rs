@@ -1,9 +1,66 @@
-use crate::structs::parsing_error::ParsingError;
-use bip32::{ChildNumber, ExtendedKeyAttrs, XPrv, XPub};
+use crate::{
+    structs::{
+        error_kind::ErrorKind, hardened_wildcard_policy::HardenedWildcardPolicy, network::Network,
+        parsing_error::ParsingError,
+    },
+    subcommands::key_expression::split_key_expression,
+    utils::error_messages::network_mismatch_err,
+};
+
+use super::number::parse_child_number;
+use bip32::{ExtendedKeyAttrs, XPrv, XPub};
 use std::str::FromStr;
 
+const EXTENDED_KEY_PREFIXES: [&str; 4] = ["xpub", "xprv", "tpub", "tprv"];
+const HARDENED_WILDCARDS: [&str; 3] = ["/*h", "/*H", "/*'"];
+/// The final derivation steps standing for all the unhardened or hardened children.
+const WILDCARDS: [&str; 4] = ["*", "*h", "*H", "*'"];
+
 pub fn has_extended_key_prefix(key: &str) -> bool {
-    key.starts_with("xpub") || key.starts_with("xprv")
+    EXTENDED_KEY_PREFIXES
+        .iter()
+        .any(|prefix| key.starts_with(prefix))
+}
+
+/// Returns whether the extended key is a public one (`xpub` or `tpub`), as opposed to a private one.
+pub fn is_extended_public_key(key: &str) -> bool {
+    key.get(1..4) == Some("pub")
+}
+
+/// Checks that all extended keys of the key expressions belong to the same network.
+///
+/// If `network` is given, every extended key must belong to it. Otherwise the network of the first
+/// extended key is expected for all of them. Keys other than extended keys are not checked.
+///
+/// Returns the network the keys belong to (the given one, or the one of the extended keys, if any).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] naming the first key expression whose key belongs to a different network.
+pub fn check_network_consistency(
+    key_expressions: &[String],
+    network: Option<Network>,
+) -> Result<Option<Network>, ParsingError> {
+    let mut expected: Option<(Network, &str)> = network.map(|network| (network, "selected"));
+
+    for key_expression in key_expressions {
+        let (_, key) = split_key_expression(key_expression.trim())?;
+        let Some(key_network) = Network::of_extended_key(key) else {
+            continue;
+        };
+        match expected {
+            None => expected = Some((key_network, "descriptor")),
+            Some((expected_network, _)) if expected_network == key_network => {}
+            Some((expected_network, source)) => {
+                return Err(ParsingError::with_kind(
+                    ErrorKind::NetworkMismatch,
+                    &network_mismatch_err(key_expression, key_network, expected_network, source),
+                ))
+            }
+        }
+    }
+
+    Ok(expected.map(|(network, _)| network))
 }
 
 pub fn validate_extended_key_attrs(attrs: &ExtendedKeyAttrs) -> Result<(), ParsingError> {
@@ -26,19 +83,57 @@ pub fn validate_extended_key_attrs(attrs: &ExtendedKeyAttrs) -> Result<(), Parsi
     }
 }
 
+/// Checks the hardened wildcards (`/*h`) of the key expressions against the policy.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] naming the first key expression with a hardened wildcard the policy does not accept.
+pub fn check_hardened_wildcards(
+    key_expressions: &[String],
+    policy: HardenedWildcardPolicy,
+) -> Result<(), ParsingError> {
+    for key_expression in key_expressions {
+        let (_, key) = split_key_expression(key_expression.trim())?;
+        if !HARDENED_WILDCARDS
+            .iter()
+            .any(|wildcard| key.ends_with(wildcard))
+        {
+            continue;
+        }
+        match policy {
+            HardenedWildcardPolicy::Allow => {}
+            HardenedWildcardPolicy::XprvOnly if !is_extended_public_key(key) => {}
+            HardenedWildcardPolicy::XprvOnly => {
+                return Err(ParsingError::new(&format!(
+                    "Key '{key_expression}' uses a hardened wildcard on an extended public key, from which hardened children cannot be derived"
+                )))
+            }
+            HardenedWildcardPolicy::Forbid => {
+                return Err(ParsingError::new(&format!(
+                    "Key '{key_expression}' uses a hardened wildcard, which is forbidden"
+                )))
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate whether key is xpub encoded extended public key or xprv encoded extended private key (as defined in BIP 32):
 ///     Followed by zero or more /NUM or /`NUMh` path elements indicating BIP 32 derivation steps to be taken after the given extended key.
 ///     Optionally followed by a single /* or /*h final step to denote all direct unhardened or hardened children.
 ///     Returns the key as a String
 pub fn validate_extended_key(key: &str) -> Result<String, ParsingError> {
     if !has_extended_key_prefix(key) {
-        return Err(ParsingError::new("Key must start with xpub or xprv"));
+        return Err(ParsingError::new(
+            "Key must start with xpub or xprv (or tpub or tprv on testnet)",
+        ));
     }
 
     let (key, path) = key.split_at(key.find('/').unwrap_or(key.len()));
 
     // Check if the key is valid
-    if key.starts_with("xpub") {
+    if is_extended_public_key(key) {
         XPub::from_str(key).map_err(|e| ParsingError::new(&format!("Invalid xpub key: {e}")))?;
     } else {
         XPrv::from_str(key).map_err(|e| ParsingError::new(&format!("Invalid xprv key: {e}")))?;
@@ -50,13 +145,38 @@ pub fn validate_extended_key(key: &str) -> Result<String, ParsingError> {
 
     let mut derivation_segments: Vec<&str> = path[1..].split('/').collect();
 
-    if [Some(&"*"), Some(&"*h"), Some(&"*H"), Some(&"*'")].contains(&derivation_segments.last()) {
+    let wildcards = derivation_segments
+        .iter()
+        .filter(|segment| WILDCARDS.contains(segment))
+        .count();
+    if wildcards > 1 {
+        return Err(ParsingError::new(&format!(
+            "The derivation path '{path}' has {wildcards} wildcards, only a single final /* or /*h step is allowed"
+        )));
+    }
+    if wildcards == 1
+        && !derivation_segments
+            .last()
+            .is_some_and(|last| WILDCARDS.contains(last))
+    {
+        return Err(ParsingError::new(&format!(
+            "The wildcard of the derivation path '{path}' must be its final step"
+        )));
+    }
+
+    if derivation_segments
+        .last()
+        .is_some_and(|last| WILDCARDS.contains(last))
+    {
         derivation_segments.pop();
     }
 
     for segment in derivation_segments {
-        ChildNumber::from_str(&segment.to_ascii_lowercase()).map_err(|e| {
-            ParsingError::new(&format!("Invalid derivation segment '{segment}': {e}"))
+        parse_child_number(segment).map_err(|e| {
+            ParsingError::new(&format!(
+                "Invalid derivation segment '{segment}': {}",
+                e.message
+            ))
         })?;
     }
 
@@ -78,4 +198,74 @@ mod tests {
         let result = validate_extended_key("xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3'/4h/5H/*'");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_extended_key_wildcard_position() {
+        let xpub = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+        assert_eq!(
+            validate_extended_key(&format!("{xpub}/*/1")),
+            Err(ParsingError::new(
+                "The wildcard of the derivation path '/*/1' must be its final step"
+            ))
+        );
+        assert_eq!(
+            validate_extended_key(&format!("{xpub}/0/*h/*")),
+            Err(ParsingError::new(
+                "The derivation path '/0/*h/*' has 2 wildcards, only a single final /* or /*h step is allowed"
+            ))
+        );
+        assert!(validate_extended_key(&format!("{xpub}/1/*")).is_ok());
+    }
+
+    #[test]
+    fn test_check_network_consistency() {
+        let xpub = "[deadbeef/0h]xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*".to_string();
+        let tpub = "tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp/*".to_string();
+        let hex = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600".to_string();
+
+        assert_eq!(
+            check_network_consistency(&[hex.clone(), xpub.clone()], None),
+            Ok(Some(Network::Mainnet))
+        );
+        assert_eq!(
+            check_network_consistency(std::slice::from_ref(&hex), None),
+            Ok(None)
+        );
+        assert_eq!(
+            check_network_consistency(&[tpub.clone(), hex], Some(Network::Testnet)),
+            Ok(Some(Network::Testnet))
+        );
+        assert_eq!(
+            check_network_consistency(&[xpub.clone(), tpub.clone()], None),
+            Err(ParsingError::with_kind(
+                ErrorKind::NetworkMismatch,
+                &network_mismatch_err(&tpub, Network::Testnet, Network::Mainnet, "descriptor")
+            ))
+        );
+        assert_eq!(
+            check_network_consistency(std::slice::from_ref(&xpub), Some(Network::Testnet)),
+            Err(ParsingError::with_kind(
+                ErrorKind::NetworkMismatch,
+                &network_mismatch_err(&xpub, Network::Mainnet, Network::Testnet, "selected")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_check_hardened_wildcards() {
+        let xpub = "[deadbeef/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3/*h".to_string();
+        let xprv = "xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc/3h/*'".to_string();
+        let unhardened = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/3h/*".to_string();
+        let keys = [xpub.clone(), xprv.clone(), unhardened.clone()];
+
+        assert!(check_hardened_wildcards(&keys, HardenedWildcardPolicy::Allow).is_ok());
+        assert!(check_hardened_wildcards(&keys, HardenedWildcardPolicy::XprvOnly).is_err());
+        assert!(check_hardened_wildcards(
+            &[xprv.clone(), unhardened.clone()],
+            HardenedWildcardPolicy::XprvOnly
+        )
+        .is_ok());
+        assert!(check_hardened_wildcards(&[xprv], HardenedWildcardPolicy::Forbid).is_err());
+        assert!(check_hardened_wildcards(&[unhardened], HardenedWildcardPolicy::Forbid).is_ok());
+    }
 }
@@ -0,0 +1,37 @@
+use bip32::secp256k1::sha2::{Digest, Sha256};
+use ripemd::Ripemd160;
+
+/// Computes `RIPEMD160(SHA256(data))`, the hash used by P2PKH and P2SH outputs.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+/// Computes `SHA256(data)`, the hash of the witness script used by P2WSH outputs.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::decode_hex;
+
+    #[test]
+    fn test_hash160() {
+        let public_key =
+            decode_hex("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+        assert_eq!(
+            hash160(&public_key).to_vec(),
+            decode_hex("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            sha256(b"").to_vec(),
+            decode_hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").unwrap()
+        );
+    }
+}
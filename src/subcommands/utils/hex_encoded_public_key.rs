@@ -1,5 +1,9 @@
+use bip32::secp256k1::{elliptic_curve::sec1::ToEncodedPoint, PublicKey};
+
 use crate::structs::parsing_error::ParsingError;
 
+use super::hexadecimal::{decode_hex, encode_hex};
+
 const HEX_ENCODED_PUBLIC_KEY_PREFIXES: [&str; 3] = ["02", "03", "04"];
 
 pub fn has_hex_encoded_public_key_prefix(input: &str) -> bool {
@@ -8,6 +12,14 @@ pub fn has_hex_encoded_public_key_prefix(input: &str) -> bool {
         .any(|prefix| input.starts_with(prefix))
 }
 
+/// The length of a hex encoded x-only public key (BIP 340), which has no prefix.
+const X_ONLY_PUBLIC_KEY_LENGTH: usize = 64;
+
+/// Returns whether the input is shaped as a hex encoded x-only public key, i.e. 64 hexadecimal characters.
+pub fn is_x_only_public_key(input: &str) -> bool {
+    input.len() == X_ONLY_PUBLIC_KEY_LENGTH && input.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// This function checks if the input is a hex encoded public key
 /// and returns it if it is valid. If the input is not a hex encoded public key,
 /// it returns an error.
@@ -45,3 +57,50 @@ pub fn parse_hex_encoded_public_key(input: &str) -> Result<(), ParsingError> {
 
     Ok(())
 }
+
+/// Checks that all of the given hex encoded public keys are valid points on the secp256k1 curve.
+///
+/// The keys are expected to have passed [`parse_hex_encoded_public_key`] (or [`is_x_only_public_key`], such a key
+/// is checked as the point with the even y-coordinate) already. They are validated
+/// in one batch, reusing a single decoding buffer, so that scripts with many keys (e.g. `multi`)
+/// do not pay the setup costs per key.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] naming the first key that is not a valid curve point.
+pub fn validate_public_keys_on_curve(keys: &[&str]) -> Result<(), ParsingError> {
+    let mut buffer: Vec<u8> = Vec::with_capacity(65);
+
+    for key in keys {
+        buffer.clear();
+        if key.len() == X_ONLY_PUBLIC_KEY_LENGTH {
+            buffer.push(0x02);
+        }
+        for index in (0..key.len()).step_by(2) {
+            buffer.push(u8::from_str_radix(&key[index..index + 2], 16)?);
+        }
+
+        if PublicKey::from_sec1_bytes(&buffer).is_err() {
+            return Err(ParsingError::new(&format!(
+                "Hex encoded public key '{key}' is not a valid point on the secp256k1 curve"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the compressed (02 or 03) form of the (previously validated) hex encoded public key, in lowercase.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key is not a valid point on the secp256k1 curve.
+pub fn compress_public_key(key: &str) -> Result<String, ParsingError> {
+    let public_key = PublicKey::from_sec1_bytes(&decode_hex(key)?).map_err(|_| {
+        ParsingError::new(&format!(
+            "Hex encoded public key '{key}' is not a valid point on the secp256k1 curve"
+        ))
+    })?;
+
+    Ok(encode_hex(public_key.to_encoded_point(true).as_bytes()))
+}
@@ -1,6 +1,6 @@
-use std::num::ParseIntError;
+use std::{fmt::Write, ops::Range};
 
-use crate::structs::parsing_error::ParsingError;
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
 
 /// The function `decode_hex` takes a hexadecimal string as input and returns a Result containing a
 /// vector of u8 bytes after decoding the hexadecimal string.
@@ -9,20 +9,52 @@ use crate::structs::parsing_error::ParsingError;
 ///
 /// * `s`: The function `decode_hex` takes a hexadecimal string `s` as input and attempts to decode it
 ///   into a vector of bytes (`Vec<u8>`). Each pair of characters in the input string represents a byte in
-///   hexadecimal format. The function processes the input string by converting each pair of characters
-///   into a
+///   hexadecimal format.
 ///
 /// Returns:
 ///
 /// The `decode_hex` function is returning a `Result` containing a decoded number in `Vec<u8>` if conversion
-/// was successful or a `ParseIntError`.
-pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
+/// was successful or a `ParsingError`, spanning the first invalid character, if the string is not hexadecimal
+/// or has an odd number of digits.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the string contains a character other than a hex digit or if it has an odd
+/// number of digits.
+pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParsingError> {
+    if let Some(span) = invalid_hex_digit_span(s, &[]) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidCharacter,
+            &format!(
+                "Invalid character '{}' in the hexadecimal string '{s}'",
+                &s[span.clone()]
+            ),
+        )
+        .with_span(span));
+    }
+    if !s.len().is_multiple_of(2) {
+        return Err(ParsingError::new(&format!(
+            "The hexadecimal string '{s}' has an odd number of digits, expected two per byte"
+        )));
+    }
+    // all the characters are ASCII hex digits now, so the byte pairs never split a character
     (0..s.len())
         .step_by(2)
-        .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(ParsingError::from))
         .collect()
 }
 
+/// Encodes the bytes as a lowercase hexadecimal string (two characters per byte).
+#[must_use]
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
 /// The function `assert_hexadecimal_format` checks if the input string is a valid hexadecimal string.
 ///
 /// Arguments:
@@ -34,24 +66,55 @@ pub fn decode_hex(s: &str) -> Result<Vec<u8>, ParseIntError> {
 ///
 /// A `Result` that indicates whether the input string is a valid hexadecimal string or not.
 /// If the input string is valid, it returns `Ok(())`. If the input string is not valid, it returns an
-/// `Err` containing a `ParsingError`.
+/// `Err` containing a `ParsingError`, spanning the first invalid character of the input (if any).
 pub fn assert_hexadecimal_format(input: &str, label: &str) -> Result<(), ParsingError> {
     let mut input_clone = input.to_string();
     input_clone.retain(|c| c != ' ');
 
     if input_clone.is_empty() || input_clone.chars().any(|c| !c.is_ascii_hexdigit()) {
-        return Err(ParsingError::new(&format!(
+        let err = ParsingError::new(&format!(
             "{label} '{input}' is not a valid hexadecimal string!"
-        )));
+        ));
+        return Err(match invalid_hex_digit_span(input, &[' ']) {
+            Some(span) => err.or_kind(ErrorKind::InvalidCharacter).with_span(span),
+            None => err,
+        });
     }
     Ok(())
 }
 
+/// Returns the byte range of the first character of the input that is neither a hex digit nor one of the separators.
+#[must_use]
+pub fn invalid_hex_digit_span(input: &str, separators: &[char]) -> Option<Range<usize>> {
+    input
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_hexdigit() && !separators.contains(c))
+        .map(|(position, c)| position..position + c.len_utf8())
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("00ff7F"), Ok(vec![0, 255, 127]));
+        assert_eq!(decode_hex(""), Ok(vec![]));
+        assert_eq!(
+            decode_hex("000"),
+            Err(ParsingError::new(
+                "The hexadecimal string '000' has an odd number of digits, expected two per byte"
+            ))
+        );
+        assert_eq!(
+            decode_hex("0g").map_err(|err| (err.kind, err.span)),
+            Err((ErrorKind::InvalidCharacter, Some(1..2)))
+        );
+        // a multibyte character is reported instead of being split
+        assert_eq!(decode_hex("0č").map_err(|err| err.span), Err(Some(1..3)));
+    }
+
     #[test]
     fn test_is_hexadecimal() {
         // ok
@@ -78,19 +141,19 @@ mod tests {
             assert_hexadecimal_format("123G", "argument")
                 .unwrap_err()
                 .to_string(),
-            "Parsing error: argument '123G' is not a valid hexadecimal string!"
+            "Parsing error [E012]: argument '123G' is not a valid hexadecimal string!"
         );
         assert_eq!(
             assert_hexadecimal_format("", "argument")
                 .unwrap_err()
                 .to_string(),
-            "Parsing error: argument '' is not a valid hexadecimal string!"
+            "Parsing error [E000]: argument '' is not a valid hexadecimal string!"
         );
         assert_eq!(
             assert_hexadecimal_format("  ", "argument")
                 .unwrap_err()
                 .to_string(),
-            "Parsing error: argument '  ' is not a valid hexadecimal string!"
+            "Parsing error [E000]: argument '  ' is not a valid hexadecimal string!"
         );
         assert_eq!(
             assert_hexadecimal_format(
@@ -98,7 +161,7 @@ mod tests {
                 "argument"
             )
             .unwrap_err().to_string(),
-            "Parsing error: argument '00\t01\t02\t03\t04\t05\t06\t07\t08\t09\t0a\t0b\t0c\t0d\t0e\t0f' is not a valid hexadecimal string!"
+            "Parsing error [E012]: argument '00\t01\t02\t03\t04\t05\t06\t07\t08\t09\t0a\t0b\t0c\t0d\t0e\t0f' is not a valid hexadecimal string!"
         );
     }
 }
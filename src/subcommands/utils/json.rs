@@ -0,0 +1,361 @@
+use std::{fmt::Write, iter::Peekable, str::Chars};
+
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+/// The deepest nesting of the arrays and objects of a parsed JSON document, the parser recurses into every level,
+/// so a deeper document (e.g. a request body of a million '[') would overflow the stack.
+pub const MAX_JSON_DEPTH: usize = 128;
+
+/// A parsed JSON value, numbers are kept as they were written.
+#[derive(Debug, PartialEq, Eq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the value of the `key` member, if this is an object containing it.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(value) => write!(f, "{value}"),
+            JsonValue::Number(number) => write!(f, "{number}"),
+            JsonValue::String(value) => write!(f, "{}", json_string(value)),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (position, item) in items.iter().enumerate() {
+                    let separator = if position == 0 { "" } else { "," };
+                    write!(f, "{separator}{item}")?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (position, (name, value)) in members.iter().enumerate() {
+                    let separator = if position == 0 { "" } else { "," };
+                    write!(f, "{separator}{}:{value}", json_string(name))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Parses a JSON document (RFC 8259).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the text is not a single valid JSON value, or its arrays and objects are nested
+/// deeper than [`MAX_JSON_DEPTH`].
+pub fn parse_json(text: &str) -> Result<JsonValue, ParsingError> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars, 0)?;
+    skip_whitespace(&mut chars);
+
+    match chars.next() {
+        None => Ok(value),
+        Some(c) => Err(unexpected_err(Some(c))),
+    }
+}
+
+/// Encodes the string as a JSON string literal (including the quotes).
+#[must_use]
+pub fn json_string(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(encoded, "\\u{:04x}", u32::from(c));
+            }
+            c => encoded.push(c),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+/// Encodes the named fields (see [`OutputFields`](crate::traits::output_fields::OutputFields)) as a JSON object of strings.
+#[must_use]
+pub fn fields_json(fields: Vec<(&'static str, String)>) -> String {
+    JsonValue::Object(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), JsonValue::String(value)))
+            .collect(),
+    )
+    .to_string()
+}
+
+fn unexpected_err(c: Option<char>) -> ParsingError {
+    match c {
+        Some(c) => ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            &format!("Invalid JSON: unexpected character '{c}'"),
+        ),
+        None => ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            "Invalid JSON: unexpected end of input",
+        ),
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars
+        .next_if(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        .is_some()
+    {}
+}
+
+fn expect_literal(chars: &mut Peekable<Chars>, literal: &str) -> Result<(), ParsingError> {
+    for expected in literal.chars() {
+        match chars.next() {
+            Some(c) if c == expected => {}
+            c => return Err(unexpected_err(c)),
+        }
+    }
+    Ok(())
+}
+
+/// Parses the value nested in `depth` arrays and objects.
+fn parse_value(chars: &mut Peekable<Chars>, depth: usize) -> Result<JsonValue, ParsingError> {
+    skip_whitespace(chars);
+
+    if depth == MAX_JSON_DEPTH && matches!(chars.peek(), Some('[' | '{')) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            &format!(
+            "Invalid JSON: the arrays and objects are nested deeper than {MAX_JSON_DEPTH} levels"
+        ),
+        ));
+    }
+    match chars.peek().copied() {
+        Some('n') => expect_literal(chars, "null").map(|()| JsonValue::Null),
+        Some('t') => expect_literal(chars, "true").map(|()| JsonValue::Bool(true)),
+        Some('f') => expect_literal(chars, "false").map(|()| JsonValue::Bool(false)),
+        Some('"') => parse_string(chars).map(JsonValue::String),
+        Some('[') => {
+            chars.next();
+            parse_sequence(chars, ']', |chars| parse_value(chars, depth + 1)).map(JsonValue::Array)
+        }
+        Some('{') => {
+            chars.next();
+            parse_sequence(chars, '}', |chars| {
+                skip_whitespace(chars);
+                let name = parse_string(chars)?;
+                skip_whitespace(chars);
+                expect_literal(chars, ":")?;
+                Ok((name, parse_value(chars, depth + 1)?))
+            })
+            .map(JsonValue::Object)
+        }
+        Some(c) if c == '-' || c.is_ascii_digit() => Ok(JsonValue::Number(parse_number(chars))),
+        c => Err(unexpected_err(c)),
+    }
+}
+
+/// Parses comma separated items up to the closing character (the opening one is already consumed).
+fn parse_sequence<T>(
+    chars: &mut Peekable<Chars>,
+    closing: char,
+    parse_item: impl Fn(&mut Peekable<Chars>) -> Result<T, ParsingError>,
+) -> Result<Vec<T>, ParsingError> {
+    let mut items = Vec::new();
+
+    skip_whitespace(chars);
+    if chars.next_if_eq(&closing).is_some() {
+        return Ok(items);
+    }
+
+    loop {
+        items.push(parse_item(chars)?);
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => {}
+            Some(c) if c == closing => return Ok(items),
+            c => return Err(unexpected_err(c)),
+        }
+    }
+}
+
+fn parse_number(chars: &mut Peekable<Chars>) -> String {
+    let mut number = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) {
+        number.push(c);
+    }
+    number
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, ParsingError> {
+    expect_literal(chars, "\"")?;
+    let mut value = String::new();
+
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('/') => value.push('/'),
+                Some('b') => value.push('\u{8}'),
+                Some('f') => value.push('\u{c}'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => value.push(parse_unicode_escape(chars)?),
+                c => return Err(unexpected_err(c)),
+            },
+            Some(c) if !c.is_control() => value.push(c),
+            c => return Err(unexpected_err(c)),
+        }
+    }
+}
+
+/// Parses the hex digits of a `\u` escape, including the low half of a surrogate pair.
+fn parse_unicode_escape(chars: &mut Peekable<Chars>) -> Result<char, ParsingError> {
+    let high = parse_code_unit(chars)?;
+    let code_point = if (0xd800..0xdc00).contains(&high) {
+        let low = expect_literal(chars, "\\u").and_then(|()| parse_code_unit(chars))?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return Err(ParsingError::with_kind(
+                ErrorKind::InvalidJson,
+                "Invalid JSON: unpaired surrogate in a '\\u' escape",
+            ));
+        }
+        0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+    } else {
+        high
+    };
+
+    char::from_u32(code_point).ok_or_else(|| {
+        ParsingError::with_kind(
+            ErrorKind::InvalidJson,
+            "Invalid JSON: escape of an invalid code point",
+        )
+    })
+}
+
+fn parse_code_unit(chars: &mut Peekable<Chars>) -> Result<u32, ParsingError> {
+    let hex: String = chars.by_ref().take(4).collect();
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .filter(|_| hex.len() == 4)
+        .ok_or_else(|| {
+            ParsingError::with_kind(
+                ErrorKind::InvalidJson,
+                &format!("Invalid JSON: invalid escape '\\u{hex}'"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json() {
+        assert_eq!(
+            parse_json(
+                r#" {"input": "xpubA\n", "options": ["--path", "0h"], "n": -1.5e3, "ok": true, "x": null} "#
+            ),
+            Ok(JsonValue::Object(vec![
+                (
+                    "input".to_string(),
+                    JsonValue::String("xpubA\n".to_string())
+                ),
+                (
+                    "options".to_string(),
+                    JsonValue::Array(vec![
+                        JsonValue::String("--path".to_string()),
+                        JsonValue::String("0h".to_string())
+                    ])
+                ),
+                ("n".to_string(), JsonValue::Number("-1.5e3".to_string())),
+                ("ok".to_string(), JsonValue::Bool(true)),
+                ("x".to_string(), JsonValue::Null),
+            ]))
+        );
+        assert_eq!(
+            parse_json(r#""\ud83d\ude00""#),
+            Ok(JsonValue::String("\u{1f600}".to_string()))
+        );
+        assert_eq!(parse_json("[]"), Ok(JsonValue::Array(vec![])));
+    }
+
+    #[test]
+    fn test_display_json() {
+        let text = r#"{"id":[1,-2.5e1,"x\"y"],"ok":false,"none":null,"empty":{}}"#;
+        assert_eq!(parse_json(text).unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn test_parse_invalid_json() {
+        for text in [
+            "",
+            "{",
+            r#"{"a" 1}"#,
+            "[1,]",
+            r#""unterminated"#,
+            "nul",
+            "{} {}",
+            r#""\x""#,
+        ] {
+            assert!(parse_json(text).is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_parse_deeply_nested_json() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse_json(&nested(MAX_JSON_DEPTH)).is_ok());
+        assert_eq!(
+            parse_json(&nested(MAX_JSON_DEPTH + 1)),
+            Err(ParsingError::with_kind(
+                ErrorKind::InvalidJson,
+                "Invalid JSON: the arrays and objects are nested deeper than 128 levels"
+            ))
+        );
+        // a million levels would overflow the stack of the recursive parser
+        assert!(parse_json(&"[".repeat(1_000_000)).is_err());
+        assert!(parse_json(&format!("{}1", "{\"a\":".repeat(1_000_000))).is_err());
+    }
+
+    #[test]
+    fn test_fields_json() {
+        assert_eq!(
+            fields_json(vec![("xpub", "xpubA".to_string()), ("xprv", String::new())]),
+            r#"{"xpub":"xpubA","xprv":""}"#
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+        assert_eq!(
+            parse_json(&json_string("x\ty\"")),
+            Ok(JsonValue::String("x\ty\"".to_string()))
+        );
+    }
+}
@@ -1,5 +1,17 @@
-use crate::structs::parsing_error::ParsingError;
-use bip32::DerivationPath;
+use crate::{
+    structs::{error_kind::ErrorKind, master_key::MasterKey, parsing_error::ParsingError},
+    subcommands::key_expression::split_key_expression,
+    utils::error_messages::{
+        origin_fingerprint_mismatch_err, origin_key_mismatch_err, script_limit_err,
+    },
+};
+use bip32::secp256k1::{elliptic_curve::sec1::ToEncodedPoint, PublicKey};
+
+use super::{
+    derived_public_key::{derive_public_key, resolve_derivation_steps},
+    hexadecimal::encode_hex,
+    number::parse_path_steps,
+};
 
 /// Validate the key origin
 ///
@@ -27,10 +39,89 @@ pub fn validate_key_origin(key_origin: &str) -> Result<(), ParsingError> {
         return Err(ParsingError::new("Fingerprint is not valid hex"));
     }
 
-    let path = format!("m{path}");
+    if !path.is_empty() {
+        let steps = path.strip_prefix('/').ok_or_else(|| {
+            ParsingError::new(
+                "Invalid derivation path: the steps must follow the fingerprint after a '/'",
+            )
+        })?;
+        parse_path_steps(steps)
+            .map_err(|e| ParsingError::new(&format!("Invalid derivation path: {}", e.message)))?;
+    }
+
+    Ok(())
+}
+
+/// Verifies that the key origin of a (previously validated) key expression matches the master key.
+///
+/// The origin fingerprint must equal the fingerprint of the master key. If the master key is an extended
+/// private key, the key must also equal the key derived from the master key along the origin path.
+/// Key expressions without a key origin are accepted as they are.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the fingerprint or the derived key does not match.
+pub fn verify_key_origin(key_expression: &str, master_key: &MasterKey) -> Result<(), ParsingError> {
+    let (Some(key_origin), key) = split_key_expression(key_expression.trim())? else {
+        return Ok(());
+    };
+    let content = &key_origin[1..key_origin.len() - 1];
+    let (fingerprint, path) = content.split_at(8);
+
+    let master_fingerprint = encode_hex(&master_key.fingerprint());
+    if !fingerprint.eq_ignore_ascii_case(&master_fingerprint) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::OriginMismatch,
+            &origin_fingerprint_mismatch_err(key_expression, &master_fingerprint),
+        ));
+    }
+
+    if let MasterKey::Private(master_xprv) = master_key {
+        let mut xprv = master_xprv.clone();
+        for step in resolve_derivation_steps(path, None)? {
+            xprv = xprv.derive_child(step)?;
+        }
 
-    path.parse::<DerivationPath>()
-        .map_err(|e| ParsingError::new(&format!("Invalid derivation path: {e}")))?;
+        // the key itself, before any derivation steps appended to it, is the one at the origin path
+        let key = key.split('/').next().unwrap_or(key);
+        let matches =
+            PublicKey::from_sec1_bytes(&derive_public_key(key, None)?).is_ok_and(|public_key| {
+                public_key.to_encoded_point(true).as_bytes() == xprv.public_key().to_bytes()
+            });
+        if !matches {
+            return Err(ParsingError::with_kind(
+                ErrorKind::OriginMismatch,
+                &origin_key_mismatch_err(key_expression),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the derivation steps of the key origin and the path appended to the key (its wildcard included)
+/// of every key expression together do not exceed the depth limit. The depth of a BIP 32 key is a single byte,
+/// so a key deeper than 255 steps can never be derived.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] reporting the combined depth of the first key expression exceeding the limit.
+pub fn check_origin_depths(key_expressions: &[String], limit: usize) -> Result<(), ParsingError> {
+    for key_expression in key_expressions {
+        let (key_origin, key) = split_key_expression(key_expression.trim())?;
+        let origin_depth = key_origin.map_or(0, |key_origin| key_origin.matches('/').count());
+        let path_depth = key.matches('/').count();
+        let depth = origin_depth + path_depth;
+        if depth > limit {
+            return Err(ParsingError::new(&script_limit_err(
+                &format!(
+                    "Key '{key_expression}' is {depth} derivation steps deep ({origin_depth} of the key origin and {path_depth} appended to the key)"
+                ),
+                limit,
+                "max-origin-depth",
+            )));
+        }
+    }
 
     Ok(())
 }
@@ -102,4 +193,56 @@ mod tests {
             "Expected short fingerprint to return error"
         );
     }
+
+    #[test]
+    fn test_verify_key_origin() {
+        // BIP 32 test vector 1, chain m/0h
+        let master_key = MasterKey::try_from("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi").unwrap();
+        let fingerprint = MasterKey::try_from("3442193e").unwrap();
+        let key = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+
+        let valid = format!("[3442193e/0h]{key}/1/*");
+        assert_eq!(verify_key_origin(&valid, &master_key), Ok(()));
+        assert_eq!(verify_key_origin(key, &master_key), Ok(()));
+
+        let wrong_path = format!("[3442193e/1h]{key}");
+        assert_eq!(
+            verify_key_origin(&wrong_path, &master_key),
+            Err(ParsingError::with_kind(
+                ErrorKind::OriginMismatch,
+                &origin_key_mismatch_err(&wrong_path)
+            ))
+        );
+        assert_eq!(verify_key_origin(&wrong_path, &fingerprint), Ok(()));
+
+        let wrong_fingerprint = format!("[deadbeef/0h]{key}");
+        assert_eq!(
+            verify_key_origin(&wrong_fingerprint, &fingerprint),
+            Err(ParsingError::with_kind(
+                ErrorKind::OriginMismatch,
+                &origin_fingerprint_mismatch_err(&wrong_fingerprint, "3442193e")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_check_origin_depths() {
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let origin = "/0".repeat(200);
+        let path = "/1".repeat(55);
+
+        let shallow = format!("[d34db33f{origin}]{xpub}{path}");
+        assert_eq!(check_origin_depths(&[shallow], 255), Ok(()));
+
+        let deep = format!("[d34db33f{origin}]{xpub}{path}/*");
+        assert_eq!(
+            check_origin_depths(std::slice::from_ref(&deep), 255),
+            Err(ParsingError::new(&script_limit_err(
+                &format!("Key '{deep}' is 256 derivation steps deep (200 of the key origin and 56 appended to the key)"),
+                255,
+                "max-origin-depth"
+            )))
+        );
+        assert_eq!(check_origin_depths(&[deep], 256), Ok(()));
+    }
 }
@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use bip32::{XPrv, XPub};
+
+use crate::{
+    structs::{
+        error_kind::ErrorKind,
+        key_policy::{KeyPolicy, PolicyKey},
+        parsing_error::ParsingError,
+    },
+    subcommands::key_expression::split_key_expression,
+};
+
+use super::{
+    extended_key::{has_extended_key_prefix, is_extended_public_key},
+    hexadecimal::decode_hex,
+};
+
+/// Checks the (previously validated) key expressions of a descriptor against the key policy: every required key
+/// must appear among them and none of the forbidden ones may. A fingerprint matches the fingerprint of a key origin,
+/// an extended public key matches the extended key of a key expression (an extended private key by its public key).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] of the [`ErrorKind::PolicyViolation`] kind listing all the violated rules.
+pub fn check_key_policy(
+    key_expressions: &[String],
+    policy: &KeyPolicy,
+) -> Result<(), ParsingError> {
+    let keys = key_expressions
+        .iter()
+        .map(|key_expression| policy_keys(key_expression))
+        .collect::<Result<Vec<Vec<PolicyKey>>, ParsingError>>()?;
+
+    let mut violations = Vec::new();
+    for rule in &policy.rules {
+        let position = keys.iter().position(|keys| keys.contains(&rule.key));
+        match (rule.required, position) {
+            (true, None) => violations.push(format!(
+                "the required key '{}' does not appear in the descriptor",
+                rule.entry
+            )),
+            (false, Some(position)) => violations.push(format!(
+                "the forbidden key '{}' appears as the key {} of the descriptor",
+                rule.entry,
+                position + 1
+            )),
+            _ => {}
+        }
+    }
+
+    if !violations.is_empty() {
+        return Err(ParsingError::with_kind(
+            ErrorKind::PolicyViolation,
+            &format!(
+                "The descriptor violates the key policy of '{}':\n{}",
+                policy.path,
+                violations.join("\n")
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// The keys of a key expression the policy rules can match: the fingerprint of its key origin and its extended
+/// public key.
+fn policy_keys(key_expression: &str) -> Result<Vec<PolicyKey>, ParsingError> {
+    let (key_origin, key) = split_key_expression(key_expression.trim())?;
+    let mut keys = Vec::new();
+
+    if let Some(fingerprint) = key_origin.and_then(|key_origin| key_origin.get(1..9)) {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&decode_hex(fingerprint)?);
+        keys.push(PolicyKey::Fingerprint(bytes));
+    }
+
+    if has_extended_key_prefix(key) {
+        let encoded = key.split('/').next().unwrap_or(key);
+        let xpub = if is_extended_public_key(encoded) {
+            XPub::from_str(encoded)?
+        } else {
+            XPrv::from_str(encoded)?.public_key()
+        };
+        keys.push(PolicyKey::Extended(xpub));
+    }
+
+    Ok(keys)
+}
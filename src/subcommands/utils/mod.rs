@@ -1,6 +1,34 @@
+pub mod address;
+pub mod anonymize;
+pub mod cbor;
 pub mod checksum;
+pub mod derived_public_key;
+pub mod descriptor;
 pub mod extended_key;
+pub mod hashes;
 pub mod hex_encoded_public_key;
 pub mod hexadecimal;
+pub mod json;
 pub mod key_origin;
+pub mod key_policy;
+pub mod multisig;
+pub mod number;
+pub mod opcodes;
+pub mod output_encoders;
+pub mod output_template;
+pub mod passphrase;
+pub mod psbt;
+pub mod qr;
+pub mod rotate_key;
+pub mod script_compiler;
+pub mod script_functions;
+pub mod secret;
+pub mod seed;
+pub mod shamir;
+pub mod slip39_wordlist;
+pub mod taproot;
+pub mod template;
+pub mod threshold;
+pub mod ur;
+pub mod ur_registry;
 pub mod wallet_import_format;
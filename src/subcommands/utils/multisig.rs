@@ -0,0 +1,261 @@
+use bip32::DerivationPath;
+
+use crate::{
+    structs::{
+        derive_key_config::parse_derivation_path, error_kind::ErrorKind,
+        parsing_error::ParsingError,
+    },
+    subcommands::key_expression::split_key_expression,
+};
+
+use super::{
+    checksum::checksum_check,
+    extended_key::{check_network_consistency, is_extended_public_key, validate_extended_key},
+    key_origin::validate_key_origin,
+    number::parse_threshold,
+};
+
+/// The most cosigners the hardware wallets (and the wallets coordinating them) support.
+const MAX_COSIGNERS: usize = 15;
+
+/// The derivation steps allowed after the extended keys (`/**` being the BSMS shorthand of `/<0;1>/*`),
+/// the wallets derive the receive and change addresses on their own.
+const ALLOWED_KEY_SUFFIXES: [&str; 5] = ["", "/0/*", "/1/*", "/<0;1>/*", "/**"];
+
+/// The address format of a multisig wallet.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MultisigFormat {
+    /// `sh(sortedmulti(...))`
+    P2sh,
+    /// `wsh(sortedmulti(...))`
+    P2wsh,
+    /// `sh(wsh(sortedmulti(...)))`
+    P2shP2wsh,
+}
+
+impl MultisigFormat {
+    /// Returns the name of the format used by the wallet setup files, e.g. `P2SH-P2WSH`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            MultisigFormat::P2sh => "P2SH",
+            MultisigFormat::P2wsh => "P2WSH",
+            MultisigFormat::P2shP2wsh => "P2SH-P2WSH",
+        }
+    }
+
+    /// Wraps the `sortedmulti(...)` script into the script expression of the format.
+    #[must_use]
+    pub fn wrap(self, multisig: &str) -> String {
+        match self {
+            MultisigFormat::P2sh => format!("sh({multisig})"),
+            MultisigFormat::P2wsh => format!("wsh({multisig})"),
+            MultisigFormat::P2shP2wsh => format!("sh(wsh({multisig}))"),
+        }
+    }
+}
+
+/// A cosigner of a multisig wallet.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cosigner {
+    /// The fingerprint of the cosigner's master key, in lowercase hexadecimal.
+    pub fingerprint: String,
+    /// The derivation path of the extended public key from the master key.
+    pub path: DerivationPath,
+    pub xpub: String,
+}
+
+/// The multisig wallet described by a `sortedmulti` descriptor.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MultisigWallet {
+    pub format: MultisigFormat,
+    pub threshold: usize,
+    pub cosigners: Vec<Cosigner>,
+}
+
+impl MultisigWallet {
+    /// Returns the `{threshold} of {cosigners}` policy of the wallet.
+    #[must_use]
+    pub fn policy(&self) -> String {
+        format!("{} of {}", self.threshold, self.cosigners.len())
+    }
+
+    /// Returns the descriptor of the wallet (without a checksum), every key followed by the `key_suffix`.
+    #[must_use]
+    pub fn descriptor(&self, key_suffix: &str) -> String {
+        let keys: Vec<String> = self
+            .cosigners
+            .iter()
+            .map(|cosigner| {
+                let path = cosigner.path.to_string();
+                format!(
+                    "[{}{}]{}{key_suffix}",
+                    cosigner.fingerprint,
+                    path.trim_start_matches('m').replace('\'', "h"),
+                    cosigner.xpub
+                )
+            })
+            .collect();
+
+        self.format.wrap(&format!(
+            "sortedmulti({},{})",
+            self.threshold,
+            keys.join(",")
+        ))
+    }
+}
+
+/// Parses a `sortedmulti` descriptor of a multisig wallet.
+///
+/// The descriptor must be one of `sh(sortedmulti(...))`, `wsh(sortedmulti(...))` or
+/// `sh(wsh(sortedmulti(...)))`, optionally with its checksum, and every key must be an extended public key
+/// with its key origin, optionally followed by `/0/*`, `/1/*`, `/<0;1>/*` or `/**`. The descriptor line
+/// of a BSMS (BIP 129) descriptor record is therefore accepted as well.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The descriptor is not a `sortedmulti` descriptor in one of the supported address formats
+///   (the hardware wallets always sort the keys, so `multi` descriptors are rejected),
+/// - The checksum does not match,
+/// - The threshold or the number of cosigners (at most 15) is not supported,
+/// - A key is not an extended public key with its key origin, or the keys belong to different networks.
+pub fn parse_multisig_descriptor(input: &str) -> Result<MultisigWallet, ParsingError> {
+    let (script, checksum) = match input.trim().split_once('#') {
+        Some((script, checksum)) => (script, Some(checksum)),
+        None => (input.trim(), None),
+    };
+    if let Some(checksum) = checksum {
+        if !checksum_check(script, checksum)? {
+            return Err(ParsingError::with_kind(
+                ErrorKind::ChecksumMismatch,
+                &format!("Checksum '{checksum}' does not match the descriptor"),
+            ));
+        }
+    }
+
+    let (format, multisig) = if let Some(inner) = function_arg(script, "sh") {
+        match function_arg(inner, "wsh") {
+            Some(multisig) => (MultisigFormat::P2shP2wsh, multisig),
+            None => (MultisigFormat::P2sh, inner),
+        }
+    } else if let Some(multisig) = function_arg(script, "wsh") {
+        (MultisigFormat::P2wsh, multisig)
+    } else {
+        return Err(ParsingError::new(
+            "Only sh(sortedmulti(...)), wsh(sortedmulti(...)) or sh(wsh(sortedmulti(...))) multisig descriptors are supported",
+        ));
+    };
+
+    let Some(args) = function_arg(multisig, "sortedmulti") else {
+        return Err(ParsingError::new(
+            "The hardware wallets always sort the keys of a multisig wallet, so only sortedmulti(...) descriptors are supported",
+        ));
+    };
+    let args: Vec<String> = args.split(',').map(|arg| arg.trim().to_string()).collect();
+    let (threshold, key_expressions) = args
+        .split_first()
+        .ok_or_else(|| ParsingError::new("at least two arguments needed"))?;
+
+    let threshold = parse_threshold(threshold)?;
+    if key_expressions.len() > MAX_COSIGNERS || threshold == 0 || threshold > key_expressions.len()
+    {
+        return Err(ParsingError::new(&format!(
+            "Policy {threshold} of {} is not supported, from 1 to {MAX_COSIGNERS} cosigners and the threshold from 1 to their number are",
+            key_expressions.len()
+        )));
+    }
+    check_network_consistency(key_expressions, None)?;
+
+    Ok(MultisigWallet {
+        format,
+        threshold,
+        cosigners: key_expressions
+            .iter()
+            .map(|key_expression| parse_cosigner(key_expression))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn parse_cosigner(key_expression: &str) -> Result<Cosigner, ParsingError> {
+    let (Some(key_origin), key) = split_key_expression(key_expression)? else {
+        return Err(ParsingError::new(&format!(
+            "Key '{key_expression}' must have its key origin, the fingerprint and the derivation of every cosigner are needed"
+        )));
+    };
+    validate_key_origin(key_origin)?;
+    let (fingerprint, path) = key_origin[1..key_origin.len() - 1].split_at(8);
+
+    let (xpub, suffix) = key.split_at(key.find('/').unwrap_or(key.len()));
+    if !is_extended_public_key(xpub) || !ALLOWED_KEY_SUFFIXES.contains(&suffix) {
+        return Err(ParsingError::new(&format!(
+            "Key '{key_expression}' must be an extended public key, optionally followed by /0/*, /1/*, /<0;1>/* or /**"
+        )));
+    }
+
+    Ok(Cosigner {
+        fingerprint: fingerprint.to_ascii_lowercase(),
+        path: parse_derivation_path(&format!("m{path}"))?,
+        xpub: validate_extended_key(xpub)?,
+    })
+}
+
+/// Returns the argument of the `name(...)` function call, if the script is one.
+fn function_arg<'a>(script: &'a str, name: &str) -> Option<&'a str> {
+    script
+        .trim()
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XPUB_1: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const XPUB_2: &str = "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB";
+
+    #[test]
+    fn test_parse_multisig_descriptor() {
+        let wallet = parse_multisig_descriptor(&format!(
+            "sh(wsh(sortedmulti(1,[3442193E/48'/0'/0'/1']{XPUB_1}/0/*,[bd16bee5]{XPUB_2}/**)))"
+        ))
+        .unwrap();
+
+        assert_eq!(wallet.format, MultisigFormat::P2shP2wsh);
+        assert_eq!(wallet.policy(), "1 of 2");
+        assert_eq!(
+            wallet.cosigners[0],
+            Cosigner {
+                fingerprint: "3442193e".to_string(),
+                path: "m/48'/0'/0'/1'".parse().unwrap(),
+                xpub: XPUB_1.to_string(),
+            }
+        );
+        assert_eq!(
+            wallet.descriptor("/<0;1>/*"),
+            format!("sh(wsh(sortedmulti(1,[3442193e/48h/0h/0h/1h]{XPUB_1}/<0;1>/*,[bd16bee5]{XPUB_2}/<0;1>/*)))")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_multisig_descriptor() {
+        let valid = format!("wsh(sortedmulti(2,[3442193e/48h]{XPUB_1},[bd16bee5/48h]{XPUB_2}))");
+        assert!(parse_multisig_descriptor(&valid).is_ok());
+
+        for invalid in [
+            valid.replace("wsh(", "tr("),
+            valid.replace("sortedmulti", "multi"),
+            valid.replace("sortedmulti(2", "sortedmulti(3"),
+            valid.replace("sortedmulti(2", "sortedmulti(0"),
+            valid.replace("[3442193e/48h]", ""),
+            valid.replace(XPUB_1, &format!("{XPUB_1}/2/*")),
+            valid.replace(XPUB_1, "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"),
+            valid.replace(XPUB_1, "tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp"),
+            format!("{valid}#00000000"),
+        ] {
+            assert!(parse_multisig_descriptor(&invalid).is_err(), "{invalid}");
+        }
+    }
+}
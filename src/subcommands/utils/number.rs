@@ -0,0 +1,189 @@
+use bip32::ChildNumber;
+
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+/// The bound of the (unhardened) indexes of the BIP 32 derivation steps, the hardened ones have the same range.
+pub const INDEX_BOUND: u32 = 1 << 31;
+
+/// The markers of a hardened derivation step.
+const HARDENED_MARKERS: [char; 3] = ['h', 'H', '\''];
+
+/// Parses an unsigned decimal number written canonically: ASCII digits only (no sign, no whitespace) and no leading
+/// zeros (but `0` itself), unlike [`str::parse`], which also accepts e.g. `+2` or `007`. `what` names the number in
+/// the errors, e.g. `threshold`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the value is not a canonical decimal number or it does not fit into 64 bits.
+pub fn parse_unsigned(value: &str, what: &str) -> Result<u64, ParsingError> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ParsingError::new(&format!(
+            "The {what} '{}' must be a decimal number without a sign",
+            value.escape_debug()
+        )));
+    }
+    if value.len() > 1 && value.starts_with('0') {
+        return Err(ParsingError::new(&format!(
+            "The {what} '{value}' must not have leading zeros"
+        )));
+    }
+
+    value
+        .parse()
+        .map_err(|_| ParsingError::new(&format!("The {what} '{value}' is too large")))
+}
+
+/// Parses the threshold of a multisig (or of the shares of a secret) the same way as [`parse_unsigned`].
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the value is not a canonical decimal number or it is too large.
+pub fn parse_threshold(value: &str) -> Result<usize, ParsingError> {
+    let threshold = parse_unsigned(value, "threshold")
+        .map_err(|err| err.or_kind(ErrorKind::InvalidThreshold))?;
+    usize::try_from(threshold).map_err(|_| {
+        ParsingError::with_kind(
+            ErrorKind::InvalidThreshold,
+            &format!("The threshold '{value}' is too large"),
+        )
+    })
+}
+
+/// Parses a derivation index (e.g. of `--index` or of an account) the same way as [`parse_unsigned`], returning
+/// `None` unless it is from the range [0,...,2^31-1], so that the flags keep their own errors.
+#[must_use]
+pub fn parse_index(value: &str) -> Option<u32> {
+    parse_unsigned(value, "derivation index")
+        .ok()
+        .and_then(|index| u32::try_from(index).ok())
+        .filter(|index| *index < INDEX_BOUND)
+}
+
+/// Parses a single step of a derivation path, e.g. `84h`, `0'` or `1`, its index the same way as
+/// [`parse_unsigned`].
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the step is empty, its index is not a canonical decimal number or it is out of the
+/// range [0,...,2^31-1].
+pub fn parse_child_number(segment: &str) -> Result<ChildNumber, ParsingError> {
+    if segment.is_empty() {
+        return Err(ParsingError::new("The derivation path has an empty step"));
+    }
+    let (index, hardened) = match segment.strip_suffix(HARDENED_MARKERS) {
+        Some(index) => (index, true),
+        None => (segment, false),
+    };
+
+    let index = parse_unsigned(index, "derivation index")?;
+    let index = u32::try_from(index)
+        .ok()
+        .filter(|index| *index < INDEX_BOUND)
+        .ok_or_else(|| {
+            ParsingError::new(&format!(
+                "The derivation index '{index}' is out of the range [0,...,2^31-1]"
+            ))
+        })?;
+    Ok(ChildNumber::new(index, hardened)?)
+}
+
+/// Parses the steps of a relative derivation path separated by slashes, e.g. `84h/0h/0h`. An empty step (e.g. of
+/// `84h//0h`, `/84h` or an empty path) is an error.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a step is invalid (see [`parse_child_number`]).
+pub fn parse_path_steps(path: &str) -> Result<Vec<ChildNumber>, ParsingError> {
+    path.split('/').map(parse_child_number).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_unsigned() {
+        assert_eq!(parse_unsigned("0", "threshold"), Ok(0));
+        assert_eq!(parse_unsigned("2", "threshold"), Ok(2));
+
+        for (value, message) in [
+            (
+                "+2",
+                "The threshold '+2' must be a decimal number without a sign",
+            ),
+            (
+                "-2",
+                "The threshold '-2' must be a decimal number without a sign",
+            ),
+            (
+                " 2",
+                "The threshold ' 2' must be a decimal number without a sign",
+            ),
+            (
+                "\t2",
+                "The threshold '\\t2' must be a decimal number without a sign",
+            ),
+            (
+                "",
+                "The threshold '' must be a decimal number without a sign",
+            ),
+            (
+                "٣",
+                "The threshold '٣' must be a decimal number without a sign",
+            ),
+            ("02", "The threshold '02' must not have leading zeros"),
+            (
+                "18446744073709551616",
+                "The threshold '18446744073709551616' is too large",
+            ),
+        ] {
+            assert_eq!(
+                parse_unsigned(value, "threshold"),
+                Err(ParsingError::new(message)),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_index() {
+        assert_eq!(parse_index("2147483647"), Some(INDEX_BOUND - 1));
+        assert_eq!(parse_index("2147483648"), None);
+        assert_eq!(parse_index("+1"), None);
+        assert_eq!(parse_index("01"), None);
+    }
+
+    #[test]
+    fn test_parse_path_steps() {
+        assert_eq!(
+            parse_path_steps("84h/0'/1H/7"),
+            Ok(vec![
+                ChildNumber::new(84, true).unwrap(),
+                ChildNumber::new(0, true).unwrap(),
+                ChildNumber::new(1, true).unwrap(),
+                ChildNumber::new(7, false).unwrap(),
+            ])
+        );
+
+        assert_eq!(
+            parse_path_steps("+1"),
+            Err(ParsingError::new(
+                "The derivation index '+1' must be a decimal number without a sign"
+            ))
+        );
+        assert_eq!(
+            parse_path_steps("2147483648h"),
+            Err(ParsingError::new(
+                "The derivation index '2147483648' is out of the range [0,...,2^31-1]"
+            ))
+        );
+        assert!(parse_path_steps("01").is_err());
+        assert!(parse_path_steps("1//2").is_err());
+        assert!(parse_path_steps("/1").is_err());
+        assert_eq!(
+            parse_path_steps(""),
+            Err(ParsingError::new("The derivation path has an empty step"))
+        );
+        assert!(parse_path_steps("1hh").is_err());
+    }
+}
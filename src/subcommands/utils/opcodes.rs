@@ -0,0 +1,290 @@
+use crate::structs::parsing_error::ParsingError;
+
+use super::hexadecimal::encode_hex;
+
+pub const OP_0: u8 = 0x00;
+pub const OP_PUSHDATA1: u8 = 0x4c;
+pub const OP_PUSHDATA2: u8 = 0x4d;
+pub const OP_PUSHDATA4: u8 = 0x4e;
+pub const OP_1: u8 = 0x51;
+pub const OP_DUP: u8 = 0x76;
+pub const OP_EQUAL: u8 = 0x87;
+pub const OP_EQUALVERIFY: u8 = 0x88;
+pub const OP_HASH160: u8 = 0xa9;
+pub const OP_CHECKSIG: u8 = 0xac;
+pub const OP_CHECKMULTISIG: u8 = 0xae;
+pub const OP_CHECKLOCKTIMEVERIFY: u8 = 0xb1;
+pub const OP_CHECKSEQUENCEVERIFY: u8 = 0xb2;
+
+/// Names of the non-push opcodes `0x4f..=0xba` as used by Bitcoin Core (e.g. `OP_CHECKSIG`).
+const OPCODE_NAMES: [&str; 0xbb - 0x4f] = [
+    "OP_1NEGATE",
+    "OP_RESERVED",
+    "OP_1",
+    "OP_2",
+    "OP_3",
+    "OP_4",
+    "OP_5",
+    "OP_6",
+    "OP_7",
+    "OP_8",
+    "OP_9",
+    "OP_10",
+    "OP_11",
+    "OP_12",
+    "OP_13",
+    "OP_14",
+    "OP_15",
+    "OP_16",
+    "OP_NOP",
+    "OP_VER",
+    "OP_IF",
+    "OP_NOTIF",
+    "OP_VERIF",
+    "OP_VERNOTIF",
+    "OP_ELSE",
+    "OP_ENDIF",
+    "OP_VERIFY",
+    "OP_RETURN",
+    "OP_TOALTSTACK",
+    "OP_FROMALTSTACK",
+    "OP_2DROP",
+    "OP_2DUP",
+    "OP_3DUP",
+    "OP_2OVER",
+    "OP_2ROT",
+    "OP_2SWAP",
+    "OP_IFDUP",
+    "OP_DEPTH",
+    "OP_DROP",
+    "OP_DUP",
+    "OP_NIP",
+    "OP_OVER",
+    "OP_PICK",
+    "OP_ROLL",
+    "OP_ROT",
+    "OP_SWAP",
+    "OP_TUCK",
+    "OP_CAT",
+    "OP_SUBSTR",
+    "OP_LEFT",
+    "OP_RIGHT",
+    "OP_SIZE",
+    "OP_INVERT",
+    "OP_AND",
+    "OP_OR",
+    "OP_XOR",
+    "OP_EQUAL",
+    "OP_EQUALVERIFY",
+    "OP_RESERVED1",
+    "OP_RESERVED2",
+    "OP_1ADD",
+    "OP_1SUB",
+    "OP_2MUL",
+    "OP_2DIV",
+    "OP_NEGATE",
+    "OP_ABS",
+    "OP_NOT",
+    "OP_0NOTEQUAL",
+    "OP_ADD",
+    "OP_SUB",
+    "OP_MUL",
+    "OP_DIV",
+    "OP_MOD",
+    "OP_LSHIFT",
+    "OP_RSHIFT",
+    "OP_BOOLAND",
+    "OP_BOOLOR",
+    "OP_NUMEQUAL",
+    "OP_NUMEQUALVERIFY",
+    "OP_NUMNOTEQUAL",
+    "OP_LESSTHAN",
+    "OP_GREATERTHAN",
+    "OP_LESSTHANOREQUAL",
+    "OP_GREATERTHANOREQUAL",
+    "OP_MIN",
+    "OP_MAX",
+    "OP_WITHIN",
+    "OP_RIPEMD160",
+    "OP_SHA1",
+    "OP_SHA256",
+    "OP_HASH160",
+    "OP_HASH256",
+    "OP_CODESEPARATOR",
+    "OP_CHECKSIG",
+    "OP_CHECKSIGVERIFY",
+    "OP_CHECKMULTISIG",
+    "OP_CHECKMULTISIGVERIFY",
+    "OP_NOP1",
+    "OP_CHECKLOCKTIMEVERIFY",
+    "OP_CHECKSEQUENCEVERIFY",
+    "OP_NOP4",
+    "OP_NOP5",
+    "OP_NOP6",
+    "OP_NOP7",
+    "OP_NOP8",
+    "OP_NOP9",
+    "OP_NOP10",
+    "OP_CHECKSIGADD",
+];
+
+/// Returns the name of a non-push opcode as used by Bitcoin Core.
+fn opcode_name(opcode: u8) -> Option<&'static str> {
+    match opcode {
+        OP_0 => Some("OP_0"),
+        0x4f..=0xba => Some(OPCODE_NAMES[usize::from(opcode - 0x4f)]),
+        _ => None,
+    }
+}
+
+fn truncated_push_err(offset: usize) -> ParsingError {
+    ParsingError::new(&format!(
+        "Script contains a truncated data push at byte offset {offset}"
+    ))
+}
+
+/// Turns a serialized Bitcoin Script into its human-readable opcode representation.
+///
+/// Data pushes are printed as lowercase hex, the other opcodes by their names separated
+/// by a single space (e.g. `OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG`).
+/// Bytes that do not correspond to any defined opcode are printed as `OP_UNKNOWN[0x..]`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a data push reaches past the end of the script.
+pub fn disassemble_script(script: &[u8]) -> Result<String, ParsingError> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut offset = 0;
+
+    while offset < script.len() {
+        let opcode = script[offset];
+        let (header_length, data_length) = match opcode {
+            0x01..=0x4b => (1, usize::from(opcode)),
+            OP_PUSHDATA1 | OP_PUSHDATA2 | OP_PUSHDATA4 => {
+                let size_length = match opcode {
+                    OP_PUSHDATA1 => 1,
+                    OP_PUSHDATA2 => 2,
+                    _ => 4,
+                };
+                let size_bytes = script
+                    .get(offset + 1..offset + 1 + size_length)
+                    .ok_or_else(|| truncated_push_err(offset))?;
+                let data_length = size_bytes
+                    .iter()
+                    .rev()
+                    .fold(0_usize, |acc, byte| (acc << 8) | usize::from(*byte));
+                (1 + size_length, data_length)
+            }
+            _ => {
+                tokens.push(
+                    opcode_name(opcode)
+                        .map_or_else(|| format!("OP_UNKNOWN[{opcode:#04x}]"), ToString::to_string),
+                );
+                offset += 1;
+                continue;
+            }
+        };
+
+        let data_start = offset + header_length;
+        let data = data_start
+            .checked_add(data_length)
+            .and_then(|data_end| script.get(data_start..data_end))
+            .ok_or_else(|| truncated_push_err(offset))?;
+        tokens.push(encode_hex(data));
+        offset = data_start + data_length;
+    }
+
+    Ok(tokens.join(" "))
+}
+
+/// Appends the minimal push of `data` to the script.
+pub fn push_data(script: &mut Vec<u8>, data: &[u8]) {
+    match data.len() {
+        length @ 0..=0x4b => script.push(u8::try_from(length).unwrap_or_default()),
+        length @ 0x4c..=0xff => {
+            script.push(OP_PUSHDATA1);
+            script.push(u8::try_from(length).unwrap_or_default());
+        }
+        length @ 0x100..=0xffff => {
+            script.push(OP_PUSHDATA2);
+            script.extend(u16::try_from(length).unwrap_or_default().to_le_bytes());
+        }
+        length => {
+            script.push(OP_PUSHDATA4);
+            script.extend(u32::try_from(length).unwrap_or_default().to_le_bytes());
+        }
+    }
+    script.extend_from_slice(data);
+}
+
+/// Appends a push of the number `value` to the script, using the dedicated small integer opcodes when possible.
+pub fn push_number(script: &mut Vec<u8>, value: usize) {
+    match value {
+        0 => script.push(OP_0),
+        1..=16 => script.push(OP_1 + u8::try_from(value - 1).unwrap_or_default()),
+        _ => {
+            let mut bytes: Vec<u8> = value.to_le_bytes().to_vec();
+            while bytes.last() == Some(&0) {
+                bytes.pop();
+            }
+            if bytes.last().is_some_and(|byte| byte & 0x80 != 0) {
+                bytes.push(0);
+            }
+            push_data(script, &bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::decode_hex;
+
+    #[test]
+    fn test_disassemble_p2pkh() {
+        let script = decode_hex("76a91489abcdefabbaabbaabbaabbaabbaabbaabbaabba88ac").unwrap();
+        assert_eq!(
+            disassemble_script(&script),
+            Ok(
+                "OP_DUP OP_HASH160 89abcdefabbaabbaabbaabbaabbaabbaabbaabba OP_EQUALVERIFY OP_CHECKSIG"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_disassemble_pushdata_and_unknown() {
+        assert_eq!(
+            disassemble_script(&[OP_PUSHDATA1, 0x02, 0xde, 0xad, 0x00, 0xff]),
+            Ok("dead OP_0 OP_UNKNOWN[0xff]".to_string())
+        );
+        assert_eq!(
+            disassemble_script(&[OP_PUSHDATA2, 0x01, 0x00, 0xbe]),
+            Ok("be".to_string())
+        );
+        assert_eq!(disassemble_script(&[]), Ok(String::new()));
+    }
+
+    #[test]
+    fn test_disassemble_truncated_push() {
+        assert_eq!(
+            disassemble_script(&[0x6a, 0x04, 0xde, 0xad]),
+            Err(truncated_push_err(1))
+        );
+        assert_eq!(
+            disassemble_script(&[OP_PUSHDATA4, 0x01]),
+            Err(truncated_push_err(0))
+        );
+    }
+
+    #[test]
+    fn test_push_number() {
+        let mut script = Vec::new();
+        push_number(&mut script, 0);
+        push_number(&mut script, 2);
+        push_number(&mut script, 16);
+        push_number(&mut script, 17);
+        push_number(&mut script, 128);
+        assert_eq!(script, vec![0x00, 0x52, 0x60, 0x01, 0x11, 0x02, 0x80, 0x00]);
+    }
+}
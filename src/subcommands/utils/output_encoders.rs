@@ -0,0 +1,112 @@
+use crate::{
+    parsers::arg_parser::Command,
+    structs::{
+        global_config::GlobalConfig, output_format::OutputFormat, parsing_error::ParsingError,
+    },
+    traits::output_encoder::OutputEncoder,
+};
+
+use super::{
+    json::fields_json, output_template::select_fields, qr::QrCode, ur::DEFAULT_MAX_FRAGMENT_LENGTH,
+};
+
+/// The usual output of the sub-command.
+pub struct PlainEncoder;
+
+/// A JSON object of the (selected) fields of the result.
+pub struct JsonEncoder<'a> {
+    pub select: &'a [String],
+}
+
+/// A CSV row (RFC 4180) of the values of the (selected) fields of the result, without a header.
+pub struct CsvEncoder<'a> {
+    pub select: &'a [String],
+}
+
+/// The result as a uniform resource, one part per line.
+pub struct UrEncoder {
+    pub max_fragment_length: usize,
+}
+
+/// The usual output of the sub-command drawn as a QR code.
+pub struct QrEncoder;
+
+impl OutputEncoder for PlainEncoder {
+    fn encode(&self, command: &Command, input: &str) -> Result<String, ParsingError> {
+        command.run(input)
+    }
+}
+
+impl OutputEncoder for JsonEncoder<'_> {
+    fn encode(&self, command: &Command, input: &str) -> Result<String, ParsingError> {
+        Ok(fields_json(select_fields(
+            command.fields(input)?,
+            self.select,
+        )?))
+    }
+}
+
+impl OutputEncoder for CsvEncoder<'_> {
+    fn encode(&self, command: &Command, input: &str) -> Result<String, ParsingError> {
+        let fields = select_fields(command.fields(input)?, self.select)?;
+        Ok(fields
+            .iter()
+            .map(|(_, value)| csv_value(value))
+            .collect::<Vec<String>>()
+            .join(","))
+    }
+}
+
+impl OutputEncoder for UrEncoder {
+    fn encode(&self, command: &Command, input: &str) -> Result<String, ParsingError> {
+        command.uniform_resource(input, self.max_fragment_length)
+    }
+}
+
+impl OutputEncoder for QrEncoder {
+    fn encode(&self, command: &Command, input: &str) -> Result<String, ParsingError> {
+        Ok(QrCode::encode(&command.run(input)?)?.render())
+    }
+}
+
+/// Returns the encoder of the `--output` format of the configuration.
+#[must_use]
+pub fn output_encoder(global_config: &GlobalConfig) -> Box<dyn OutputEncoder + '_> {
+    match global_config.output {
+        OutputFormat::Plain => Box::new(PlainEncoder),
+        OutputFormat::Json => Box::new(JsonEncoder {
+            select: &global_config.select,
+        }),
+        OutputFormat::Csv => Box::new(CsvEncoder {
+            select: &global_config.select,
+        }),
+        OutputFormat::Ur => Box::new(UrEncoder {
+            max_fragment_length: global_config
+                .ur_max_fragment
+                .unwrap_or(DEFAULT_MAX_FRAGMENT_LENGTH),
+        }),
+        OutputFormat::Qr => Box::new(QrEncoder),
+    }
+}
+
+/// Quotes the value of a CSV field if it contains a comma, a quote or a line break, doubling its quotes.
+fn csv_value(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_value() {
+        assert_eq!(csv_value("xpub"), "xpub");
+        assert_eq!(csv_value(""), "");
+        assert_eq!(csv_value("0,1"), "\"0,1\"");
+        assert_eq!(csv_value("a \"b\"\nc"), "\"a \"\"b\"\"\nc\"");
+    }
+}
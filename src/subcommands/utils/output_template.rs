@@ -0,0 +1,156 @@
+use crate::structs::parsing_error::ParsingError;
+
+/// Formats the output according to the template, replacing every `{name}` with the value of the named field.
+///
+/// The braces themselves can be written as `{{` and `}}`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the template has an unmatched brace or names a field that is not available.
+pub fn render_template(
+    template: &str,
+    fields: &[(&'static str, String)],
+) -> Result<String, ParsingError> {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if template_escape(&mut chars, '{') => output.push('{'),
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => {
+                            return Err(ParsingError::new(
+                                "Unterminated '{' in the template, use '{{' for a literal brace",
+                            ))
+                        }
+                    }
+                }
+                let value = fields
+                    .iter()
+                    .find(|(field, _)| *field == name)
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| unknown_field_err(&name, fields))?;
+                output.push_str(value);
+            }
+            '}' if template_escape(&mut chars, '}') => output.push('}'),
+            '}' => {
+                return Err(ParsingError::new(
+                    "Unmatched '}' in the template, use '}}' for a literal brace",
+                ))
+            }
+            c => output.push(c),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Limits the fields to the selected ones, in the order they were selected. No selection keeps all the fields.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a selected field is not available.
+pub fn select_fields(
+    fields: Vec<(&'static str, String)>,
+    selection: &[String],
+) -> Result<Vec<(&'static str, String)>, ParsingError> {
+    if selection.is_empty() {
+        return Ok(fields);
+    }
+
+    selection
+        .iter()
+        .map(|name| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .cloned()
+                .ok_or_else(|| {
+                    let available: Vec<&str> = fields.iter().map(|(field, _)| *field).collect();
+                    ParsingError::new(&format!(
+                        "Unknown field '{name}' of '--select', the available fields are: {}",
+                        available.join(", ")
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Consumes the second brace of an escaped (doubled) brace.
+fn template_escape(chars: &mut std::str::Chars, brace: char) -> bool {
+    let mut lookahead = chars.clone();
+    if lookahead.next() == Some(brace) {
+        *chars = lookahead;
+        return true;
+    }
+    false
+}
+
+fn unknown_field_err(name: &str, fields: &[(&'static str, String)]) -> ParsingError {
+    let available: Vec<&str> = fields.iter().map(|(field, _)| *field).collect();
+    ParsingError::new(&format!(
+        "Unknown template field '{{{name}}}', the available fields are: {}",
+        available.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let fields = [
+            ("xpub", "xpub123".to_string()),
+            ("fingerprint", "deadbeef".to_string()),
+        ];
+
+        assert_eq!(
+            render_template("{xpub},{fingerprint}", &fields),
+            Ok("xpub123,deadbeef".to_string())
+        );
+        assert_eq!(
+            render_template("{{\"fp\": \"{fingerprint}\"}}", &fields),
+            Ok("{\"fp\": \"deadbeef\"}".to_string())
+        );
+        assert_eq!(
+            render_template("{xprv}", &fields),
+            Err(ParsingError::new(
+                "Unknown template field '{xprv}', the available fields are: xpub, fingerprint"
+            ))
+        );
+        assert!(render_template("{xpub}}", &fields).is_err());
+        assert!(render_template("{xpub", &fields).is_err());
+    }
+
+    #[test]
+    fn test_select_fields() {
+        let fields = vec![
+            ("xpub", "xpub123".to_string()),
+            ("fingerprint", "deadbeef".to_string()),
+        ];
+
+        assert_eq!(
+            select_fields(
+                fields.clone(),
+                &["fingerprint".to_string(), "xpub".to_string()]
+            ),
+            Ok(vec![
+                ("fingerprint", "deadbeef".to_string()),
+                ("xpub", "xpub123".to_string()),
+            ])
+        );
+        assert_eq!(select_fields(fields.clone(), &[]), Ok(fields.clone()));
+        assert_eq!(
+            select_fields(fields, &["xprv".to_string()]),
+            Err(ParsingError::new(
+                "Unknown field 'xprv' of '--select', the available fields are: xpub, fingerprint"
+            ))
+        );
+    }
+}
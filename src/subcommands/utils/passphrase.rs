@@ -0,0 +1,161 @@
+use zeroize::Zeroize;
+
+use crate::{
+    parsers::flag_parser::{parse_boolean_flag, parse_value_flags},
+    structs::{parsing_error::ParsingError, secret_buffer::SecretBuffer},
+};
+
+/// Parses the `--passphrase-env {variable}`, `--passphrase-file {path}` and `--passphrase-prompt` flags,
+/// reading the passphrase from the one given, if any. The `label` names the passphrase in the prompt
+/// (e.g. `BIP 39`).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if more than one of the flags is given, or the passphrase cannot be read.
+pub fn parse_passphrase_flags(
+    args: &mut Vec<&str>,
+    label: &str,
+) -> Result<Option<SecretBuffer>, ParsingError> {
+    let [variable, path]: [Option<String>; 2] =
+        parse_value_flags(args, &["passphrase-env", "passphrase-file"])?
+            .try_into()
+            .unwrap_or_default();
+    let prompt = parse_boolean_flag(args, "passphrase-prompt");
+
+    match (variable, path, prompt) {
+        (None, None, false) => Ok(None),
+        (Some(variable), None, false) => passphrase_from_env(&variable).map(Some),
+        (None, Some(path), false) => passphrase_from_file(&path).map(Some),
+        (None, None, true) => prompt_passphrase(label).map(Some),
+        _ => Err(ParsingError::new(
+            "use only one of '--passphrase-env', '--passphrase-file' or '--passphrase-prompt'",
+        )),
+    }
+}
+
+/// Reads the passphrase from the environment variable.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the variable is not set or its value is not valid UTF-8.
+pub fn passphrase_from_env(variable: &str) -> Result<SecretBuffer, ParsingError> {
+    std::env::var(variable)
+        .map(|passphrase| SecretBuffer::new(passphrase.into_bytes()))
+        .map_err(|err| {
+            ParsingError::new(&format!(
+                "Could not read the passphrase from the environment variable '{variable}': {err}"
+            ))
+        })
+}
+
+/// Reads the passphrase from the first line of the file, without the line ending.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the file cannot be read or it is not valid UTF-8.
+pub fn passphrase_from_file(path: &str) -> Result<SecretBuffer, ParsingError> {
+    let mut contents = std::fs::read_to_string(path).map_err(|err| {
+        ParsingError::new(&format!(
+            "Could not read the passphrase from the file '{path}': {err}"
+        ))
+    })?;
+    let passphrase = SecretBuffer::new(first_line(&contents).as_bytes().to_vec());
+    contents.zeroize();
+    Ok(passphrase)
+}
+
+/// Prompts for the passphrase (named by the `label`) on the terminal (not the standard input, which may carry the inputs),
+/// with the echo turned off, so it never appears on the screen, in the shell history or in the process list.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if there is no terminal or it cannot be read from.
+#[cfg(unix)]
+pub fn prompt_passphrase(label: &str) -> Result<SecretBuffer, ParsingError> {
+    use std::{
+        fs::{File, OpenOptions},
+        io::{BufRead, BufReader, Write},
+        process::Command,
+    };
+
+    let tty_err = |err: std::io::Error| {
+        ParsingError::new(&format!(
+            "Could not prompt for the passphrase on the terminal: {err}"
+        ))
+    };
+    let set_echo = |tty: &File, echo: &str| -> std::io::Result<()> {
+        Command::new("stty")
+            .arg(echo)
+            .stdin(tty.try_clone()?)
+            .status()
+            .map(|_| ())
+    };
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map_err(tty_err)?;
+    tty.write_all(format!("{label} passphrase: ").as_bytes())
+        .map_err(tty_err)?;
+    set_echo(&tty, "-echo").map_err(tty_err)?;
+
+    let mut line = String::new();
+    let read = BufReader::new(&tty).read_line(&mut line);
+    // the echo is restored even if the reading failed
+    let restored = set_echo(&tty, "echo").and_then(|()| tty.write_all(b"\n"));
+    let passphrase = SecretBuffer::new(first_line(&line).as_bytes().to_vec());
+    line.zeroize();
+
+    read.and(restored).map_err(tty_err)?;
+    Ok(passphrase)
+}
+
+/// Interactive prompts are only supported on Unix.
+///
+/// # Errors
+///
+/// Always returns a [`ParsingError`].
+#[cfg(not(unix))]
+pub fn prompt_passphrase(_label: &str) -> Result<SecretBuffer, ParsingError> {
+    Err(ParsingError::new(
+        "Prompting for the passphrase is only supported on Unix, use '--passphrase-env' or '--passphrase-file' instead",
+    ))
+}
+
+fn first_line(contents: &str) -> &str {
+    contents.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passphrase_from_env() {
+        std::env::set_var("BIP380_TEST_PASSPHRASE_ENV", "TREZOR");
+
+        assert_eq!(
+            passphrase_from_env("BIP380_TEST_PASSPHRASE_ENV")
+                .unwrap()
+                .as_ref(),
+            b"TREZOR"
+        );
+        assert!(passphrase_from_env("BIP380_TEST_PASSPHRASE_UNSET").is_err());
+    }
+
+    #[test]
+    fn test_passphrase_from_file() {
+        let path = std::env::temp_dir().join(format!("bip380-passphrase-{}", std::process::id()));
+        std::fs::write(&path, "correct horse\r\nignored\n").unwrap();
+
+        assert_eq!(
+            passphrase_from_file(path.to_str().unwrap())
+                .unwrap()
+                .as_ref(),
+            b"correct horse"
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(passphrase_from_file(path.to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use bip32::DerivationPath;
+
+use crate::{
+    structs::{hardened_notation::HardenedNotation, parsing_error::ParsingError},
+    subcommands::{key_expression::split_key_expression, path::format_path},
+    traits::script_function::ScriptFunction,
+};
+
+use super::{
+    derived_public_key::{derive_public_key, resolve_derivation_steps},
+    hashes::hash160,
+    hexadecimal::{decode_hex, encode_hex},
+    json::JsonValue,
+    script_compiler::{collect_key_expressions, compile_script_expression},
+    script_functions::parse_script_function,
+};
+
+/// The type of the redeem script entry of the output map of a PSBT (BIP 174).
+const PSBT_OUT_REDEEM_SCRIPT: u8 = 0x00;
+/// The type of the witness script entry of the output map of a PSBT (BIP 174).
+const PSBT_OUT_WITNESS_SCRIPT: u8 = 0x01;
+/// The type of the BIP 32 derivation path entry of the output map of a PSBT (BIP 174).
+const PSBT_OUT_BIP32_DERIVATION: u8 = 0x02;
+
+/// The origin of a public key of the output: the fingerprint of the master key and the path derived from it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Bip32Derivation {
+    pub public_key: Vec<u8>,
+    pub fingerprint: [u8; 4],
+    pub path: DerivationPath,
+}
+
+/// Returns the origin of the public key the (previously validated) key expression stands for, with the ranged
+/// keys derived at `index`. The key origin is followed by the path appended to the key. A key without a key origin
+/// is its own master key, so its fingerprint is taken from its own public key (the same as Bitcoin Core does).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the public key cannot be derived (see [`derive_public_key`]).
+pub fn key_derivation(
+    key_expression: &str,
+    index: Option<u32>,
+) -> Result<Bip32Derivation, ParsingError> {
+    let public_key = derive_public_key(key_expression, index)?;
+    let (key_origin, key) = split_key_expression(key_expression.trim())?;
+    let (key, appended_path) = key.split_at(key.find('/').unwrap_or(key.len()));
+
+    let (fingerprint, origin_path) = match key_origin {
+        Some(key_origin) => {
+            let (fingerprint, origin_path) = key_origin[1..key_origin.len() - 1].split_at(8);
+            (decode_hex(fingerprint)?, origin_path)
+        }
+        None => (hash160(&derive_public_key(key, None)?)[..4].to_vec(), ""),
+    };
+    let mut path = DerivationPath::default();
+    for step in resolve_derivation_steps(origin_path, None)?
+        .into_iter()
+        .chain(resolve_derivation_steps(appended_path, index)?)
+    {
+        path.push(step);
+    }
+
+    Ok(Bip32Derivation {
+        public_key,
+        fingerprint: fingerprint.try_into().unwrap_or_default(),
+        path,
+    })
+}
+
+/// Returns the output-side PSBT metadata of the (previously validated) script expression, without its checksum,
+/// with the ranged keys derived at `index`: the `redeem_script` of a `sh(...)` script, the `witness_script` of a
+/// `wsh(...)` script, the `bip32_derivs` of its keys (sorted by the public keys, the same as in the serialized PSBT)
+/// and the `hex` of the serialized output map (BIP 174), which the transaction builders need to fill in for the
+/// change outputs.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script cannot be compiled, a key cannot be derived or the script is a `tr(...)`
+/// one, whose keys go into the taproot fields of BIP 371.
+pub fn psbt_output(script: &str, index: u32) -> Result<JsonValue, ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+    if function.name() == "tr" {
+        return Err(ParsingError::new(
+            "The PSBT output of a tr(...) script needs the taproot fields of BIP 371, which are not supported",
+        ));
+    }
+    let redeem_script = if function.name() == "sh" {
+        Some(compile_script_expression(&args[0], Some(index))?)
+    } else {
+        None
+    };
+    let witness_script = witness_script_expression(function, &args)?
+        .map(|witness_script| compile_script_expression(&witness_script, Some(index)))
+        .transpose()?;
+
+    let mut derivations = collect_key_expressions(script)?
+        .iter()
+        .map(|key_expression| key_derivation(key_expression, Some(index)))
+        .collect::<Result<Vec<Bip32Derivation>, ParsingError>>()?;
+    derivations.sort_by(|left, right| left.public_key.cmp(&right.public_key));
+    derivations.dedup_by(|left, right| left.public_key == right.public_key);
+
+    let mut map = Vec::new();
+    let mut members = Vec::new();
+    if let Some(redeem_script) = redeem_script {
+        push_entry(&mut map, &[PSBT_OUT_REDEEM_SCRIPT], &redeem_script);
+        members.push((
+            "redeem_script".to_string(),
+            JsonValue::String(encode_hex(&redeem_script)),
+        ));
+    }
+    if let Some(witness_script) = witness_script {
+        push_entry(&mut map, &[PSBT_OUT_WITNESS_SCRIPT], &witness_script);
+        members.push((
+            "witness_script".to_string(),
+            JsonValue::String(encode_hex(&witness_script)),
+        ));
+    }
+
+    let mut bip32_derivs = Vec::new();
+    for derivation in &derivations {
+        let key = [
+            &[PSBT_OUT_BIP32_DERIVATION],
+            derivation.public_key.as_slice(),
+        ]
+        .concat();
+        let value: Vec<u8> =
+            derivation
+                .path
+                .iter()
+                .fold(derivation.fingerprint.to_vec(), |mut value, step| {
+                    value.extend(u32::from(step).to_le_bytes());
+                    value
+                });
+        push_entry(&mut map, &key, &value);
+
+        bip32_derivs.push(JsonValue::Object(vec![
+            (
+                "pubkey".to_string(),
+                JsonValue::String(encode_hex(&derivation.public_key)),
+            ),
+            (
+                "master_fingerprint".to_string(),
+                JsonValue::String(encode_hex(&derivation.fingerprint)),
+            ),
+            (
+                "path".to_string(),
+                JsonValue::String(format_path(
+                    &derivation.path,
+                    HardenedNotation::Lowercase,
+                    true,
+                )),
+            ),
+        ]));
+    }
+    // the separator closing the map
+    map.push(0x00);
+
+    members.push(("bip32_derivs".to_string(), JsonValue::Array(bip32_derivs)));
+    members.push(("hex".to_string(), JsonValue::String(encode_hex(&map))));
+    Ok(JsonValue::Object(members))
+}
+
+/// Returns the witness script expression of a `wsh(...)` script, on its own or as the redeem script of `sh(...)`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the redeem script of `sh(...)` cannot be parsed.
+fn witness_script_expression(
+    function: &dyn ScriptFunction,
+    args: &[String],
+) -> Result<Option<String>, ParsingError> {
+    match function.name() {
+        "wsh" => Ok(Some(args[0].clone())),
+        "sh" => {
+            let (function, args) = parse_script_function(&args[0])?;
+            witness_script_expression(function, &args)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Appends the key-value entry to the serialized map, both prefixed with their compact size length.
+fn push_entry(map: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    for data in [key, value] {
+        push_compact_size(map, data.len());
+        map.extend_from_slice(data);
+    }
+}
+
+fn push_compact_size(map: &mut Vec<u8>, length: usize) {
+    match u32::try_from(length) {
+        Ok(length @ 0..=0xfc) => map.push(u8::try_from(length).unwrap_or_default()),
+        Ok(length @ 0xfd..=0xffff) => {
+            map.push(0xfd);
+            map.extend(u16::try_from(length).unwrap_or_default().to_le_bytes());
+        }
+        Ok(length) => {
+            map.push(0xfe);
+            map.extend(length.to_le_bytes());
+        }
+        Err(_) => {
+            map.push(0xff);
+            map.extend(u64::try_from(length).unwrap_or_default().to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XPUB: &str = "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+
+    #[test]
+    fn test_key_derivation() {
+        let derivation =
+            key_derivation(&format!("[d34db33f/44h/0h/0h]{XPUB}/1/*"), Some(7)).unwrap();
+        assert_eq!(derivation.fingerprint, [0xd3, 0x4d, 0xb3, 0x3f]);
+        assert_eq!(
+            format_path(&derivation.path, HardenedNotation::Lowercase, true),
+            "m/44h/0h/0h/1/7"
+        );
+        assert_eq!(
+            derivation.public_key,
+            derive_public_key(&format!("{XPUB}/1/7"), None).unwrap()
+        );
+
+        // a key without its key origin is its own master key
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let derivation = key_derivation(key, None).unwrap();
+        assert_eq!(
+            derivation.fingerprint.to_vec(),
+            hash160(&decode_hex(key).unwrap())[..4].to_vec()
+        );
+        assert_eq!(derivation.path, DerivationPath::default());
+        assert!(key_derivation(&format!("{XPUB}/*"), None).is_err());
+    }
+
+    #[test]
+    fn test_psbt_output_pkh() {
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let output = psbt_output(&format!("pkh([deadbeef/0h/1]{key})"), 0).unwrap();
+        assert_eq!(output.get("redeem_script"), None);
+        // {0x22, 0x02 || key} -> {0x0c, fingerprint || 0h || 1}, the separator
+        assert_eq!(
+            output.get("hex"),
+            Some(&JsonValue::String(format!(
+                "2202{key}0cdeadbeef000000800100000000"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_psbt_output_sh_multi() {
+        let key_1 = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let key_2 = "02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13";
+        let output = psbt_output(&format!("sh(multi(1,{key_1},{key_2},{key_1}))"), 0).unwrap();
+        let redeem_script = encode_hex(
+            &compile_script_expression(&format!("multi(1,{key_1},{key_2},{key_1})"), None).unwrap(),
+        );
+
+        assert_eq!(
+            output.get("redeem_script"),
+            Some(&JsonValue::String(redeem_script.clone()))
+        );
+        let Some(JsonValue::Array(derivs)) = output.get("bip32_derivs") else {
+            panic!("bip32_derivs is not an array");
+        };
+        // the repeated key appears once, the keys are sorted
+        assert_eq!(derivs.len(), 2);
+        assert_eq!(
+            derivs[0].get("pubkey"),
+            Some(&JsonValue::String(key_2.to_string()))
+        );
+        assert_eq!(
+            derivs[0].get("path"),
+            Some(&JsonValue::String("m".to_string()))
+        );
+        let Some(JsonValue::String(hex)) = output.get("hex") else {
+            panic!("hex is not a string");
+        };
+        assert!(hex.starts_with(&format!(
+            "0100{:02x}{redeem_script}",
+            redeem_script.len() / 2
+        )));
+    }
+
+    #[test]
+    fn test_psbt_output_sh_wsh() {
+        let key = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+        let witness_script = format!("sortedmulti(1,{key})");
+        let compiled = |script: &str| {
+            JsonValue::String(encode_hex(
+                &compile_script_expression(script, None).unwrap(),
+            ))
+        };
+
+        let output = psbt_output(&format!("sh(wsh({witness_script}))"), 0).unwrap();
+        assert_eq!(
+            output.get("redeem_script"),
+            Some(&compiled(&format!("wsh({witness_script})")))
+        );
+        assert_eq!(
+            output.get("witness_script"),
+            Some(&compiled(&witness_script))
+        );
+
+        let output = psbt_output(&format!("wsh({witness_script})"), 0).unwrap();
+        assert_eq!(output.get("redeem_script"), None);
+        assert_eq!(
+            output.get("witness_script"),
+            Some(&compiled(&witness_script))
+        );
+
+        assert!(psbt_output(&format!("tr({key})"), 0).is_err());
+    }
+}
@@ -0,0 +1,578 @@
+use crate::structs::parsing_error::ParsingError;
+
+/// The number of the error correction codewords of every block of the versions 1 to 40 (the index 0 is unused) at
+/// the low error correction level, which leaves the most room for the long descriptors.
+const ECC_CODEWORDS_PER_BLOCK: [usize; 41] = [
+    0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30,
+    30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+];
+
+/// The number of the error correction blocks of the versions 1 to 40 (the index 0 is unused) at the low error
+/// correction level.
+const ERROR_CORRECTION_BLOCKS: [usize; 41] = [
+    0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14,
+    15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+];
+
+/// The largest version of a QR code, 177 modules wide.
+const MAX_VERSION: usize = 40;
+
+/// The bits of the low error correction level in the format information.
+const LOW_ECC_FORMAT_BITS: u32 = 0b01;
+
+/// The mode indicator of the byte mode, the data of every QR code is encoded in.
+const BYTE_MODE: u32 = 0b0100;
+
+/// The light modules around the QR code, which the scanners need to find it.
+const QUIET_ZONE: usize = 4;
+
+/// The penalties of the mask evaluation (ISO/IEC 18004): the runs of five or more modules of the same color, the
+/// 2x2 blocks of the same color, the finder-like patterns and the imbalance of the dark and light modules.
+const PENALTY_RUN: i32 = 3;
+const PENALTY_BLOCK: i32 = 3;
+const PENALTY_FINDER: i32 = 40;
+const PENALTY_BALANCE: i32 = 10;
+
+/// A QR code (ISO/IEC 18004) of the bytes, in the byte mode at the low error correction level, of the smallest
+/// version they fit into.
+pub struct QrCode {
+    size: usize,
+    /// The modules row by row, `true` for a dark one.
+    modules: Vec<bool>,
+    /// Whether the module belongs to a function pattern (finders, timing, alignment, format and version), which
+    /// the data and the masks leave untouched.
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encodes the text as a QR code.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the text does not fit into the largest QR code (2953 bytes).
+    pub fn encode(text: &str) -> Result<Self, ParsingError> {
+        let data = text.as_bytes();
+        let version = (1..=MAX_VERSION)
+            .find(|version| {
+                4 + count_bits(*version) + 8 * data.len() <= 8 * data_codewords(*version)
+            })
+            .ok_or_else(|| {
+                ParsingError::new(&format!(
+                    "The output of {} bytes is too long for a QR code, at most {} bytes fit into one",
+                    data.len(),
+                    data_codewords(MAX_VERSION) - 3
+                ))
+            })?;
+
+        let size = version * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&add_error_correction(
+            &data_codewords_of(data, version),
+            version,
+        ));
+
+        let mut best = (i32::MAX, 0);
+        for mask in 0..8 {
+            qr.apply_mask(mask);
+            qr.draw_format_bits(mask);
+            best = best.min((qr.penalty(), mask));
+            // the mask is its own inverse
+            qr.apply_mask(mask);
+        }
+        qr.apply_mask(best.1);
+        qr.draw_format_bits(best.1);
+        Ok(qr)
+    }
+
+    /// Returns whether the module is dark, the modules outside of the code (e.g. of the quiet zone) are light.
+    #[must_use]
+    pub fn is_dark(&self, x: isize, y: isize) -> bool {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.size && y < self.size => self.modules[y * self.size + x],
+            _ => false,
+        }
+    }
+
+    /// Renders the QR code as text for a terminal with a dark background, two rows of modules per line: the light
+    /// modules (with the quiet zone around the code) are drawn with the block characters, the dark ones are left
+    /// blank.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let quiet_zone = QUIET_ZONE.cast_signed();
+        let end = self.size.cast_signed() + quiet_zone;
+        let light = |x: isize, y: isize| y < end && !self.is_dark(x, y);
+
+        (-quiet_zone..end)
+            .step_by(2)
+            .map(|y| {
+                (-quiet_zone..end)
+                    .map(|x| match (light(x, y), light(x, y + 1)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        for i in 0..self.size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (self.size - 4, 3), (3, self.size - 4)] {
+            self.draw_finder_pattern(x, y);
+        }
+
+        let positions = alignment_positions(version, self.size);
+        let last = positions.len().saturating_sub(1);
+        for (i, x) in positions.iter().enumerate() {
+            for (j, y) in positions.iter().enumerate() {
+                // the corners of the finder patterns
+                if !matches!((i, j), (0, 0)) && (i, j) != (0, last) && (i, j) != (last, 0) {
+                    self.draw_alignment_pattern(*x, *y);
+                }
+            }
+        }
+
+        // reserved for the format bits of the final mask
+        self.draw_format_bits(0);
+        self.draw_version_bits(version);
+    }
+
+    /// Draws the 9x9 finder pattern (with its separator) centered at the module.
+    fn draw_finder_pattern(&mut self, x: usize, y: usize) {
+        for dy in -4..=4_isize {
+            for dx in -4..=4_isize {
+                let distance = dx.abs().max(dy.abs());
+                if let (Some(x), Some(y)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) {
+                    if x < self.size && y < self.size {
+                        self.set_function(x, y, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws the 5x5 alignment pattern centered at the module.
+    fn draw_alignment_pattern(&mut self, x: usize, y: usize) {
+        for dy in -2..=2_isize {
+            for dx in -2..=2_isize {
+                self.set_function(
+                    x.saturating_add_signed(dx),
+                    y.saturating_add_signed(dy),
+                    dx.abs().max(dy.abs()) != 1,
+                );
+            }
+        }
+    }
+
+    /// Draws both copies of the format information (the error correction level and the mask, with their BCH
+    /// code), and the dark module.
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = (LOW_ECC_FORMAT_BITS << 3) | mask;
+        let remainder = (0..10).fold(data, |remainder, _| {
+            (remainder << 1) ^ ((remainder >> 9) * 0x537)
+        });
+        let bits = ((data << 10) | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(self.size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, self.size - 15 + i, bit(i));
+        }
+        self.set_function(8, self.size - 8, true);
+    }
+
+    /// Draws both copies of the version information (with its BCH code) of the versions 7 and above.
+    fn draw_version_bits(&mut self, version: usize) {
+        if version < 7 {
+            return;
+        }
+
+        let version = u32::try_from(version).unwrap_or_default();
+        let remainder = (0..12).fold(version, |remainder, _| {
+            (remainder << 1) ^ ((remainder >> 11) * 0x1f25)
+        });
+        let bits = (version << 12) | remainder;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let (a, b) = (self.size - 11 + i % 3, i / 3);
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Draws the codewords in the zigzag order: two columns at a time from the right, upwards and downwards in
+    /// turns, skipping the vertical timing pattern and the function patterns.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let mut bits = codewords
+            .iter()
+            .flat_map(|codeword| (0..8).rev().map(move |i| (codeword >> i) & 1 == 1));
+        let mut right = self.size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..self.size {
+                for x in [right, right - 1] {
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        self.size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.function[y * self.size + x] {
+                        // the remainder bits are light
+                        self.modules[y * self.size + x] = bits.next().unwrap_or_default();
+                    }
+                }
+            }
+            if right == 1 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Inverts the data modules of the mask pattern.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// Evaluates the penalty of the masked QR code, the mask with the lowest one is chosen.
+    fn penalty(&self) -> i32 {
+        let size = self.size;
+        let dark = |x: usize, y: usize| self.modules[y * size + x];
+        let mut penalty = 0;
+
+        for transposed in [false, true] {
+            for line in 0..size {
+                let mut runs = FinderRuns::new(size);
+                let (mut color, mut run) = (false, 0);
+                for position in 0..size {
+                    let module = if transposed {
+                        dark(line, position)
+                    } else {
+                        dark(position, line)
+                    };
+                    if module == color {
+                        run += 1;
+                        if run == 5 {
+                            penalty += PENALTY_RUN;
+                        } else if run > 5 {
+                            penalty += 1;
+                        }
+                    } else {
+                        runs.push(run);
+                        if !color {
+                            penalty += runs.count_patterns() * PENALTY_FINDER;
+                        }
+                        color = module;
+                        run = 1;
+                    }
+                }
+                penalty += runs.terminate(color, run) * PENALTY_FINDER;
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = dark(x, y);
+                if color == dark(x + 1, y) && color == dark(x, y + 1) && color == dark(x + 1, y + 1)
+                {
+                    penalty += PENALTY_BLOCK;
+                }
+            }
+        }
+
+        let total = i32::try_from(size * size).unwrap_or(i32::MAX);
+        let dark_count = i32::try_from(self.modules.iter().filter(|module| **module).count())
+            .unwrap_or_default();
+        // the number of the 5% steps the share of the dark modules is away from 50%
+        let steps = ((dark_count * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + steps * PENALTY_BALANCE
+    }
+}
+
+/// The lengths of the last seven runs of the modules of a row or a column, the most recent first, searched for the
+/// 1:1:3:1:1 pattern of the finders with four light modules on either side.
+struct FinderRuns {
+    runs: [i32; 7],
+    size: i32,
+}
+
+impl FinderRuns {
+    fn new(size: usize) -> Self {
+        FinderRuns {
+            runs: [0; 7],
+            size: i32::try_from(size).unwrap_or_default(),
+        }
+    }
+
+    fn push(&mut self, run: i32) {
+        // the light border before the first run
+        let run = if self.runs[0] == 0 {
+            run + self.size
+        } else {
+            run
+        };
+        self.runs.rotate_right(1);
+        self.runs[0] = run;
+    }
+
+    fn count_patterns(&self) -> i32 {
+        let runs = &self.runs;
+        let n = runs[1];
+        let core = n > 0 && runs[2] == n && runs[3] == n * 3 && runs[4] == n && runs[5] == n;
+        i32::from(core && runs[0] >= n * 4 && runs[6] >= n)
+            + i32::from(core && runs[6] >= n * 4 && runs[0] >= n)
+    }
+
+    /// Ends the line with the light border after it, counting the patterns of its end.
+    fn terminate(&mut self, color: bool, run: i32) -> i32 {
+        let run = if color {
+            self.push(run);
+            0
+        } else {
+            run
+        };
+        self.push(run + self.size);
+        self.count_patterns()
+    }
+}
+
+/// Returns the number of the bits of the length of the data in the byte mode.
+fn count_bits(version: usize) -> usize {
+    if version <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Returns the number of the modules left for the codewords, after the function patterns.
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+/// Returns the number of the data codewords (without the error correction) of the version.
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[version] * ERROR_CORRECTION_BLOCKS[version]
+}
+
+/// Returns the centers of the alignment patterns in either direction, in the ascending order.
+fn alignment_positions(version: usize, size: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Encodes the data in the byte mode into the data codewords of the version: the mode, the length, the data, the
+/// terminator and the padding.
+fn data_codewords_of(data: &[u8], version: usize) -> Vec<u8> {
+    let capacity = 8 * data_codewords(version);
+    let mut bits = Vec::with_capacity(capacity);
+    push_bits(&mut bits, BYTE_MODE, 4);
+    push_bits(
+        &mut bits,
+        u32::try_from(data.len()).unwrap_or_default(),
+        count_bits(version),
+    );
+    for byte in data {
+        push_bits(&mut bits, u32::from(*byte), 8);
+    }
+    let terminator = 4.min(capacity - bits.len());
+    push_bits(&mut bits, 0, terminator);
+    let padding = (8 - bits.len() % 8) % 8;
+    push_bits(&mut bits, 0, padding);
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .fold(0, |value, bit| (value << 1) | u8::from(*bit))
+        })
+        .collect();
+    for pad in [0xec, 0x11].into_iter().cycle() {
+        if codewords.len() == capacity / 8 {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+/// Appends the lowest `length` bits of the value, the most significant first.
+fn push_bits(bits: &mut Vec<bool>, value: u32, length: usize) {
+    bits.extend((0..length).rev().map(|i| (value >> i) & 1 == 1));
+}
+
+/// Splits the data codewords into the blocks of the version, appends the Reed-Solomon error correction codewords to
+/// every block and interleaves the blocks.
+fn add_error_correction(data: &[u8], version: usize) -> Vec<u8> {
+    let block_count = ERROR_CORRECTION_BLOCKS[version];
+    let ecc_length = ECC_CODEWORDS_PER_BLOCK[version];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_blocks = block_count - raw_codewords % block_count;
+    let short_block_length = raw_codewords / block_count;
+    let divisor = reed_solomon_divisor(ecc_length);
+
+    let mut blocks = Vec::with_capacity(block_count);
+    let mut start = 0;
+    for i in 0..block_count {
+        let data_length = short_block_length - ecc_length + usize::from(i >= short_blocks);
+        let mut block = data[start..start + data_length].to_vec();
+        start += data_length;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        // the short blocks are padded to line up the error correction codewords
+        if i < short_blocks {
+            block.push(0);
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+
+    let mut interleaved = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_block_length {
+        for (j, block) in blocks.iter().enumerate() {
+            if i != short_block_length - ecc_length || j >= short_blocks {
+                interleaved.push(block[i]);
+            }
+        }
+    }
+    interleaved
+}
+
+/// Multiplies the elements of GF(2^8) modulo the polynomial x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let product = (0..8).rev().fold(0_u16, |product, i| {
+        let product = (product << 1) ^ ((product >> 7) * 0x11d);
+        product ^ (u16::from((y >> i) & 1) * u16::from(x))
+    });
+    u8::try_from(product).unwrap_or_default()
+}
+
+/// Returns the coefficients of the generator polynomial of the degree (without the leading 1), the highest first.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0; degree];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+/// Returns the error correction codewords of the data: the remainder of its division by the generator polynomial.
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0; divisor.len()];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (coefficient, term) in remainder.iter_mut().zip(divisor) {
+            *coefficient ^= gf_multiply(*term, factor);
+        }
+    }
+    remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_selection() {
+        for (length, size) in [(0, 21), (17, 21), (18, 25), (2953, 177)] {
+            let qr = QrCode::encode(&"a".repeat(length)).unwrap();
+            assert_eq!(qr.size, size, "{length}");
+        }
+        assert!(QrCode::encode(&"a".repeat(2954)).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            QrCode::encode("raw(deadbeef)").unwrap().render(),
+            "█████████████████████████████\n\
+             █████████████████████████████\n\
+             ████ ▄▄▄▄▄ ██ ▀ ▄█ ▄▄▄▄▄ ████\n\
+             ████ █   █ █▄ █ ▄█ █   █ ████\n\
+             ████ █▄▄▄█ ███▄█ █ █▄▄▄█ ████\n\
+             ████▄▄▄▄▄▄▄█ ▀▄▀ █▄▄▄▄▄▄▄████\n\
+             ████ ▄ ▀ ▄▄  ▄  █▄▄▀▀▀▄▀▀████\n\
+             ████▀▀ ▄▄▀▄█▀▄██▄█▄▄▄▄▄  ████\n\
+             █████▄███▄▄█▀█▄▀▀▀█▀ ▄██ ████\n\
+             ████ ▄▄▄▄▄ █ █▄▀ ██▄▄ █▄▄████\n\
+             ████ █   █ █▄▀▀  ██  ▀▀ █████\n\
+             ████ █▄▄▄█ █ ▀█▄▄▄▄ ▄█▄▀▄████\n\
+             ████▄▄▄▄▄▄▄█▄▄█▄▄██▄▄██▄▄████\n\
+             █████████████████████████████\n\
+             ▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀"
+        );
+    }
+}
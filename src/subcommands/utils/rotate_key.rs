@@ -0,0 +1,79 @@
+use crate::structs::parsing_error::ParsingError;
+
+use super::script_compiler::collect_key_expressions;
+
+/// Replaces the old key expression in a (previously validated) script expression, without its checksum, with the
+/// new one, e.g. to rotate the key of a compromised signer. A key expression is replaced if it is the old one, or
+/// the old one followed by a derivation path, which is kept after the new key expression. Everything else (the
+/// script functions, the other keys and the whitespace) is kept. Returns the rotated script and the number of the
+/// replaced key expressions.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression is malformed or the old key expression does not appear in it.
+pub fn rotate_key(script: &str, old: &str, new: &str) -> Result<(String, usize), ParsingError> {
+    let key_expressions = collect_key_expressions(script)?;
+    let mut replaced = 0;
+
+    let mut rotated = String::with_capacity(script.len());
+    for token in script.split_inclusive(['(', ')', ',']) {
+        let (argument, delimiter) = match token.char_indices().last() {
+            Some((index, '(' | ')' | ',')) => token.split_at(index),
+            _ => (token, ""),
+        };
+        let trimmed = argument.trim();
+        let path = trimmed
+            .strip_prefix(old)
+            .filter(|path| path.is_empty() || path.starts_with('/'));
+        match path {
+            Some(path)
+                if key_expressions
+                    .iter()
+                    .any(|key_expression| key_expression.trim() == trimmed) =>
+            {
+                rotated.push_str(&argument.replace(trimmed, &format!("{new}{path}")));
+                replaced += 1;
+            }
+            _ => rotated.push_str(argument),
+        }
+        rotated.push_str(delimiter);
+    }
+
+    if replaced == 0 {
+        return Err(ParsingError::new(&format!(
+            "The key '{old}' does not appear in the descriptor"
+        )));
+    }
+
+    Ok((rotated, replaced))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "[deadbeef/0h]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL";
+    const NEW: &str = "[3442193e/0h]xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const OTHER: &str = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600";
+
+    #[test]
+    fn test_rotate_key() {
+        assert_eq!(
+            rotate_key(&format!("sh(multi(1, {OLD}/0/*, {OTHER}))"), OLD, NEW),
+            Ok((format!("sh(multi(1, {NEW}/0/*, {OTHER}))"), 1))
+        );
+        assert_eq!(
+            rotate_key(&format!("multi(1,{OLD}/0/*,{OLD}/1/*)"), OLD, NEW),
+            Ok((format!("multi(1,{NEW}/0/*,{NEW}/1/*)"), 2))
+        );
+
+        // a key extending the old one is another key
+        assert!(rotate_key(&format!("pk({OLD}0)"), OLD, NEW).is_err());
+        assert_eq!(
+            rotate_key(&format!("pk({OTHER})"), OLD, NEW),
+            Err(ParsingError::new(&format!(
+                "The key '{OLD}' does not appear in the descriptor"
+            )))
+        );
+    }
+}
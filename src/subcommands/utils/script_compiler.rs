@@ -0,0 +1,147 @@
+use crate::{
+    structs::parsing_error::ParsingError, subcommands::key_expression::split_key_expression,
+};
+
+use super::{
+    hex_encoded_public_key::has_hex_encoded_public_key_prefix,
+    script_functions::parse_script_function,
+};
+
+/// Compiles a (previously validated) script expression, without its checksum, into the serialized output script.
+///
+/// Ranged key expressions are derived at `index`. The script is compiled by its function, see
+/// [`ScriptFunction::compile`](crate::traits::script_function::ScriptFunction::compile).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The script expression is not supported or malformed,
+/// - A key cannot be resolved into a public key (see [`derive_public_key`]),
+/// - The `raw` argument does not form whole bytes.
+pub fn compile_script_expression(
+    script: &str,
+    index: Option<u32>,
+) -> Result<Vec<u8>, ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+    function.compile(&args, index)
+}
+
+/// Collects all key expressions of a (previously validated) script expression, without its checksum,
+/// in the order they appear in the script.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression is not supported or malformed.
+pub fn collect_key_expressions(script: &str) -> Result<Vec<String>, ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+    function.key_expressions(&args)
+}
+
+/// Lowercases the hex encoded public keys and the `raw` payload of a script expression (without its checksum),
+/// keeping everything else (including the whitespace) untouched.
+#[must_use]
+pub fn normalize_hex_case(script: &str) -> String {
+    if script.trim_start_matches(' ').starts_with("raw") {
+        return script.to_ascii_lowercase();
+    }
+
+    let mut normalized = String::with_capacity(script.len());
+    for token in script.split_inclusive(['(', ')', ',']) {
+        let (argument, delimiter) = match token.char_indices().last() {
+            Some((index, '(' | ')' | ',')) => token.split_at(index),
+            _ => (token, ""),
+        };
+        let key = split_key_expression(argument.trim_matches(' '))
+            .map(|(_, key)| key)
+            .unwrap_or_default();
+
+        if has_hex_encoded_public_key_prefix(key) && key.chars().all(|c| c.is_ascii_hexdigit()) {
+            normalized.push_str(&argument.replace(key, &key.to_ascii_lowercase()));
+        } else {
+            normalized.push_str(argument);
+        }
+        normalized.push_str(delimiter);
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::{
+        hashes::hash160, hexadecimal::encode_hex, opcodes::disassemble_script,
+    };
+
+    fn disassemble(script: &str, index: Option<u32>) -> String {
+        disassemble_script(&compile_script_expression(script, index).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_compile_raw() {
+        assert_eq!(
+            compile_script_expression("raw(dead beef)", None),
+            Ok(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert!(compile_script_expression("raw(dea)", None).is_err());
+    }
+
+    #[test]
+    fn test_compile_pk_and_pkh() {
+        assert_eq!(
+            disassemble(
+                "pk(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+                None
+            ),
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 OP_CHECKSIG"
+        );
+        assert_eq!(
+            disassemble(
+                "pkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+                None
+            ),
+            "OP_DUP OP_HASH160 751e76e8199196d454941c45d1b3a323f1433bd6 OP_EQUALVERIFY OP_CHECKSIG"
+        );
+    }
+
+    #[test]
+    fn test_compile_sh_multi() {
+        let multi = "multi(1, 0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798, 0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600)";
+        assert_eq!(
+            disassemble(multi, None),
+            "OP_1 0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798 0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600 OP_2 OP_CHECKMULTISIG"
+        );
+
+        let redeem_script = compile_script_expression(multi, None).unwrap();
+        let expected = format!(
+            "OP_HASH160 {} OP_EQUAL",
+            encode_hex(&hash160(&redeem_script))
+        );
+        assert_eq!(disassemble(&format!("sh({multi})"), None), expected);
+    }
+
+    #[test]
+    fn test_collect_key_expressions() {
+        assert_eq!(
+            collect_key_expressions("sh(multi(1, 02aa, [deadbeef]03bb))"),
+            Ok(vec!["02aa".to_string(), "[deadbeef]03bb".to_string()])
+        );
+        assert_eq!(collect_key_expressions("raw(deadbeef)"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_normalize_hex_case() {
+        assert_eq!(normalize_hex_case(" raw( DEAD beef )"), " raw( dead beef )");
+        assert_eq!(
+            normalize_hex_case("sh(multi(1, [DEADBEEF/0H]0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798, xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*))"),
+            "sh(multi(1, [DEADBEEF/0H]0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798, xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*))"
+        );
+    }
+
+    #[test]
+    fn test_compile_ranged_requires_index() {
+        let script = "pkh(xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw/*)";
+        assert!(compile_script_expression(script, None).is_err());
+        assert!(compile_script_expression(script, Some(0)).is_ok());
+    }
+}
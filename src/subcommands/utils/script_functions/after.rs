@@ -0,0 +1,76 @@
+use crate::{
+    structs::parsing_error::ParsingError,
+    subcommands::utils::opcodes::{push_number, OP_CHECKLOCKTIMEVERIFY},
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+use super::parse_timelock;
+
+/// `after(n)`, the absolute timelock (BIP 65) of the miniscript fragments, satisfied once the block height
+/// (below 500000000) or the UNIX timestamp `n` is reached.
+pub struct After;
+
+impl ScriptFunction for After {
+    fn name(&self) -> &'static str {
+        "after"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    /// A timelock holds no key, so anyone could spend the script of the fragment on its own. It only belongs
+    /// into the miniscript fragments combining it with the keys, none of the contexts takes it for now.
+    fn allowed_in(&self, _context: ScriptContext) -> bool {
+        false
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        parse_timelock(self.name(), &args[0]).map(|_| ())
+    }
+
+    fn compile(&self, args: &[String], _index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut compiled = Vec::new();
+        push_number(
+            &mut compiled,
+            parse_timelock(self.name(), &args[0])? as usize,
+        );
+        compiled.push(OP_CHECKLOCKTIMEVERIFY);
+        Ok(compiled)
+    }
+
+    fn key_expressions(&self, _args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_after() {
+        assert!(!After.allowed_in(ScriptContext::TopLevel));
+        assert!(!After.allowed_in(ScriptContext::Sh));
+
+        let args = vec!["840000".to_string()];
+        assert_eq!(After.validate(&args, ScriptContext::Sh), Ok(()));
+        assert_eq!(
+            After.compile(&args, None),
+            Ok(vec![0x03, 0x40, 0xd1, 0x0c, OP_CHECKLOCKTIMEVERIFY])
+        );
+
+        for locktime in ["0", "2147483648", "-1", "+5", "0x10", ""] {
+            assert!(
+                After
+                    .validate(&[locktime.to_string()], ScriptContext::TopLevel)
+                    .is_err(),
+                "{locktime}"
+            );
+        }
+        assert_eq!(
+            After.validate(&["2147483647".to_string()], ScriptContext::TopLevel),
+            Ok(())
+        );
+    }
+}
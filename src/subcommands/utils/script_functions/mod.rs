@@ -0,0 +1,299 @@
+use crate::{
+    structs::{parsing_error::ParsingError, script_limits::ScriptLimits},
+    subcommands::utils::script_compiler::compile_script_expression,
+    traits::{
+        script_function::{Arity, ScriptContext, ScriptFunction},
+        string_utils::{CharArrayUtils, StringSliceUtils, Trimifiable},
+    },
+    utils::error_messages::{script_limit_err, script_top_level_unsupported_err},
+};
+
+pub mod after;
+pub mod multi;
+pub mod older;
+pub mod pk;
+pub mod pkh;
+pub mod raw;
+pub mod sh;
+pub mod sortedmulti;
+pub mod tr;
+pub mod wpkh;
+pub mod wsh;
+
+/// The registry of the supported script functions.
+///
+/// A script expression is matched against the names in this order, so a name that is a prefix of another one
+/// (`pk` of `pkh`) must come after it.
+pub const SCRIPT_FUNCTIONS: [&dyn ScriptFunction; 11] = [
+    &raw::Raw,
+    &multi::Multi,
+    &sortedmulti::SortedMulti,
+    &pkh::Pkh,
+    &pk::Pk,
+    &wpkh::Wpkh,
+    &sh::Sh,
+    &wsh::Wsh,
+    &tr::Tr,
+    &after::After,
+    &older::Older,
+];
+
+/// The exclusive upper bound of the value of a timelock, the values with the highest bit set are not timelocks.
+const TIMELOCK_BOUND: u32 = 1 << 31;
+
+/// Returns the registered script function the script expression (without its checksum) starts with,
+/// together with its arguments.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if no registered function matches, the arguments cannot be extracted from
+/// the parentheses or their count does not match the arity of the function.
+pub fn parse_script_function(
+    script: &str,
+) -> Result<(&'static dyn ScriptFunction, Vec<String>), ParsingError> {
+    let chars = script.charify().trimify();
+    let Some(function) = find_script_function(script) else {
+        return Err(ParsingError::new("parsing of the script failed!"));
+    };
+
+    let args = chars[function.name().len()..].extract_args(function.name())?;
+    if !function.arity().accepts(args.len()) {
+        return Err(ParsingError::new(&arity_err(function)));
+    }
+
+    Ok((function, args))
+}
+
+/// Returns the registered script function the script expression starts with, if any, without looking
+/// at its arguments.
+#[must_use]
+pub fn find_script_function(script: &str) -> Option<&'static dyn ScriptFunction> {
+    let chars = script.charify().trimify();
+    SCRIPT_FUNCTIONS
+        .into_iter()
+        .find(|function| chars.starts_with(&function.name().charify()))
+}
+
+/// Validates the script expression (without its checksum) appearing in the context.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression cannot be parsed (see [`parse_script_function`]),
+/// its function is not allowed at the top level or the arguments of its function are invalid. The span of an error of a function with a single argument
+/// is moved to point into the script, other spans are dropped.
+pub fn validate_script(script: &str, context: ScriptContext) -> Result<(), ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+    // the functions not allowed in the redeem script are refused by `sh(...)` itself
+    if context == ScriptContext::TopLevel && !function.allowed_in(context) {
+        return Err(ParsingError::new(&script_top_level_unsupported_err(
+            function.name(),
+        )));
+    }
+    function.validate(&args, context).map_err(|err| {
+        match (function.arity(), argument_offset(script, &args)) {
+            (Arity::Exactly(1), Some(offset)) => err.shifted(offset),
+            _ => err.without_span(),
+        }
+    })
+}
+
+/// Checks the (previously validated) script expression against the limits: the nesting of its parentheses,
+/// the limits of its functions and the size of the script compiled with the ranged keys derived at index 0.
+/// The size of a script with keys that cannot be derived (e.g. a hardened wildcard after an xpub) is not checked.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if any of the limits is exceeded.
+pub fn check_script_limits(script: &str, limits: &ScriptLimits) -> Result<(), ParsingError> {
+    let nesting = script_nesting(script);
+    if nesting > limits.nesting {
+        return Err(ParsingError::new(&script_limit_err(
+            &format!("The script is nested {nesting} levels deep"),
+            limits.nesting,
+            "max-nesting",
+        )));
+    }
+
+    check_function_limits(script, limits)?;
+
+    if let Ok(compiled) = compile_script_expression(script, Some(0)) {
+        if compiled.len() > limits.script_size {
+            return Err(ParsingError::new(&script_limit_err(
+                &format!("The compiled script has {} bytes", compiled.len()),
+                limits.script_size,
+                "max-script-size",
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the arguments of the script function the script expression starts with against its limits.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression cannot be parsed or a limit is exceeded.
+pub fn check_function_limits(script: &str, limits: &ScriptLimits) -> Result<(), ParsingError> {
+    let (function, args) = parse_script_function(script)?;
+    function.check_limits(&args, limits)
+}
+
+/// Returns the deepest nesting of the parentheses in the script, e.g. 2 for `sh(pk(KEY))`.
+fn script_nesting(script: &str) -> usize {
+    let mut depth: usize = 0;
+    let mut deepest = 0;
+    for c in script.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// Returns the byte offset of the single argument in the script.
+fn argument_offset(script: &str, args: &[String]) -> Option<usize> {
+    let [arg] = args else {
+        return None;
+    };
+    let start = script.find('(')? + 1;
+    Some(start + script[start..].find(arg.as_str())?)
+}
+
+/// Parses the value of the timelock of `after(n)` or `older(n)`, which must be from the range [1,...,2^31-1].
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the value is not a decimal number or it is out of the range.
+pub fn parse_timelock(function: &str, value: &str) -> Result<u32, ParsingError> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ParsingError::new(&format!(
+            "The timelock '{value}' of {function}(n) must be a decimal number"
+        )));
+    }
+
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|timelock| (1..TIMELOCK_BOUND).contains(timelock))
+        .ok_or_else(|| {
+            ParsingError::new(&format!(
+                "The timelock '{value}' of {function}(n) is out of the range [1,...,2^31-1]"
+            ))
+        })
+}
+
+fn arity_err(function: &dyn ScriptFunction) -> String {
+    let name = function.name();
+    match function.arity() {
+        Arity::Exactly(1) => format!("exactly one argument is needed for {name} script"),
+        Arity::Exactly(count) => format!("exactly {count} arguments are needed for {name} script"),
+        Arity::AtLeast(count) => format!("at least {count} arguments are needed for {name} script"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script_function() {
+        let (function, args) = parse_script_function("  pkh( 02aa )").unwrap();
+        assert_eq!(function.name(), "pkh");
+        assert_eq!(args, vec!["02aa".to_string()]);
+
+        let (function, _) = parse_script_function("pk(02aa)").unwrap();
+        assert_eq!(function.name(), "pk");
+
+        assert_eq!(
+            parse_script_function("combo(02aa)").map(|(function, _)| function.name()),
+            Err(ParsingError::new("parsing of the script failed!"))
+        );
+        assert_eq!(
+            parse_script_function("raw(de, ad)").map(|(function, _)| function.name()),
+            Err(ParsingError::new(
+                "exactly one argument is needed for raw script"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_script_span() {
+        assert_eq!(
+            validate_script("  raw( de x )", ScriptContext::TopLevel).map_err(|err| err.span),
+            Err(Some(10..11))
+        );
+        for (script, name) in [("after(1)", "after"), ("older(144)", "older")] {
+            assert_eq!(
+                validate_script(script, ScriptContext::TopLevel),
+                Err(ParsingError::new(&script_top_level_unsupported_err(name)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_script_limits() {
+        let script =
+            "sh(multi(1, 0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798))";
+        assert_eq!(script_nesting(script), 2);
+        assert_eq!(
+            check_script_limits(script, &ScriptLimits::default()),
+            Ok(())
+        );
+
+        for limits in [
+            ScriptLimits {
+                nesting: 1,
+                ..ScriptLimits::default()
+            },
+            ScriptLimits {
+                script_size: 22,
+                ..ScriptLimits::default()
+            },
+        ] {
+            assert!(check_script_limits(script, &limits).is_err());
+        }
+        assert_eq!(
+            check_script_limits(
+                script,
+                &ScriptLimits {
+                    multi_keys: 1,
+                    ..ScriptLimits::default()
+                }
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_script_limits(
+                &script.replace(
+                    "))",
+                    ", 0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600))"
+                ),
+                &ScriptLimits {
+                    multi_keys: 1,
+                    ..ScriptLimits::default()
+                }
+            ),
+            Err(ParsingError::new(&script_limit_err(
+                "'multi' script has 2 keys",
+                1,
+                "max-multi-keys"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_registry_names() {
+        for (position, function) in SCRIPT_FUNCTIONS.iter().enumerate() {
+            // a name shadowed by an earlier prefix could never be matched
+            assert!(SCRIPT_FUNCTIONS[..position]
+                .iter()
+                .all(|earlier| !function.name().starts_with(earlier.name())));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    structs::{error_kind::ErrorKind, parsing_error::ParsingError, script_limits::ScriptLimits},
+    subcommands::{
+        key_expression::validate_key_expressions,
+        utils::{
+            derived_public_key::derive_public_key,
+            number::parse_threshold,
+            opcodes::{push_data, push_number, OP_CHECKMULTISIG},
+        },
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+    utils::error_messages::script_limit_err,
+};
+
+/// `multi(k, KEY_1, ..., KEY_n)`, the bare k-of-n multisig script.
+pub struct Multi;
+
+impl ScriptFunction for Multi {
+    fn name(&self) -> &'static str {
+        "multi"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::AtLeast(1)
+    }
+
+    fn allowed_in(&self, _context: ScriptContext) -> bool {
+        true
+    }
+
+    fn validate(&self, args: &[String], context: ScriptContext) -> Result<(), ParsingError> {
+        validate_multisig(args, context)
+    }
+
+    fn check_limits(&self, args: &[String], limits: &ScriptLimits) -> Result<(), ParsingError> {
+        check_multisig_limits(self.name(), args, limits)
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        compile_multisig(args, index, false)
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(args[1..].to_vec())
+    }
+}
+
+/// Validates the threshold and the keys of a `multi` or `sortedmulti` script appearing in the context.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the threshold is negative or higher than the number of keys, or a key is invalid.
+pub fn validate_multisig(args: &[String], context: ScriptContext) -> Result<(), ParsingError> {
+    let (threshold, keys) = (&args[0], &args[1..]);
+    if threshold
+        .strip_prefix('-')
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidThreshold,
+            "arg count indicator cannot be negative",
+        ));
+    }
+    if parse_threshold(threshold)? > keys.len() {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidThreshold,
+            "arg count indicator cannot be higher than actual args count",
+        ));
+    }
+    validate_key_expressions(keys, context.key_context())
+}
+
+/// Checks the number of keys of the (previously validated) `multi` or `sortedmulti` script against the limits.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script has more keys than the limit.
+pub fn check_multisig_limits(
+    name: &str,
+    args: &[String],
+    limits: &ScriptLimits,
+) -> Result<(), ParsingError> {
+    let key_count = args.len() - 1;
+    if key_count > limits.multi_keys {
+        return Err(ParsingError::new(&script_limit_err(
+            &format!("'{name}' script has {key_count} keys"),
+            limits.multi_keys,
+            "max-multi-keys",
+        )));
+    }
+    Ok(())
+}
+
+/// Compiles the (previously validated) k-of-n multisig script, with its public keys sorted (BIP 67) if `sorted`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a key cannot be derived (see [`derive_public_key`]).
+pub fn compile_multisig(
+    args: &[String],
+    index: Option<u32>,
+    sorted: bool,
+) -> Result<Vec<u8>, ParsingError> {
+    let (threshold, keys) = (&args[0], &args[1..]);
+    let mut public_keys = keys
+        .iter()
+        .map(|key| derive_public_key(key, index))
+        .collect::<Result<Vec<Vec<u8>>, ParsingError>>()?;
+    if sorted {
+        public_keys.sort();
+    }
+
+    let mut compiled = Vec::new();
+    push_number(&mut compiled, parse_threshold(threshold)?);
+    for public_key in &public_keys {
+        push_data(&mut compiled, public_key);
+    }
+    push_number(&mut compiled, keys.len());
+    compiled.push(OP_CHECKMULTISIG);
+    Ok(compiled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi() {
+        let args: Vec<String> = ["1", "02aa", "[deadbeef]03bb"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            Multi.key_expressions(&args),
+            Ok(vec!["02aa".to_string(), "[deadbeef]03bb".to_string()])
+        );
+
+        for (threshold, message) in [
+            ("-1", "arg count indicator cannot be negative"),
+            (
+                "3",
+                "arg count indicator cannot be higher than actual args count",
+            ),
+        ] {
+            let mut args = args.clone();
+            args[0] = threshold.to_string();
+            assert_eq!(
+                Multi.validate(&args, ScriptContext::TopLevel),
+                Err(ParsingError::with_kind(
+                    ErrorKind::InvalidThreshold,
+                    message
+                ))
+            );
+        }
+
+        let limits = ScriptLimits {
+            multi_keys: 1,
+            ..ScriptLimits::default()
+        };
+        assert!(Multi.check_limits(&args[..2], &limits).is_ok());
+        assert!(Multi.check_limits(&args, &limits).is_err());
+    }
+}
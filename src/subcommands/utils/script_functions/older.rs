@@ -0,0 +1,76 @@
+use crate::{
+    structs::parsing_error::ParsingError,
+    subcommands::utils::opcodes::{push_number, OP_CHECKSEQUENCEVERIFY},
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+use super::parse_timelock;
+
+/// `older(n)`, the relative timelock (BIP 112) of the miniscript fragments, satisfied once the output is `n`
+/// blocks old, or with the type flag (bit 22) set, once the units of 512 seconds of its low 16 bits pass (BIP 68).
+pub struct Older;
+
+impl ScriptFunction for Older {
+    fn name(&self) -> &'static str {
+        "older"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    /// A timelock holds no key, so anyone could spend the script of the fragment on its own. It only belongs
+    /// into the miniscript fragments combining it with the keys, none of the contexts takes it for now.
+    fn allowed_in(&self, _context: ScriptContext) -> bool {
+        false
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        parse_timelock(self.name(), &args[0]).map(|_| ())
+    }
+
+    fn compile(&self, args: &[String], _index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut compiled = Vec::new();
+        push_number(
+            &mut compiled,
+            parse_timelock(self.name(), &args[0])? as usize,
+        );
+        compiled.push(OP_CHECKSEQUENCEVERIFY);
+        Ok(compiled)
+    }
+
+    fn key_expressions(&self, _args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_older() {
+        assert!(!Older.allowed_in(ScriptContext::TopLevel));
+        assert!(!Older.allowed_in(ScriptContext::Sh));
+
+        let args = vec!["144".to_string()];
+        assert_eq!(Older.validate(&args, ScriptContext::TopLevel), Ok(()));
+        assert_eq!(
+            Older.compile(&args, None),
+            Ok(vec![0x02, 0x90, 0x00, OP_CHECKSEQUENCEVERIFY])
+        );
+        assert_eq!(
+            Older.compile(&["16".to_string()], None),
+            Ok(vec![0x60, OP_CHECKSEQUENCEVERIFY])
+        );
+
+        for sequence in ["0", "2147483648", "4294967296", "1.5"] {
+            assert!(
+                Older
+                    .validate(&[sequence.to_string()], ScriptContext::Sh)
+                    .is_err(),
+                "{sequence}"
+            );
+        }
+    }
+}
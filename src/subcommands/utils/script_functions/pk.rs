@@ -0,0 +1,59 @@
+use crate::{
+    structs::parsing_error::ParsingError,
+    subcommands::{
+        key_expression::validate_key_expression,
+        utils::{
+            derived_public_key::derive_public_key,
+            opcodes::{push_data, OP_CHECKSIG},
+        },
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+/// `pk(KEY)`, the pay-to-pubkey script.
+pub struct Pk;
+
+impl ScriptFunction for Pk {
+    fn name(&self) -> &'static str {
+        "pk"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn allowed_in(&self, _context: ScriptContext) -> bool {
+        true
+    }
+
+    fn validate(&self, args: &[String], context: ScriptContext) -> Result<(), ParsingError> {
+        validate_key_expression(args[0].clone(), context.key_context()).map(|_| ())
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut compiled = Vec::new();
+        push_data(&mut compiled, &derive_public_key(&args[0], index)?);
+        compiled.push(OP_CHECKSIG);
+        Ok(compiled)
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(args.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pk() {
+        let args =
+            vec!["0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798".to_string()];
+        assert_eq!(Pk.validate(&args, ScriptContext::Sh), Ok(()));
+        assert_eq!(Pk.compile(&args, None).unwrap().last(), Some(&OP_CHECKSIG));
+        assert!(Pk
+            .validate(&["02aa".to_string()], ScriptContext::TopLevel)
+            .is_err());
+    }
+}
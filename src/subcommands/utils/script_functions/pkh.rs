@@ -0,0 +1,64 @@
+use crate::{
+    structs::parsing_error::ParsingError,
+    subcommands::{
+        key_expression::validate_key_expression,
+        utils::{
+            derived_public_key::derive_public_key,
+            hashes::hash160,
+            opcodes::{push_data, OP_CHECKSIG, OP_DUP, OP_EQUALVERIFY, OP_HASH160},
+        },
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+/// `pkh(KEY)`, the pay-to-pubkey-hash script.
+pub struct Pkh;
+
+impl ScriptFunction for Pkh {
+    fn name(&self) -> &'static str {
+        "pkh"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn allowed_in(&self, _context: ScriptContext) -> bool {
+        true
+    }
+
+    fn validate(&self, args: &[String], context: ScriptContext) -> Result<(), ParsingError> {
+        validate_key_expression(args[0].clone(), context.key_context()).map(|_| ())
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut compiled = vec![OP_DUP, OP_HASH160];
+        push_data(
+            &mut compiled,
+            &hash160(&derive_public_key(&args[0], index)?),
+        );
+        compiled.extend([OP_EQUALVERIFY, OP_CHECKSIG]);
+        Ok(compiled)
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(args.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::encode_hex;
+
+    #[test]
+    fn test_pkh() {
+        let args =
+            vec!["0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798".to_string()];
+        assert_eq!(Pkh.validate(&args, ScriptContext::TopLevel), Ok(()));
+        assert_eq!(
+            encode_hex(&Pkh.compile(&args, None).unwrap()),
+            "76a914751e76e8199196d454941c45d1b3a323f1433bd688ac"
+        );
+    }
+}
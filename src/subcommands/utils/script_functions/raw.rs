@@ -0,0 +1,53 @@
+use crate::{
+    structs::parsing_error::ParsingError,
+    subcommands::utils::hexadecimal::{assert_hexadecimal_format, decode_hex},
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+/// `raw(HEX)`, the script given by its hex encoded bytes.
+pub struct Raw;
+
+impl ScriptFunction for Raw {
+    fn name(&self) -> &'static str {
+        "raw"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        assert_hexadecimal_format(&args[0], "raw function argument")
+    }
+
+    fn compile(&self, args: &[String], _index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut hex = args.concat();
+        hex.retain(|c| c != ' ');
+        if !hex.len().is_multiple_of(2) {
+            return Err(ParsingError::new(
+                "raw function argument must consist of whole bytes to be compiled",
+            ));
+        }
+        decode_hex(&hex)
+    }
+
+    fn key_expressions(&self, _args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw() {
+        let args = vec!["DEAD beef".to_string()];
+        assert_eq!(Raw.validate(&args, ScriptContext::TopLevel), Ok(()));
+        assert_eq!(Raw.compile(&args, None), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert!(Raw
+            .validate(&["xyz".to_string()], ScriptContext::TopLevel)
+            .is_err());
+        assert!(!Raw.allowed_in(ScriptContext::Sh));
+    }
+}
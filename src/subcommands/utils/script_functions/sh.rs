@@ -0,0 +1,85 @@
+use crate::{
+    structs::{parsing_error::ParsingError, script_limits::ScriptLimits},
+    subcommands::utils::{
+        hashes::hash160,
+        opcodes::{push_data, OP_EQUAL, OP_HASH160},
+        script_compiler::{collect_key_expressions, compile_script_expression},
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+    utils::error_messages::script_sh_unsupported_arg_err,
+};
+
+use super::{check_function_limits, find_script_function, validate_script};
+
+/// `sh(SCRIPT)`, the pay-to-script-hash of the redeem script, which is one of the functions allowed
+/// in the [`ScriptContext::Sh`] context.
+pub struct Sh;
+
+impl ScriptFunction for Sh {
+    fn name(&self) -> &'static str {
+        "sh"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        let redeem_script = &args[0];
+        match find_script_function(redeem_script) {
+            Some(function) if function.allowed_in(ScriptContext::Sh) => {
+                validate_script(redeem_script, ScriptContext::Sh)
+            }
+            _ => Err(ParsingError::new(&script_sh_unsupported_arg_err(
+                redeem_script,
+            ))),
+        }
+    }
+
+    fn check_limits(&self, args: &[String], limits: &ScriptLimits) -> Result<(), ParsingError> {
+        check_function_limits(&args[0], limits)
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut compiled = vec![OP_HASH160];
+        push_data(
+            &mut compiled,
+            &hash160(&compile_script_expression(&args[0], index)?),
+        );
+        compiled.push(OP_EQUAL);
+        Ok(compiled)
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        collect_key_expressions(&args[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sh() {
+        for redeem_script in ["multi(0)", "sortedmulti(0)", "wsh(multi(0))"] {
+            assert_eq!(
+                Sh.validate(&[redeem_script.to_string()], ScriptContext::TopLevel),
+                Ok(())
+            );
+        }
+        for redeem_script in [
+            "raw(deadbeef)",
+            "sh(pk(02aa))",
+            "tr(02aa)",
+            "after(1)",
+            "older(144)",
+        ] {
+            assert_eq!(
+                Sh.validate(&[redeem_script.to_string()], ScriptContext::TopLevel),
+                Err(ParsingError::new(&script_sh_unsupported_arg_err(
+                    redeem_script
+                )))
+            );
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    structs::{parsing_error::ParsingError, script_limits::ScriptLimits},
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+use super::multi::{check_multisig_limits, compile_multisig, validate_multisig};
+
+/// `sortedmulti(k, KEY_1, ..., KEY_n)`, the k-of-n multisig script with its public keys sorted (BIP 67),
+/// so the order of the keys in the expression does not matter.
+pub struct SortedMulti;
+
+impl ScriptFunction for SortedMulti {
+    fn name(&self) -> &'static str {
+        "sortedmulti"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::AtLeast(1)
+    }
+
+    fn allowed_in(&self, _context: ScriptContext) -> bool {
+        true
+    }
+
+    fn validate(&self, args: &[String], context: ScriptContext) -> Result<(), ParsingError> {
+        validate_multisig(args, context)
+    }
+
+    fn check_limits(&self, args: &[String], limits: &ScriptLimits) -> Result<(), ParsingError> {
+        check_multisig_limits(self.name(), args, limits)
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        compile_multisig(args, index, true)
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(args[1..].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::script_functions::multi::Multi;
+
+    #[test]
+    fn test_sortedmulti() {
+        let args: Vec<String> = [
+            "1",
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let sorted_args = [&args[..1], &[args[2].clone(), args[1].clone()]].concat();
+        assert_eq!(SortedMulti.validate(&args, ScriptContext::Wsh), Ok(()));
+        assert_eq!(
+            SortedMulti.compile(&args, None),
+            Multi.compile(&sorted_args, None)
+        );
+        assert_eq!(
+            SortedMulti.compile(&sorted_args, None),
+            Multi.compile(&sorted_args, None)
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    structs::{address_type::AddressType, key_context::KeyContext, parsing_error::ParsingError},
+    subcommands::{
+        key_expression::validate_key_expression,
+        utils::{address::key_output_script, derived_public_key::derive_public_key},
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+/// `tr(KEY)`, the taproot output spendable by the key path only (BIP 386), without a script tree.
+pub struct Tr;
+
+impl ScriptFunction for Tr {
+    fn name(&self) -> &'static str {
+        "tr"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        validate_key_expression(args[0].clone(), KeyContext::Taproot).map(|_| ())
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        key_output_script(
+            &derive_public_key(&args[0], index)?,
+            AddressType::P2tr,
+            None,
+        )
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(args.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::encode_hex;
+
+    #[test]
+    fn test_tr() {
+        // the key of the first receive address of the BIP 86 test vectors
+        let x_only = "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115";
+        for key in [x_only.to_string(), format!("02{x_only}")] {
+            let args = vec![key];
+            assert_eq!(Tr.validate(&args, ScriptContext::TopLevel), Ok(()));
+            assert_eq!(
+                encode_hex(&Tr.compile(&args, None).unwrap()),
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+            );
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    structs::{address_type::AddressType, key_context::KeyContext, parsing_error::ParsingError},
+    subcommands::{
+        key_expression::validate_key_expression,
+        utils::{address::key_output_script, derived_public_key::derive_public_key},
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+};
+
+/// `wpkh(KEY)`, the pay-to-witness-pubkey-hash script, on its own or as the redeem script of `sh(...)`.
+pub struct Wpkh;
+
+impl ScriptFunction for Wpkh {
+    fn name(&self) -> &'static str {
+        "wpkh"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn allowed_in(&self, context: ScriptContext) -> bool {
+        context != ScriptContext::Wsh
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        validate_key_expression(args[0].clone(), KeyContext::SegwitV0).map(|_| ())
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        key_output_script(
+            &derive_public_key(&args[0], index)?,
+            AddressType::P2wpkh,
+            None,
+        )
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        Ok(args.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::encode_hex;
+
+    #[test]
+    fn test_wpkh() {
+        let args =
+            vec!["0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798".to_string()];
+        assert_eq!(Wpkh.validate(&args, ScriptContext::TopLevel), Ok(()));
+        assert_eq!(
+            encode_hex(&Wpkh.compile(&args, None).unwrap()),
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+
+        // only the compressed keys are allowed in the segwit scripts
+        let uncompressed = vec!["04a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd5b8dec5235a0fa8722476c7709c02559e3aa73aa03918ba2d492eea75abea235".to_string()];
+        assert!(Wpkh
+            .validate(&uncompressed, ScriptContext::TopLevel)
+            .is_err());
+    }
+}
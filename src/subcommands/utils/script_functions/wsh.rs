@@ -0,0 +1,87 @@
+use crate::{
+    structs::{parsing_error::ParsingError, script_limits::ScriptLimits},
+    subcommands::utils::{
+        hashes::sha256,
+        opcodes::{push_data, OP_0},
+        script_compiler::{collect_key_expressions, compile_script_expression},
+    },
+    traits::script_function::{Arity, ScriptContext, ScriptFunction},
+    utils::error_messages::script_wsh_unsupported_arg_err,
+};
+
+use super::{check_function_limits, find_script_function, validate_script};
+
+/// `wsh(SCRIPT)`, the pay-to-witness-script-hash of the witness script, which is one of the functions allowed
+/// in the [`ScriptContext::Wsh`] context. It can also be the redeem script of `sh(...)`.
+pub struct Wsh;
+
+impl ScriptFunction for Wsh {
+    fn name(&self) -> &'static str {
+        "wsh"
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Exactly(1)
+    }
+
+    fn allowed_in(&self, context: ScriptContext) -> bool {
+        context != ScriptContext::Wsh
+    }
+
+    fn validate(&self, args: &[String], _context: ScriptContext) -> Result<(), ParsingError> {
+        let witness_script = &args[0];
+        match find_script_function(witness_script) {
+            Some(function) if function.allowed_in(ScriptContext::Wsh) => {
+                validate_script(witness_script, ScriptContext::Wsh)
+            }
+            _ => Err(ParsingError::new(&script_wsh_unsupported_arg_err(
+                witness_script,
+            ))),
+        }
+    }
+
+    fn check_limits(&self, args: &[String], limits: &ScriptLimits) -> Result<(), ParsingError> {
+        check_function_limits(&args[0], limits)
+    }
+
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError> {
+        let mut compiled = vec![OP_0];
+        push_data(
+            &mut compiled,
+            &sha256(&compile_script_expression(&args[0], index)?),
+        );
+        Ok(compiled)
+    }
+
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError> {
+        collect_key_expressions(&args[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wsh() {
+        assert_eq!(
+            Wsh.validate(&["sortedmulti(0)".to_string()], ScriptContext::TopLevel),
+            Ok(())
+        );
+        for witness_script in [
+            "raw(deadbeef)",
+            "sh(pk(02aa))",
+            "wsh(pk(02aa))",
+            "wpkh(02aa)",
+            "tr(02aa)",
+            "after(1)",
+        ] {
+            assert_eq!(
+                Wsh.validate(&[witness_script.to_string()], ScriptContext::TopLevel),
+                Err(ParsingError::new(&script_wsh_unsupported_arg_err(
+                    witness_script
+                )))
+            );
+        }
+    }
+}
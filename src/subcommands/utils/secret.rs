@@ -0,0 +1,140 @@
+use crate::structs::secret_kind::SecretKind;
+
+use super::{
+    seed::{is_hex_seed, is_mnemonic},
+    wallet_import_format::validate_wif_private_key,
+};
+
+const PRIVATE_KEY_PREFIXES: [&str; 2] = ["xprv", "tprv"];
+
+/// The lengths of the WIF private keys, for the uncompressed and the compressed public keys.
+const WIF_LENGTHS: [usize; 2] = [51, 52];
+
+/// The numbers of hex digits of the seeds accepted by BIP 32 (16 to 64 bytes).
+const SEED_HEX_DIGITS: std::ops::RangeInclusive<usize> = 32..=128;
+
+/// Returns the kind of secret the value looks like, if any.
+///
+/// The extended private keys and the WIF private keys are looked for anywhere in the value (e.g. within
+/// a key or a script expression), while a seed or a mnemonic must be the whole value. A hexadecimal value
+/// is only taken for a seed if the sub-command takes seeds (`seed_input`), elsewhere it is e.g. an x-only
+/// public key, and a hexadecimal compressed public key is never taken for one.
+#[must_use]
+pub fn classify_secret(value: &str, seed_input: bool) -> Option<SecretKind> {
+    let value = value.trim();
+
+    let private_key = find_private_key(value);
+    if private_key.is_some() {
+        return private_key;
+    }
+
+    if is_hex_seed(value) && !value.is_empty() {
+        if !seed_input {
+            return None;
+        }
+        let digits: String = value.split_whitespace().collect();
+        let is_public_key =
+            digits.len() == 66 && (digits.starts_with("02") || digits.starts_with("03"));
+        if SEED_HEX_DIGITS.contains(&digits.len())
+            && digits.len().is_multiple_of(2)
+            && !is_public_key
+        {
+            return Some(SecretKind::Seed);
+        }
+        return None;
+    }
+
+    is_mnemonic(value).then_some(SecretKind::Mnemonic)
+}
+
+/// Returns the kind of the first private key (an extended private key or a WIF private key) found anywhere
+/// in the value, if any.
+#[must_use]
+pub fn find_private_key(value: &str) -> Option<SecretKind> {
+    value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find_map(classify_private_key)
+}
+
+fn classify_private_key(token: &str) -> Option<SecretKind> {
+    // the bare prefix is not a key, but e.g. the label of one
+    if token.len() > 4
+        && PRIVATE_KEY_PREFIXES
+            .iter()
+            .any(|prefix| token.starts_with(prefix))
+    {
+        Some(SecretKind::ExtendedPrivateKey)
+    } else if WIF_LENGTHS.contains(&token.len()) && validate_wif_private_key(token).is_ok() {
+        Some(SecretKind::Wif)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_secret() {
+        assert_eq!(
+            classify_secret("[deadbeef/0h]xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi/0/*", true),
+            Some(SecretKind::ExtendedPrivateKey)
+        );
+        assert_eq!(
+            classify_secret(
+                "pk(L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1)",
+                true
+            ),
+            Some(SecretKind::Wif)
+        );
+        assert_eq!(
+            classify_secret("000102030405060708090a0b0c0d0e0f", true),
+            Some(SecretKind::Seed)
+        );
+        assert_eq!(
+            classify_secret("00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f", true),
+            Some(SecretKind::Seed)
+        );
+        assert_eq!(
+            classify_secret("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", true),
+            Some(SecretKind::Mnemonic)
+        );
+
+        for value in [
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+            "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600",
+            "raw(deadbeef)",
+            "deadbeef",
+            "--path",
+            "xprv: ",
+            "abandon abandon",
+            "",
+        ] {
+            assert_eq!(classify_secret(value, true), None, "{value}");
+        }
+
+        // the x-only public key of BIP 340 has the length of a seed, only the sub-commands taking seeds see one
+        let x_only_key = "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115";
+        assert_eq!(classify_secret(x_only_key, true), Some(SecretKind::Seed));
+        assert_eq!(classify_secret(x_only_key, false), None);
+        assert_eq!(
+            classify_secret("000102030405060708090a0b0c0d0e0f", false),
+            None
+        );
+        assert_eq!(
+            classify_secret(
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                false
+            ),
+            Some(SecretKind::Mnemonic)
+        );
+        assert_eq!(
+            classify_secret(
+                "pk(L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1)",
+                false
+            ),
+            Some(SecretKind::Wif)
+        );
+    }
+}
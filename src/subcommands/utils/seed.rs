@@ -0,0 +1,181 @@
+use bip39::Mnemonic;
+
+use crate::{
+    structs::{
+        error_kind::ErrorKind, mnemonic_language::MnemonicLanguage, parsing_error::ParsingError,
+    },
+    utils::error_messages::invalid_seed_length_err,
+};
+
+use super::hexadecimal::{decode_hex, invalid_hex_digit_span};
+
+/// The lengths of the entropy of a mnemonic in bytes, 128 to 256 bits in steps of 32 bits.
+pub const ENTROPY_LENGTHS: [usize; 5] = [16, 20, 24, 28, 32];
+
+/// Decodes a hexadecimal seed, whose byte pairs may be separated by spaces or tabs.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if a whitespace separated part has an odd length or is not valid hexadecimal,
+/// spanning the part or the invalid digit.
+pub fn decode_seed(seed_input: &str) -> Result<Vec<u8>, ParsingError> {
+    let mut offset = 0;
+    let seed_no_whitespace = seed_input
+        .split([' ', '\t'])
+        .map(|slice| {
+            let span = offset..offset + slice.len();
+            offset = span.end + 1;
+            if slice.chars().count() % 2 == 0 {
+                Ok(slice)
+            } else {
+                Err(ParsingError::with_kind(
+                    ErrorKind::InvalidSeed,
+                    &invalid_seed_length_err(slice),
+                )
+                .with_span(span))
+            }
+        })
+        .collect::<Result<String, ParsingError>>()?;
+
+    decode_hex(&seed_no_whitespace).map_err(|err| {
+        match invalid_hex_digit_span(seed_input, &[' ', '\t']) {
+            Some(span) => err.with_span(span),
+            None => err,
+        }
+    })
+}
+
+/// Decodes the hexadecimal entropy of a BIP 39 mnemonic, 128 to 256 bits long.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the entropy is not hexadecimal or not of one of the [`ENTROPY_LENGTHS`].
+pub fn decode_entropy(input: &str) -> Result<Vec<u8>, ParsingError> {
+    decode_hex(input)
+        .ok()
+        .filter(|entropy| ENTROPY_LENGTHS.contains(&entropy.len()))
+        .ok_or_else(|| {
+            ParsingError::with_kind(ErrorKind::InvalidSeed, &format!(
+                "Invalid entropy '{input}', expected 32, 40, 48, 56 or 64 hex digits (128 to 256 bits)"
+            ))
+        })
+}
+
+/// Returns whether the input consists only of hex digits, spaces and tabs, i.e. it is meant as a hexadecimal seed
+/// (see [`decode_seed`]) rather than a mnemonic.
+#[must_use]
+pub fn is_hex_seed(input: &str) -> bool {
+    input
+        .chars()
+        .all(|c| c.is_ascii_hexdigit() || c == ' ' || c == '\t')
+}
+
+/// Returns whether the phrase is a valid BIP 39 mnemonic in any of the official languages.
+#[must_use]
+pub fn is_mnemonic(phrase: &str) -> bool {
+    parse_mnemonic(phrase, None).is_ok()
+}
+
+/// Parses a BIP 39 mnemonic phrase in the language, or in the language detected from its words if none is given.
+///
+/// The phrase is normalized (NFKD) first, so the words may be written with composed accents as well.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the phrase has an invalid word count, contains unknown words, its words belong
+/// to several wordlists at once or its checksum does not match.
+pub fn parse_mnemonic(
+    phrase: &str,
+    language: Option<MnemonicLanguage>,
+) -> Result<Mnemonic, ParsingError> {
+    match language {
+        Some(language) => Mnemonic::parse_in(language.wordlist(), phrase),
+        None => Mnemonic::parse(phrase),
+    }
+    .map_err(|err| {
+        ParsingError::with_kind(ErrorKind::InvalidSeed, &format!("Invalid mnemonic: {err}"))
+    })
+}
+
+/// Turns a BIP 39 mnemonic phrase (see [`parse_mnemonic`]) into its seed, using the passphrase (normalized the same way).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`parse_mnemonic`].
+pub fn mnemonic_to_seed(
+    phrase: &str,
+    language: Option<MnemonicLanguage>,
+    passphrase: &str,
+) -> Result<Vec<u8>, ParsingError> {
+    Ok(parse_mnemonic(phrase, language)?
+        .to_seed(passphrase)
+        .to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_seed() {
+        assert_eq!(decode_seed("00 01\t0203"), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(
+            decode_seed("000 1"),
+            Err(
+                ParsingError::with_kind(ErrorKind::InvalidSeed, &invalid_seed_length_err("000"))
+                    .with_span(0..3)
+            )
+        );
+        assert_eq!(
+            decode_seed("00 0g").map_err(|err| err.span),
+            Err(Some(4..5))
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_to_seed() {
+        // BIP 39 test vector (with an empty passphrase instead of "TREZOR")
+        let seed = mnemonic_to_seed(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+            "",
+        )
+        .unwrap();
+        assert_eq!(seed.len(), 64);
+        assert_eq!(&seed[..4], &[0x5e, 0xb0, 0x0b, 0xbd]);
+
+        // the BIP 39 test vector itself
+        let seed = mnemonic_to_seed(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            None,
+            "TREZOR",
+        )
+        .unwrap();
+        assert_eq!(&seed[..4], &[0xc5, 0x52, 0x57, 0xc3]);
+
+        assert!(mnemonic_to_seed("abandon abandon abandon", None, "").is_err());
+    }
+
+    #[test]
+    fn test_parse_mnemonic_languages() {
+        // the entropy of zeros, in Spanish with composed accents and in Japanese with ideographic spaces
+        let spanish = format!("{}abierto", "ábaco ".repeat(11));
+        let japanese = format!("{}あおぞら", "あいこくしん\u{3000}".repeat(11));
+
+        for (phrase, language) in [
+            (spanish.as_str(), MnemonicLanguage::Spanish),
+            (japanese.as_str(), MnemonicLanguage::Japanese),
+        ] {
+            assert_eq!(
+                parse_mnemonic(phrase, None).map(|mnemonic| mnemonic.language()),
+                Ok(language.wordlist())
+            );
+            assert_eq!(
+                parse_mnemonic(phrase, Some(language)).map(|mnemonic| mnemonic.to_entropy()),
+                Ok(vec![0; 16])
+            );
+            assert!(is_mnemonic(phrase));
+            assert!(parse_mnemonic(phrase, Some(MnemonicLanguage::English)).is_err());
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use bip32::secp256k1::sha2::Sha256;
+use hmac::{Hmac, Mac};
+
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+/// The x-coordinate of the share holding the digest of the secret.
+const DIGEST_INDEX: u8 = 254;
+/// The x-coordinate of the share holding the secret itself.
+const SECRET_INDEX: u8 = 255;
+/// The length of the digest guarding the secret against a wrong set of shares, in bytes.
+const DIGEST_LENGTH: usize = 4;
+
+/// The exponentials and logarithms of the generator 3 of GF(256) (with the Rijndael polynomial
+/// `x^8 + x^4 + x^3 + x + 1`), which turn the multiplications of the field into additions.
+const TABLES: ([u8; 255], [usize; 256]) = exp_log_tables();
+
+const fn exp_log_tables() -> ([u8; 255], [usize; 256]) {
+    let mut exp = [0; 255];
+    let mut log = [0; 256];
+    let mut power: u8 = 1;
+    let mut exponent = 0;
+    while exponent < 255 {
+        exp[exponent] = power;
+        log[power as usize] = exponent;
+        // multiplying by 3 is multiplying by 2 (reduced by the polynomial) and adding the power itself
+        let doubled = if power & 0x80 == 0 {
+            power << 1
+        } else {
+            (power << 1) ^ 0x1b
+        };
+        power ^= doubled;
+        exponent += 1;
+    }
+    (exp, log)
+}
+
+/// Splits the secret into `count` shares (with the x-coordinates 0 to `count - 1`), any `threshold` of which
+/// recover it, as defined by SLIP-39.
+///
+/// Unless the threshold is 1 (when every share is the secret itself), the polynomial is given by `threshold - 2`
+/// random shares, the share of the digest at 254 (the first 4 bytes of `HMAC-SHA256(random, secret)`, followed
+/// by the random bytes) and the secret at 255, so [`recover_secret`] can tell a wrong set of shares apart.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the random bytes cannot be generated.
+pub fn split_secret(
+    threshold: u8,
+    count: u8,
+    secret: &[u8],
+) -> Result<Vec<(u8, Vec<u8>)>, ParsingError> {
+    if threshold == 1 {
+        return Ok((0..count).map(|index| (index, secret.to_vec())).collect());
+    }
+
+    let mut shares = (0..threshold - 2)
+        .map(|index| Ok((index, random_bytes(secret.len())?)))
+        .collect::<Result<Vec<_>, ParsingError>>()?;
+    let random = random_bytes(secret.len() - DIGEST_LENGTH)?;
+    let digest_share = [&create_digest(&random, secret)[..], &random].concat();
+
+    let mut base_shares = shares.clone();
+    base_shares.extend([
+        (DIGEST_INDEX, digest_share),
+        (SECRET_INDEX, secret.to_vec()),
+    ]);
+    shares.extend((threshold - 2..count).map(|index| (index, interpolate(&base_shares, index))));
+
+    Ok(shares)
+}
+
+/// Recovers the secret from at least `threshold` of its shares (see [`split_secret`]), verifying its digest.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the digest of the recovered secret does not match, which means some of the shares
+/// do not belong together.
+pub fn recover_secret(threshold: u8, shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, ParsingError> {
+    if threshold == 1 {
+        return Ok(shares[0].1.clone());
+    }
+
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest_share = interpolate(shares, DIGEST_INDEX);
+    if digest_share[..DIGEST_LENGTH] != create_digest(&digest_share[DIGEST_LENGTH..], &secret) {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidShare,
+            "Invalid digest of the shared secret, the shares do not belong together",
+        ));
+    }
+
+    Ok(secret)
+}
+
+/// Returns the bytes read from the random number generator of the operating system.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the generator cannot be read.
+#[cfg(unix)]
+pub fn random_bytes(length: usize) -> Result<Vec<u8>, ParsingError> {
+    use std::io::Read;
+
+    let mut bytes = vec![0; length];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut generator| generator.read_exact(&mut bytes))
+        .map_err(|err| ParsingError::new(&format!("Could not generate random bytes: {err}")))?;
+    Ok(bytes)
+}
+
+/// Generating random bytes is only supported on Unix.
+///
+/// # Errors
+///
+/// Always returns a [`ParsingError`].
+#[cfg(not(unix))]
+pub fn random_bytes(_length: usize) -> Result<Vec<u8>, ParsingError> {
+    Err(ParsingError::new(
+        "Generating random bytes is only supported on Unix",
+    ))
+}
+
+fn create_digest(random: &[u8], secret: &[u8]) -> [u8; DIGEST_LENGTH] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random).expect("HMAC accepts a key of any length");
+    mac.update(secret);
+
+    let mut digest = [0; DIGEST_LENGTH];
+    digest.copy_from_slice(&mac.finalize().into_bytes()[..DIGEST_LENGTH]);
+    digest
+}
+
+/// Evaluates the polynomial going through the shares at `x`, by the Lagrange interpolation over GF(256).
+fn interpolate(shares: &[(u8, Vec<u8>)], x: u8) -> Vec<u8> {
+    if let Some((_, value)) = shares.iter().find(|(share_x, _)| *share_x == x) {
+        return value.clone();
+    }
+
+    let (exp, log) = TABLES;
+    let log_product: usize = shares
+        .iter()
+        .map(|(share_x, _)| log[usize::from(share_x ^ x)])
+        .sum();
+    let mut result = vec![0; shares[0].1.len()];
+
+    for (share_x, value) in shares {
+        // the logarithm of the Lagrange basis polynomial of the share evaluated at x, the share's own term is log(1)
+        let log_denominator: usize = log[usize::from(share_x ^ x)]
+            + shares
+                .iter()
+                .map(|(other_x, _)| log[usize::from(share_x ^ other_x)])
+                .sum::<usize>();
+        let log_basis = (log_product + 255 - log_denominator % 255) % 255;
+
+        for (byte, &share_byte) in result.iter_mut().zip(value) {
+            if share_byte != 0 {
+                *byte ^= exp[(log[usize::from(share_byte)] + log_basis) % 255];
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_recover_secret() {
+        let secret: Vec<u8> = (0..16).collect();
+        let shares = split_secret(3, 5, &secret).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<(u8, Vec<u8>)> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(recover_secret(3, &subset), Ok(secret.clone()));
+        }
+
+        let mut tampered = shares[..3].to_vec();
+        tampered[1].1[0] ^= 1;
+        assert!(recover_secret(3, &tampered).is_err());
+
+        assert_eq!(
+            split_secret(1, 2, &secret).unwrap(),
+            vec![(0, secret.clone()), (1, secret)]
+        );
+    }
+}
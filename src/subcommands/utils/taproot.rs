@@ -0,0 +1,83 @@
+use bip32::secp256k1::{
+    elliptic_curve::{sec1::ToEncodedPoint, PrimeField},
+    sha2::{Digest, Sha256},
+    ProjectivePoint, PublicKey, Scalar,
+};
+
+use crate::structs::parsing_error::ParsingError;
+
+/// Computes `SHA256(SHA256(tag) || SHA256(tag) || data)`, the tagged hash of BIP 340.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(data)
+        .finalize()
+        .into()
+}
+
+/// Tweaks the x-only internal key into the x-only output key of a taproot output (BIP 341), i.e.
+/// `P + hash_TapTweak(P || merkle_root)G` where `P` is the internal key with the even y coordinate.
+/// Without a merkle root (no script tree), the tweak commits to the internal key alone.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the internal key is not 32 bytes long or not the x coordinate of a point
+/// on the curve, or the tweak is out of the range of the curve order.
+pub fn taproot_output_key(
+    internal_key: &[u8],
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<[u8; 32], ParsingError> {
+    let invalid_key = || ParsingError::new("Invalid internal key for a taproot output");
+    if internal_key.len() != 32 {
+        return Err(invalid_key());
+    }
+    let point =
+        PublicKey::from_sec1_bytes(&[&[0x02], internal_key].concat()).map_err(|_| invalid_key())?;
+
+    let mut tweak_data = internal_key.to_vec();
+    tweak_data.extend(merkle_root.into_iter().flatten());
+    let tweak = Option::<Scalar>::from(Scalar::from_repr(
+        tagged_hash("TapTweak", &tweak_data).into(),
+    ))
+    .ok_or_else(|| ParsingError::new("The taproot tweak is out of the range of the curve order"))?;
+
+    let output_key = point.to_projective() + ProjectivePoint::GENERATOR * tweak;
+    let output_key = PublicKey::from_affine(output_key.to_affine()).map_err(|_| invalid_key())?;
+
+    let mut x_only = [0; 32];
+    x_only.copy_from_slice(&output_key.to_encoded_point(true).as_bytes()[1..]);
+    Ok(x_only)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::{decode_hex, encode_hex};
+
+    #[test]
+    fn test_taproot_output_key() {
+        // the key path only and the script tree outputs of the BIP 341 wallet test vectors
+        let internal_key =
+            decode_hex("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+        assert_eq!(
+            taproot_output_key(&internal_key, None).map(|key| encode_hex(&key)),
+            Ok("53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343".to_string())
+        );
+
+        let internal_key =
+            decode_hex("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27").unwrap();
+        let merkle_root: [u8; 32] =
+            decode_hex("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            taproot_output_key(&internal_key, Some(&merkle_root)).map(|key| encode_hex(&key)),
+            Ok("147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3".to_string())
+        );
+
+        assert!(taproot_output_key(&internal_key[1..], None).is_err());
+    }
+}
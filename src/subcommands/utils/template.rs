@@ -0,0 +1,148 @@
+use crate::{
+    structs::parsing_error::ParsingError,
+    utils::error_messages::{
+        duplicate_template_key_err, invalid_template_key_err, undefined_placeholder_err,
+        unused_template_key_err, EMPTY_PLACEHOLDER_ERR_MSG,
+    },
+};
+
+pub const PLACEHOLDER_SYMBOL: char = '$';
+const TEMPLATE_KEY_DIVIDER_SYMBOL: char = '=';
+
+fn is_placeholder_name_char(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+/// Parses `name=KEY` template key definitions (as provided to the `--key` flag) into name and key pairs.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - A definition is not in the `name=KEY` form or the name contains unsupported characters,
+/// - The same name is defined multiple times.
+pub fn parse_template_keys(entries: &[String]) -> Result<Vec<(String, String)>, ParsingError> {
+    let mut keys: Vec<(String, String)> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let (name, key) = entry
+            .split_once(TEMPLATE_KEY_DIVIDER_SYMBOL)
+            .filter(|(name, key)| {
+                !name.is_empty() && !key.is_empty() && name.chars().all(is_placeholder_name_char)
+            })
+            .ok_or_else(|| ParsingError::new(&invalid_template_key_err(entry)))?;
+
+        if keys.iter().any(|(known_name, _)| known_name == name) {
+            return Err(ParsingError::new(&duplicate_template_key_err(name)));
+        }
+        keys.push((name.to_string(), key.to_string()));
+    }
+
+    Ok(keys)
+}
+
+/// Replaces every `$name` placeholder in the script expression template with the key expression defined for `name`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - A placeholder symbol is not followed by a name,
+/// - A placeholder has no key defined,
+/// - A defined key is not used by any placeholder (which usually indicates a typo).
+pub fn substitute_key_placeholders(
+    template: &str,
+    keys: &[(String, String)],
+) -> Result<String, ParsingError> {
+    let mut substituted = String::with_capacity(template.len());
+    let mut used = vec![false; keys.len()];
+    let mut characters = template.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        if character != PLACEHOLDER_SYMBOL {
+            substituted.push(character);
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(name_char) = characters.next_if(|c| is_placeholder_name_char(*c)) {
+            name.push(name_char);
+        }
+        if name.is_empty() {
+            return Err(ParsingError::new(EMPTY_PLACEHOLDER_ERR_MSG));
+        }
+        let position = keys
+            .iter()
+            .position(|(key_name, _)| *key_name == name)
+            .ok_or_else(|| ParsingError::new(&undefined_placeholder_err(&name)))?;
+        used[position] = true;
+        substituted.push_str(&keys[position].1);
+    }
+
+    if let Some(position) = used.iter().position(|was_used| !was_used) {
+        return Err(ParsingError::new(&unused_template_key_err(
+            &keys[position].0,
+        )));
+    }
+
+    Ok(substituted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(entries: &[&str]) -> Vec<(String, String)> {
+        parse_template_keys(
+            &entries
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_template_keys() {
+        assert_eq!(
+            keys(&["alice=02aa", "bob_2=[deadbeef/0h]xpub/0/*"]),
+            vec![
+                ("alice".to_string(), "02aa".to_string()),
+                ("bob_2".to_string(), "[deadbeef/0h]xpub/0/*".to_string())
+            ]
+        );
+
+        for invalid in ["alice", "=02aa", "alice=", "al ice=02aa", "$alice=02aa"] {
+            assert_eq!(
+                parse_template_keys(&[invalid.to_string()]),
+                Err(ParsingError::new(&invalid_template_key_err(invalid)))
+            );
+        }
+
+        assert_eq!(
+            parse_template_keys(&["alice=02aa".to_string(), "alice=03bb".to_string()]),
+            Err(ParsingError::new(&duplicate_template_key_err("alice")))
+        );
+    }
+
+    #[test]
+    fn test_substitute_key_placeholders() {
+        assert_eq!(
+            substitute_key_placeholders(
+                "sh(multi(2,$alice,$bob,$alice))",
+                &keys(&["alice=02aa", "bob=03bb"])
+            ),
+            Ok("sh(multi(2,02aa,03bb,02aa))".to_string())
+        );
+        assert_eq!(
+            substitute_key_placeholders("pk($alice)", &keys(&["alice=02aa", "bob=03bb"])),
+            Err(ParsingError::new(&unused_template_key_err("bob")))
+        );
+        assert_eq!(
+            substitute_key_placeholders("pk($carol)", &keys(&["alice=02aa"])),
+            Err(ParsingError::new(&undefined_placeholder_err("carol")))
+        );
+        assert_eq!(
+            substitute_key_placeholders("pk($)", &[]),
+            Err(ParsingError::new(EMPTY_PLACEHOLDER_ERR_MSG))
+        );
+    }
+}
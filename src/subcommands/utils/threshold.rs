@@ -0,0 +1,83 @@
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+use super::script_compiler::collect_key_expressions;
+
+/// Sets the threshold `k` of the `multi(k, ...)` (or `sortedmulti(k, ...)`) of a (previously validated) script
+/// expression, without its checksum, e.g. `sh(multi(2, A, B, C))` with the threshold 3 becomes
+/// `sh(multi(3, A, B, C))`. Everything else (the keys and the whitespace) is kept.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression has no `multi(...)`, or the threshold is not from 1 to the
+/// number of its keys.
+pub fn set_multi_threshold(script: &str, threshold: usize) -> Result<String, ParsingError> {
+    let mut updated = String::with_capacity(script.len());
+    let mut found = false;
+    let mut in_multi = false;
+    for token in script.split_inclusive(['(', ')', ',']) {
+        let (argument, delimiter) = match token.char_indices().last() {
+            Some((index, '(' | ')' | ',')) => token.split_at(index),
+            _ => (token, ""),
+        };
+        if in_multi {
+            // the first argument of the multi(...) is its threshold
+            updated.push_str(&argument.replace(argument.trim(), &threshold.to_string()));
+            in_multi = false;
+            found = true;
+        } else {
+            in_multi = matches!(argument.trim(), "multi" | "sortedmulti") && delimiter == "(";
+            updated.push_str(argument);
+        }
+        updated.push_str(delimiter);
+    }
+
+    if !found {
+        return Err(ParsingError::new(
+            "The script has no multi(...) to set the threshold of",
+        ));
+    }
+    let keys = collect_key_expressions(script)?.len();
+    if threshold == 0 || threshold > keys {
+        return Err(ParsingError::with_kind(
+            ErrorKind::InvalidThreshold,
+            &format!(
+                "Threshold {threshold} is out of bounds, the multi(...) has {keys} keys, so it must be from 1 to {keys}"
+            ),
+        ));
+    }
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "0260b2003c386519fc9eadf2b5cf124dd8eea4c4e68d5e154050a9346ea98ce600";
+    const KEY_2: &str = "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd";
+
+    #[test]
+    fn test_set_multi_threshold() {
+        assert_eq!(
+            set_multi_threshold(&format!("sh(multi( 1 , {KEY_1}, {KEY_2}))"), 2),
+            Ok(format!("sh(multi( 2 , {KEY_1}, {KEY_2}))"))
+        );
+        assert_eq!(
+            set_multi_threshold(&format!("wsh(sortedmulti(1,{KEY_1},{KEY_2}))"), 2),
+            Ok(format!("wsh(sortedmulti(2,{KEY_1},{KEY_2}))"))
+        );
+        assert_eq!(
+            set_multi_threshold(&format!("multi(2,{KEY_1},{KEY_2})"), 3),
+            Err(ParsingError::with_kind(ErrorKind::InvalidThreshold,
+                "Threshold 3 is out of bounds, the multi(...) has 2 keys, so it must be from 1 to 2"
+            ))
+        );
+        assert!(set_multi_threshold(&format!("multi(2,{KEY_1},{KEY_2})"), 0).is_err());
+        assert_eq!(
+            set_multi_threshold(&format!("pkh({KEY_1})"), 1),
+            Err(ParsingError::new(
+                "The script has no multi(...) to set the threshold of"
+            ))
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use super::cbor::CborEncoder;
+
+/// The Bytewords (BCR-2020-012) encoding each byte value, in the minimal encoding only the first and the last
+/// letter of the word is used.
+const BYTEWORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+/// The longest fragment of a single part of a multi-part uniform resource, in bytes, unless configured otherwise.
+pub const DEFAULT_MAX_FRAGMENT_LENGTH: usize = 200;
+
+/// The shortest fragment a multi-part uniform resource can be split into.
+pub const MIN_FRAGMENT_LENGTH: usize = 10;
+
+/// Computes the CRC-32 (ISO-HDLC, as used by zlib) checksum of the bytes.
+#[must_use]
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+/// Encodes the bytes followed by their CRC-32 checksum in the minimal Bytewords encoding.
+#[must_use]
+pub fn bytewords_minimal(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .chain(&crc32(bytes).to_be_bytes())
+        .flat_map(|byte| {
+            let word = BYTEWORDS[usize::from(*byte)];
+            [&word[..1], &word[3..]]
+        })
+        .collect()
+}
+
+/// Encodes the CBOR message as the uniform resource (BCR-2020-005) of the type, e.g. `ur:crypto-hdkey/...`.
+///
+/// A message longer than `max_fragment_length` bytes is split into a multi-part uniform resource of
+/// evenly sized fragments, e.g. `ur:crypto-output/1-3/...`. Only the first `seqLen` parts are produced, which
+/// carry the fragments as they are, so a fountain decoder reassembles the message once it has scanned them all.
+#[must_use]
+pub fn encode_ur(ur_type: &str, message: &[u8], max_fragment_length: usize) -> Vec<String> {
+    if message.len() <= max_fragment_length {
+        return vec![format!("ur:{ur_type}/{}", bytewords_minimal(message))];
+    }
+
+    let fragment_count = message.len().div_ceil(max_fragment_length);
+    let fragment_length = message.len().div_ceil(fragment_count);
+    let checksum = crc32(message);
+
+    (1..=fragment_count)
+        .map(|sequence_number| {
+            let start = (sequence_number - 1) * fragment_length;
+            let mut fragment = message[start..message.len().min(start + fragment_length)].to_vec();
+            fragment.resize(fragment_length, 0);
+
+            let mut part = CborEncoder::new();
+            part.array(5)
+                .unsigned(sequence_number as u64)
+                .unsigned(fragment_count as u64)
+                .unsigned(message.len() as u64)
+                .unsigned(u64::from(checksum))
+                .bytes(&fragment);
+
+            format!(
+                "ur:{ur_type}/{sequence_number}-{fragment_count}/{}",
+                bytewords_minimal(&part.into_bytes())
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_bytewords_minimal() {
+        // the example of BCR-2020-012
+        assert_eq!(
+            bytewords_minimal(&[0, 1, 2, 128, 255]),
+            "aeadaolazmjendeoti"
+        );
+    }
+
+    #[test]
+    fn test_encode_single_part_ur() {
+        assert_eq!(
+            encode_ur("bytes", &[0, 1, 2, 128, 255], DEFAULT_MAX_FRAGMENT_LENGTH),
+            vec!["ur:bytes/aeadaolazmjendeoti"]
+        );
+    }
+
+    #[test]
+    fn test_encode_multi_part_ur() {
+        let message: Vec<u8> = (0..=24).collect();
+        let parts = encode_ur("bytes", &message, 10);
+
+        assert_eq!(parts.len(), 3);
+        for (index, part) in parts.iter().enumerate() {
+            assert!(part.starts_with(&format!("ur:bytes/{}-3/", index + 1)));
+        }
+
+        // the last fragment (bytes 18 to 24) is padded with zeros to the length of the others (9 bytes)
+        let mut expected = CborEncoder::new();
+        expected
+            .array(5)
+            .unsigned(3)
+            .unsigned(3)
+            .unsigned(25)
+            .unsigned(u64::from(crc32(&message)))
+            .bytes(&[18, 19, 20, 21, 22, 23, 24, 0, 0]);
+        assert_eq!(
+            parts[2],
+            format!("ur:bytes/3-3/{}", bytewords_minimal(&expected.into_bytes()))
+        );
+    }
+}
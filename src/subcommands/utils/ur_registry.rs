@@ -0,0 +1,312 @@
+use std::str::FromStr;
+
+use bip32::{ExtendedKey, Prefix};
+
+use crate::{
+    structs::parsing_error::ParsingError, subcommands::key_expression::split_key_expression,
+};
+
+use super::{
+    cbor::CborEncoder,
+    extended_key::is_extended_public_key,
+    hex_encoded_public_key::has_hex_encoded_public_key_prefix,
+    hexadecimal::{assert_hexadecimal_format, decode_hex},
+    number::parse_unsigned,
+};
+
+/// The CBOR tags of the registry of the uniform resource types (BCR-2020-006, 007 and 010).
+const TAG_CRYPTO_HDKEY: u64 = 303;
+const TAG_CRYPTO_KEYPATH: u64 = 304;
+const TAG_CRYPTO_COININFO: u64 = 305;
+const TAG_CRYPTO_ECKEY: u64 = 306;
+const SCRIPT_TAGS: [(&str, u64); 10] = [
+    ("sh", 400),
+    ("wsh", 401),
+    ("pk", 402),
+    ("pkh", 403),
+    ("wpkh", 404),
+    ("combo", 405),
+    ("multi", 406),
+    ("sortedmulti", 407),
+    ("raw", 408),
+    ("tr", 409),
+];
+
+/// Encodes the extended public key expression (e.g. `[deadbeef/84h/0h/0h]xpub.../0/*`) as the CBOR message
+/// of the `crypto-hdkey` uniform resource (BCR-2020-007), with its key origin and its children, if any.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key is not an extended public key, or its key origin or path is malformed.
+pub fn crypto_hdkey(key_expression: &str) -> Result<Vec<u8>, ParsingError> {
+    let mut encoder = CborEncoder::new();
+    write_hdkey(&mut encoder, key_expression.trim())?;
+    Ok(encoder.into_bytes())
+}
+
+/// Encodes the script expression (without its checksum) as the CBOR message of the `crypto-output` uniform
+/// resource (BCR-2020-010). The extended public keys are encoded as `crypto-hdkey` and the hex encoded public
+/// keys as `crypto-eckey`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the script expression is malformed or not supported, or it contains
+/// a private key.
+pub fn crypto_output(script: &str) -> Result<Vec<u8>, ParsingError> {
+    let mut encoder = CborEncoder::new();
+    write_script(&mut encoder, script.trim())?;
+    Ok(encoder.into_bytes())
+}
+
+fn write_script(encoder: &mut CborEncoder, script: &str) -> Result<(), ParsingError> {
+    let (name, args) = script
+        .split_once('(')
+        .and_then(|(name, rest)| Some((name.trim(), rest.strip_suffix(')')?)))
+        .ok_or_else(|| ParsingError::new(&format!("Malformed script expression '{script}'")))?;
+    let tag = SCRIPT_TAGS
+        .iter()
+        .find(|(script_name, _)| *script_name == name)
+        .map(|(_, tag)| *tag)
+        .ok_or_else(|| {
+            ParsingError::new(&format!(
+                "Script '{name}' cannot be encoded as crypto-output"
+            ))
+        })?;
+    encoder.tag(tag);
+
+    let args = split_args(args);
+    match (name, args.as_slice()) {
+        ("sh" | "wsh", [inner]) => write_script(encoder, inner),
+        ("raw", [hex]) => {
+            assert_hexadecimal_format(hex, "raw function argument")
+                .map_err(ParsingError::without_span)?;
+            if !hex.len().is_multiple_of(2) {
+                return Err(ParsingError::new(
+                    "raw function argument must consist of whole bytes to be encoded",
+                ));
+            }
+            encoder.bytes(&decode_hex(hex)?);
+            Ok(())
+        }
+        ("multi" | "sortedmulti", [threshold, keys @ ..]) if !keys.is_empty() => {
+            encoder
+                .map(2)
+                .unsigned(1)
+                .unsigned(parse_unsigned(threshold, "threshold")?)
+                .unsigned(2)
+                .array(keys.len());
+            keys.iter().try_for_each(|key| write_key(encoder, key))
+        }
+        ("pk" | "pkh" | "wpkh" | "combo" | "tr", [key]) => write_key(encoder, key),
+        _ => Err(ParsingError::new(&format!(
+            "Unexpected arguments of the '{name}' script"
+        ))),
+    }
+}
+
+/// Writes the tagged `crypto-hdkey` or `crypto-eckey` of the key expression.
+fn write_key(encoder: &mut CborEncoder, key_expression: &str) -> Result<(), ParsingError> {
+    let (_, key) = split_key_expression(key_expression)?;
+    if has_hex_encoded_public_key_prefix(key) {
+        assert_hexadecimal_format(key, "public key").map_err(ParsingError::without_span)?;
+        encoder
+            .tag(TAG_CRYPTO_ECKEY)
+            .map(1)
+            .unsigned(3)
+            .bytes(&decode_hex(key)?);
+        Ok(())
+    } else {
+        encoder.tag(TAG_CRYPTO_HDKEY);
+        write_hdkey(encoder, key_expression)
+    }
+}
+
+fn write_hdkey(encoder: &mut CborEncoder, key_expression: &str) -> Result<(), ParsingError> {
+    let (key_origin, key) = split_key_expression(key_expression)?;
+    let (key, children) = key.split_at(key.find('/').unwrap_or(key.len()));
+    if !is_extended_public_key(key) {
+        return Err(ParsingError::new(
+            "Only extended public keys can be encoded as crypto-hdkey, private keys are never encoded",
+        ));
+    }
+    let key = ExtendedKey::from_str(key)?;
+
+    let mut entries: Vec<(u64, Vec<u8>)> = vec![(3, cbor(|e| e.bytes(&key.key_bytes)))];
+    entries.push((4, cbor(|e| e.bytes(&key.attrs.chain_code))));
+    if key.prefix == Prefix::TPUB {
+        // the coin type defaults to bitcoin and the network to the mainnet
+        entries.push((
+            5,
+            cbor(|e| e.tag(TAG_CRYPTO_COININFO).map(1).unsigned(2).unsigned(1)),
+        ));
+    }
+    if let Some(key_origin) = key_origin {
+        let key_origin = key_origin.trim_start_matches('[').trim_end_matches(']');
+        let (fingerprint, path) = key_origin.split_at(8.min(key_origin.len()));
+        let fingerprint = u32::from_str_radix(fingerprint, 16)
+            .map_err(|_| ParsingError::new("Fingerprint is not valid hex"))?;
+        let keypath = keypath(path)?;
+        entries.push((
+            6,
+            cbor(|e| {
+                e.tag(TAG_CRYPTO_KEYPATH)
+                    .map(2)
+                    .unsigned(1)
+                    .encoded(&keypath);
+                e.unsigned(2).unsigned(u64::from(fingerprint))
+            }),
+        ));
+    }
+    if !children.is_empty() {
+        let keypath = keypath(children)?;
+        entries.push((
+            7,
+            cbor(|e| {
+                e.tag(TAG_CRYPTO_KEYPATH)
+                    .map(1)
+                    .unsigned(1)
+                    .encoded(&keypath)
+            }),
+        ));
+    }
+    let parent_fingerprint = u32::from_be_bytes(key.attrs.parent_fingerprint);
+    if parent_fingerprint != 0 {
+        entries.push((8, cbor(|e| e.unsigned(u64::from(parent_fingerprint)))));
+    }
+
+    encoder.map(entries.len());
+    for (key, value) in entries {
+        encoder.unsigned(key).encoded(&value);
+    }
+    Ok(())
+}
+
+/// Encodes the components of the path (e.g. `/84h/0h/*`) as the array of the keypath, each step as its index
+/// (an empty array for the wildcard) followed by whether it is hardened.
+fn keypath(path: &str) -> Result<Vec<u8>, ParsingError> {
+    let steps: Vec<&str> = path.split('/').skip(1).collect();
+    let mut encoder = CborEncoder::new();
+    encoder.array(steps.len() * 2);
+
+    for step in steps {
+        let (index, hardened) = match step.strip_suffix(['h', 'H', '\'']) {
+            Some(index) => (index, true),
+            None => (step, false),
+        };
+        if index == "*" {
+            encoder.array(0);
+        } else {
+            let index: u32 = index
+                .parse()
+                .ok()
+                .filter(|index| *index < 1 << 31)
+                .ok_or_else(|| ParsingError::new(&format!("Invalid derivation step '{step}'")))?;
+            encoder.unsigned(u64::from(index));
+        }
+        encoder.bool(hardened);
+    }
+
+    Ok(encoder.into_bytes())
+}
+
+/// Splits the arguments of a script on the commas outside of the nested scripts and key origins.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (index, c) in args.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(args[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    split.push(args[start..].trim());
+    split
+}
+
+fn cbor(write: impl FnOnce(&mut CborEncoder) -> &mut CborEncoder) -> Vec<u8> {
+    let mut encoder = CborEncoder::new();
+    write(&mut encoder);
+    encoder.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subcommands::utils::hexadecimal::encode_hex;
+
+    const XPUB: &str = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+
+    #[test]
+    fn test_crypto_hdkey() {
+        let key = ExtendedKey::from_str(XPUB).unwrap();
+        let hdkey = crypto_hdkey(&format!("[3442193e/0h]{XPUB}/1/*h")).unwrap();
+
+        let mut expected = CborEncoder::new();
+        expected
+            .map(5)
+            .unsigned(3)
+            .bytes(&key.key_bytes)
+            .unsigned(4)
+            .bytes(&key.attrs.chain_code)
+            .unsigned(6)
+            .tag(304)
+            .map(2)
+            .unsigned(1)
+            .array(2)
+            .unsigned(0)
+            .bool(true)
+            .unsigned(2)
+            .unsigned(0x3442_193e)
+            .unsigned(7)
+            .tag(304)
+            .map(1)
+            .unsigned(1)
+            .array(4)
+            .unsigned(1)
+            .bool(false)
+            .array(0)
+            .bool(true)
+            .unsigned(8)
+            .unsigned(0x3442_193e);
+        assert_eq!(encode_hex(&hdkey), encode_hex(&expected.into_bytes()));
+
+        assert!(crypto_hdkey("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi").is_err());
+        assert!(crypto_hdkey(&format!("{XPUB}/2147483648")).is_err());
+    }
+
+    #[test]
+    fn test_crypto_output() {
+        // the example of BCR-2020-010: pkh of a public key
+        assert_eq!(
+            encode_hex(
+                &crypto_output(
+                    "pkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)"
+                )
+                .unwrap()
+            ),
+            "d90193d90132a103582102c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+        );
+
+        let output = crypto_output(&format!(
+            "sh(wsh(sortedmulti(1,[3442193e/0h]{XPUB}/0/*,02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)))"
+        ))
+        .unwrap();
+        // sh, wsh and sortedmulti tags followed by the map of the threshold and the two keys
+        assert!(encode_hex(&output).starts_with("d90190d90191d90197a201010282d9012f"));
+
+        assert_eq!(
+            encode_hex(&crypto_output("raw(deadbeef)").unwrap()),
+            "d9019844deadbeef"
+        );
+        assert!(crypto_output("addr(bc1q)").is_err());
+        assert!(
+            crypto_output("pkh(L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1)").is_err()
+        );
+        assert!(crypto_output("pk(").is_err());
+    }
+}
@@ -1,8 +1,14 @@
 use bip32::secp256k1::sha2::{Digest, Sha256};
 
-use crate::structs::parsing_error::ParsingError;
+use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
 
-pub fn validate_wif_private_key(key: &str) -> Result<(), ParsingError> {
+/// Validates a WIF encoded private key, returning whether its public key is compressed.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the key is not valid base58, has an invalid length or version or its checksum
+/// does not match.
+pub fn validate_wif_private_key(key: &str) -> Result<bool, ParsingError> {
     let bytes = bs58::decode(key)
         .into_vec()
         .map_err(|_| ParsingError::new("Could not convert WIF from base58"))?;
@@ -22,10 +28,14 @@ pub fn validate_wif_private_key(key: &str) -> Result<(), ParsingError> {
     let new_checksum = &new_checksum[..4];
 
     if expected_checksum != new_checksum {
-        return Err(ParsingError::new("WIF checksum does not match"));
+        return Err(ParsingError::with_kind(
+            ErrorKind::ChecksumMismatch,
+            "WIF checksum does not match",
+        ));
     }
 
-    Ok(())
+    // the compressed key has the 0x01 suffix
+    Ok(bytes.len() == 34)
 }
 
 #[cfg(test)]
@@ -50,6 +60,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_wif_private_key_compression() {
+        assert_eq!(
+            validate_wif_private_key("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ"),
+            Ok(false)
+        );
+        assert_eq!(
+            validate_wif_private_key("L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1"),
+            Ok(true)
+        );
+    }
+
     #[test]
     fn test_validate_wif_private_key_invalid_checksum() {
         let invalid_wif = "L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyXw";
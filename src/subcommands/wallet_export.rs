@@ -0,0 +1,172 @@
+use crate::structs::{
+    export_target::ExportTarget, parsing_error::ParsingError,
+    wallet_export_config::WalletExportConfig,
+};
+
+use super::utils::{
+    checksum::checksum_create,
+    json::JsonValue,
+    multisig::{parse_multisig_descriptor, MultisigWallet},
+};
+
+/// Produces the wallet file of the multisig wallet for the target wallet software.
+///
+/// The `sortedmulti` descriptor (see [`parse_multisig_descriptor`]) is written with its checksum, together
+/// with the `label` and the `blockheight` to scan from, as a single line JSON object:
+/// - Specter Desktop: the receive (`/0/*`) descriptor and the `devices` of the cosigners (labeled by their
+///   fingerprints), e.g. `{"label":"Multisig","blockheight":0,"descriptor":"wsh(...)#...","devices":[...]}`,
+/// - Sparrow: the receive and change descriptor (`/<0;1>/*`),
+///   e.g. `{"label":"Multisig","blockheight":0,"descriptor":"wsh(...)#..."}`.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the descriptor is not a supported multisig descriptor.
+pub fn wallet_export(input: &str, config: &WalletExportConfig) -> Result<String, ParsingError> {
+    let wallet = parse_multisig_descriptor(input)?;
+
+    let mut members = vec![
+        ("label".to_string(), JsonValue::String(config.label.clone())),
+        (
+            "blockheight".to_string(),
+            JsonValue::Number(config.blockheight.to_string()),
+        ),
+    ];
+    match config.target {
+        ExportTarget::Specter => {
+            members.push(("descriptor".to_string(), descriptor_json(&wallet, "/0/*")?));
+            members.push((
+                "devices".to_string(),
+                JsonValue::Array(
+                    wallet
+                        .cosigners
+                        .iter()
+                        .map(|cosigner| {
+                            JsonValue::Object(vec![
+                                ("type".to_string(), JsonValue::String("other".to_string())),
+                                (
+                                    "label".to_string(),
+                                    JsonValue::String(cosigner.fingerprint.clone()),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ));
+        }
+        ExportTarget::Sparrow => {
+            members.push((
+                "descriptor".to_string(),
+                descriptor_json(&wallet, "/<0;1>/*")?,
+            ));
+        }
+    }
+
+    Ok(JsonValue::Object(members).to_string())
+}
+
+fn descriptor_json(wallet: &MultisigWallet, key_suffix: &str) -> Result<JsonValue, ParsingError> {
+    let descriptor = wallet.descriptor(key_suffix);
+    let checksum = checksum_create(&descriptor)?;
+    Ok(JsonValue::String(format!("{descriptor}#{checksum}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{subcommands::utils::checksum::checksum_check, test_utils::get_cmd};
+
+    const XPUB_1: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+    const XPUB_2: &str = "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB";
+
+    fn descriptor() -> String {
+        format!("wsh(sortedmulti(2,[3442193e/48'/0'/0'/2']{XPUB_1}/**,[bd16bee5/48'/0'/0'/2']{XPUB_2}/**))")
+    }
+
+    #[test]
+    fn test_specter_export() {
+        let config = WalletExportConfig {
+            target: ExportTarget::Specter,
+            label: "Vault \"A\"".to_string(),
+            blockheight: 800_000,
+        };
+        let receive = format!("wsh(sortedmulti(2,[3442193e/48h/0h/0h/2h]{XPUB_1}/0/*,[bd16bee5/48h/0h/0h/2h]{XPUB_2}/0/*))");
+
+        assert_eq!(
+            wallet_export(&descriptor(), &config),
+            Ok(format!(
+                r#"{{"label":"Vault \"A\"","blockheight":800000,"descriptor":"{receive}#{}","devices":[{{"type":"other","label":"3442193e"}},{{"type":"other","label":"bd16bee5"}}]}}"#,
+                checksum_create(&receive).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_specter_export_round_trip() {
+        let config = WalletExportConfig {
+            target: ExportTarget::Specter,
+            label: "Multisig".to_string(),
+            blockheight: 0,
+        };
+        let export = wallet_export(&descriptor(), &config).unwrap();
+        let descriptor = export
+            .split('"')
+            .skip_while(|part| *part != "descriptor")
+            .nth(2)
+            .unwrap();
+
+        // the descriptor is checked and its checksum computed again by script-expression
+        get_cmd()
+            .args(["script-expression", "--compute-checksum", descriptor])
+            .assert()
+            .success()
+            .stdout(format!("{descriptor}\n"));
+        get_cmd()
+            .args(["script-expression", "--verify-checksum", descriptor])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_sparrow_export() {
+        let config = WalletExportConfig {
+            target: ExportTarget::Sparrow,
+            label: "Multisig".to_string(),
+            blockheight: 0,
+        };
+
+        let export = wallet_export(&descriptor(), &config).unwrap();
+        let descriptor = export
+            .strip_prefix(r#"{"label":"Multisig","blockheight":0,"descriptor":""#)
+            .and_then(|rest| rest.strip_suffix(r#""}"#))
+            .unwrap();
+        let (script, checksum) = descriptor.split_once('#').unwrap();
+        assert!(script.ends_with(&format!("{XPUB_2}/<0;1>/*))")));
+        assert_eq!(checksum_check(script, checksum), Ok(true));
+
+        assert!(wallet_export(&format!("wsh(multi(1,[3442193e]{XPUB_1}))"), &config).is_err());
+    }
+
+    #[test]
+    fn test_wallet_export_cli() {
+        let output = get_cmd()
+            .args([
+                "wallet-export",
+                "-",
+                "--target",
+                "sparrow",
+                "--label",
+                "Vault",
+            ])
+            .write_stdin(descriptor())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with(r#"{"label":"Vault","blockheight":0,"descriptor":"wsh(sortedmulti(2,"#));
+    }
+}
@@ -0,0 +1,223 @@
+use bip32::{ChildNumber, XPrv};
+
+use crate::structs::{
+    parsing_error::ParsingError, secret_buffer::SecretBuffer, wallet_account::WalletAccount,
+    wallet_init_config::WalletInitConfig,
+};
+
+use super::utils::{
+    descriptor::receive_and_change_descriptors,
+    hexadecimal::encode_hex,
+    seed::{decode_seed, is_hex_seed, mnemonic_to_seed},
+};
+
+/// Bootstraps a single-key wallet account from a BIP 39 mnemonic or a hexadecimal seed.
+///
+/// The account key is derived at `m/purpose'/coin_type'/account'` as described in BIP 44 (and BIP 49, 84, 86).
+///
+/// # Arguments
+///
+/// * `input` - The BIP 39 mnemonic phrase in any of the official languages, or the seed in hexadecimal.
+/// * `config` - The configuration specifying the purpose, account number, network, the language of the mnemonic
+///   and its passphrase (empty if not given).
+///
+/// # Returns
+///
+/// Returns `Ok(String)` with the master key fingerprint, the account extended private and public keys and the
+/// receive and change descriptors (with the key origin and checksums), each on a separate labeled line.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if:
+/// - The input is neither a valid mnemonic, nor a valid hexadecimal seed,
+/// - A passphrase is given with a hexadecimal seed,
+/// - The seed has an invalid length,
+/// - Any cryptographic operation fails.
+pub fn wallet_init(input: &str, config: &WalletInitConfig) -> Result<String, ParsingError> {
+    wallet_account(input, config).map(|account| account.to_string())
+}
+
+/// Bootstraps the wallet account the same way as [`wallet_init`], returning it instead of its output.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] in the same cases as [`wallet_init`].
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn wallet_account(
+    input: &str,
+    config: &WalletInitConfig,
+) -> Result<WalletAccount, ParsingError> {
+    let seed = SecretBuffer::new(if is_hex_seed(input) {
+        if config.passphrase.is_some() {
+            return Err(ParsingError::new(
+                "A passphrase can only be used with a mnemonic, not with a hexadecimal seed",
+            ));
+        }
+        decode_seed(input)?
+    } else {
+        let passphrase = config.passphrase.as_ref().map_or(Ok(""), |passphrase| {
+            std::str::from_utf8(passphrase.as_ref())
+                .map_err(|_| ParsingError::new("The passphrase is not valid UTF-8"))
+        })?;
+        mnemonic_to_seed(input, config.language, passphrase)?
+    });
+
+    let master_xprv = XPrv::new(seed)?;
+    let fingerprint = encode_hex(&master_xprv.public_key().fingerprint());
+
+    let purpose = config.script_type.purpose();
+    let coin_type = config.network.coin_type();
+    let mut account_xprv = master_xprv;
+    for index in [purpose, coin_type, config.account] {
+        account_xprv = account_xprv.derive_child(ChildNumber::new(index, true)?)?;
+    }
+    let account_xpub = account_xprv
+        .public_key()
+        .to_string(config.network.public_prefix());
+
+    let account_key_expression = format!(
+        "[{fingerprint}/{purpose}h/{coin_type}h/{}h]{account_xpub}",
+        config.account
+    );
+    let [receive, change] =
+        receive_and_change_descriptors(&account_key_expression, config.script_type)?;
+
+    Ok(WalletAccount {
+        fingerprint,
+        xprv: account_xprv
+            .to_string(config.network.private_prefix())
+            .to_string(),
+        xpub: account_xpub,
+        receive,
+        change,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::get_cmd;
+
+    const MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn wallet_init_output(args: &[&str]) -> Vec<String> {
+        let output = get_cmd()
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_wallet_init_bip84_vector() {
+        // test vector from BIP 84
+        // https://github.com/bitcoin/bips/blob/master/bip-0084.mediawiki#test-vectors
+        let lines = wallet_init_output(&["wallet-init", MNEMONIC, "--purpose", "84"]);
+
+        assert_eq!(lines[0], "fingerprint: 73c5da0a");
+        assert!(lines[1].starts_with("xprv: xprv"));
+        assert_eq!(lines[2], "xpub: xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V");
+        assert!(lines[3].starts_with("receive: wpkh([73c5da0a/84h/0h/0h]xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)#"));
+        assert!(lines[4].starts_with("change: wpkh([73c5da0a/84h/0h/0h]xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/1/*)#"));
+    }
+
+    #[test]
+    #[cfg(feature = "script-expression")]
+    fn test_wallet_init_round_trip() {
+        for purpose in ["44", "49", "84", "86"] {
+            let lines = wallet_init_output(&["wallet-init", MNEMONIC, "--purpose", purpose]);
+            let descriptors: String = [("receive: ", &lines[3]), ("change: ", &lines[4])]
+                .iter()
+                .map(|(label, line)| format!("{}\n", line.strip_prefix(label).unwrap()))
+                .collect();
+
+            // the descriptors are checked and their checksums computed again by script-expression
+            get_cmd()
+                .args(["script-expression", "--compute-checksum", "-"])
+                .write_stdin(descriptors.clone())
+                .assert()
+                .success()
+                .stdout(descriptors.clone());
+            get_cmd()
+                .args(["script-expression", "--verify-checksum", "-"])
+                .write_stdin(descriptors)
+                .assert()
+                .success();
+        }
+    }
+
+    #[test]
+    fn test_wallet_init_testnet_seed() {
+        let lines = wallet_init_output(&[
+            "wallet-init",
+            "000102030405060708090a0b0c0d0e0f",
+            "--network",
+            "testnet",
+            "--purpose",
+            "44",
+        ]);
+
+        // master fingerprint from the BIP 32 test vector 1
+        assert_eq!(lines[0], "fingerprint: 3442193e");
+        assert!(lines[1].starts_with("xprv: tprv"));
+        assert!(lines[2].starts_with("xpub: tpub"));
+        assert!(lines[3].starts_with("receive: pkh([3442193e/44h/1h/0h]tpub"));
+    }
+
+    #[test]
+    fn test_wallet_init_invalid_mnemonic() {
+        get_cmd()
+            .args(["wallet-init", "abandon abandon abandon"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_wallet_init_passphrase() {
+        let path = std::env::temp_dir().join(format!("bip380-wallet-init-{}", std::process::id()));
+        std::fs::write(&path, "TREZOR\n").unwrap();
+
+        let from_file = get_cmd()
+            .args(["wallet-init", MNEMONIC, "--template", "{fingerprint}"])
+            .args(["--passphrase-file", path.to_str().unwrap()])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let from_env = get_cmd()
+            .args(["wallet-init", MNEMONIC, "--template", "{fingerprint}"])
+            .args(["--passphrase-env", "BIP380_PASSPHRASE"])
+            .env("BIP380_PASSPHRASE", "TREZOR")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(from_file, from_env);
+        assert_ne!(from_file, b"73c5da0a\n");
+
+        get_cmd()
+            .args(["wallet-init", "000102030405060708090a0b0c0d0e0f"])
+            .args(["--passphrase-env", "BIP380_PASSPHRASE"])
+            .env("BIP380_PASSPHRASE", "TREZOR")
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_wallet_init_template() {
+        let lines = wallet_init_output(&["wallet-init", MNEMONIC, "--template", "{fingerprint}"]);
+
+        assert_eq!(lines, vec!["73c5da0a"]);
+    }
+}
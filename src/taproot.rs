@@ -0,0 +1,28 @@
+//! The BIP 341 tweak of taproot keys, for verifying P2TR outputs (and their addresses) end-to-end.
+//!
+//! ```
+//! let internal_key = [
+//!     0xd6, 0x88, 0x9c, 0xb0, 0x81, 0x03, 0x6e, 0x0f, 0xae, 0xfa, 0x3a, 0x35, 0x15, 0x7a, 0xd7, 0x10,
+//!     0x86, 0xb1, 0x23, 0xb2, 0xb1, 0x44, 0xb6, 0x49, 0x79, 0x8b, 0x49, 0x4c, 0x30, 0x0a, 0x96, 0x1d,
+//! ];
+//! let output_key = bip380::taproot::output_key(&internal_key, None)?;
+//! assert_eq!(output_key[..4], [0x53, 0xa1, 0xf6, 0xe4]);
+//! # Ok::<(), bip380::taproot::ParsingError>(())
+//! ```
+
+use crate::subcommands::utils::taproot::taproot_output_key;
+
+pub use crate::structs::{error_kind::ErrorKind, parsing_error::ParsingError};
+
+/// Computes the x-only output key of the taproot output with the x-only internal key and the merkle root of its
+/// script tree, `None` for an output without scripts (BIP 86).
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the internal key is not the x coordinate of a point on the curve.
+pub fn output_key(
+    internal_key: &[u8; 32],
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<[u8; 32], ParsingError> {
+    taproot_output_key(internal_key, merkle_root)
+}
@@ -1,2 +1,5 @@
+pub mod output_encoder;
+pub mod output_fields;
 pub mod parsable;
+pub mod script_function;
 pub mod string_utils;
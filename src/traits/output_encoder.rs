@@ -0,0 +1,11 @@
+use crate::{parsers::arg_parser::Command, structs::parsing_error::ParsingError};
+
+/// A trait for the encoders of the `--output` formats, shared by all sub-commands.
+pub trait OutputEncoder {
+    /// Runs the sub-command on a single input and encodes its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the sub-command fails on the input or its result cannot be encoded.
+    fn encode(&self, command: &Command, input: &str) -> Result<String, ParsingError>;
+}
@@ -0,0 +1,5 @@
+/// A trait for sub-command results exposing named fields to the `--template` option.
+pub trait OutputFields {
+    /// Returns the names and the values of the fields.
+    fn fields(&self) -> Vec<(&'static str, String)>;
+}
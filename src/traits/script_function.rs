@@ -0,0 +1,93 @@
+use crate::structs::{
+    key_context::KeyContext, parsing_error::ParsingError, script_limits::ScriptLimits,
+};
+
+/// The number of arguments a script function takes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    /// Returns whether the number of arguments is accepted.
+    #[must_use]
+    pub fn accepts(self, count: usize) -> bool {
+        match self {
+            Arity::Exactly(expected) => count == expected,
+            Arity::AtLeast(minimum) => count >= minimum,
+        }
+    }
+}
+
+/// Where a script function appears within the script expression.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScriptContext {
+    /// The whole script expression.
+    TopLevel,
+    /// The argument of `sh(...)`, the redeem script.
+    Sh,
+    /// The argument of `wsh(...)`, the witness script.
+    Wsh,
+}
+
+impl ScriptContext {
+    /// Returns the context of the key expressions of a function appearing in this context.
+    #[must_use]
+    pub fn key_context(self) -> KeyContext {
+        match self {
+            ScriptContext::TopLevel => KeyContext::Legacy,
+            ScriptContext::Sh => KeyContext::P2sh,
+            ScriptContext::Wsh => KeyContext::SegwitV0,
+        }
+    }
+}
+
+/// A function of the script expressions, e.g. `pkh(KEY)`, implemented by its own module of
+/// [`script_functions`](crate::subcommands::utils::script_functions) and listed in its registry.
+///
+/// The arguments given to the methods are already extracted from the parentheses and their count
+/// is checked against the [`arity`](ScriptFunction::arity).
+pub trait ScriptFunction {
+    /// The name the script expression starts with, e.g. `pkh`.
+    fn name(&self) -> &'static str;
+
+    /// The number of arguments the function takes.
+    fn arity(&self) -> Arity;
+
+    /// Returns whether the function can appear in the context, only at the top level by default.
+    fn allowed_in(&self, context: ScriptContext) -> bool {
+        context == ScriptContext::TopLevel
+    }
+
+    /// Validates the arguments of the function appearing in the context.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if any of the arguments is invalid.
+    fn validate(&self, args: &[String], context: ScriptContext) -> Result<(), ParsingError>;
+
+    /// Checks the (previously validated) arguments against the limits specific to the function, nothing
+    /// to check by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if a limit is exceeded.
+    fn check_limits(&self, _args: &[String], _limits: &ScriptLimits) -> Result<(), ParsingError> {
+        Ok(())
+    }
+
+    /// Compiles the (previously validated) function into the serialized script, deriving the ranged keys at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if the function cannot be compiled (e.g. a ranged key without an index).
+    fn compile(&self, args: &[String], index: Option<u32>) -> Result<Vec<u8>, ParsingError>;
+
+    /// Returns the key expressions among the (previously validated) arguments, in the order they appear.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParsingError`] if a nested script expression is malformed.
+    fn key_expressions(&self, args: &[String]) -> Result<Vec<String>, ParsingError>;
+}
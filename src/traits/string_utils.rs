@@ -14,13 +14,8 @@ pub trait Trimifiable: Sized {
 impl<'a> Trimifiable for &'a str {
     type Output = &'a str;
     fn trimify(self) -> Self::Output {
-        let first_non_space = self.find(|c: char| c != SPACE);
-        let last_non_space = self.rfind(|c: char| c != SPACE);
-
-        match (first_non_space, last_non_space) {
-            (Some(start), Some(end)) => &self[start..=end],
-            _ => "",
-        }
+        // slicing by the byte positions of the characters would split a multibyte one
+        self.trim_matches(SPACE)
     }
 }
 
@@ -126,5 +121,7 @@ mod tests {
         assert_eq!("Hello ".trimify(), "Hello");
         assert_eq!("Hel lo".trimify(), "Hel lo");
         assert_eq!(" \t Hello \t ".trimify(), "\t Hello \t");
+        assert_eq!(" č ".trimify(), "č");
+        assert_eq!("deadbeefč".trimify(), "deadbeefč");
     }
 }
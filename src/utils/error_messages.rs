@@ -1,3 +1,5 @@
+use crate::structs::{network::Network, secret_kind::SecretKind};
+
 #[must_use]
 pub fn missing_follow_up_val_err(key: &str) -> String {
     format!("Missing follow-up value after flag '{key}'!")
@@ -20,9 +22,104 @@ pub fn script_arg_extraction_err(label: &str) -> String {
 
 #[must_use]
 pub fn script_sh_unsupported_arg_err(arg: &str) -> String {
-    format!("'sh' script's argument must be either 'pk', 'pkh' or 'multi' scripts, but '{arg}' was given.")
+    format!("'sh' script's argument must be either 'pk', 'pkh', 'multi', 'sortedmulti', 'wpkh' or 'wsh' scripts, but '{arg}' was given.")
+}
+
+#[must_use]
+pub fn script_wsh_unsupported_arg_err(arg: &str) -> String {
+    format!("'wsh' script's argument must be either 'pk', 'pkh', 'multi' or 'sortedmulti' scripts, but '{arg}' was given.")
+}
+
+#[must_use]
+pub fn script_top_level_unsupported_err(name: &str) -> String {
+    format!("'{name}' script cannot be used on its own at the top level of a script expression.")
+}
+
+#[must_use]
+pub fn script_limit_err(subject: &str, limit: usize, flag: &str) -> String {
+    format!("{subject}, more than the limit of {limit}. The limit can be changed with '--{flag}'.")
+}
+
+#[must_use]
+pub fn invalid_template_key_err(entry: &str) -> String {
+    format!("Template key '{entry}' must be in the form 'name=KEY', where the name consists only of letters, digits or underscores.")
+}
+
+#[must_use]
+pub fn duplicate_template_key_err(name: &str) -> String {
+    format!("Template key '{name}' is defined multiple times.")
+}
+
+#[must_use]
+pub fn undefined_placeholder_err(name: &str) -> String {
+    format!("Placeholder '${name}' has no value. Provide it using '--key {name}=KEY'.")
+}
+
+#[must_use]
+pub fn unused_template_key_err(name: &str) -> String {
+    format!("Template key '{name}' is not used by any placeholder in the script expression.")
+}
+
+#[must_use]
+pub fn origin_fingerprint_mismatch_err(key_expression: &str, master_fingerprint: &str) -> String {
+    format!("Key origin fingerprint of '{key_expression}' does not match the master key fingerprint '{master_fingerprint}'.")
+}
+
+#[must_use]
+pub fn origin_key_mismatch_err(key_expression: &str) -> String {
+    format!("Key '{key_expression}' does not match the key derived from the master key along its origin path.")
+}
+
+#[must_use]
+pub fn network_mismatch_err(
+    key_expression: &str,
+    key_network: Network,
+    expected_network: Network,
+    source: &str,
+) -> String {
+    format!("Key '{key_expression}' is a {key_network} key, but the {source} network is {expected_network}.")
+}
+
+#[must_use]
+pub fn unsupported_request_arg_err(arg: &str) -> String {
+    format!("Argument '{arg}' is not supported in requests sent to the server.")
+}
+
+#[must_use]
+pub fn unexpected_positional_arg_err(arg: &str) -> String {
+    format!("Unexpected argument '{arg}' among the options, the input is given on its own.")
+}
+
+#[must_use]
+pub fn private_output_err(secret_kind: SecretKind) -> String {
+    format!("The output contains {secret_kind}, which is refused with '--no-private'.")
+}
+
+#[must_use]
+pub fn hardened_from_xpub_err(index: u32, path: &str) -> String {
+    format!("Cannot derive the hardened child '{index}h' of the path '{path}' from an extended public key, hardened derivation requires the extended private key or the seed. Use '--split-at-hardened' to derive the deepest public prefix of the path instead.")
+}
+
+#[must_use]
+pub fn unavailable_command_err(command: &str, feature: &str) -> String {
+    format!("'{command}' is not available, the utility was built without the '{feature}' feature")
 }
 
 pub const MISSING_INPUT_ERR_MSG: &str = "No input argument provided. You must provide at least one input argument or include '-' to read from standard input.";
 
+pub const REPEATED_STDIN_ERR_MSG: &str =
+    "The standard input '-' can be given only once among the inputs.";
+
 pub const MISSING_ARG_ERR_MSG: &str = "No argument provided. Please specify the sub-command.";
+
+pub const EMPTY_PLACEHOLDER_ERR_MSG: &str =
+    "Placeholder symbol '$' must be followed by a template key name.";
+
+pub const MISSING_LISTENER_ERR_MSG: &str =
+    "No listener provided. The serve sub-command requires '--socket {path}' or '--http {address}'.";
+
+pub const CORE_IMPORT_COMMAND_ERR_MSG: &str =
+    "Only the script-expression and wallet-init sub-commands produce descriptors to import with '--import-to-core'.";
+
+pub const UR_COMMAND_ERR_MSG: &str =
+    "Only the expressions of the script-expression and key-expression sub-commands and the derived extended public keys of derive-key can be encoded with '--ur'.";
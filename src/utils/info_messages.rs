@@ -1,8 +1,10 @@
+use crate::structs::secret_kind::SecretKind;
+
 pub const HELP_MESSAGE: &str = "\
 BIP 380
 
 Usage:
-    derive-key {value} [--path {path}] [-]
+    derive-key {value} [--path {path}] [--show-intermediate] [--split-at-hardened] [--plan] [-]
 
     The derive-key sub-command takes one required positional argument {value}
     (with one exception, see below), which can be either a seed, or Base58 encoded
@@ -15,26 +17,57 @@ Usage:
     - On input extended public key, outputs the extended public key, i.e., echos
       itself.
 
+    An extended key {value} may be preceded by its key origin, as in a key expression, e.g.
+    [d34db33f/44h/0h/0h]xprv... The {path} is then appended to the origin and both output keys
+    are preceded by the cumulative origin, e.g. [d34db33f/44h/0h/0h/0/1]xpub..., so they can be
+    used in a descriptor right away.
+
     Valid seed {value} is a byte sequence of length between 128 and 512 bits
     represented as case-insensitive hexadecimal values. The space character ' '
     or the tab character (sometimes denoted as '\\t') can be used to separate
     the individual hexadecimal values.
 
-    If a single dash '-' parameter is present, it indicates reading the {value}
-    from the standard input. Reading from the standard input takes precendence over
-    {value} provided as a command-line argument (in that case the {value}
-    argument is ignored). When reading from standard input, each line of the file is
-    processed as a single {value} with all the previous rules on {value} still applicable.
+    Any number of {value} arguments can be given, they are processed in order. A single dash '-'
+    parameter stands for reading the {value}s from the standard input at its position, e.g.
+    'derive-key A - B' processes A, then the lines of the standard input and then B. Each line
+    of the standard input is processed as a single {value} with all the previous rules on {value}
+    still applicable. The '-' can be given only once. A parameter '@path' stands for the whole
+    content of the file at the path (trimmed) as a single {value}, so very long descriptors or
+    seeds don't have to pass through the command line, e.g. 'derive-key @seed.txt'.
 
 
     --path {path}   The {path} value is a sequence of /NUM and /NUMh, where NUM is from the range
-                    [0,...,2^31-1] as described in BIP 32. The path does not need to start with /.
+                    [0,...,2^31-1] as described in BIP 32. The path does not need to start with /,
+                    a full path starting with m/ (e.g. m/44h/0h/0h) is accepted as well.
                     In the hardened version /NUMh the h indentifier can also be substituted with H
                      or ' and these can also be mixed within a single path.
 
+    --show-intermediate  Prints one line per path element instead of the derived key, each with the
+                    path prefix, the extended public key and its fingerprint, starting with the key of
+                    the {value} itself (m), e.g. 'm/0h xpub68Gm... 5c1bd648'. Helps to find the step of
+                    a long path at which two implementations diverge. Cannot be combined with
+                    --template or any --output but plain.
+
+    --split-at-hardened  Derives an extended public key {value} only up to the first hardened step of
+                    the {path} (which requires the private key) instead of failing, and prints the
+                    derivable prefix and the rest of the {path} on an additional line separated by
+                    a space, e.g. 'm/1 2h/3'. Has no effect on private keys, seeds or paths without
+                    hardened steps.
+
+    --plan          Prints only the plan of the derivation instead of the derived key, without deriving
+                    any key: the kind of the {value} (seed, xprv or xpub), the full path of the derived
+                    keys (the cumulative key origin, if the {value} has one) and the keys that would be
+                    printed, e.g. 'seed m/44h/0h/0h xpub:xprv'. The {value} is still validated (the
+                    length of a seed, the checksum of an extended key) and a hardened step to derive
+                    from an xpub still fails, so generated job specs can be checked cheaply. Cannot be
+                    combined with --show-intermediate, --template or any --output but plain.
 
 
-    key-expression {expr} [-]
+
+    key-expression {expr} [--account-descriptors {type}] [--verify-origin {master}]
+                   [--network {network}] [--hardened-wildcard {policy}] [--key-context {context}]
+                   [--canonical-key {form}] [--lenient] [--sortedmulti-order [--index {i}]]
+                   [--evaluate] [--max-origin-depth {n}] [-]
 
     The key-expression parses the {expr} according to the BIP 380 Key Expressions specification
     (https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki#key-expressions). If there 
@@ -42,13 +75,14 @@ Usage:
 
     If a single dash '-' parameter is present, it indicates reading the {expr}
     from the standard input. Similar rules as described for the previous
-    derive-key sub-command apply, such as, the standard input takes precendence and is
-    processed line by line, etc.
+    derive-key sub-command apply, such as, several {expr}s are processed in order and
+    the standard input is processed line by line at the position of the '-', etc.
 
     The key expression consists of the optional key origin information and then the
     actual key. Regarding the key types:
     - The utility will accept any hex encoded public keys that conform to the
-      single-byte prefix (02, 03 or 04) and length (66 or 130) constraints.
+      single-byte prefix (02, 03 or 04) and length (66 or 130) constraints and
+      that represent a valid point on the secp256k1 curve.
     - Wallet Import Format (WIF) encoded private keys parsing and checking, see
       this wiki page - https://en.bitcoin.it/wiki/Wallet_import_format. Only expected WIF encoded
       private keys, are private keys originating as random 32 bytes and encoded using the Private 
@@ -56,10 +90,84 @@ Usage:
       private key routine is expected to be 0x80.
     - Finally, extended public and private keys must be checked using the same BIP 32 library that
       you were using in derive-key already.
+      Testnet extended keys (tpub and tprv) are accepted as well.
+
+    --account-descriptors {type}   If this option is used, {expr} must be an account level extended
+                                   key (optionally with key origin, without a wildcard) and instead of
+                                   echoing it, the receive (.../0/*) and change (.../1/*) descriptors
+                                   with checksums are printed on two lines. The {type} is one of
+                                   pkh, sh-wpkh, wpkh or tr.
+
+    --verify-origin {master}       If this option is used, the key origin fingerprint of {expr} must
+                                   match the fingerprint of {master}, which is either a master xprv,
+                                   a master xpub or an 8 character hex fingerprint. When a master xprv
+                                   is given, the key must also equal the key derived from it along
+                                   the origin path. Keys without a key origin are not checked.
+
+    --network {network}            If this option is used, an extended key in {expr} must belong
+                                   to the {network}, which is either mainnet (xpub/xprv) or testnet
+                                   (tpub/tprv).
+
+    --hardened-wildcard {policy}   Sets what is accepted for a hardened wildcard final step (/*h).
+                                   The {policy} is one of allow (the default, as BIP 380 permits it
+                                   for any extended key), xprv-only (rejects /*h after an extended
+                                   public key, as hardened children cannot be derived from it) or
+                                   forbid (rejects every hardened wildcard).
+
+    --key-context {context}        Validates the key for its position within a descriptor, the {context}
+                                   is one of legacy (the default, any key), p2sh (any key), segwit-v0
+                                   (within wpkh or wsh, only compressed keys) or taproot (within tr,
+                                   only compressed or 64 character x-only public keys).
+
+    --canonical-key {form}         Echoes a hex encoded public key in the canonical {form}, which is
+                                   either lowercase or compressed (lowercase, and a 04 key is
+                                   compressed into its 02 or 03 form). Other keys and the key origin
+                                   are echoed as they are. If the {expr} was not canonical, a note is
+                                   printed to the standard error. Useful for deduplicating keys
+                                   collected from multiple vendors.
+
+    --lenient                      Removes the whitespace surrounding the {expr} and padding the
+                                   brackets of its key origin before the validation, e.g.
+                                   ' [ deadbeef/0h ] 02...' is read as '[deadbeef/0h]02...', as the
+                                   hand-edited files often carry stray spaces. The whitespace within
+                                   the fingerprint, the path or the key is still rejected. Without
+                                   it, the {expr} is validated strictly as it is given.
+
+    --sortedmulti-order            Prints every {expr} after its public key (an extended key derived
+                                   along its path) and, once all of them are read, sorts the lines
+                                   into the order sortedmulti(...) places the keys in (BIP 67), so the
+                                   coordinators can verify the order their wallet uses. Cannot be
+                                   combined with --account-descriptors, --template or any --output
+                                   but plain.
 
+    --index {i}                    The index the ranged keys (ending with /*) are derived at for
+                                   --sortedmulti-order, from 0 to 2^31-1. A ranged key is refused
+                                   without it, as its order differs from one index to another.
 
+    --evaluate                     Performs the derivation steps appended to the extended key of the
+                                   {expr} the same way as derive-key and prints the key expression of
+                                   the derived key instead, the steps appended to its key origin, e.g.
+                                   '[d34db33f/44h]xprv.../0h/1' is printed as '[d34db33f/44h/0h/1]xprv...'.
+                                   The hardened steps require an xprv, a ranged {expr} is refused.
 
-    script-expression {expr} [-]
+    --max-origin-depth {n}         The most derivation steps of the key origin and of the path appended
+                                   to the key (its wildcard included) together, defaults to 255 (the
+                                   depth of BIP 32 is a single byte, so a deeper key can never be
+                                   derived). A larger {n} relaxes the check.
+
+
+
+    script-expression {expr} [--verify-checksum|--compute-checksum [--verify-after]
+                      |--ensure-checksum] [--normalize] [--ignore-checksum-case]
+                      [--disassemble|--first-address [--explorer-url {explorer}]
+                      |--verify-addresses {file}|--check-change {change}|--anonymize
+                      |--find-fingerprint {fingerprint}|--rotate-key {old}={new}
+                      |--set-threshold {k}|--psbt-output {index}]
+                      [--key {name}={KEY}]... [--verify-origin {master}] [--network {network}]
+                      [--policy-file {path}] [--hardened-wildcard {policy}] [--whitespace {policy}]
+                      [--max-multi-keys {n}] [--max-nesting {n}] [--max-script-size {bytes}]
+                      [--max-origin-depth {n}]
+                      [--rewrite [--output-file {path}]|--report] [-]
 
     The script-expression sub-command implements parsing of some of the script
     expressions and optionally also checksum verification and calculation. The
@@ -71,16 +179,29 @@ Usage:
 
       pk(KEY)
       pkh(KEY)
+      wpkh(KEY)
       multi(k, KEY_1, KEY_2, ..., KEY_n)
-      sh(pk(KEY))
-      sh(pkh(KEY))
-      sh(multi(k, KEY_1, KEY_2, ..., KEY_n))
+      sortedmulti(k, KEY_1, KEY_2, ..., KEY_n)
+      sh(pk(KEY)), sh(pkh(KEY)), sh(multi(...)), sh(sortedmulti(...))
+      sh(wpkh(KEY))
+      wsh(pk(KEY)), wsh(pkh(KEY)), wsh(multi(...)), wsh(sortedmulti(...))
+      sh(wsh(...))
+      tr(KEY)
       raw(HEX)
 
+    The keys of wpkh(...) and wsh(...) must be compressed (BIP 143), the key of tr(...) may also
+    be an x-only public key. Only the key path of tr(...) is supported, without a script tree.
+
+    The timelock fragments of miniscript, the absolute timelock after(n) (a block height below
+    500000000, a UNIX timestamp otherwise) and the relative timelock older(n) (BIP 68), both from
+    the range [1,...,2^31-1], hold no key and would be spendable by anyone on their own. They are
+    refused at the top level, as the redeem script of sh(...) and as the witness script of wsh(...)
+    until the fragments combining them with the keys are supported.
+
     If a single dash '-' parameter is present, it indicates reading the {expr}
     from the standard input. Similar rules as described for the previous
-    derive-key sub-command apply, such as, the standard input takes precendence
-    and is processed line by line, etc.
+    derive-key sub-command apply, such as, several {expr}s are processed in order
+    and the standard input is processed line by line at the position of the '-', etc.
 
     --verify-checksum   If this option is used, then the checksum is 
                         expected and is verified by recalculating the checksum over 
@@ -90,9 +211,762 @@ Usage:
     --compute-checksum  If this option is used, then the #CHECKSUM, if provided, is 
                         ignored and new CHECKSUM is computed. The output is then the
                         original script and the checksum in the form SCRIPT#CHECKSUM.
+                        Everything after the first # is replaced, e.g. 'raw(00)#abc#def'
+                        becomes 'raw(00)#CHECKSUM', while without --compute-checksum more
+                        than one # is an InvalidChecksum error.
+
+    --ignore-checksum-case  Lowercases the #CHECKSUM before it is verified, accepting the
+                        uppercase checksums some tools emit (e.g. 'raw(deadbeef)#89F8SPXM').
+                        The lowercased checksum is printed, with a note on the standard error
+                        naming the given one. By default, the checksum must be lowercase.
+                        Cannot be combined with --compute-checksum.
+
+    --verify-after      If this option is used with --compute-checksum, the computed SCRIPT#CHECKSUM
+                        is verified again the same way as with --verify-checksum before it is
+                        output, failing loudly if it does not pass, as a self-check for
+                        safety-critical pipelines.
+
+    --ensure-checksum   If this option is used, the missing #CHECKSUM is computed and appended,
+                        while a provided one is verified (failing on a mismatch) and kept. The
+                        output is SCRIPT#CHECKSUM either way, so the operation is idempotent, e.g.
+                        to normalize the descriptors of configuration repositories (see --rewrite).
+
+    Note that mixing --verify-checksum, --compute-checksum and --ensure-checksum options leads
+    to an error.
+
+    --normalize         If this option is used, the hex encoded public keys and the raw(HEX) payload
+                        are lowercased before the checksum is computed or verified, and the
+                        normalized SCRIPT is output. Without it, the SCRIPT is kept as it is.
+
+    --disassemble       If this option is used, the SCRIPT is compiled into the Bitcoin Script
+                        it describes (raw(HEX) scripts are taken as they are) and printed as
+                        human-readable opcodes instead of the usual output, e.g.
+                        OP_DUP OP_HASH160 {hash} OP_EQUALVERIFY OP_CHECKSIG. Ranged key
+                        expressions (ending with a wildcard) cannot be disassembled.
+
+    --first-address     If this option is used, the SCRIPT is compiled with ranged key expressions
+                        derived at index 0 and its (mainnet) address is printed instead of the
+                        usual output. Only pkh(KEY) (1...) and sh(...) (3...) scripts have an
+                        address. Note that mixing --disassemble and --first-address leads to an error.
+
+    --explorer-url {explorer}  Prints the URL of the --first-address on the block {explorer} after
+                        it, separated by a space, to check the address by hand. The {explorer} is
+                        'mempool' (mempool.space), 'blockstream' (blockstream.info) or a custom URL
+                        template with the {address} placeholder, e.g.
+                        https://explorer.example/address/{address}. The testnet addresses point to
+                        the testnet pages of mempool and blockstream.
+
+    --verify-addresses {file}  Verifies that the addresses of the {file}, one address:index pair per
+                        line (e.g. 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH:0), are the addresses the SCRIPT
+                        derives at those indexes, e.g. to audit that a custodian's address list
+                        really derives from the stated descriptor. The empty lines are skipped.
+                        All the mismatches are reported together with their lines, failing the
+                        input with the AddressMismatch error kind. It cannot be combined with
+                        --disassemble or --first-address.
+
+    --check-change {change}  Verifies that the {change} descriptor is the change descriptor of the
+                        SCRIPT (the receive descriptor): both must differ only in the branch of their
+                        ranged keys, .../0/* of the SCRIPT against .../1/* of the {change}, with the
+                        same script functions, thresholds and keys otherwise, catching a change
+                        descriptor of another wallet. All the differences are reported together,
+                        failing the input with the ChangeMismatch error kind. It cannot be combined
+                        with --disassemble, --first-address, --verify-addresses or --anonymize.
+
+    --anonymize         Prints the SCRIPT with its keys replaced with dummy keys and its checksum
+                        recomputed, so that a failing input can be shared publicly (e.g. in a bug
+                        report) without revealing the wallet. The n-th distinct key is replaced with
+                        the key of the private key n in the same form (a compressed, uncompressed
+                        or x-only key, a WIF key or an extended key of the same depth and child
+                        number) and the n-th distinct fingerprint of the key origins with n (e.g.
+                        00000001). The structure, the origin and derivation paths, the wildcards
+                        and the raw() payloads are kept. It cannot be combined with --disassemble,
+                        --first-address, --verify-addresses or --check-change.
+
+    --find-fingerprint {fingerprint}  Prints the SCRIPT followed by the positions of its keys (from 1)
+                        whose key origin has the {fingerprint} (8 hex characters), and of the master
+                        extended keys of the {fingerprint} given without a key origin, e.g.
+                        'SCRIPT: keys 2, 3', or 'SCRIPT: not found'. Feeding many descriptors through
+                        the standard input shows which of them contain a key of a signer, e.g. a
+                        compromised one. It cannot be combined with the other output flags above.
+
+    --rotate-key {old}={new}  Prints the SCRIPT with every occurrence of the {old} key expression
+                        replaced with the {new} one and its checksum recomputed, e.g. to rotate the
+                        key of a compromised signer. A key expression is replaced if it is the {old}
+                        one, or the {old} one followed by a derivation path (e.g. /0/*), which is
+                        kept. Everything else is kept untouched. The rotated SCRIPT is validated
+                        again (the context rules of its keys, the limits, the network, the hardened
+                        wildcards and the --policy-file), failing with the kind of the error. It
+                        fails if the {old} key expression does not appear. It cannot be combined with
+                        the other output flags above.
+
+    --set-threshold {k}  Prints the SCRIPT with the threshold of its multi(k, ...) (or
+                        sortedmulti(k, ...)) set to {k} and its checksum recomputed, keeping the keys
+                        untouched. The {k} must be from 1 to the number of the keys. It cannot be
+                        combined with the other output flags above.
+
+    --psbt-output {index}  Prints the PSBT output metadata (BIP 174) of the SCRIPT as JSON, with its
+                        ranged keys derived at {index}: the redeem_script of a sh(...) SCRIPT, the
+                        witness_script of a wsh(...) SCRIPT (nested in sh(...) or not), the
+                        bip32_derivs of its keys (the pubkey, the master_fingerprint and the path of
+                        the key origin followed by the path appended to the key, a key without its
+                        key origin being its own master) and the hex of the serialized output map,
+                        which a transaction builder needs to fill in for a change output. The
+                        {index} must be from 0 to 2^31-1. A tr(...) SCRIPT is refused, as its keys
+                        belong to the taproot fields (BIP 371). It cannot be combined with the other
+                        output flags above.
+
+    --key {name}={KEY}  Defines the key expression {KEY} for the $name placeholder, the flag can be
+                        repeated. Every $name placeholder in the {expr} template is substituted with
+                        its key expression and the output is the substituted script together with
+                        its newly computed checksum (a checksum provided with the template is
+                        ignored), e.g. sh(multi(2,$alice,$bob)) --key alice=KEY_1 --key bob=KEY_2.
+                        Each placeholder must have a key and each key must be used. Cannot be
+                        combined with --verify-checksum.
+
+    --verify-origin {master}  Verifies the key origins of all keys in the SCRIPT against {master},
+                        the same way as described for the key-expression sub-command.
+
+    --policy-file {path}  Checks the keys of the SCRIPT against the policy of the file, e.g. that
+                        the vendor keys of a 2-of-3 wallet are present. Every line is a rule
+                        'require {key}' (the key must appear) or 'forbid {key}' (it must not),
+                        where the {key} is an 8 character hex fingerprint, matching the
+                        fingerprints of the key origins, or an xpub, matching the extended keys
+                        (an xprv by its xpub). Empty lines and lines starting with '#' are
+                        skipped. A violated policy fails with the PolicyViolation error kind,
+                        listing the violated rules.
+
+    --network {network}  All extended keys in the SCRIPT must belong to the {network} (mainnet
+                        or testnet). Even without this option, the extended keys of a single
+                        SCRIPT must not mix networks (e.g. an xpub next to a tpub). The network
+                        also determines the address format of --first-address and
+                        --verify-addresses.
+
+    --hardened-wildcard {policy}  Applies the hardened wildcard {policy} to all keys in the SCRIPT,
+                        the same way as described for the key-expression sub-command.
+
+    --whitespace {policy}  Sets how whitespace other than spaces (e.g. tabs) in the SCRIPT is
+                        treated. The {policy} is one of preserve (the default, it is kept as it is,
+                        so it is part of the checksum and fails the parsing in some positions),
+                        strict (it is rejected anywhere) or normalize (it is replaced with spaces
+                        before the SCRIPT is parsed and its checksum computed or verified).
+
+    --max-multi-keys {n}  The most keys a multi(k, ...) script may have, defaults to 20 (the limit
+                        of OP_CHECKMULTISIG).
+
+    --max-nesting {n}   The deepest nesting of the parentheses in the SCRIPT, defaults to 8, e.g.
+                        sh(multi(...)) is nested 2 levels deep.
+
+    --max-script-size {bytes}  The largest size of the compiled SCRIPT (with ranged keys derived at
+                        index 0), defaults to 10000 (the consensus limit of Bitcoin). The size of a
+                        SCRIPT with keys that cannot be derived is not checked.
+
+    --max-origin-depth {n}  The most derivation steps of the key origin and of the path appended to
+                        the key of every key expression together, defaults to 255 (the depth limit
+                        of BIP 32). All the limits can be adjusted for chains with other policies,
+                        e.g. Liquid/Elements.
+
+    --rewrite           The {expr}s are the paths of files with one script expression per line
+                        instead (e.g. legacy wallet dumps). The checksum of every script expression
+                        is computed (replacing any previous one) and the file is rewritten in place
+                        with the checksums appended, keeping the empty lines. Nothing is written
+                        unless all the script expressions are valid, the error names the line at
+                        fault. A summary of the rewritten file is printed. With --ensure-checksum,
+                        only the missing checksums are appended and the present ones are verified
+                        instead. Cannot be combined with --verify-checksum, --disassemble,
+                        --first-address, --verify-addresses, --check-change, --anonymize,
+                        --find-fingerprint, --rotate-key, --set-threshold, --key, --template or
+                        --output json, csv or ur.
+
+    --output-file {path}  Writes the rewritten script expressions to {path} instead of rewriting the
+                        file in place, only with a single file.
+
+    --report            The {expr}s are the paths of files with one script expression per line
+                        instead (e.g. wallet backups), skipping the empty lines and the comments
+                        starting with '#'. Every script expression is validated (its checksum
+                        verified, if present) and a single report of the file is printed: the
+                        number of the valid and invalid descriptors, the valid ones counted by
+                        their top-level function (e.g. 'wpkh: 2') and the errors of the invalid
+                        ones with their line numbers. Cannot be combined with --rewrite,
+                        --compute-checksum, --verify-checksum, --ensure-checksum, the other
+                        outputs, --key, --template or --output json, csv or ur.
+
+
+
+    wallet-init {value} [--purpose {purpose}] [--account {account}] [--network {network}]
+                [--language {language}]
+                [--passphrase-env {variable} | --passphrase-file {path} | --passphrase-prompt] [-]
+
+    The wallet-init sub-command bootstraps a single-key wallet account in one run. The {value}
+    is either a BIP 39 mnemonic phrase (passed as a single, quoted argument, used with an
+    empty passphrase unless one is given) or a seed in the same hexadecimal format as accepted by derive-key.
+
+    The account key is derived at m/{purpose}h/{coin_type}h/{account}h and the output consists
+    of the following labeled lines: the master key fingerprint, the account extended private and
+    public key and the receive (.../0/*) and change (.../1/*) descriptors with the key origin and
+    checksums, ready to be imported into a watch-only wallet.
+
+    The standard input '-' parameter behaves the same as with the other sub-commands.
+
+    --purpose {purpose}   One of 44 (pkh), 49 (sh(wpkh)), 84 (wpkh) or 86 (tr), defaults to 84.
+
+    --account {account}   The account number from the range [0,...,2^31-1], defaults to 0.
+
+    --network {network}   Either mainnet (xprv/xpub, coin type 0) or testnet (tprv/tpub, coin
+                          type 1), defaults to mainnet.
+
+    --language {language} The wordlist of the mnemonic, one of english, japanese, korean, spanish,
+                          chinese-simplified, chinese-traditional, french, italian, czech or
+                          portuguese. Detected from the words of the mnemonic if not given.
+
+    --passphrase-env {variable}  Reads the BIP 39 passphrase of the mnemonic from the environment
+                          {variable}.
+
+    --passphrase-file {path}  Reads the BIP 39 passphrase from the first line of the file at {path}.
+
+    --passphrase-prompt   Prompts for the BIP 39 passphrase on the terminal, without echoing it.
+
+    The passphrase is never accepted as an argument, as the arguments leak through the shell history
+    and the process list. It cannot be used with a hexadecimal seed.
+
+
+
+    convert-key (--decode | --encode) {value} [-]
+
+    The convert-key sub-command decomposes an extended key (xpub, xprv, tpub or tprv) into its
+    BIP 32 fields, or reassembles it from them. No derivation is performed.
+
+    --decode              Prints every field of the extended key {value} in hexadecimal, one
+                          labeled line each: version (4 bytes), depth (1 byte), parent_fingerprint
+                          (4 bytes), child_number (4 bytes, hardened ones have the top bit set),
+                          chain_code (32 bytes) and key (33 bytes, a private key is prefixed with 00).
+
+    --encode              Reassembles the extended key from the {value} consisting of all the six
+                          fields as whitespace separated name=hex pairs (in any order), e.g. the
+                          --decode output with ': ' replaced by '='. The key is validated
+                          (a known version, a valid key and consistent depth and parent fingerprint
+                          and child number) and printed Base58 encoded.
+
+    The fields of a decoded extended private key contain the private key, so --no-private refuses
+    the decoding of an xprv or tprv.
+
+
+
+    path {path} [--join {suffix} | --relative-to {base}] [--notation {notation}] [-]
+
+    The path sub-command validates the derivation {path}, given the same way as the --path of
+    derive-key (with or without the leading m/, the hardened indexes marked with any of h, H or ').
+    The {path} is printed as an absolute path (e.g. m/84h/0h/0h) in the chosen notation.
+
+    --join {suffix}       Appends the relative {suffix} path to the {path}, e.g. m/84h/0h joined
+                          with 0/5 prints m/84h/0h/0/5.
+
+    --relative-to {base}  Prints the {path} relative to the absolute {base} path it must descend
+                          from, without the leading m/, e.g. m/84h/0h/0/5 relative to m/84h/0h
+                          prints 0/5 (and the {base} path itself an empty line).
+
+    --notation {notation} The marker of the hardened indexes in the output, one of h (the default),
+                          H or '.
+
+
+
+    mnemonic (--to-seedqr {format} | --from-seedqr | --from-entropy | --to-entropy) {value}
+             [--language {language}] [-]
+
+    The mnemonic sub-command converts between a 12 or 24 word BIP 39 mnemonic and its
+    SeedQR, the encoding of the paper backups of SeedSigner and other airgapped signers, or
+    between a mnemonic and its raw entropy, so the backups can be cross-checked between formats.
+
+    --to-seedqr {format}  Encodes the mnemonic {value} as a SeedQR of the {format}, one of:
+                          - standard: the 4-digit wordlist index of every word, concatenated
+                            (48 or 96 digits),
+                          - compact: the entropy of the mnemonic (16 or 32 bytes) in hexadecimal.
+
+    --from-seedqr         Decodes the standard or compact SeedQR {value} (told apart by its length)
+                          and prints the mnemonic.
+
+    --from-entropy        Encodes the hexadecimal entropy {value} of 128 to 256 bits (32, 40, 48,
+                          56 or 64 hex digits) as the mnemonic of 12 to 24 words.
+
+    --to-entropy          Decodes the mnemonic {value} of any valid word count back into its
+                          entropy in hexadecimal.
+
+    --language {language} The wordlist of the mnemonic, the same as with wallet-init. Detected from
+                          the words of the mnemonic to encode if not given, the decoded (or
+                          encoded) mnemonic is printed in English by default.
+
+    The mnemonic is a secret, passing it through the standard input ('-') keeps it out of the shell
+    history.
+
+    generate-seed (--dice | --coins) {value} [--words {words}] [-]
+
+    The generate-seed sub-command generates a BIP 39 mnemonic from physical entropy, the dice rolls
+    or coin flips of the {value} (whitespace is ignored). The mnemonic is printed only once the
+    {value} supplies enough entropy for it, estimated conservatively from how evenly the rolls or
+    flips are distributed (e.g. the same roll over and over supplies none).
+
+    --dice                The {value} consists of the rolls of a six-sided die, 1 to 6, which are
+                          hashed with SHA-256 the same way as by Coldcard. At least 50 rolls are
+                          needed for 12 words and 100 rolls for 24 words, a few more unless the
+                          rolls are perfectly even.
+
+    --coins               The {value} consists of coin flips, 0 or T and 1 or H. The flips are
+                          debiased by the von Neumann method (of every pair of flips, HT is 1, TH is 0
+                          and HH and TT are discarded), so about 4 flips are needed per bit.
+
+    --words {words}       The number of words of the mnemonic, 12 or 24 (the default).
+
+    slip39 (--split | --combine) {value} [--group {threshold}of{count} ...] [--group-threshold {n}]
+           [--iteration-exponent {e}]
+           [--passphrase-env {variable} | --passphrase-file {path} | --passphrase-prompt] [-]
+
+    The slip39 sub-command splits a master secret into SLIP-39 (Shamir's secret sharing) mnemonic
+    shares, or combines the shares back into the master secret.
+
+    --split               The {value} is the master secret in hexadecimal, 16 to 64 bytes (an even
+                          number of them), e.g. a seed accepted by derive-key. The mnemonics of all
+                          the shares are printed, one per line, group by group.
+
+    --combine             The {value} are the mnemonics of the shares separated by commas. The
+                          recovered master secret is printed in hexadecimal, so it can be passed to
+                          derive-key as the seed right away, e.g.
+                          bip380 slip39 --combine - | bip380 derive-key - --path 84h/0h/0h
+
+    --group {threshold}of{count}  A group of {count} shares, {threshold} of which recover the share
+                          of the group, e.g. 2of3. Repeated for every group, up to 16 groups of up
+                          to 16 shares. Required by --split.
+
+    --group-threshold {n} How many of the groups are needed to recover the secret, defaults to 1.
+
+    --iteration-exponent {e}  The master secret is encrypted with 10000 * 2^{e} PBKDF2 iterations,
+                          {e} is from 0 to 15, defaults to 1. Recorded in the shares.
+
+    --passphrase-env {variable}, --passphrase-file {path}, --passphrase-prompt
+                          Read the passphrase the master secret is encrypted with, the same way as
+                          wallet-init does. Only printable ASCII characters are allowed. A wrong
+                          passphrase cannot be detected, it recovers a different master secret.
 
-    Note that mixing --verify-checksum and --compute-checksum options leads to an error.
+    seed-xor (--split {n} | --combine) {value} [--hex] [--language {language}] [-]
+
+    The seed-xor sub-command splits a BIP 39 seed into SeedXOR shares, or combines the shares back
+    into the seed. Every share is a valid mnemonic of the same word count on its own, and all of
+    them are needed to recover the seed, which is the XOR of their entropy (so it can be recovered
+    with pen and paper as well).
+
+    --split {n}           The {value} is the mnemonic (or its entropy in hexadecimal, 128 to 256
+                          bits). The mnemonics of {n} random shares, 2 to 16, are printed one per line.
+
+    --combine             The {value} are the shares (mnemonics or their entropy in hexadecimal)
+                          separated by commas. The mnemonic of the seed is printed.
+
+    --hex                 Prints the entropy in hexadecimal instead of the mnemonics.
+
+    --language {language} The wordlist of the mnemonics, the same as with wallet-init. Detected from
+                          the words of the mnemonics if not given, the shares and the seed are
+                          printed in the language of the input mnemonic (or English).
+
+    coldcard-export {descriptor} [--name {name}] [-]
+
+    The coldcard-export sub-command renders a multisig {descriptor} into the Coldcard multisig
+    setup file (the Name, Policy, Format, Derivation and {fingerprint}: {xpub} lines), ready to be
+    imported into Coldcard. The {descriptor} is one of sh(sortedmulti(...)) (P2SH),
+    wsh(sortedmulti(...)) (P2WSH) or sh(wsh(sortedmulti(...))) (P2SH-P2WSH), optionally with its
+    checksum. Coldcard always sorts the keys, so multi(...) descriptors are rejected.
+
+    Every key must be an extended public key with its key origin, optionally followed by /0/*,
+    /1/*, /<0;1>/* or /** (so the descriptor line of a BSMS record is accepted as well). At most
+    15 cosigners are supported.
+
+    --name {name}         The name of the wallet shown by Coldcard, 1 to 20 printable ASCII
+                          characters, defaults to Multisig.
+
+
+
+    wallet-export {descriptor} --target {target} [--label {label}] [--blockheight {height}] [-]
+
+    The wallet-export sub-command produces the wallet file of the multisig {descriptor} (accepted
+    the same way as by coldcard-export) for the {target} wallet software, as a single line JSON
+    object with the label, the block height to scan from and the descriptor with its checksum:
+    - specter: Specter Desktop, with the receive (/0/*) descriptor and the devices of the cosigners
+      (labeled by their fingerprints),
+    - sparrow: Sparrow, with the receive and change (/<0;1>/*) descriptor. The multipath step of
+      BIP 389 is not parsed by script-expression, unlike the descriptor of specter.
+
+    --label {label}       The name of the wallet, defaults to Multisig.
+
+    --blockheight {height}  The height of the block the wallet is scanned from, defaults to 0
+                          (the whole chain).
+
+
+
+    process {value} [derive-key flags] [script-expression flags] [-]
+
+    The process sub-command routes every {value} to the sub-command of its kind, so that files
+    of mixed content (e.g. audit files) can be checked in one run:
+    - a hexadecimal seed or an xpub/xprv (with or without its key origin) is derived by derive-key,
+    - a script expression (anything with a parenthesis) is validated by script-expression.
+    Any other {value} is an error. The flags of both sub-commands are accepted and apply to the
+    inputs routed to them, e.g. 'process - --path 0h/1 --verify-checksum'. With '--template' or
+    '--output json', the fields of the sub-command are preceded by the kind of the input (seed,
+    extended-key or script-expression).
+
+
+
+    address {expr} --type {type} [--network {network}] [--index {index}]
+            [--merkle-root {hex}] [--explorer-url {explorer}] [-]
+
+    The address sub-command encodes a single-key {expr} as the address of the {type}, for
+    users who don't want to construct a full descriptor first:
+    - p2pkh: legacy pay-to-pubkey-hash (1...),
+    - p2wpkh: native segwit (bc1q...), the key must be compressed,
+    - p2sh-p2wpkh: nested segwit (3...), the key must be compressed,
+    - p2tr: taproot (bc1p...) with no script tree (BIP 86) unless --merkle-root is given, the
+      key must be compressed or x-only.
+    An extended key is derived along its path, the wildcard of a ranged {expr} is replaced with
+    the {index}. The {network} (mainnet or testnet) defaults to mainnet.
+
+    --merkle-root {hex}  The merkle root (32 bytes in hexadecimal) of the script tree the p2tr
+                        output key commits to (BIP 341). Without it, the output key commits to
+                        the key alone. It can only be used with '--type p2tr'.
+
+    --explorer-url {explorer}  Prints the URL of the address on the block {explorer} after it, the
+                        same as with script-expression --first-address.
+
+
+
+    lint {expr} [--deny {warning}] [--check-bip67] [-]
+
+    The lint sub-command validates the script expression {expr} (and its checksum, if present) and
+    prints its non-fatal warnings one per line, e.g. 'W003 (multi-all-keys): ...', or 'No warnings'.
+    Every warning has a stable code:
+    - W001 uncompressed-key: an uncompressed key, which makes the scripts larger and cannot be
+      used in segwit outputs,
+    - W002 missing-origin: an extended key below the master key without its key origin,
+    - W003 multi-all-keys: a k-of-n multi or sortedmulti script with k = n (1-of-1 included),
+      losing any key locks the funds,
+    - W004 unhardened-account: a key origin whose first three steps (the purpose, coin type and
+      account) are not all hardened,
+    - W005 deprecated-combo: the deprecated combo({key}), whose key is linted on its own,
+    - W006 unsorted-multi: the keys of a multi script not in the lexicographic order of their
+      public keys (BIP 67), or ranged keys whose order changes between the indexes. Reported only
+      with --check-bip67.
+
+    --deny {warning}     Turns the {warning} (its code or name, or 'all' for every warning) into
+                        an error of the DeniedWarning kind listing the denied warnings. Can be
+                        given multiple times.
+
+    --check-bip67        Checks the keys of every multi script for the order of BIP 67, which several
+                        legacy coordinators require even outside sortedmulti. A violation is the
+                        warning W006, turned into an error by '--deny W006'.
+
+
+
+    bench {seed|xprv|xpub} [--count {n}] [--path {path}] [-]
+
+    The bench sub-command derives the keys {path}/0 to {path}/{n-1} from the input the same way
+    as derive-key (parsing the input for every key) and prints the throughput in keys per second
+    and the number of the allocations, so that the performance of the releases can be compared,
+    e.g. 'Derived 1000 keys along m/0h/* in 0.512 s: 1953 keys/s, 41000 allocations (41.0 per
+    key)'. The results depend on the machine, compare them only on the same one. Only available
+    when the utility is built with the 'bench' feature (not enabled by default), which counts the
+    allocations of the whole process, including the other requests a running serve handles.
+
+    --count {n}          The number of the keys to derive, from 1 to 2^31 (1000 by default).
+    --path {path}        The path the keys are derived below (m by default).
+
+
+
+    demo {vector} [--purpose {purpose}] [--network {network}] [-]
+
+    The demo sub-command generates a complete worked example from the seed of the BIP 32 test
+    {vector} (from 1 to 4), so that the training material and tutorials can be reproduced by the
+    utility itself. The output consists of the labeled lines of the test vector and its seed, the
+    master extended private and public key, the account of wallet-init (the fingerprint, the
+    account keys and the receive and change descriptors) and the first receive address. The
+    seeds are public, never use the keys of the examples for real funds.
+
+    --purpose {purpose}   The same as with wallet-init, defaults to 84.
+
+    --network {network}   The same as with wallet-init, defaults to mainnet.
+
+
+
+    compose {keys} --script {script} [--threshold {k}] [-]
+
+    The compose sub-command assembles the descriptor of the {script} from the key expressions
+    {keys} (separated by commas) and prints it with its checksum, e.g. 'compose KEY_1,KEY_2
+    --script wsh-sortedmulti --threshold 2' prints wsh(sortedmulti(2,KEY_1,KEY_2))#checksum. Every
+    key must be valid in the context of the {script}, e.g. uncompressed keys are rejected in the
+    witness scripts. The keys of a sortedmulti are sorted, so the same keys always compose the
+    same descriptor, while the keys of a multi are kept in the given order.
+
+    --script {script}   One of pkh, sh-wpkh, wpkh and tr (a single key), or sh-multi, wsh-multi,
+                        sh-wsh-multi, sh-sortedmulti, wsh-sortedmulti and sh-wsh-sortedmulti
+                        (from 1 to 15 keys under sh, to 20 keys otherwise).
+
+    --threshold {k}     The number of the signatures of a multisig {script} (required by them),
+                        from 1 to the number of the keys.
+
+
+
+    hwi-import {json} [--keys] [--network {network}] [-]
+
+    The hwi-import sub-command converts the JSON output of HWI (the Hardware Wallet Interface)
+    into the descriptors of this utility, validating every key for its script and the checksums.
+    The {json} is either the output of 'hwi getdescriptors' (the object of the receive and
+    internal descriptors) or of 'hwi getkeypool' (the array of the entries with their desc and
+    internal members), e.g. '@descriptors.json' or a single line read from the standard input.
+    Every descriptor is printed on its own line labeled receive or change, the same as with
+    wallet-init, and a descriptor without a checksum gets it computed. Only the single key pkh,
+    sh(wpkh), wpkh and tr descriptors are supported. The error reported by HWI is printed as the
+    error of the input.
+
+    --keys               Prints the key expressions of the descriptors instead of them.
+
+    --network {network}  All extended keys must belong to the {network} (mainnet or testnet),
+                        otherwise to the network of the first one.
+
+
+
+    analyze {expr} [-]
+
+    The analyze sub-command validates the script expressions {expr} (and their checksums, if
+    present) the same way as script-expression and prints a single report of all of them once
+    the inputs end, e.g. of a corpus of wallet descriptors read from the standard input: the
+    number of the descriptors, how many descriptors use every script type (e.g. sh(multi)), how
+    many descriptors every key origin fingerprint and every extended public key appears in (the
+    most frequent first) and the largest multi(k, ...) script, e.g. 'Largest multisig: 2 of 3
+    keys'. The error of every invalid descriptor is printed to the standard error with its
+    position, e.g. 'Input #2: ...', and the sub-command fails if there is any. Cannot be combined
+    with --template or any --output but plain.
+
+
+
+    serve --socket {path} | --http {address}
+
+    The serve sub-command keeps the utility resident and answers requests over a Unix socket
+    created at {path} (which must not exist yet), so that services calling it often do not pay
+    the process startup costs. The server runs until it is killed. On SIGINT or SIGTERM, it stops
+    accepting the connections, removes the socket and exits with 128 plus the signal number.
+
+    Each request is a single line holding the arguments of another sub-command, e.g.
+    derive-key 000102030405060708090a0b0c0d0e0f --path 0h/1. Arguments are separated by
+    whitespace and an argument containing whitespace (such as a mnemonic) can be enclosed in
+    double quotes. Reading from the standard input '-' or from the server's files with an '@path'
+    input is not supported, nor are the flags using the server's files, environment, terminal or
+    Bitcoin Core (e.g. --input-file, --rewrite, --passphrase-env, --import-to-core), --watch and --help.
+
+    The response holds the output of every input on its own line, or a single line
+    error: {message} if the request fails, and is always terminated by an empty line.
+
+    A request is at most 1 MiB long (the HTTP headers at most 8 KiB), at most 64 connections are
+    handled at once and a connection is closed after 30 seconds without a request.
+
+    --http {address}    Serves an HTTP JSON API on the {address} (e.g. 127.0.0.1:8380) instead
+                        of the Unix socket. Only available when the utility is built with the
+                        'http' feature. The endpoints POST /derive-key, POST /key-expression and
+                        POST /script-expression take a JSON object with the input and optionally
+                        the flags of the sub-command, e.g.
+                        {\"input\": \"000102030405060708090a0b0c0d0e0f\", \"options\": [\"--path\", \"0h/1\"]},
+                        and respond with {\"output\": \"...\"}, or {\"error\": \"...\"} and the status
+                        400 if the request fails.
+
+
+
+    --rpc
+
+    The --rpc mode (used instead of a sub-command) reads JSON requests from the standard input,
+    one per line, and answers each with a single JSON line on the standard output, letting one
+    process multiplex heterogeneous requests. A request has the following form:
+
+      {\"id\": 1, \"cmd\": \"script-expression\", \"input\": \"raw(deadbeef)\", \"flags\": {...}}
+
+    The {cmd} is one of the sub-commands above (except serve) and the optional {flags} object
+    holds its flags without the leading dashes: true for a flag without a value (false and null
+    are left out), a string or a number for a flag with a value and an array for a repeated flag,
+    e.g. {\"compute-checksum\": true, \"key\": [\"a=02aa\", \"b=03bb\"]}. The response carries the
+    {id} of its request (any JSON value, null if missing), so the responses can be correlated
+    with the requests: {\"id\": 1, \"output\": \"...\"}, or {\"id\": 1, \"error\": \"...\"} if the
+    request fails. A failed request does not stop the processing of the following ones.
+
+
+Shared options:
+    --watch {source}  Keeps following the {source} indefinitely instead of the inputs described
+                      above, where {source} is either '-' for the standard input or a path to a file
+                      (read from its beginning and then tailed as it grows). Each line is processed
+                      as soon as it arrives and its output is flushed right away, so the utility can
+                      sit behind a FIFO feeding a long-running service. An invalid line is reported
+                      on the standard error and the processing continues.
+
+    --args-file {path}  Replaces itself with the arguments read from the file at {path}, one
+                      argument per line (a line is taken as a whole, without quoting), e.g. when
+                      passing more --key substitutions than the command line allows. It can be
+                      given several times, but the file cannot name another --args-file. Unlike
+                      an '@path' input, the file holds any arguments, not a single input.
+
+    --input-file {path} Reads the inputs line by line (like with '-') from the file at {path}
+                      instead of the positional arguments, which must be left out. It can be
+                      given several times, the files are read one after another, e.g.
+                      script-expression --input-file file1.txt --input-file file2.txt. A '-'
+                      {path} stands for the standard input. All the files must exist. The files
+                      ending with .gz or .zst are decompressed while they are read (built in with
+                      the 'gzip' and 'zstd' features), so compressed dumps don't have to be
+                      unpacked first.
+
+    --skip {count}    Skips the first {count} inputs (e.g. to resume a batch after a failure).
+
+    --limit {count}   Processes at most {count} inputs (after the skipped ones), so huge input
+                      files can be processed in windows, e.g. --skip 1000 --limit 1000.
+
+    --sort            Buffers the results and emits them lexicographically sorted (each result as
+                      a whole) once all inputs are processed, e.g. when preparing sortedmulti key
+                      sets or diffing runs. As nothing is emitted before the inputs end, --sort
+                      cannot be combined with the streaming --watch mode.
+
+    --check           Suppresses the results on the standard output, so only the errors on the
+                      standard error and the exit code tell the outcome, e.g. as a lint step
+                      validating descriptor files in CI pipelines. Unlike usual, the processing
+                      does not stop at the first invalid input, all of them are reported.
+
+    --any             Inverts the outcome of the batch for searches, e.g. verifying the checksums
+                      of many candidates: the processing stops at the first input that succeeds,
+                      exiting with 0, and exits with 1 only if all of them fail. The errors of the
+                      failed inputs are reported on a single line each. It cannot be combined with
+                      --sort.
+
+    --stats           Prints a summary of the batch to the standard error once the processing
+                      stops: the number of processed, succeeded and failed inputs, the elapsed
+                      time and the throughput. The never ending --watch mode prints it only when
+                      a signal stops it.
+
+    --timing          Measures the processing time of every input, to find the pathological ones in
+                      large corpora. With '--output json', the time in microseconds is appended to
+                      the JSON object of every result and error as \"elapsed_us\", otherwise a line
+                      such as 'Input #3 took 1.203ms' is printed to the standard error for every input.
+                      The --stats summary is followed by the positions of the five slowest inputs
+                      (counting the skipped ones), e.g. 'Slowest inputs: #3 (1.203ms), #1 (0.412ms)'.
+                      The inputs themselves are never printed, as they may hold secrets.
+
+    SIGINT and SIGTERM stop a batch (or --watch) gracefully after the input being processed: the
+    results buffered by --sort and the --stats summary are still printed, and the exit code is 128
+    plus the signal number (130 for SIGINT, 143 for SIGTERM), telling the partial run apart from a
+    failure. A second signal stops the utility right away. Builds without the 'signals' feature
+    leave the signals to their default handling.
+
+    --template {template}  Formats every result according to the {template} instead of the usual
+                      output, replacing each {name} with the named field of the result, e.g.
+                      --template '{xpub},{fingerprint}'. Literal braces are written as {{ and }}.
+                      The available fields are:
+                      - derive-key: xpub, xprv (empty for an xpub input), fingerprint,
+                        parent_fingerprint and depth (of the derived key) and origin (the
+                        cumulative key origin, empty for an input without one),
+                      - key-expression: expression, fingerprint and origin_path (of the key
+                        origin, empty without one), key, and receive and change (with
+                        --account-descriptors),
+                      - script-expression: script, checksum (empty without one) and output,
+                      - wallet-init: fingerprint, xprv, xpub, receive and change.
+                      - convert-key: encoded, version, depth, parent_fingerprint, child_number,
+                        chain_code and key.
+                      - path: path and depth (the number of its indexes).
+                      - mnemonic: mnemonic, entropy, seedqr and compact_seedqr (the SeedQRs are
+                        empty unless the mnemonic has 12 or 24 words).
+                      - generate-seed: mnemonic, entropy, bits (the estimate of the entropy
+                        supplied) and discarded (the pairs of coin flips discarded).
+                      - slip39: secret, identifier, shares (the mnemonics separated by commas)
+                        and xprv (the BIP 32 master key of the secret).
+                      - seed-xor: seed, entropy, shares (separated by commas) and count.
+                      - coldcard-export: name, policy (e.g. 2 of 3), format and file.
+                      - process: kind, followed by the fields of derive-key or script-expression.
+                      - address: address, type, script (the output script in hexadecimal) and url
+                        (empty without --explorer-url).
+                      - lint: warnings (the codes separated by commas) and count.
+                      - bench: count, seconds, keys_per_second and allocations.
+                      - demo: vector, seed, master_xprv, master_xpub, the fields of wallet-init
+                        and address.
+                      - compose: descriptor, checksum and keys (their number).
+                      - hwi-import: receive and change (separated by commas) and count.
+
+    --output {format}  Selects how the results and the errors are printed, {format} is one of 'plain'
+                      (the default), 'json', 'csv', 'ur' or 'qr', each encoded the same way for all
+                      the sub-commands. 'csv' prints every result as a CSV row (RFC 4180, without
+                      a header) of the values of its fields, in the order of their JSON object.
+                      'ur' is the same as --ur. 'qr' draws the plain result of every input as a QR
+                      code (byte mode, error correction level L, up to 2953 bytes) with the block
+                      characters, for a terminal with a dark background. The errors are printed
+                      the same way as with 'plain'. With 'json', every result is printed as a JSON
+                      object of its fields (the same as available to --template, which cannot be
+                      combined with any --output but plain), and every error of an input as a JSON
+                      object on the standard error, e.g.
+                      {\"input\":\"raw(00)#00000000\",\"kind\":\"ChecksumMismatch\",\"code\":\"E001\",
+                      \"message\":\"...\"}. The kind and its stable code (also printed by a 'plain' error,
+                      e.g. 'Parsing error [E001]: ...', and in the errors of --rpc and serve --http)
+                      are one of:
+                      E000 InvalidInput (any error not classified more precisely),
+                      E001 ChecksumMismatch, E002 InvalidChecksum, E003 MissingChecksum,
+                      E004 NetworkMismatch, E005 OriginMismatch, E006 PrivateOutput,
+                      E007 AddressMismatch, E008 DeniedWarning, E009 PolicyViolation,
+                      E010 ChangeMismatch, E011 HardenedFromXpub, E012 InvalidCharacter,
+                      E013 InvalidThreshold, E014 InvalidKey, E015 InvalidJson, E016 InvalidSeed
+                      or E017 InvalidShare.
+                      The codes are never reassigned, so tools and documentation can rely on them.
+                      If the part of the input at fault is known (e.g. the checksum or an invalid
+                      hex digit), its byte range is added as \"span\":[start,end], while a 'plain'
+                      error underlines it below the input (except with --check, which keeps every
+                      error on a single line).
+
+    --select {fields}  Limits the JSON object (or the CSV row) of every result of '--output json'
+                      ('--output csv') to the {fields} separated by commas, in the given order, e.g.
+                      'derive-key - --output json --select xpub,fingerprint'. An unknown field is an
+                      error listing the available ones. It can only be used with '--output json' or
+                      '--output csv'.
+
+    --no-secret-warning  Silences the warning printed to the standard error when a command-line
+                      argument looks like a secret (an extended private key, a WIF private key,
+                      a seed given to a sub-command taking seeds or a mnemonic), as the arguments
+                      may leak through the shell history or the process list. Passing the
+                      secrets through the standard input ('-') avoids both the leak and the warning.
+
+    --no-private      Refuses every result that would contain private material (an extended private
+                      key or a WIF private key): it is reported as an error of the PrivateOutput
+                      kind instead of being printed, so shared CI logs can never contain secrets,
+                      e.g. derive-key --no-private fails for an xprv or seed input, unless the
+                      --template selects only the public fields.
+
+    --no-cache        Processes every repeated input again. By default, the results of the last
+                      10000 distinct inputs are cached, so the duplicate lines of a batch (common
+                      in exported logs) are answered without deriving them again. The cache is
+                      never used for slip39 --split and seed-xor --split (the shares are random),
+                      --rpc or --import-to-core. Use --no-cache in memory-constrained environments.
+
+    --log-file {path}  Appends the logs to the file at {path} instead of the standard error.
+
+    --ur              Encodes every result as a uniform resource (BCR-2020-005) for airgapped signers:
+                      the script-expression as ur:crypto-output, the key-expression and the derived
+                      xpub of derive-key as ur:crypto-hdkey. A long result is split into a multi-part
+                      sequence, one part per line (ur:crypto-output/1-3/...). The shorthand of
+                      '--output ur', cannot be combined with --template or another --output.
+
+    --ur-max-fragment {length}  The longest fragment of a multi-part uniform resource in bytes
+                      (200 by default, at least 10), only with '--output ur'.
+
+    --import-to-core --rpc-url {url} --rpc-cookie {path}
+                      Imports the descriptors of every result into a Bitcoin Core wallet with the
+                      importdescriptors RPC (watching them from now on, without a rescan): the
+                      script-expression (which must have a checksum) or the active receive and
+                      change descriptors of wallet-init. The {url} is the JSON-RPC endpoint of the
+                      wallet, e.g. http://127.0.0.1:8332/wallet/watchonly, and {path} the cookie
+                      file with its credentials, e.g. ~/.bitcoin/.cookie. Only available when built
+                      with the 'core-import' feature.
+
+Logging:
+    Debug spans around the parsing, the derivation and the checksum computation (with the time spent
+    in each) are logged when enabled by the BIP380_LOG environment variable, e.g. BIP380_LOG=debug.
+    The inputs themselves are never logged, as they may contain private keys and seeds.
 
 
 The option --help displays this descriptive help message regarding the sub-comands and
 flags. When --help is used it takes precendence over any other command-line arguments.";
+
+#[must_use]
+pub fn secret_argument_warning(secret_kind: SecretKind) -> String {
+    format!("Warning: a command-line argument looks like {secret_kind}, which may leak through the shell history or the process list. Consider passing it through the standard input ('-') instead, or silence this warning with --no-secret-warning.")
+}
@@ -0,0 +1,48 @@
+use std::{fs::OpenOptions, sync::Mutex};
+
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+use crate::structs::parsing_error::ParsingError;
+
+/// The environment variable holding the filter of the logged spans and events, e.g. `BIP380_LOG=debug`
+/// or `BIP380_LOG=bip380::subcommands::utils::checksum=debug` (see the `tracing-subscriber` `EnvFilter` syntax).
+pub const LOG_ENV_VAR: &str = "BIP380_LOG";
+
+/// Installs the global subscriber writing the logs to the standard error, or appending them to the log file.
+///
+/// Nothing is logged unless enabled by [`LOG_ENV_VAR`]. Every span is logged when it closes, together with
+/// the time spent in it, so the time spent parsing, deriving and computing checksums can be observed.
+/// The inputs are never logged, as they may contain private keys and seeds.
+///
+/// # Errors
+///
+/// Returns a [`ParsingError`] if the filter is invalid or the log file cannot be opened.
+pub fn init_logging(log_file: Option<&str>) -> Result<(), ParsingError> {
+    let filter = match std::env::var(LOG_ENV_VAR) {
+        Ok(directives) => EnvFilter::try_new(&directives)
+            .map_err(|e| ParsingError::new(&format!("Invalid value of '{LOG_ENV_VAR}': {e}")))?,
+        Err(_) => EnvFilter::new("off"),
+    };
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE);
+
+    let result = match log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| {
+                    ParsingError::new(&format!("Could not open the log file '{path}': {e}"))
+                })?;
+            subscriber
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .try_init()
+        }
+        None => subscriber.with_writer(std::io::stderr).try_init(),
+    };
+
+    result.map_err(|e| ParsingError::new(&format!("Could not initialize the logging: {e}")))
+}
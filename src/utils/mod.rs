@@ -1,2 +1,4 @@
 pub mod error_messages;
 pub mod info_messages;
+pub mod logging;
+pub mod signals;
@@ -0,0 +1,58 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// The signal that requested the shutdown, 0 until one is received.
+static SHUTDOWN_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// The offset of the exit code of a process stopped by a signal, the same as the shells report.
+const SIGNAL_EXIT_CODE_OFFSET: i32 = 128;
+
+/// Installs the handlers of `SIGINT` and `SIGTERM`, which only request a graceful shutdown: the batch stops after
+/// the input being processed, the buffered results and the `--stats` summary are still printed.
+///
+/// The handlers are reset once they run, so a second signal stops the process right away (e.g. while it waits for
+/// the next line of the standard input). Without the `signals` feature (or outside of Unix), nothing is installed
+/// and the signals stop the process the usual way.
+#[cfg(all(feature = "signals", unix))]
+pub fn install_shutdown_handlers() {
+    extern "C" fn request_shutdown(signal: libc::c_int) {
+        SHUTDOWN_SIGNAL.store(signal, Ordering::SeqCst);
+    }
+
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: the handler only stores to an atomic, which is async-signal-safe, and the zeroed sigaction
+        // is a valid empty mask with no flags besides the ones set below
+        let installed = unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction =
+                request_shutdown as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESETHAND;
+            libc::sigemptyset(std::ptr::addr_of_mut!(action.sa_mask));
+            libc::sigaction(signal, std::ptr::addr_of!(action), std::ptr::null_mut())
+        } == 0;
+        if !installed {
+            tracing::warn!(
+                "Could not install the handler of the signal {signal}: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+#[cfg(not(all(feature = "signals", unix)))]
+pub fn install_shutdown_handlers() {}
+
+/// Returns whether a signal requested the shutdown.
+#[must_use]
+pub fn shutdown_requested() -> bool {
+    SHUTDOWN_SIGNAL.load(Ordering::SeqCst) != 0
+}
+
+/// Returns the exit code of a process stopped by the signal that requested the shutdown (130 for `SIGINT`,
+/// 143 for `SIGTERM`), or `None` if no signal was received.
+#[must_use]
+pub fn shutdown_exit_code() -> Option<i32> {
+    match SHUTDOWN_SIGNAL.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(SIGNAL_EXIT_CODE_OFFSET + signal),
+    }
+}